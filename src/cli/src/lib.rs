@@ -0,0 +1,299 @@
+// Argument parsing and account decoding of bs-contract-cli, main.rs talks to the cluster.
+use clap::{crate_description, crate_name, crate_version, App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
+use helloworld::{Bet, EventBets, MatchOutcome};
+use solana_sdk::{
+    clock::UnixTimestamp,
+    commitment_config::CommitmentConfig,
+    native_token::{lamports_to_sol, sol_to_lamports},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use std::{fmt::Write, str::FromStr};
+
+// When an event stops taking bets, a unix timestamp or +SECS from the cluster's clock.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Deadline {
+    At(UnixTimestamp),
+    In(i64),
+}
+
+impl Deadline {
+    pub fn resolve(self, now: UnixTimestamp) -> UnixTimestamp {
+        match self {
+            Deadline::At(deadline) => deadline,
+            Deadline::In(secs) => now.saturating_add(secs),
+        }
+    }
+}
+
+impl FromStr for Deadline {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.strip_prefix('+') {
+            Some(secs) => secs.parse().map(Deadline::In),
+            None => input.parse().map(Deadline::At),
+        }
+        .map_err(|_| format!("{} is neither a unix timestamp nor +SECS", input))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    CreateEvent { deadline: Deadline },
+    AddBet { event: Pubkey, choice: MatchOutcome, lamports: u64 },
+    // With a metadata hash, the program refuses an event initialized with another one.
+    SetWinner { event: Pubkey, result: MatchOutcome, metadata_hash: Option<[u8; 32]> },
+    // The betor defaults to the keypair's pubkey.
+    Withdraw { event: Pubkey, bet: Pubkey, betor: Option<Pubkey> },
+    ShowEvent { event: Pubkey },
+    // At least one of the two, both lists the event's bets of that betor.
+    ListBets { event: Option<Pubkey>, betor: Option<Pubkey> },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    pub json_rpc_url: String,
+    pub keypair_path: String,
+    pub commitment: CommitmentConfig,
+    pub program_id: Pubkey,
+    pub command: Command,
+}
+
+fn pubkey_arg<'a, 'b>(name: &'a str, help: &'b str) -> Arg<'a, 'b> {
+    Arg::with_name(name).value_name("PUBKEY").takes_value(true).help(help)
+}
+
+fn outcome_arg<'a, 'b>(name: &'a str, help: &'b str) -> Arg<'a, 'b> {
+    Arg::with_name(name).value_name("OUTCOME").takes_value(true).required(true).help(help)
+}
+
+pub fn app<'a, 'b>() -> App<'a, 'b> {
+    App::new(crate_name!())
+        .about(crate_description!())
+        .version(crate_version!())
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("keypair")
+                .short("k")
+                .long("keypair")
+                .value_name("KEYPAIR")
+                .takes_value(true)
+                .global(true)
+                .help("Pays, arbitrates and bets [default: the Solana CLI config's keypair]"),
+        )
+        .arg(
+            Arg::with_name("json_rpc_url")
+                .short("u")
+                .long("url")
+                .value_name("URL")
+                .takes_value(true)
+                .global(true)
+                .help("JSON RPC URL of the cluster [default: the Solana CLI config's]"),
+        )
+        .arg(
+            Arg::with_name("commitment")
+                .long("commitment")
+                .value_name("LEVEL")
+                .takes_value(true)
+                .possible_values(&["processed", "confirmed", "finalized"])
+                .global(true)
+                .help("Commitment of reads and confirmations [default: the Solana CLI config's]"),
+        )
+        .arg(pubkey_arg("program_id", "The program's address [default: helloworld::id()]").long("program-id").global(true))
+        .subcommand(
+            SubCommand::with_name("create-event").about("Creates and initializes an event arbitrated by the keypair, prints its pubkey").arg(
+                Arg::with_name("deadline")
+                    .value_name("DEADLINE")
+                    .required(true)
+                    .help("Last moment bets are taken, a unix timestamp or +SECS from now"),
+            ),
+        )
+        .subcommand(
+            SubCommand::with_name("add-bet")
+                .about("Bets from a new bet account, prints its pubkey")
+                .arg(pubkey_arg("event", "The event").required(true))
+                .arg(outcome_arg("choice", "TeamA or TeamB"))
+                .arg(Arg::with_name("amount").value_name("SOL").required(true).help("The stake, on top of the bet account's rent")),
+        )
+        .subcommand(
+            SubCommand::with_name("set-winner")
+                .about("Publishes the event's result, the keypair is the arbiter")
+                .arg(pubkey_arg("event", "The event").required(true))
+                .arg(outcome_arg("result", "TeamA, TeamB or Draw"))
+                .arg(
+                    Arg::with_name("metadata_hash")
+                        .long("metadata-hash")
+                        .value_name("HEX")
+                        .takes_value(true)
+                        .help("The event's metadata hash, 64 hex digits, confirms the right event is settled"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("withdraw")
+                .about("Pays out what is left of a bet")
+                .arg(pubkey_arg("event", "The event").required(true))
+                .arg(pubkey_arg("bet", "The bet account").required(true))
+                .arg(pubkey_arg("betor", "Who is paid [default: the keypair's pubkey]").long("betor")),
+        )
+        .subcommand(
+            SubCommand::with_name("show-event")
+                .about("Prints an event with its pools and implied odds")
+                .arg(pubkey_arg("event", "The event").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("list-bets")
+                .about("Prints the bets of an event, of a betor, or of a betor on an event")
+                .arg(pubkey_arg("event", "Only this event's bets").long("event"))
+                .arg(pubkey_arg("betor", "Only this betor's bets").long("betor"))
+                .group(ArgGroup::with_name("filter").args(&["event", "betor"]).multiple(true).required(true)),
+        )
+}
+
+fn pubkey_of(matches: &ArgMatches, name: &str) -> Result<Option<Pubkey>, String> {
+    matches
+        .value_of(name)
+        .map(|value| Pubkey::from_str(value).map_err(|_| format!("{} {} is not a pubkey", name, value)))
+        .transpose()
+}
+
+fn required_pubkey(matches: &ArgMatches, name: &str) -> Result<Pubkey, String> {
+    pubkey_of(matches, name)?.ok_or_else(|| format!("{} is required", name))
+}
+
+fn outcome_of(matches: &ArgMatches, name: &str) -> Result<MatchOutcome, String> {
+    MatchOutcome::from_str(matches.value_of(name).unwrap_or_default()).map_err(|err| err.to_string())
+}
+
+fn hash_of(matches: &ArgMatches, name: &str) -> Result<Option<[u8; 32]>, String> {
+    let value = match matches.value_of(name) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    let mut hash = [0; 32];
+    if value.len() != 2 * hash.len() || !value.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+        return Err(format!("{} {} is not 64 hex digits", name, value));
+    }
+    for (byte, i) in hash.iter_mut().zip((0..value.len()).step_by(2)) {
+        *byte = u8::from_str_radix(&value[i..i + 2], 16).map_err(|err| err.to_string())?;
+    }
+    Ok(Some(hash))
+}
+
+fn lamports_of(matches: &ArgMatches, name: &str) -> Result<u64, String> {
+    let value = matches.value_of(name).unwrap_or_default();
+    match value.parse::<f64>() {
+        Ok(sol) if sol.is_finite() && sol > 0.0 => Ok(sol_to_lamports(sol)),
+        _ => Err(format!("{} {} is not a positive amount of SOL", name, value)),
+    }
+}
+
+fn command_of(matches: &ArgMatches) -> Result<Command, String> {
+    Ok(match matches.subcommand() {
+        ("create-event", Some(matches)) => Command::CreateEvent { deadline: matches.value_of("deadline").unwrap_or_default().parse()? },
+        ("add-bet", Some(matches)) => Command::AddBet {
+            event: required_pubkey(matches, "event")?,
+            choice: outcome_of(matches, "choice")?,
+            lamports: lamports_of(matches, "amount")?,
+        },
+        ("set-winner", Some(matches)) => Command::SetWinner {
+            event: required_pubkey(matches, "event")?,
+            result: outcome_of(matches, "result")?,
+            metadata_hash: hash_of(matches, "metadata_hash")?,
+        },
+        ("withdraw", Some(matches)) => Command::Withdraw {
+            event: required_pubkey(matches, "event")?,
+            bet: required_pubkey(matches, "bet")?,
+            betor: pubkey_of(matches, "betor")?,
+        },
+        ("show-event", Some(matches)) => Command::ShowEvent { event: required_pubkey(matches, "event")? },
+        ("list-bets", Some(matches)) => Command::ListBets { event: pubkey_of(matches, "event")?, betor: pubkey_of(matches, "betor")? },
+        (name, _) => return Err(format!("unknown command {}", name)),
+    })
+}
+
+// The matches of app() over `defaults`, usually the Solana CLI config.
+pub fn parse_args(matches: &ArgMatches, defaults: &solana_cli_config::Config) -> Result<Config, String> {
+    let commitment = matches.value_of("commitment").unwrap_or(&defaults.commitment);
+    Ok(Config {
+        json_rpc_url: matches.value_of("json_rpc_url").unwrap_or(&defaults.json_rpc_url).to_string(),
+        keypair_path: matches.value_of("keypair").unwrap_or(&defaults.keypair_path).to_string(),
+        commitment: CommitmentConfig::from_str(commitment).map_err(|_| format!("{} is not a commitment level", commitment))?,
+        program_id: pubkey_of(matches, "program_id")?.unwrap_or_else(helloworld::id),
+        command: command_of(matches)?,
+    })
+}
+
+// An initialized event of the program's current layout, anything else is refused.
+pub fn decode_event(data: &[u8]) -> Result<EventBets, ProgramError> {
+    EventBets::unpack(data)
+}
+
+pub fn decode_bet(data: &[u8]) -> Result<Bet, ProgramError> {
+    Bet::unpack(data)
+}
+
+// What a winning lamport on a side returns per the pools net of commission, None while the side is empty.
+pub fn decimal_odds(bets: &EventBets, pool: u64) -> Option<f64> {
+    if pool == 0 {
+        return None;
+    }
+    let pools = bets.balance_a as f64 + bets.balance_b as f64;
+    Some(pools * (10_000 - bets.commission_bps) as f64 / 10_000.0 / pool as f64)
+}
+
+fn outcome_name(outcome: u8) -> String {
+    MatchOutcome::from_u8(outcome).map_or_else(|| format!("invalid ({})", outcome), |outcome| outcome.to_string())
+}
+
+fn percent(bps: u16) -> String {
+    format!("{}.{:02}%", bps / 100, bps % 100)
+}
+
+pub fn describe_event(event: &Pubkey, bets: &EventBets) -> String {
+    let quote = bets.quote_odds();
+    let mut out = String::new();
+    let _ = writeln!(out, "Event:        {}", event);
+    let _ = writeln!(out, "Arbiter:      {}", bets.arbiter);
+    let _ = writeln!(out, "Bets until:   {}", bets.bets_allowed_until_ts);
+    let _ = writeln!(out, "Result:       {}", outcome_name(bets.outcome));
+    if bets.winner_set_at != 0 {
+        let _ = writeln!(out, "Final at:     {}", bets.claimable_at());
+    }
+    if bets.paused {
+        let _ = writeln!(out, "Paused:       yes");
+    }
+    let _ = writeln!(out, "Commission:   {}", percent(bets.commission_bps));
+    let sides = [
+        (MatchOutcome::TeamA, quote.balance_a, quote.implied_a_bps, bets.bets_count_a, bets.odds_a),
+        (MatchOutcome::TeamB, quote.balance_b, quote.implied_b_bps, bets.bets_count_b, bets.odds_b),
+    ];
+    for &(side, pool, implied_bps, count, odds) in sides.iter() {
+        let pays = if bets.fixed_odds {
+            format!("{}/{} fixed", odds.numerator, odds.denominator)
+        } else {
+            decimal_odds(bets, pool).map_or_else(|| "-".to_string(), |odds| format!("{:.2}x", odds))
+        };
+        let _ = writeln!(
+            out,
+            "{:<13} {} SOL in {} bets, implied {}, pays {}",
+            format!("{}:", side),
+            lamports_to_sol(pool),
+            count,
+            percent(implied_bps),
+            pays
+        );
+    }
+    let _ = writeln!(out, "Paid out:     {} SOL", lamports_to_sol(bets.total_paid_out));
+    out
+}
+
+pub fn describe_bet(bet: &Pubkey, this_bet: &Bet) -> String {
+    let state = match MatchOutcome::from_u8(this_bet.outcome) {
+        Some(outcome) if outcome.is_closed_bet() => outcome.name().to_lowercase(),
+        _ if this_bet.claimed > 0 => format!("on {}, claimed {}", outcome_name(this_bet.outcome), this_bet.claimed),
+        _ => format!("on {}", outcome_name(this_bet.outcome)),
+    };
+    format!("{} of {} by {}: {} SOL {}", bet, this_bet.event, this_bet.betor, lamports_to_sol(this_bet.amount), state)
+}
@@ -0,0 +1,120 @@
+// bs-contract-cli: creates, bets on and settles events, e.g. against `solana-test-validator`, see README.md.
+use bs_contract_cli::{app, decode_bet, decode_event, describe_bet, describe_event, parse_args, Command, Config};
+use helloworld::client;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+};
+use solana_sdk::{
+    account::from_account,
+    clock::Clock,
+    instruction::Instruction,
+    rent::Rent,
+    signature::{read_keypair_file, Keypair, Signer},
+    sysvar::Sysvar,
+    transaction::Transaction,
+};
+use std::{error::Error, process::exit};
+
+type CliResult = Result<(), Box<dyn Error>>;
+
+fn main() {
+    let matches = app().get_matches();
+    let defaults = solana_cli_config::CONFIG_FILE
+        .as_ref()
+        .and_then(|path| solana_cli_config::Config::load(path).ok())
+        .unwrap_or_default();
+    let config = parse_args(&matches, &defaults).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        exit(1);
+    });
+    if let Err(err) = run(&config) {
+        eprintln!("error: {}", err);
+        exit(1);
+    }
+}
+
+fn sysvar<S: Sysvar>(rpc: &RpcClient) -> Result<S, Box<dyn Error>> {
+    let account = rpc.get_account(&S::id())?;
+    from_account(&account).ok_or_else(|| format!("can't decode sysvar {}", S::id()).into())
+}
+
+// Sends `instructions` paid by `payer`, `signers` sign besides it.
+fn send(rpc: &RpcClient, instructions: &[Instruction], payer: &Keypair, signers: &[&Keypair]) -> CliResult {
+    let (blockhash, _) = rpc.get_recent_blockhash()?;
+    let mut keypairs: Vec<&dyn Signer> = vec![payer];
+    keypairs.extend(signers.iter().map(|keypair| *keypair as &dyn Signer));
+    let transaction = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &keypairs, blockhash);
+    let signature = rpc.send_and_confirm_transaction_with_spinner(&transaction)?;
+    eprintln!("Signature: {}", signature);
+    Ok(())
+}
+
+fn run(config: &Config) -> CliResult {
+    let rpc = RpcClient::new_with_commitment(config.json_rpc_url.clone(), config.commitment);
+    let keypair = || read_keypair_file(&config.keypair_path).map_err(|err| format!("can't read keypair {}: {}", config.keypair_path, err));
+    let program_id = &config.program_id;
+
+    match &config.command {
+        Command::CreateEvent { deadline } => {
+            let payer = keypair()?;
+            let event = Keypair::new();
+            let deadline = deadline.resolve(sysvar::<Clock>(&rpc)?.unix_timestamp);
+            let rent = sysvar::<Rent>(&rpc)?;
+            let instructions = client::create_event_transaction(program_id, &payer.pubkey(), &payer.pubkey(), &event.pubkey(), deadline, &rent);
+            send(&rpc, &instructions, &payer, &[&event])?;
+            println!("{}", event.pubkey());
+        }
+        Command::AddBet { event, choice, lamports } => {
+            let payer = keypair()?;
+            let bet = Keypair::new();
+            let rent = sysvar::<Rent>(&rpc)?;
+            let instructions = client::place_bet_transaction(program_id, &payer.pubkey(), &payer.pubkey(), event, &bet.pubkey(), *choice, *lamports, &rent);
+            send(&rpc, &instructions, &payer, &[&bet])?;
+            println!("{}", bet.pubkey());
+        }
+        Command::SetWinner { event, result, metadata_hash } => {
+            let payer = keypair()?;
+            let instruction = match metadata_hash {
+                Some(hash) => client::set_winner_confirmed(program_id, &payer.pubkey(), event, *result, *hash),
+                None => client::set_winner(program_id, &payer.pubkey(), event, *result),
+            };
+            send(&rpc, &[instruction], &payer, &[])?;
+        }
+        Command::Withdraw { event, bet, betor } => {
+            let payer = keypair()?;
+            let betor = betor.unwrap_or_else(|| payer.pubkey());
+            send(&rpc, &[client::withdraw(program_id, &betor, event, bet)], &payer, &[])?;
+        }
+        Command::ShowEvent { event } => {
+            let account = rpc.get_account(event)?;
+            if account.owner != *program_id {
+                return Err(format!("{} is owned by {}, not the program {}", event, account.owner, program_id).into());
+            }
+            print!("{}", describe_event(event, &decode_event(&account.data)?));
+        }
+        Command::ListBets { event, betor } => {
+            let filters = match (event, betor) {
+                (Some(event), _) => client::bets_for_event(event),
+                (None, Some(betor)) => client::bets_by_betor(betor),
+                (None, None) => return Err("list-bets takes --event, --betor or both".into()),
+            };
+            let accounts = rpc.get_program_accounts_with_config(
+                program_id,
+                RpcProgramAccountsConfig {
+                    filters: Some(filters),
+                    account_config: RpcAccountInfoConfig { encoding: Some(UiAccountEncoding::Base64), ..RpcAccountInfoConfig::default() },
+                    ..RpcProgramAccountsConfig::default()
+                },
+            )?;
+            for (bet, account) in accounts {
+                let this_bet = decode_bet(&account.data)?;
+                if betor.map_or(true, |betor| this_bet.betor == betor) {
+                    println!("{}", describe_bet(&bet, &this_bet));
+                }
+            }
+        }
+    }
+    Ok(())
+}
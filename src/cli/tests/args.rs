@@ -0,0 +1,103 @@
+use bs_contract_cli::{app, parse_args, Command, Config, Deadline};
+use helloworld::MatchOutcome;
+use solana_sdk::{commitment_config::CommitmentConfig, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+
+fn defaults() -> solana_cli_config::Config {
+    solana_cli_config::Config {
+        json_rpc_url: "http://127.0.0.1:8899".to_string(),
+        keypair_path: "/home/arbiter/id.json".to_string(),
+        commitment: "confirmed".to_string(),
+        ..solana_cli_config::Config::default()
+    }
+}
+
+fn parse(args: &[&str]) -> Result<Config, String> {
+    let matches = app().get_matches_from_safe([&["bs-contract-cli"][..], args].concat()).map_err(|err| err.message)?;
+    parse_args(&matches, &defaults())
+}
+
+fn command(args: &[&str]) -> Command {
+    parse(args).unwrap().command
+}
+
+#[test]
+fn test_global_options_and_defaults() {
+    let event = Pubkey::new_unique().to_string();
+    let config = parse(&["show-event", &event]).unwrap();
+    assert_eq!(
+        (config.json_rpc_url.as_str(), config.keypair_path.as_str(), config.commitment, config.program_id),
+        ("http://127.0.0.1:8899", "/home/arbiter/id.json", CommitmentConfig::confirmed(), helloworld::id())
+    );
+
+    let program_id = Pubkey::new_unique();
+    let config = parse(&[
+        "--url",
+        "https://api.devnet.solana.com",
+        "show-event",
+        &event,
+        "-k",
+        "betor.json",
+        "--commitment",
+        "finalized",
+        "--program-id",
+        &program_id.to_string(),
+    ])
+    .unwrap();
+    assert_eq!(
+        (config.json_rpc_url.as_str(), config.keypair_path.as_str(), config.commitment, config.program_id),
+        ("https://api.devnet.solana.com", "betor.json", CommitmentConfig::finalized(), program_id)
+    );
+    assert!(parse(&["--commitment", "recent", "show-event", &event]).is_err());
+}
+
+#[test]
+fn test_subcommands() {
+    let (event, bet, betor) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    assert_eq!(command(&["create-event", "1700000000"]), Command::CreateEvent { deadline: Deadline::At(1_700_000_000) });
+    assert_eq!(command(&["create-event", "+600"]), Command::CreateEvent { deadline: Deadline::In(600) });
+    assert_eq!(
+        command(&["add-bet", &event.to_string(), "TeamB", "1.5"]),
+        Command::AddBet { event, choice: MatchOutcome::TeamB, lamports: 3 * LAMPORTS_PER_SOL / 2 }
+    );
+    assert_eq!(command(&["set-winner", &event.to_string(), "draw"]), Command::SetWinner { event, result: MatchOutcome::Draw, metadata_hash: None });
+    let hash = "07".repeat(32);
+    assert_eq!(
+        command(&["set-winner", &event.to_string(), "TeamA", "--metadata-hash", &hash]),
+        Command::SetWinner { event, result: MatchOutcome::TeamA, metadata_hash: Some([7; 32]) }
+    );
+    assert_eq!(command(&["withdraw", &event.to_string(), &bet.to_string()]), Command::Withdraw { event, bet, betor: None });
+    assert_eq!(
+        command(&["withdraw", &event.to_string(), &bet.to_string(), "--betor", &betor.to_string()]),
+        Command::Withdraw { event, bet, betor: Some(betor) }
+    );
+    assert_eq!(command(&["list-bets", "--betor", &betor.to_string()]), Command::ListBets { event: None, betor: Some(betor) });
+    assert_eq!(
+        command(&["list-bets", "--event", &event.to_string(), "--betor", &betor.to_string()]),
+        Command::ListBets { event: Some(event), betor: Some(betor) }
+    );
+}
+
+#[test]
+fn test_bad_arguments() {
+    let event = Pubkey::new_unique().to_string();
+    assert!(parse(&[]).is_err());
+    assert!(parse(&["create-event", "tomorrow"]).is_err());
+    assert!(parse(&["create-event", "+"]).is_err());
+    assert!(parse(&["add-bet", "not-a-pubkey", "TeamA", "1"]).is_err());
+    assert!(parse(&["add-bet", &event, "TeamC", "1"]).is_err());
+    assert!(parse(&["add-bet", &event, "TeamA", "0"]).is_err());
+    assert!(parse(&["add-bet", &event, "TeamA", "-1"]).is_err());
+    assert!(parse(&["add-bet", &event, "TeamA"]).is_err());
+    assert!(parse(&["set-winner", &event]).is_err());
+    assert!(parse(&["set-winner", &event, "TeamA", "--metadata-hash", "07"]).is_err());
+    assert!(parse(&["set-winner", &event, "TeamA", "--metadata-hash", &"+7".repeat(32)]).is_err());
+    // A filter is required.
+    assert!(parse(&["list-bets"]).is_err());
+}
+
+#[test]
+fn test_deadline_resolves_against_the_clock() {
+    assert_eq!(Deadline::At(1_700_000_000).resolve(1_600_000_000), 1_700_000_000);
+    assert_eq!(Deadline::In(600).resolve(1_600_000_000), 1_600_000_600);
+    assert_eq!("-5".parse::<Deadline>(), Ok(Deadline::At(-5)));
+}
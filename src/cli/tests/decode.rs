@@ -0,0 +1,74 @@
+use bs_contract_cli::{decimal_odds, decode_bet, decode_event, describe_bet, describe_event};
+use helloworld::{Bet, EventBets, MatchOutcome, Odds, STATE_VERSION};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+fn event_bets() -> EventBets {
+    let mut bets = EventBets::unpack_unchecked(&[0; EventBets::LEN]).unwrap();
+    bets.version = STATE_VERSION;
+    bets.arbiter = Pubkey::new_unique();
+    bets.bets_allowed_until_ts = 1_700_000_000;
+    bets.balance_a = 3 * LAMPORTS_PER_SOL;
+    bets.balance_b = LAMPORTS_PER_SOL;
+    bets.bets_count_a = 2;
+    bets.bets_count_b = 1;
+    bets.commission_bps = 300;
+    bets
+}
+
+fn packed<T: Pack>(state: T) -> Vec<u8> {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_decode_event() {
+    let event = Pubkey::new_unique();
+    let bets = decode_event(&packed(event_bets())).unwrap();
+    assert_eq!((bets.balance_a, bets.bets_count_b), (3 * LAMPORTS_PER_SOL, 1));
+
+    let description = describe_event(&event, &bets);
+    assert!(description.starts_with(&format!("Event:        {}\n", event)), "{}", description);
+    assert!(description.contains("Result:       Unknown\n"), "{}", description);
+    assert!(description.contains("Commission:   3.00%\n"), "{}", description);
+    assert!(description.contains("Team A:       3 SOL in 2 bets, implied 75.00%, pays 1.29x\n"), "{}", description);
+    assert!(description.contains("Team B:       1 SOL in 1 bets, implied 25.00%, pays 3.88x\n"), "{}", description);
+
+    // Accounts that aren't events.
+    assert_eq!(decode_event(&[0; EventBets::LEN]).err(), Some(ProgramError::UninitializedAccount));
+    assert_eq!(decode_event(&[0; Bet::LEN]).err(), Some(ProgramError::InvalidAccountData));
+}
+
+#[test]
+fn test_implied_odds() {
+    let mut bets = event_bets();
+    assert_eq!(decimal_odds(&bets, bets.balance_b), Some(3.88));
+    bets.balance_b = 0;
+    assert_eq!(decimal_odds(&bets, bets.balance_b), None);
+    assert!(describe_event(&Pubkey::new_unique(), &bets).contains("implied 0.00%, pays -\n"));
+
+    bets.fixed_odds = true;
+    bets.odds_a = Odds { numerator: 5, denominator: 2 };
+    assert!(describe_event(&Pubkey::new_unique(), &bets).contains("pays 5/2 fixed\n"));
+}
+
+#[test]
+fn test_decode_bet() {
+    let mut this_bet = Bet::unpack_unchecked(&[0; Bet::LEN]).unwrap();
+    this_bet.version = STATE_VERSION;
+    this_bet.betor = Pubkey::new_unique();
+    this_bet.event = Pubkey::new_unique();
+    this_bet.amount = LAMPORTS_PER_SOL / 4;
+    this_bet.outcome = u8::from(MatchOutcome::TeamA);
+    let bet = Pubkey::new_unique();
+    let decoded = decode_bet(&packed(this_bet)).unwrap();
+    assert_eq!(
+        describe_bet(&bet, &decoded),
+        format!("{} of {} by {}: 0.25 SOL on Team A", bet, decoded.event, decoded.betor)
+    );
+
+    let mut closed = decoded;
+    closed.outcome = u8::from(MatchOutcome::Withdrawn);
+    assert!(describe_bet(&bet, &closed).ends_with("0.25 SOL withdrawn"));
+    assert!(decode_bet(&[0; Bet::LEN]).is_err());
+}
@@ -0,0 +1,77 @@
+import {
+  Connection,
+  PublicKey,
+  Transaction,
+  TransactionInstruction,
+} from '@solana/web3.js';
+import * as borsh from 'borsh';
+import BN from 'bn.js';
+
+/**
+ * Tag of the QuoteOdds instruction
+ */
+const QUOTE_ODDS_TAG = 14;
+
+/**
+ * Odds of an event as computed by the program, see OddsQuote in lib.rs
+ */
+export class OddsQuote {
+  balance_a = new BN(0);
+  balance_b = new BN(0);
+  implied_a_bps = 0;
+  implied_b_bps = 0;
+  commission_bps = 0;
+  constructor(fields: Partial<OddsQuote> | undefined = undefined) {
+    Object.assign(this, fields);
+  }
+}
+
+const OddsQuoteSchema = new Map([
+  [
+    OddsQuote,
+    {
+      kind: 'struct',
+      fields: [
+        ['balance_a', 'u64'],
+        ['balance_b', 'u64'],
+        ['implied_a_bps', 'u16'],
+        ['implied_b_bps', 'u16'],
+        ['commission_bps', 'u16'],
+      ],
+    },
+  ],
+]);
+
+/**
+ * Simulates QuoteOdds against the event and decodes the program's return data.
+ * Nothing is sent, the fee payer only has to exist.
+ */
+export async function quoteOdds(
+  connection: Connection,
+  programId: PublicKey,
+  event: PublicKey,
+  feePayer: PublicKey,
+): Promise<OddsQuote> {
+  const instruction = new TransactionInstruction({
+    keys: [{pubkey: event, isSigner: false, isWritable: false}],
+    programId,
+    data: Buffer.from([QUOTE_ODDS_TAG]),
+  });
+  const transaction = new Transaction({feePayer}).add(instruction);
+  const {value} = await connection.simulateTransaction(transaction);
+  if (value.err) {
+    throw new Error(`QuoteOdds failed: ${JSON.stringify(value.err)}`);
+  }
+  // Older web3.js typings don't know about returnData yet.
+  const {returnData} = value as {
+    returnData?: {programId: string; data: [string, string]} | null;
+  };
+  if (!returnData || returnData.programId !== programId.toBase58()) {
+    throw new Error('QuoteOdds returned no data');
+  }
+  return borsh.deserialize(
+    OddsQuoteSchema,
+    OddsQuote,
+    Buffer.from(returnData.data[0], 'base64'),
+  );
+}
@@ -0,0 +1,9 @@
+// A program of its own that forwards to helloworld, the way a program using it as a library would.
+// Linking fails with a duplicate entrypoint symbol unless helloworld leaves its own out.
+use solana_program::{account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey};
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    helloworld::process_instruction(program_id, accounts, instruction_data)
+}
@@ -0,0 +1,12 @@
+use helloworld::{Instruction, MatchOutcome, MEMO_BYTES};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn test_instructions_pack_through_the_library() {
+    let instruction = Instruction::AddBet { choice: MatchOutcome::TeamA, referrer: Pubkey::new_unique(), amount: 5, memo: [0; MEMO_BYTES] };
+    assert_eq!(Instruction::unpack(&instruction.pack()).unwrap(), instruction);
+    let set_winner = Instruction::SetWinner { result: MatchOutcome::Draw, metadata_hash: [0; 32] };
+    assert_eq!(Instruction::unpack(&set_winner.pack()).unwrap(), set_winner);
+    // The forwarding entrypoint links, and reaches helloworld's checks.
+    assert!(helloworld_library_test::process_instruction(&Pubkey::new_unique(), &[], &[]).is_err());
+}
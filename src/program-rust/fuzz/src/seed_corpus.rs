@@ -0,0 +1,30 @@
+// Writes valid encodings of every instruction, an event and a bet to corpus/<target>/, run it once
+// before the first `cargo fuzz run`.
+use helloworld::{
+    test_utils::{every_instruction, new_bet, new_event_bets},
+    Bet, EventBets, MatchOutcome,
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use std::{fs, io, path::Path};
+
+fn write(target: &str, name: &str, data: &[u8]) -> io::Result<()> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus").join(target);
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(name), data)
+}
+
+fn main() -> io::Result<()> {
+    for (tag, instruction) in every_instruction().iter().enumerate() {
+        write("instruction_unpack", &format!("tag-{}", tag), &instruction.pack())?;
+    }
+
+    let mut event = vec![0; EventBets::LEN];
+    EventBets::pack(new_event_bets(&Pubkey::new_unique(), 1_700_000_000), &mut event).unwrap();
+    write("event_unpack", "event", &event)?;
+    write("event_unpack", "event-v1", include_bytes!("../../tests/fixtures/event_bets.bin"))?;
+
+    let mut bet = vec![0; Bet::LEN];
+    Bet::pack(new_bet(&Pubkey::new_unique(), &Pubkey::new_unique(), 1_000_000, MatchOutcome::TeamA), &mut bet).unwrap();
+    write("bet_unpack", "bet", &bet)?;
+    write("bet_unpack", "bet-v1", include_bytes!("../../tests/fixtures/bet.bin"))
+}
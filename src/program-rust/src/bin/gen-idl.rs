@@ -0,0 +1,16 @@
+// Writes the program's IDL to the path given, target/idl/helloworld.json by default.
+//    cargo run --features idl --bin gen-idl [path]
+use std::{env, fs, path::PathBuf};
+
+fn main() -> std::io::Result<()> {
+    let path = env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/target/idl/helloworld.json")));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, helloworld::idl::to_json_string())?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,146 @@
+// Builders of the common instructions with their accounts in the order the processor reads them,
+// for off-chain code depending on this crate with the no-entrypoint feature. The accounts a
+// builder leaves out are the optional ones, see Instruction for those.
+
+use crate::{Bet, BetError, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome, MEMO_BYTES, METADATA_HASH_BYTES};
+use solana_program::{
+    clock::UnixTimestamp,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{self, rent::Rent},
+};
+
+// A message for the user when one of the program's instructions failed with `error`, None for
+// errors that are not the program's own.
+pub fn error_message(error: &InstructionError) -> Option<&'static str> {
+    match error {
+        InstructionError::Custom(code) => BetError::from_code(*code).map(|error| error.message()),
+        _ => None,
+    }
+}
+
+// Initializes `bets_account`, an empty account of EventBets::LEN owned by the program, as an
+// event taking bets until `deadline` with the defaults of EventParams::new.
+pub fn initialize(program_id: &Pubkey, owner: &Pubkey, bets_account: &Pubkey, deadline: UnixTimestamp) -> Instruction {
+    initialize_with(program_id, owner, bets_account, EventParams::new(deadline))
+}
+
+pub fn initialize_with(program_id: &Pubkey, owner: &Pubkey, bets_account: &Pubkey, params: EventParams) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &BetInstruction::Initialize(params).pack(),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(*bets_account, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    )
+}
+
+// Bets `amount` lamports of `bet_account`, an empty account of Bet::LEN owned by the program
+// holding at least that above rent, on `choice`. 0 bets all the lamports above rent.
+pub fn add_bet(program_id: &Pubkey, betor: &Pubkey, bets_account: &Pubkey, bet_account: &Pubkey, choice: MatchOutcome, amount: u64) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &BetInstruction::AddBet { choice, referrer: Pubkey::default(), amount, memo: [0; MEMO_BYTES] }.pack(),
+        vec![
+            AccountMeta::new(*betor, false),
+            AccountMeta::new(*bets_account, false),
+            AccountMeta::new(*bet_account, false),
+        ],
+    )
+}
+
+pub fn set_winner(program_id: &Pubkey, arbiter: &Pubkey, bets_account: &Pubkey, result: MatchOutcome) -> Instruction {
+    set_winner_confirmed(program_id, arbiter, bets_account, result, [0; METADATA_HASH_BYTES])
+}
+
+// As set_winner, refused unless `metadata_hash` is the one the event was initialized with.
+pub fn set_winner_confirmed(
+    program_id: &Pubkey,
+    arbiter: &Pubkey,
+    bets_account: &Pubkey,
+    result: MatchOutcome,
+    metadata_hash: [u8; METADATA_HASH_BYTES],
+) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &BetInstruction::SetWinner { result, metadata_hash }.pack(),
+        vec![AccountMeta::new(*arbiter, true), AccountMeta::new(*bets_account, false)],
+    )
+}
+
+// Claims all that is left of the bet, anyone may send it for the betor.
+pub fn withdraw(program_id: &Pubkey, betor: &Pubkey, bets_account: &Pubkey, bet_account: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &BetInstruction::Withdraw { amount: 0 }.pack(),
+        vec![
+            AccountMeta::new(*betor, false),
+            AccountMeta::new(*bets_account, false),
+            AccountMeta::new(*bet_account, false),
+        ],
+    )
+}
+
+// Creates `bets_account` with `payer`'s lamports and initializes it, both `payer` and the new
+// `bets_account` sign besides the arbiter. `rent` is the cluster's, e.g. from getMinimumBalanceForRentExemption.
+pub fn create_event_transaction(program_id: &Pubkey, payer: &Pubkey, arbiter: &Pubkey, bets_account: &Pubkey, deadline: UnixTimestamp, rent: &Rent) -> Vec<Instruction> {
+    create_event_transaction_with(program_id, payer, arbiter, bets_account, EventParams::new(deadline), rent)
+}
+
+// Same as create_event_transaction, the account also holding the bond of `params`.
+pub fn create_event_transaction_with(program_id: &Pubkey, payer: &Pubkey, arbiter: &Pubkey, bets_account: &Pubkey, params: EventParams, rent: &Rent) -> Vec<Instruction> {
+    let lamports = rent.minimum_balance(EventBets::LEN) + params.bond_lamports;
+    vec![
+        system_instruction::create_account(payer, bets_account, lamports, EventBets::LEN as u64, program_id),
+        initialize_with(program_id, arbiter, bets_account, params),
+    ]
+}
+
+// Creates `bet_account` holding `amount` lamports above its rent exemption and bets them on `choice`.
+// `payer` and the new `bet_account` sign.
+#[allow(clippy::too_many_arguments)]
+pub fn place_bet_transaction(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    betor: &Pubkey,
+    bets_account: &Pubkey,
+    bet_account: &Pubkey,
+    choice: MatchOutcome,
+    amount: u64,
+    rent: &Rent,
+) -> Vec<Instruction> {
+    let lamports = rent.minimum_balance(Bet::LEN) + amount;
+    vec![
+        system_instruction::create_account(payer, bet_account, lamports, Bet::LEN as u64, program_id),
+        add_bet(program_id, betor, bets_account, bet_account, choice, amount),
+    ]
+}
+
+// getProgramAccounts filters, with the rpc-filters feature.
+#[cfg(feature = "rpc-filters")]
+fn filters(len: usize, offset: usize, key: &Pubkey) -> Vec<solana_client::rpc_filter::RpcFilterType> {
+    use solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
+    vec![
+        RpcFilterType::DataSize(len as u64),
+        RpcFilterType::Memcmp(Memcmp { offset, bytes: MemcmpEncodedBytes::Binary(key.to_string()), encoding: None }),
+    ]
+}
+
+#[cfg(feature = "rpc-filters")]
+pub fn events_by_arbiter(arbiter: &Pubkey) -> Vec<solana_client::rpc_filter::RpcFilterType> {
+    filters(EventBets::LEN, EventBets::ARBITER_OFFSET, arbiter)
+}
+
+#[cfg(feature = "rpc-filters")]
+pub fn bets_by_betor(betor: &Pubkey) -> Vec<solana_client::rpc_filter::RpcFilterType> {
+    filters(Bet::LEN, Bet::BETOR_OFFSET, betor)
+}
+
+// The bets of an event or a market.
+#[cfg(feature = "rpc-filters")]
+pub fn bets_for_event(event: &Pubkey) -> Vec<solana_client::rpc_filter::RpcFilterType> {
+    filters(Bet::LEN, Bet::EVENT_OFFSET, event)
+}
@@ -0,0 +1,76 @@
+use crate::processor::Processor;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+// Declare and export the program's entrypoint, left out with no-entrypoint for programs and clients
+// depending on this crate, which would otherwise link two entrypoints.
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+// Disclosure contacts in the format of the solana-security-txt crate: null terminated name/value pairs
+// between the markers, which query-security-txt finds in the deployed .so.
+macro_rules! security_txt {
+    ($($name:literal: $value:expr),* $(,)?) => {
+        concat!("=======BEGIN SECURITY.TXT V1=======\0", $($name, "\0", $value, "\0",)* "=======END SECURITY.TXT V1=======\0")
+    };
+}
+
+#[cfg_attr(feature = "no-entrypoint", allow(dead_code))]
+const SECURITY_TXT: &str = security_txt! {
+    "name": "bs-contract",
+    "project_url": "https://github.com/unordered-set/bs-contract",
+    "contacts": "link:https://github.com/unordered-set/bs-contract/security/advisories/new",
+    "policy": "https://github.com/unordered-set/bs-contract/blob/master/SECURITY.md",
+    "source_code": "https://github.com/unordered-set/bs-contract",
+    "encryption": "https://github.com/unordered-set/bs-contract/blob/master/SECURITY.md#encryption",
+};
+
+// Only in the program binary, library builds don't carry it.
+#[cfg(not(feature = "no-entrypoint"))]
+#[allow(dead_code, non_upper_case_globals)]
+#[link_section = ".security.txt"]
+#[no_mangle]
+pub static security_txt: &str = SECURITY_TXT;
+
+// Program entrypoint's implementation
+pub fn process_instruction(
+    program_id: &Pubkey, // Public key of the account the program was loaded into
+    accounts: &[AccountInfo], // The instruction's accounts, see Instruction for each one's
+    instruction_data: &[u8], // A packed Instruction
+) -> ProgramResult {
+    #[cfg(not(feature = "any-id"))]
+    crate::check_program_account(program_id)?;
+    Processor::process(program_id, accounts, instruction_data)
+}
+
+// Sanity tests
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::program_error::ProgramError;
+
+    #[test]
+    fn test_sanity() {
+        assert_eq!(process_instruction(&crate::id(), &[], &[]), Err(ProgramError::InvalidInstructionData));
+        // Accounts are checked after the data, a bare SetWinner is missing its arbiter.
+        let set_winner = crate::Instruction::SetWinner { result: crate::MatchOutcome::TeamA, metadata_hash: [0; crate::METADATA_HASH_BYTES] }.pack();
+        assert_eq!(process_instruction(&crate::id(), &[], &set_winner), Err(ProgramError::NotEnoughAccountKeys));
+        #[cfg(not(feature = "any-id"))]
+        assert_eq!(process_instruction(&Pubkey::new_unique(), &[], &set_winner), Err(ProgramError::IncorrectProgramId));
+    }
+
+    #[test]
+    fn test_security_txt_has_the_required_fields() {
+        let body = SECURITY_TXT
+            .strip_prefix("=======BEGIN SECURITY.TXT V1=======\0")
+            .and_then(|rest| rest.strip_suffix("=======END SECURITY.TXT V1=======\0"))
+            .unwrap();
+        let parts: Vec<_> = body.strip_suffix('\0').unwrap().split('\0').collect();
+        assert_eq!(parts.len() % 2, 0);
+        let fields: Vec<_> = parts.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+        for name in &["name", "project_url", "contacts", "policy", "source_code", "encryption"] {
+            let value = fields.iter().find(|(field, _)| field == name).map(|(_, value)| *value);
+            assert!(value.map_or(false, |value| !value.is_empty()), "{} is missing", name);
+        }
+        assert!(fields.iter().all(|(name, value)| !name.is_empty() && !value.contains('\0')));
+    }
+}
@@ -0,0 +1,147 @@
+use solana_program::program_error::ProgramError;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BetError {
+    // Withdrawing before winner_set_at + finality delay.
+    ResultNotFinal,
+    // Lamport or balance arithmetic would overflow.
+    ArithmeticOverflow,
+    // The withdrawal would leave the treasury below its rent exemption.
+    TreasuryBelowRentExemption,
+    // Stake is below the event's min_bet_lamports.
+    BelowMinimumBet,
+    // The wallet's total stake on the event would go above max_stake_per_wallet.
+    StakeCapExceeded,
+    // The bet would make one pool larger than max_imbalance_ratio times the other.
+    PoolImbalance,
+    // The betor's token account doesn't hold enough of the event's gate_mint.
+    TokenGateNotPassed,
+    // The payouts so far plus this one would exceed the pools net of commission.
+    PayoutExceedsPools,
+    // The event's outcome byte is not a result an event can have.
+    InvalidOutcome,
+    // The event's deadlines or result timestamps are not in a possible order.
+    DeadlinesOutOfOrder,
+    // The event holds fewer lamports than its rent exemption plus what it owes.
+    EventUnderfunded,
+    // A Withdraw amount above what is left to claim on the bet.
+    ClaimExceedsPayout,
+    // The arbiter paused the event, bets and withdrawals wait for UnpauseEvent.
+    EventPaused,
+    // The admin halted the program, no new events or bets until ResumeProgram.
+    ProgramHalted,
+    // The registry page passed to Initialize holds REGISTRY_PAGE_CAPACITY events already.
+    RegistryPageFull,
+    // The arbiter already has an event at the seeded address.
+    EventSeedTaken,
+    // A fixed-odds event's worst case liability would be above its stakes plus the arbiter's top-ups.
+    ExposureExceeded,
+    // The event or bet is in a layout this program doesn't read, version 1 ones need MigrateEvent first.
+    VersionMismatch,
+    // SweepDust while some of the event's bets are not withdrawn, voided or cashed out yet.
+    BetsOutstanding,
+    // A lamport transfer out of an event (or another program account) is more than it holds.
+    InsufficientPot,
+    // Withdraw of a bet that was withdrawn, cashed out or voided already.
+    AlreadyWithdrawn,
+    // CorrectResult once a bet was withdrawn under the current result.
+    ResultAlreadyClaimed,
+    // The betor holds max_bets_per_wallet open bets on the event already.
+    TooManyBets,
+    // The event is private and the betor is not on its allowlist.
+    NotAllowlisted,
+    // AddToAllowlist on an allowlist holding WALLET_LIST_CAPACITY wallets already.
+    AllowlistFull,
+    // The arbiter put the betor on the event's denylist.
+    BettorDenied,
+    // AddToDenylist on a denylist holding WALLET_LIST_CAPACITY wallets already.
+    DenylistFull,
+    // The arbiter or the commission recipient betting on an event without arbiter_may_bet.
+    ArbiterCannotBet,
+    // CorrectResult sooner than correction_cooldown_secs after the last change of the result.
+    CorrectionTooSoon,
+    // SetWinner confirmed a metadata hash other than the event's.
+    EventMismatch,
+}
+
+impl BetError {
+    // Every error in code order, ProgramError::Custom(code) is ALL[code].
+    pub const ALL: [BetError; 30] = [
+        BetError::ResultNotFinal,
+        BetError::ArithmeticOverflow,
+        BetError::TreasuryBelowRentExemption,
+        BetError::BelowMinimumBet,
+        BetError::StakeCapExceeded,
+        BetError::PoolImbalance,
+        BetError::TokenGateNotPassed,
+        BetError::PayoutExceedsPools,
+        BetError::InvalidOutcome,
+        BetError::DeadlinesOutOfOrder,
+        BetError::EventUnderfunded,
+        BetError::ClaimExceedsPayout,
+        BetError::EventPaused,
+        BetError::ProgramHalted,
+        BetError::RegistryPageFull,
+        BetError::EventSeedTaken,
+        BetError::ExposureExceeded,
+        BetError::VersionMismatch,
+        BetError::BetsOutstanding,
+        BetError::InsufficientPot,
+        BetError::AlreadyWithdrawn,
+        BetError::ResultAlreadyClaimed,
+        BetError::TooManyBets,
+        BetError::NotAllowlisted,
+        BetError::AllowlistFull,
+        BetError::BettorDenied,
+        BetError::DenylistFull,
+        BetError::ArbiterCannotBet,
+        BetError::CorrectionTooSoon,
+        BetError::EventMismatch,
+    ];
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.get(code as usize).copied()
+    }
+
+    // What a wallet can show the user, see client::error_message.
+    pub fn message(&self) -> &'static str {
+        match self {
+            BetError::ResultNotFinal => "The result can still change, try again once it is final",
+            BetError::ArithmeticOverflow => "The amount is too large",
+            BetError::TreasuryBelowRentExemption => "The treasury has to keep its rent exemption",
+            BetError::BelowMinimumBet => "The stake is below this event's minimum bet",
+            BetError::StakeCapExceeded => "You reached this event's stake limit per wallet",
+            BetError::PoolImbalance => "This bet would make the pools too uneven",
+            BetError::TokenGateNotPassed => "This event is only open to holders of its token",
+            BetError::PayoutExceedsPools => "The event can't pay this much",
+            BetError::InvalidOutcome => "That is not a possible outcome",
+            BetError::DeadlinesOutOfOrder => "The event's deadlines are in the wrong order",
+            BetError::EventUnderfunded => "The event holds less than it owes",
+            BetError::ClaimExceedsPayout => "That is more than what is left to claim on this bet",
+            BetError::EventPaused => "The event is paused while the match is under review",
+            BetError::ProgramHalted => "Betting is halted for now",
+            BetError::RegistryPageFull => "This registry page is full",
+            BetError::EventSeedTaken => "You already have an event with this name",
+            BetError::ExposureExceeded => "The event can't cover the payout of this bet at its odds",
+            BetError::VersionMismatch => "This account needs to be migrated first",
+            BetError::BetsOutstanding => "Some bets on this event are still to be claimed",
+            BetError::InsufficientPot => "The event doesn't hold enough to pay this",
+            BetError::AlreadyWithdrawn => "This bet was already paid out",
+            BetError::ResultAlreadyClaimed => "Bets were already withdrawn under this result, it can't be corrected",
+            BetError::TooManyBets => "You reached this event's limit of bets per wallet",
+            BetError::NotAllowlisted => "This event is private and you are not invited",
+            BetError::AllowlistFull => "The allowlist is full",
+            BetError::BettorDenied => "This wallet can't bet on this event",
+            BetError::DenylistFull => "The denylist is full",
+            BetError::ArbiterCannotBet => "The event's arbiter can't bet on it",
+            BetError::CorrectionTooSoon => "The result changed too recently, try again later",
+            BetError::EventMismatch => "The metadata hash is not the event's, wrong event account?",
+        }
+    }
+}
+
+impl From<BetError> for ProgramError {
+    fn from(e: BetError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
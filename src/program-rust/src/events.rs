@@ -0,0 +1,62 @@
+// Structured events for indexers, logged with sol_log_data at the end of a successful instruction.
+// Each log is a ProgramEvent in Borsh, its leading byte tells the event type, so appending a variant
+// keeps the old ones decodable. Outcomes are the packed MatchOutcome bytes as in Bet and EventBets.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{clock::UnixTimestamp, entrypoint::ProgramResult, log::sol_log_data, pubkey::Pubkey};
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct EventInitialized {
+    pub event: Pubkey,
+    pub arbiter: Pubkey,
+    // Bets are accepted until then.
+    pub deadline: UnixTimestamp,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct BetPlaced {
+    pub event: Pubkey,
+    pub bet: Pubkey,
+    pub betor: Pubkey,
+    pub outcome: u8,
+    // The stake, in tokens on a token event.
+    pub amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct WinnerSet {
+    pub event: Pubkey,
+    pub outcome: u8,
+    // Taken by this settlement, referral part included. 0 on a CorrectResult and in SOL events,
+    // which charge it at Withdraw.
+    pub commission: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct BetWithdrawn {
+    pub event: Pubkey,
+    pub bet: Pubkey,
+    // What this claim paid out of the bet's payout.
+    pub amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ResultFinalized {
+    pub event: Pubkey,
+    pub outcome: u8,
+}
+
+// Variants are only ever appended, their index is the discriminant byte.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub enum ProgramEvent {
+    EventInitialized(EventInitialized),
+    BetPlaced(BetPlaced),
+    WinnerSet(WinnerSet),
+    BetWithdrawn(BetWithdrawn),
+    ResultFinalized(ResultFinalized),
+}
+
+pub(crate) fn emit(event: &ProgramEvent) -> ProgramResult {
+    sol_log_data(&[&event.try_to_vec()?]);
+    Ok(())
+}
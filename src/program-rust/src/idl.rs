@@ -0,0 +1,174 @@
+// A JSON IDL in the manner of Anchor's for client codegen, written by `cargo run --features idl --bin gen-idl`.
+// Instructions and accounts come from the Borsh schemas in schema.rs, tags being the WireInstruction variant
+// indices. The instruction accounts come from the `[writable, signer] - name` lines documenting
+// each Instruction variant, accounts under a "Only for ...:" line are marked optional with that line
+// as their condition.
+
+use crate::schema;
+use borsh::schema::{BorshSchemaContainer, Definition, Fields};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+const SOURCE: &str = include_str!("instruction.rs");
+
+// The IDL type of a Borsh declaration.
+fn idl_type(declaration: &str) -> Value {
+    if let Some(inner) = declaration.strip_prefix("ShortVec<").and_then(|rest| rest.strip_suffix('>')) {
+        return json!({ "shortVec": idl_type(inner) });
+    }
+    if let Some(inner) = declaration.strip_prefix("Array<").and_then(|rest| rest.strip_suffix('>')) {
+        let (elements, length) = inner.rsplit_once(", ").expect("Array<T, N>");
+        return json!({ "array": [idl_type(elements), length.parse::<u64>().expect("array length")] });
+    }
+    match declaration {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128" | "bool" | "string" => json!(declaration),
+        "Pubkey" => json!("publicKey"),
+        _ => json!({ "defined": declaration }),
+    }
+}
+
+// Encoded size of `declaration`, None when it depends on the value.
+pub fn fixed_size(container: &BorshSchemaContainer, declaration: &str) -> Option<usize> {
+    match declaration {
+        "u8" | "i8" | "bool" => return Some(1),
+        "u16" | "i16" => return Some(2),
+        "u32" | "i32" => return Some(4),
+        "u64" | "i64" => return Some(8),
+        "u128" | "i128" => return Some(16),
+        _ => {},
+    }
+    let sum = |declarations: Vec<&String>| declarations.into_iter().map(|d| fixed_size(container, d)).sum::<Option<usize>>();
+    match container.definitions.get(declaration)? {
+        Definition::Array { length, elements } => fixed_size(container, elements).map(|size| size * *length as usize),
+        Definition::Struct { fields: Fields::NamedFields(fields) } => sum(fields.iter().map(|(_, d)| d).collect()),
+        Definition::Struct { fields: Fields::UnnamedFields(fields) } | Definition::Tuple { elements: fields } => sum(fields.iter().collect()),
+        Definition::Struct { fields: Fields::Empty } => Some(0),
+        Definition::Sequence { .. } | Definition::Enum { .. } => None,
+    }
+}
+
+// Named fields of a struct, an unnamed one being named after its position.
+fn fields(container: &BorshSchemaContainer, declaration: &str) -> Vec<(String, String)> {
+    match &container.definitions[declaration] {
+        Definition::Struct { fields: Fields::NamedFields(fields) } => fields.clone(),
+        Definition::Struct { fields: Fields::UnnamedFields(fields) } => fields.iter().enumerate().map(|(i, d)| (i.to_string(), d.clone())).collect(),
+        _ => vec![],
+    }
+}
+
+// The documented accounts of every Instruction variant, by name.
+fn documented_accounts() -> BTreeMap<String, Vec<Value>> {
+    let body = SOURCE.split("\npub enum Instruction {\n").nth(1).expect("Instruction in instruction.rs");
+    let body = &body[..body.find("\n}\n").expect("end of Instruction")];
+    let mut documented = BTreeMap::new();
+    let (mut accounts, mut condition) = (vec![], None::<String>);
+    for line in body.lines() {
+        let line = match line.strip_prefix("    ") {
+            Some(line) if !line.starts_with(' ') => line,
+            _ => continue,
+        };
+        if let Some(comment) = line.strip_prefix("//") {
+            let comment = comment.trim();
+            if let Some((flags, name)) = comment.strip_prefix('[').and_then(|rest| rest.split_once("] - ")) {
+                let flags: Vec<_> = flags.split(',').map(str::trim).collect();
+                accounts.push(json!({
+                    "name": name,
+                    "isMut": flags.contains(&"writable"),
+                    "isSigner": flags.contains(&"signer") || flags.contains(&"signed"),
+                    "optional": condition.is_some() || name.contains("optional"),
+                    "condition": condition,
+                }));
+            } else if comment.ends_with(':') && !comment.starts_with("Accepted accounts") {
+                condition = Some(comment.trim_end_matches(':').to_string());
+            }
+        } else if let Some(name) = line.split(|c: char| !c.is_alphanumeric()).next().filter(|name| !name.is_empty()) {
+            documented.insert(name.to_string(), std::mem::take(&mut accounts));
+            condition = None;
+        }
+    }
+    documented
+}
+
+fn type_definition(container: &BorshSchemaContainer, declaration: &str) -> Option<Value> {
+    if declaration == "MatchOutcome" {
+        let variants: Vec<_> = schema::MATCH_OUTCOME_VALUES.iter().map(|(name, value)| json!({ "name": name, "value": value })).collect();
+        return Some(json!({ "name": declaration, "type": { "kind": "enum", "repr": "u8", "variants": variants } }));
+    }
+    match container.definitions.get(declaration)? {
+        Definition::Struct { .. } => {
+            let fields: Vec<_> = fields(container, declaration).iter().map(|(name, d)| json!({ "name": name, "type": idl_type(d) })).collect();
+            Some(json!({ "name": declaration, "type": { "kind": "struct", "fields": fields } }))
+        },
+        _ => None,
+    }
+}
+
+pub fn generate() -> Value {
+    let instruction = schema::instruction();
+    let variants = match &instruction.definitions["WireInstruction"] {
+        Definition::Enum { variants } => variants.clone(),
+        _ => unreachable!(),
+    };
+    let mut documented = documented_accounts();
+    let instructions: Vec<_> = variants
+        .iter()
+        .enumerate()
+        .map(|(tag, (name, declaration))| {
+            let args: Vec<_> = match &instruction.definitions[declaration] {
+                // Tuple variants hold a single struct, whose fields are the args.
+                Definition::Struct { fields: Fields::UnnamedFields(inner) } => fields(&instruction, &inner[0]),
+                _ => fields(&instruction, declaration),
+            }
+            .iter()
+            .map(|(name, d)| json!({ "name": name, "type": idl_type(d), "size": fixed_size(&instruction, d) }))
+            .collect();
+            json!({ "name": name, "tag": tag, "args": args, "accounts": documented.remove(name).unwrap_or_default() })
+        })
+        .collect();
+
+    let mut accounts = vec![];
+    let mut types = BTreeMap::new();
+    for (container, len) in vec![(schema::event_bets(), crate::EventBets::LEN), (schema::bet(), crate::Bet::LEN)] {
+        let mut offset = 0;
+        let fields: Vec<_> = fields(&container, &container.declaration)
+            .iter()
+            .map(|(name, d)| {
+                let field = json!({ "name": name, "type": idl_type(d), "offset": offset });
+                offset += fixed_size(&container, d).expect("fixed size state");
+                field
+            })
+            .collect();
+        assert_eq!(offset, len);
+        accounts.push(json!({ "name": container.declaration, "size": len, "type": { "kind": "struct", "fields": fields } }));
+        types.extend(
+            container
+                .definitions
+                .keys()
+                .filter(|d| **d != container.declaration)
+                .filter_map(|d| type_definition(&container, d).map(|t| (d.clone(), t))),
+        );
+    }
+    // The instruction args' own types, the variants themselves are in "instructions".
+    let variant_declarations: Vec<_> = variants.iter().map(|(_, d)| d.as_str()).collect();
+    types.extend(
+        instruction
+            .definitions
+            .keys()
+            .filter(|d| d.as_str() != "WireInstruction" && !variant_declarations.contains(&d.as_str()))
+            .filter_map(|d| type_definition(&instruction, d).map(|t| (d.clone(), t))),
+    );
+    types.extend(type_definition(&schema::match_outcome(), "MatchOutcome").map(|t| ("MatchOutcome".to_string(), t)));
+    types.remove("Pubkey");
+
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "name": "helloworld",
+        "instructions": instructions,
+        "accounts": accounts,
+        "types": types.into_iter().map(|(_, t)| t).collect::<Vec<_>>(),
+    })
+}
+
+pub fn to_json_string() -> String {
+    serde_json::to_string_pretty(&generate()).expect("IDL is plain JSON")
+}
@@ -0,0 +1,1261 @@
+use crate::{
+    state::{result_outcome, MatchOutcome, Odds, DEFAULT_CORRECTION_WINDOW_SECS, MARKET_KIND_MONEYLINE, MEMO_BYTES, METADATA_HASH_BYTES},
+    USE_DEFAULT_COMISSION,
+};
+use borsh::{BorshSchema, BorshSerialize};
+use solana_program::{
+    clock::UnixTimestamp,
+    program_error::ProgramError,
+    pubkey::{Pubkey, PUBKEY_BYTES},
+};
+
+// Prefix of the message an arbiter signs off-chain to publish a result,
+// the full message is RESULT_MESSAGE_PREFIX || event pubkey || outcome byte.
+pub const RESULT_MESSAGE_PREFIX: &[u8] = b"bs-contract:result";
+pub const SIGNATURE_BYTES: usize = 64;
+
+pub fn result_message(event: &Pubkey, outcome: MatchOutcome) -> Vec<u8> {
+    let mut message = Vec::with_capacity(RESULT_MESSAGE_PREFIX.len() + PUBKEY_BYTES + 1);
+    message.extend_from_slice(RESULT_MESSAGE_PREFIX);
+    message.extend_from_slice(event.as_ref());
+    message.push(u8::from(outcome));
+    message
+}
+
+// Event settings chosen by the arbiter at Initialize, packed as their Borsh encoding.
+#[derive(BorshSerialize, BorshSchema, Clone, Copy, Debug, PartialEq)]
+pub struct EventParams {
+    pub bets_accepted_until: UnixTimestamp,
+    pub dispute_window_secs: i64,
+    pub finality_delay_secs: i64,
+    pub bond_lamports: u64,
+    pub challenger: Pubkey,
+    pub commission_bps: u16,
+    pub min_bet_lamports: u64,
+    pub max_stake_per_wallet: u64,
+    pub max_imbalance_ratio: u16,
+    pub bonus_commission_free: bool,
+    pub gate_mint: Pubkey,
+    pub gate_min_amount: u64,
+    pub mint: Pubkey,
+    pub metadata_hash: [u8; METADATA_HASH_BYTES],
+    pub fixed_odds: bool,
+    pub market_kind: u8,
+    pub handicap: i16,
+    pub correction_window_secs: i64,
+    // Default pubkey for the arbiter.
+    pub commission_recipient: Pubkey,
+    pub max_bets_per_wallet: u8,
+    // Lets the arbiter and the commission recipient bet on the event, for house participation.
+    pub arbiter_may_bet: bool,
+    // Least time between two changes of the result, SetWinner included, 0 for none.
+    pub correction_cooldown_secs: i64,
+}
+
+impl EventParams {
+    // Defaults are what older clients get for the fields they don't send.
+    pub fn new(bets_accepted_until: UnixTimestamp) -> Self {
+        EventParams {
+            bets_accepted_until,
+            dispute_window_secs: 0,
+            finality_delay_secs: 0,
+            bond_lamports: 0,
+            challenger: Pubkey::default(),
+            commission_bps: USE_DEFAULT_COMISSION,
+            min_bet_lamports: 0,
+            max_stake_per_wallet: 0,
+            max_imbalance_ratio: 0,
+            bonus_commission_free: false,
+            gate_mint: Pubkey::default(),
+            gate_min_amount: 0,
+            mint: Pubkey::default(),
+            metadata_hash: [0; METADATA_HASH_BYTES],
+            fixed_odds: false,
+            market_kind: MARKET_KIND_MONEYLINE,
+            handicap: 0,
+            correction_window_secs: DEFAULT_CORRECTION_WINDOW_SECS,
+            commission_recipient: Pubkey::default(),
+            max_bets_per_wallet: 0,
+            arbiter_may_bet: false,
+            correction_cooldown_secs: 0,
+        }
+    }
+}
+
+// Values the admin sets with InitializeConfig and UpdateConfig, packed as their Borsh encoding.
+#[derive(BorshSerialize, BorshSchema, Clone, Copy, Debug, PartialEq)]
+pub struct ConfigParams {
+    pub default_commission_bps: u16,
+    pub max_commission_bps: u16,
+    pub min_deadline_offset_secs: i64,
+    pub arbiter_share_bps: u16,
+    pub referral_bps: u16,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Instruction {
+    // Checks and initializes an empty account.
+    // Accepted accounts:
+    //    [readable, signed] - owner account, signed, mostly to avoid fat finger errors.
+    //    [writable] - bets account, holding rent exemption + bond_lamports
+    //    [readable] - rent sysvar
+    //    [readable] - vault_address(event, mint, token program), only for SPL token events, created beforehand
+    //    [readable] - mint, only for SPL token events
+    //    [readable] - config account, optional
+    //    [writable] - registry page with room left, optional, the event is appended to it
+    // Everything after `bets_accepted_until` is optional in the instruction data,
+    // missing fields get the defaults from EventParams::new.
+    Initialize(EventParams),
+
+    // Adds a bet
+    // Accepted accounts:
+    //    [writable] - betor
+    //    [writable] - bets account
+    //    [writable] - bet info, holding at least `amount` above rent in SOL events
+    // `referrer`, `amount` and `memo` are optional in the instruction data, the referrer can't be the betor.
+    // Neither the arbiter nor the commission recipient can be the betor, unless the event has arbiter_may_bet.
+    // In SOL events exactly `amount` lamports are staked and any surplus goes back to the betor,
+    // 0 stakes all the lamports above rent.
+    // Only for SPL token events, which take `amount` tokens instead of the lamports above rent:
+    //    [writable] - betor's token account, betor signs as its owner
+    //    [writable] - vault
+    //    [readable] - vault authority
+    //    [readable] - mint
+    //    [readable] - token program
+    // Only when the event has gate_mint:
+    //    [readable] - betor's token account of gate_mint
+    // Only when the event is allowlisted, see AddToAllowlist:
+    //    [readable] - allowlist_address(event), the betor must be on it
+    // Only when the event has a denylist, see AddToDenylist:
+    //    [readable] - denylist_address(event), the betor must not be on it
+    // Only when the event has max_stake_per_wallet or max_bets_per_wallet:
+    //    [writable] - limit_address(event, betor), created on the first bet
+    //    [writable, signer] - payer for the limit account
+    //    [readable] - system program
+    // Only to count the betor in unique_bettors, the betor signs and pays for the marker on their first bet:
+    //    [writable] - seen_address(event, betor)
+    //    [readable] - system program
+    // Only to keep the betor's BettorStats, SOL events only:
+    //    [writable] - stats_address(betor), created on first use
+    //    [writable, signer] - payer for the stats account
+    //    [readable] - system program
+    //    [readable] - config account, optional, refused while the program is halted
+    AddBet{
+        choice: MatchOutcome,
+        referrer: Pubkey,
+        amount: u64,
+        memo: [u8; MEMO_BYTES],
+    },
+
+    // Sets a winner. SOL events charge their commission at Withdraw, see CollectCommission, the config
+    // only sets the referrers' share.
+    // Accepted accounts
+    //    [writable, signer] - owner account
+    //    [writable] - bets account
+    //    [readable] - config account, optional
+    //    [writable] - treasury account, required with the config account
+    // SPL token events take instead, and pay the whole commission to the commission recipient:
+    //    [writable] - commission recipient's token account
+    //    [writable] - vault
+    //    [readable] - vault authority
+    //    [readable] - mint
+    //    [readable] - token program
+    // `metadata_hash` is optional in the instruction data, all zeros for none. Given, it must match the
+    // event's metadata_hash, confirming the right event is settled. Events without a hash skip the check.
+    SetWinner{
+        result: MatchOutcome,
+        metadata_hash: [u8; METADATA_HASH_BYTES],
+    },
+
+    // Withdraw your win
+    //    [readable] - betor (no need to be signed, bc. it's ok if someone else decides to withdraw for you)
+    //    (but a bet with a delegate needs the betor's or the delegate's signature, see below)
+    //    [writable] - bets account
+    //    [writable] - bet info
+    // Only for tokenized bets, the betor above is then the token's holder and signs:
+    //    [writable] - holder's token account of the receipt mint
+    //    [writable] - receipt mint
+    //    [readable] - SPL Token program
+    // Only when the bet has a delegate:
+    //    [signer] - betor or delegate
+    // Only on the first claim of a bet with a referrer:
+    //    [writable] - referrer
+    // Only for SPL token events:
+    //    [writable] - betor's token account
+    //    [writable] - vault
+    //    [readable] - vault authority
+    //    [readable] - mint
+    //    [readable] - token program
+    // Only to keep the betor's BettorStats, SOL events only:
+    //    [writable] - stats_address(betor), created by AddBet
+    // Only for SOL events with a leaderboard, see CreateLeaderboard:
+    //    [writable] - leaderboard_address(event)
+    // `amount` is optional in the instruction data, 0 claims all that is left. Claims in parts are paid
+    // in the payout's unit (lamports, or tokens in SPL token events), the bet is Withdrawn once all is claimed.
+    // Referral and, in SPL token events, the lamport shares of bond and bonus go with the first claim.
+    // Refused until the result is final and its correction window closed, or FinalizeResult was sent.
+    Withdraw{
+        amount: u64,
+    },
+
+    // Sets a winner using a result signed off-chain by the arbiter, can be relayed by anyone.
+    // Must be preceded by an ed25519_program instruction verifying `signature` by the arbiter
+    // over result_message(event, result).
+    // Accepted accounts
+    //    [writable] - arbiter account, no need to be signed
+    //    [writable] - bets account
+    //    [readable] - instructions sysvar
+    //    [readable] - config account, optional
+    //    [writable] - treasury account, required with the config account
+    // SPL token events take the same token accounts as SetWinner instead.
+    SetWinnerSigned{
+        result: MatchOutcome,
+        event: Pubkey,
+        signature: [u8; SIGNATURE_BYTES],
+    },
+
+    // Replaces an already set result without moving any lamports. Allowed until correction_window_secs
+    // after SetWinner, or the dispute window if longer, and only while no bet was withdrawn yet.
+    // Accepted accounts
+    //    [readable, signer] - owner account
+    //    [writable] - bets account
+    CorrectResult{
+        result: MatchOutcome,
+    },
+
+    // Slashes the arbiter's bond for misreporting, allowed only during the dispute window.
+    // Accepted accounts
+    //    [readable, signer] - challenger account
+    //    [writable] - bets account
+    SlashBond,
+
+    // Returns the bond to the arbiter once the result is final and can no longer be corrected.
+    // Accepted accounts
+    //    [writable, signer] - owner account
+    //    [writable] - bets account
+    ReleaseBond,
+
+    // Creates the global config and treasury accounts, admin only.
+    // Accepted accounts
+    //    [writable, signer] - admin account, pays for both accounts
+    //    [writable] - config account, config_address()
+    //    [writable] - treasury account, treasury_address()
+    //    [readable] - system program
+    InitializeConfig(ConfigParams),
+
+    // Updates the global config, admin only.
+    // Accepted accounts
+    //    [readable, signer] - admin account
+    //    [writable] - config account
+    UpdateConfig(ConfigParams),
+
+    // Moves accrued commission out of the treasury, admin only.
+    // Accepted accounts
+    //    [readable, signer] - admin account
+    //    [readable] - config account
+    //    [writable] - treasury account
+    //    [writable] - recipient account
+    WithdrawTreasury{
+        amount: u64,
+    },
+
+    // Seeds both pools with house bets owned by the arbiter, so the event has odds
+    // before the first bettor. The arbiter withdraws them with Withdraw like any other bet.
+    // Accepted accounts
+    //    [readable, signer] - owner account
+    //    [writable] - bets account
+    //    [writable] - bet info for TeamA, holding rent exemption + seed
+    //    [writable] - bet info for TeamB, holding rent exemption + seed
+    SeedLiquidity,
+
+    // Adds promotional lamports to the pot, shared pro rata by the winners. Allowed until the result is set.
+    // Accepted accounts
+    //    [writable, signer] - owner account, funds the bonus
+    //    [writable] - bets account
+    //    [readable] - system program
+    TopUpPot{
+        amount: u64,
+    },
+
+    // Checks that the event is internally consistent, fails with the first broken invariant. Changes nothing.
+    // Accepted accounts
+    //    [readable] - bets account
+    AuditEvent,
+
+    // Sets an OddsQuote as return data, meant for simulateTransaction. Changes nothing.
+    // Accepted accounts
+    //    [readable] - bets account
+    QuoteOdds,
+
+    // Sets as return data, little-endian u64, what Withdraw would pay the betor: lamports in SOL events,
+    // tokens in SPL token events. Fails while the result is not set, ignores the finality delay. Changes nothing.
+    // Accepted accounts
+    //    [readable] - bets account
+    //    [readable] - bet account
+    QuotePayout,
+
+    // Adds to an existing bet while bets are accepted, `choice` must be the bet's outcome.
+    // Accepted accounts:
+    //    [writable, signer] - betor
+    //    [writable] - bets account
+    //    [writable] - bet info, holding the lamports to add above its rent exemption
+    // `amount` is optional in the instruction data, 0 adds everything above rent, any surplus goes back to the betor.
+    // Followed by the same accounts as AddBet for SPL token events, gated, allowlisted and denylisted events
+    // and events with max_stake_per_wallet or max_bets_per_wallet, then the optional config account.
+    IncreaseBet{
+        choice: MatchOutcome,
+        amount: u64,
+    },
+
+    // Closes a bet while bets are still accepted, paying cash_out_value with the event's commission as the fee.
+    // The stake leaves its pool, what is not paid out stays in the pot as bonus. SOL events only.
+    // Accepted accounts:
+    //    [writable, signer] - betor
+    //    [writable] - bets account
+    //    [writable] - bet info
+    // Only when the event has max_stake_per_wallet or max_bets_per_wallet, frees the stake and the bet:
+    //    [writable] - limit_address(event, betor)
+    CashOut,
+
+    // Hands a bet that is not withdrawn yet over to `new_betor`, signed by the current betor.
+    // Accepted accounts:
+    //    [signer] - betor
+    //    [readable] - bets account
+    //    [writable] - bet info
+    // Only when the event has max_stake_per_wallet or max_bets_per_wallet, the stake and the bet move
+    // from one allowance to the other:
+    //    [writable] - limit_address(event, betor)
+    //    [writable] - limit_address(event, new_betor), created if needed
+    //    [writable, signer] - payer for the new limit account
+    //    [readable] - system program
+    TransferBet{
+        new_betor: Pubkey,
+    },
+
+    // Puts a bet up for sale at `price` lamports until the result is set, a price of 0 takes it off sale.
+    // Accepted accounts:
+    //    [signer] - betor
+    //    [readable] - bets account
+    //    [writable] - bet info
+    ListBet{
+        price: u64,
+    },
+
+    // Buys a listed bet, `price` must be the current ask. The buyer becomes the betor.
+    // Accepted accounts:
+    //    [writable, signer] - buyer
+    //    [writable] - seller, the current betor
+    //    [readable] - bets account
+    //    [writable] - bet info
+    //    [readable] - system program
+    // Only when the event has max_stake_per_wallet or max_bets_per_wallet, same as TransferBet:
+    //    [writable] - limit_address(event, seller)
+    //    [writable] - limit_address(event, buyer), created if needed
+    //    [writable, signer] - payer for the new limit account
+    //    [readable] - system program
+    BuyBet{
+        price: u64,
+    },
+
+    // Mints a supply-1 SPL token at receipt_mint_address(bet) to the betor's associated token account.
+    // From then on the bet belongs to the token's holder, who burns it on the first Withdraw.
+    // Accepted accounts:
+    //    [writable, signer] - betor, pays for the mint and the token account
+    //    [readable] - bets account
+    //    [writable] - bet info
+    //    [writable] - receipt_mint_address(bet)
+    //    [writable] - betor's associated token account of the receipt mint
+    //    [readable] - rent sysvar
+    //    [readable] - SPL Token program
+    //    [readable] - associated token account program
+    //    [readable] - system program
+    TokenizeBet,
+
+    // Withdraws all that is left of several bets of one betor at once
+    //    [readable] - betor
+    //    [writable] - bets account
+    // Then for every bet:
+    //    [writable] - bet info
+    //    and the same optional accounts Withdraw takes after it
+    // Bets already withdrawn are skipped and take no further accounts. Tested with 25 bets, about as many
+    // as fit in one transaction.
+    WithdrawMany,
+
+    // Pays out bets on their betors' behalf once the result is final, anyone can call it. SOL events only.
+    //    [writable] - bets account
+    // Then pairs of:
+    //    [writable] - bet info
+    //    [writable] - its betor
+    // Entries that are already withdrawn, don't match, are tokenized, are delegated (which needs a signature)
+    // or still owe a referral (which needs the referrer's account) are skipped, so calling it again over the
+    // same bets is harmless.
+    SettleBets,
+
+    // Annuls a single bet before the result is set, its stake goes back to the betor and leaves its pool
+    //    [signer] - arbiter
+    //    [writable] - bets account
+    //    [writable] - bet info
+    //    [writable] - betor
+    // Only for SPL token events:
+    //    [writable] - betor's token account
+    //    [writable] - vault
+    //    [readable] - vault authority
+    //    [readable] - mint
+    //    [readable] - token program
+    // Only when the event has max_stake_per_wallet or max_bets_per_wallet, frees the stake and the bet:
+    //    [writable] - limit_address(event, betor)
+    // `reason` is the arbiter's own code, only logged.
+    VoidBet{
+        reason: u8,
+    },
+
+    // Freezes AddBet, IncreaseBet, SeedLiquidity, CashOut and all withdrawals until UnpauseEvent, only before the
+    // result is set. SetWinner still works so the event can resolve.
+    //    [signer] - arbiter
+    //    [writable] - bets account
+    PauseEvent,
+
+    // Lifts PauseEvent, same accounts
+    UnpauseEvent,
+
+    // Refuses new events and bets program-wide until ResumeProgram. Withdrawals and refunds keep working.
+    //    [signer] - config admin
+    //    [writable] - config account
+    HaltProgram,
+
+    // Lifts HaltProgram, same accounts
+    ResumeProgram,
+
+    // Creates the registry page `page`, only once the previous one is full
+    //    [writable, signer] - payer
+    //    [writable] - registry_address(page)
+    //    [readable] - system program
+    //    [readable] - registry_address(page - 1), unless page is 0
+    CreateRegistryPage{
+        page: u32,
+    },
+
+    // Same as Initialize, creating the event at event_address(arbiter, index) where index is the
+    // arbiter's next one
+    //    [writable, signer] - arbiter, pays for the event with its bond and for the counter
+    //    [writable] - arbiter_counter_address(arbiter), created on the first event
+    //    [writable] - event_address(arbiter, index)
+    //    [readable] - system program
+    //    [readable] - rent sysvar
+    //    and the optional accounts of Initialize after it
+    InitializeIndexed(EventParams),
+
+    // Same as InitializeIndexed, the event living at seeded_event_address(arbiter, seed) instead.
+    // `seed` is 1 to EVENT_SEED_BYTES of ASCII letters, digits, '-' and '_', and is stored in the event.
+    //    [writable, signer] - arbiter, pays for the event with its bond
+    //    [writable] - seeded_event_address(arbiter, seed)
+    //    [readable] - system program
+    //    [readable] - rent sysvar
+    //    and the optional accounts of Initialize after it
+    InitializeSeeded{
+        seed: String,
+        params: EventParams,
+    },
+
+    // Initializes an empty account of Market::LEN as a market of `num_outcomes` (2 to MAX_OUTCOMES)
+    //    [signer] - arbiter
+    //    [writable] - market account, rent exempt
+    InitializeMarket{
+        bets_accepted_until: UnixTimestamp,
+        num_outcomes: u8,
+        commission_bps: u16,
+    },
+
+    // Bets the lamports above rent of the bet account on outcome `outcome` of a market
+    //    [readable] - betor
+    //    [writable] - market account
+    //    [writable] - bet info
+    AddMarketBet{
+        outcome: u8,
+    },
+
+    // Settles a market once bets are closed, the commission goes to the arbiter
+    //    [writable, signer] - arbiter
+    //    [writable] - market account
+    SetMarketWinner{
+        winner: u8,
+    },
+
+    // Pays a market bet out, see calculate_market_payout
+    //    [writable] - betor
+    //    [writable] - market account
+    //    [writable] - bet info
+    WithdrawMarketBet,
+
+    // Posts the odds of a fixed-odds event while bets are open, bets placed from then on are paid at them.
+    // Each side's odds must be at least 1, or 0/0 to stop taking bets on it. Refused while the
+    // worst case liability is above the stakes plus the top-ups.
+    //    [signer] - arbiter
+    //    [writable] - bets account
+    SetOdds{
+        odds_a: Odds,
+        odds_b: Odds,
+    },
+
+    // Creates the parlay pool, once
+    //    [writable, signer] - payer
+    //    [writable] - parlay_pool_address()
+    //    [readable] - system program
+    CreateParlayPool,
+
+    // Initializes an empty account of Parlay::LEN as a parlay picking `choices[i]` (TeamA or TeamB)
+    // on the i-th event, and escrows the lamports above rent of the account in the parlay pool.
+    // Parimutuel SOL events open for bets only, each at most once.
+    //    [readable] - betor
+    //    [writable] - parlay account
+    //    [writable] - parlay pool
+    //    [readable] - one bets account per choice, in order
+    PlaceParlay{
+        choices: Vec<MatchOutcome>,
+    },
+
+    // Pays a parlay out of the pool once every leg's result is final, see calculate_parlay_payout.
+    // Fails while the pool can't pay it.
+    //    [writable] - betor
+    //    [writable] - parlay account
+    //    [writable] - parlay pool
+    //    [readable] - the legs' bets accounts, in order
+    SettleParlay,
+
+    // Creates a tournament, `slug` following the InitializeSeeded seed rules and `name` being
+    // up to TOURNAMENT_NAME_BYTES of UTF-8
+    //    [writable, signer] - arbiter, pays for the tournament
+    //    [writable] - tournament_address(arbiter, slug)
+    //    [readable] - system program
+    CreateTournament{
+        slug: String,
+        name: String,
+    },
+
+    // Links an event to a tournament, both the arbiter's own. Once per event.
+    //    [signer] - arbiter
+    //    [writable] - tournament
+    //    [writable] - bets account
+    AddEventToTournament,
+
+    // Moves an event and its bets from an older layout to STATE_VERSION, in place since the accounts
+    // keep their size. Accounts already current are left as they are, so the bets can be passed in
+    // several transactions. The event may also be a market, whose bets are the only accounts migrated.
+    //    [signer] - arbiter
+    //    [writable] - bets account or market
+    //    [writable] - the event's bet accounts, any number
+    MigrateEvent,
+
+    // Sends what is left in a settled event above its rent exemption, a bond that is still the arbiter's
+    // to release and the commission to collect, to the arbiter. That is the rounding dust of the payouts once every bet
+    // was withdrawn, voided or cashed out, fails with BetsOutstanding before. Lamports only, the vault
+    // of an SPL token event keeps its token dust.
+    //    [writable, signer] - arbiter
+    //    [writable] - bets account
+    // Only for a slashed bond when no bet backed the result:
+    //    [writable] - challenger, receives the bond
+    SweepDust,
+
+    // Wipes a bet that was withdrawn, cashed out or voided, so that its account can be placed again with
+    // AddBet, on any event. Its old event doesn't count it any more, see EventBets::outstanding_bets.
+    // Fund the account with the new stake and put AddBet right after it in the same transaction.
+    //    [signer] - the bet's betor
+    //    [writable] - bet account
+    ResetBet,
+
+    // Places a bet paid for by `funder` on behalf of `beneficiary`, e.g. a gift, who doesn't sign and is the
+    // bet's betor: only they can withdraw, transfer or cash it out. Otherwise the same as AddBet without a
+    // referrer or memo, the beneficiary passes the token gate and is held to the stake cap. A surplus over
+    // `amount` goes back to the funder.
+    // Accepted accounts:
+    //    [writable, signer] - funder
+    //    [writable] - bets account
+    //    [writable] - bet info, holding the stake above rent in SOL events
+    // Then the optional accounts of AddBet, with the funder's token account in SPL token events and
+    // the beneficiary's gate token account, the allowlist and denylist, limit_address(event, beneficiary),
+    // seen_address(event, beneficiary), paid for by the funder, and stats_address(beneficiary).
+    AddBetFor{
+        choice: MatchOutcome,
+        beneficiary: Pubkey,
+        amount: u64,
+    },
+
+    // Lets `delegate` sign Withdraw for the bet, which still pays the betor. The default pubkey clears it.
+    // Handing the bet over with TransferBet, BuyBet or TokenizeBet clears it as well.
+    // Accepted accounts:
+    //    [signer] - betor
+    //    [readable] - bets account
+    //    [writable] - bet info
+    SetDelegate{
+        delegate: Pubkey,
+    },
+
+    // Pays a SOL event's commission_accrued, the commission Withdraw charged so far, to its commission
+    // recipient, split with the treasury when the config is passed. Once the result is final, as often
+    // as the arbiter likes.
+    //    [signer] - arbiter
+    //    [writable] - bets account
+    //    [writable] - commission recipient, the arbiter unless Initialize named another key
+    //    [readable] - config account, optional
+    //    [writable] - treasury account, required with the config account
+    CollectCommission,
+
+    // Marks the result final once it can't be corrected any more, anyone may call it. Claims wait for
+    // the same moment, and the first one finalizes the result if nobody sent FinalizeResult.
+    //    [writable] - bets account
+    FinalizeResult,
+
+    // Creates the event's leaderboard of its LEADERBOARD_SIZE biggest winners, kept by the Withdraw that
+    // pass it. Best sent with Initialize, withdrawals made before it are not on it.
+    //    [writable, signer] - arbiter, pays for the leaderboard
+    //    [readable] - bets account
+    //    [writable] - leaderboard_address(event)
+    //    [readable] - system program
+    CreateLeaderboard,
+
+    // Adds `wallet` to the event's allowlist, creating it on the first call. From then on only wallets on
+    // it can bet, at most WALLET_LIST_CAPACITY of them. Adding a wallet already on it changes nothing.
+    //    [writable, signer] - arbiter, pays for the allowlist
+    //    [writable] - bets account
+    //    [writable] - allowlist_address(event)
+    //    [readable] - system program
+    AddToAllowlist{
+        wallet: Pubkey,
+    },
+
+    // Takes `wallet` off the event's allowlist, their bets placed so far stay. The event stays private
+    // even with an empty allowlist.
+    //    [signer] - arbiter
+    //    [readable] - bets account
+    //    [writable] - allowlist_address(event)
+    RemoveFromAllowlist{
+        wallet: Pubkey,
+    },
+
+    // Adds `wallet` to the event's denylist, creating it on the first call. Wallets on it can't place new
+    // bets, their bets placed so far are withdrawn as usual. At most WALLET_LIST_CAPACITY of them, adding
+    // a wallet already on it changes nothing.
+    //    [writable, signer] - arbiter, pays for the denylist
+    //    [writable] - bets account
+    //    [writable] - denylist_address(event)
+    //    [readable] - system program
+    AddToDenylist{
+        wallet: Pubkey,
+    },
+
+    // Takes `wallet` off the event's denylist, a wallet that is not on it changes nothing.
+    //    [signer] - arbiter
+    //    [readable] - bets account
+    //    [writable] - denylist_address(event)
+    RemoveFromDenylist{
+        wallet: Pubkey,
+    },
+}
+
+impl Instruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        use std::convert::TryInto;
+        use ProgramError::InvalidInstructionData;
+        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
+        Ok(match tag {
+            0 => Self::Initialize(unpack_event_params(rest)?),
+            1 => {
+                let (&choice, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let (referrer, rest) = unpack_optional_pubkey(rest)?;
+                let (amount, rest) = unpack_optional_u64(rest, 0)?;
+                let (memo, _rest) = unpack_optional::<MEMO_BYTES>(rest)?;
+                Self::AddBet { choice: result_outcome(choice)?, referrer, amount, memo: memo.unwrap_or([0; MEMO_BYTES]) }
+            },
+            2 => {
+                let (&result, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let (metadata_hash, _rest) = unpack_optional::<METADATA_HASH_BYTES>(rest)?;
+                Self::SetWinner { result: result_outcome(result)?, metadata_hash: metadata_hash.unwrap_or([0; METADATA_HASH_BYTES]) }
+            },
+            3 => {
+                let (amount, _rest) = unpack_optional_u64(rest, 0)?;
+                Self::Withdraw { amount }
+            },
+            4 => {
+                let (&result, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let event = rest
+                    .get(..PUBKEY_BYTES)
+                    .map(Pubkey::new)
+                    .ok_or(InvalidInstructionData)?;
+                let signature = rest
+                    .get(PUBKEY_BYTES..PUBKEY_BYTES + SIGNATURE_BYTES)
+                    .and_then(|slice| slice.try_into().ok())
+                    .ok_or(InvalidInstructionData)?;
+                Self::SetWinnerSigned { result: result_outcome(result)?, event, signature }
+            },
+            5 => {
+                let (&result, _rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                Self::CorrectResult { result: result_outcome(result)? }
+            },
+            6 => Self::SlashBond,
+            7 => Self::ReleaseBond,
+            8 => Self::InitializeConfig(unpack_config_params(rest)?),
+            9 => Self::UpdateConfig(unpack_config_params(rest)?),
+            10 => {
+                let amount = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstructionData)?;
+                Self::WithdrawTreasury { amount }
+            },
+            11 => Self::SeedLiquidity,
+            12 => {
+                let amount = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstructionData)?;
+                Self::TopUpPot { amount }
+            },
+            13 => Self::AuditEvent,
+            14 => Self::QuoteOdds,
+            15 => Self::QuotePayout,
+            16 => {
+                let (&choice, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let (amount, _rest) = unpack_optional_u64(rest, 0)?;
+                Self::IncreaseBet { choice: result_outcome(choice)?, amount }
+            },
+            17 => Self::CashOut,
+            18 => {
+                let new_betor = rest
+                    .get(..PUBKEY_BYTES)
+                    .map(Pubkey::new)
+                    .ok_or(InvalidInstructionData)?;
+                Self::TransferBet { new_betor }
+            },
+            19 | 20 => {
+                let price = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstructionData)?;
+                if tag == 19 { Self::ListBet { price } } else { Self::BuyBet { price } }
+            },
+            21 => Self::TokenizeBet,
+            22 => Self::WithdrawMany,
+            23 => Self::SettleBets,
+            25 => Self::PauseEvent,
+            26 => Self::UnpauseEvent,
+            27 => Self::HaltProgram,
+            28 => Self::ResumeProgram,
+            29 => {
+                let page = rest
+                    .get(..4)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u32::from_le_bytes)
+                    .ok_or(InvalidInstructionData)?;
+                Self::CreateRegistryPage { page }
+            },
+            30 => Self::InitializeIndexed(unpack_event_params(rest)?),
+            31 => {
+                let (seed, rest) = unpack_string(rest)?;
+                Self::InitializeSeeded { seed, params: unpack_event_params(rest)? }
+            },
+            32 => {
+                let bets_accepted_until = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(UnixTimestamp::from_le_bytes)
+                    .ok_or(InvalidInstructionData)?;
+                let num_outcomes = *rest.get(8).ok_or(InvalidInstructionData)?;
+                let commission_bps = rest
+                    .get(9..11)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstructionData)?;
+                Self::InitializeMarket { bets_accepted_until, num_outcomes, commission_bps }
+            },
+            33 => Self::AddMarketBet { outcome: *rest.first().ok_or(InvalidInstructionData)? },
+            34 => Self::SetMarketWinner { winner: *rest.first().ok_or(InvalidInstructionData)? },
+            35 => Self::WithdrawMarketBet,
+            36 => {
+                let odds = |offset: usize| -> Result<Odds, ProgramError> {
+                    let value = |start: usize| rest
+                        .get(start..start + 4)
+                        .and_then(|slice| slice.try_into().ok())
+                        .map(u32::from_le_bytes)
+                        .ok_or(InvalidInstructionData);
+                    Ok(Odds { numerator: value(offset)?, denominator: value(offset + 4)? })
+                };
+                Self::SetOdds { odds_a: odds(0)?, odds_b: odds(Odds::LEN)? }
+            },
+            37 => Self::CreateParlayPool,
+            38 => {
+                let (&len, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let choices = rest
+                    .get(..len as usize)
+                    .ok_or(InvalidInstructionData)?
+                    .iter()
+                    .map(|&choice| result_outcome(choice))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Self::PlaceParlay { choices }
+            },
+            39 => Self::SettleParlay,
+            40 => {
+                let (slug, rest) = unpack_string(rest)?;
+                let (name, _rest) = unpack_string(rest)?;
+                Self::CreateTournament { slug, name }
+            },
+            41 => Self::AddEventToTournament,
+            42 => Self::MigrateEvent,
+            43 => Self::SweepDust,
+            44 => Self::ResetBet,
+            45 => {
+                let (&choice, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
+                let beneficiary = rest
+                    .get(..PUBKEY_BYTES)
+                    .map(Pubkey::new)
+                    .ok_or(InvalidInstructionData)?;
+                let (amount, _rest) = unpack_optional_u64(&rest[PUBKEY_BYTES..], 0)?;
+                Self::AddBetFor { choice: result_outcome(choice)?, beneficiary, amount }
+            },
+            46 => {
+                let delegate = rest
+                    .get(..PUBKEY_BYTES)
+                    .map(Pubkey::new)
+                    .ok_or(InvalidInstructionData)?;
+                Self::SetDelegate { delegate }
+            },
+            47 => Self::CollectCommission,
+            48 => Self::FinalizeResult,
+            49 => Self::CreateLeaderboard,
+            50..=53 => {
+                let wallet = rest
+                    .get(..PUBKEY_BYTES)
+                    .map(Pubkey::new)
+                    .ok_or(InvalidInstructionData)?;
+                match tag {
+                    50 => Self::AddToAllowlist { wallet },
+                    51 => Self::RemoveFromAllowlist { wallet },
+                    52 => Self::AddToDenylist { wallet },
+                    _ => Self::RemoveFromDenylist { wallet },
+                }
+            },
+            24 => {
+                let &reason = rest.first().ok_or(InvalidInstructionData)?;
+                Self::VoidBet { reason }
+            },
+            _ => return Err(InvalidInstructionData),
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::Initialize(params) => {
+                buf.push(0);
+                pack_event_params(params, &mut buf);
+            },
+            Self::AddBet { choice, referrer, amount, memo } => {
+                buf.push(1);
+                buf.push(u8::from(*choice));
+                buf.extend_from_slice(referrer.as_ref());
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(memo);
+            },
+            Self::SetWinner { result, metadata_hash } => {
+                buf.push(2);
+                buf.push(u8::from(*result));
+                buf.extend_from_slice(metadata_hash);
+            },
+            Self::Withdraw { amount } => {
+                buf.push(3);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            },
+            Self::SetWinnerSigned { result, event, signature } => {
+                buf.push(4);
+                buf.push(u8::from(*result));
+                buf.extend_from_slice(event.as_ref());
+                buf.extend_from_slice(signature);
+            },
+            Self::CorrectResult { result } => {
+                buf.push(5);
+                buf.push(u8::from(*result));
+            },
+            Self::SlashBond => buf.push(6),
+            Self::ReleaseBond => buf.push(7),
+            Self::InitializeConfig(params) => {
+                buf.push(8);
+                pack_config_params(params, &mut buf);
+            },
+            Self::UpdateConfig(params) => {
+                buf.push(9);
+                pack_config_params(params, &mut buf);
+            },
+            Self::WithdrawTreasury { amount } => {
+                buf.push(10);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            },
+            Self::SeedLiquidity => buf.push(11),
+            Self::TopUpPot { amount } => {
+                buf.push(12);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            },
+            Self::AuditEvent => buf.push(13),
+            Self::QuoteOdds => buf.push(14),
+            Self::QuotePayout => buf.push(15),
+            Self::IncreaseBet { choice, amount } => {
+                buf.push(16);
+                buf.push(u8::from(*choice));
+                buf.extend_from_slice(&amount.to_le_bytes());
+            },
+            Self::CashOut => buf.push(17),
+            Self::TransferBet { new_betor } => {
+                buf.push(18);
+                buf.extend_from_slice(new_betor.as_ref());
+            },
+            Self::ListBet { price } => {
+                buf.push(19);
+                buf.extend_from_slice(&price.to_le_bytes());
+            },
+            Self::BuyBet { price } => {
+                buf.push(20);
+                buf.extend_from_slice(&price.to_le_bytes());
+            },
+            Self::TokenizeBet => buf.push(21),
+            Self::WithdrawMany => buf.push(22),
+            Self::SettleBets => buf.push(23),
+            Self::VoidBet { reason } => {
+                buf.push(24);
+                buf.push(*reason);
+            },
+            Self::PauseEvent => buf.push(25),
+            Self::UnpauseEvent => buf.push(26),
+            Self::HaltProgram => buf.push(27),
+            Self::ResumeProgram => buf.push(28),
+            Self::CreateRegistryPage { page } => {
+                buf.push(29);
+                buf.extend_from_slice(&page.to_le_bytes());
+            },
+            Self::InitializeIndexed(params) => {
+                buf.push(30);
+                pack_event_params(params, &mut buf);
+            },
+            Self::InitializeSeeded { seed, params } => {
+                buf.push(31);
+                buf.push(seed.len() as u8);
+                buf.extend_from_slice(seed.as_bytes());
+                pack_event_params(params, &mut buf);
+            },
+            Self::InitializeMarket { bets_accepted_until, num_outcomes, commission_bps } => {
+                buf.push(32);
+                buf.extend_from_slice(&bets_accepted_until.to_le_bytes());
+                buf.push(*num_outcomes);
+                buf.extend_from_slice(&commission_bps.to_le_bytes());
+            },
+            Self::AddMarketBet { outcome } => {
+                buf.push(33);
+                buf.push(*outcome);
+            },
+            Self::SetMarketWinner { winner } => {
+                buf.push(34);
+                buf.push(*winner);
+            },
+            Self::WithdrawMarketBet => buf.push(35),
+            Self::SetOdds { odds_a, odds_b } => {
+                buf.push(36);
+                for odds in &[odds_a, odds_b] {
+                    buf.extend_from_slice(&odds.numerator.to_le_bytes());
+                    buf.extend_from_slice(&odds.denominator.to_le_bytes());
+                }
+            },
+            Self::CreateParlayPool => buf.push(37),
+            Self::PlaceParlay { choices } => {
+                buf.push(38);
+                buf.push(choices.len() as u8);
+                buf.extend(choices.iter().map(|&choice| u8::from(choice)));
+            },
+            Self::SettleParlay => buf.push(39),
+            Self::CreateTournament { slug, name } => {
+                buf.push(40);
+                for string in &[slug, name] {
+                    buf.push(string.len() as u8);
+                    buf.extend_from_slice(string.as_bytes());
+                }
+            },
+            Self::AddEventToTournament => buf.push(41),
+            Self::MigrateEvent => buf.push(42),
+            Self::SweepDust => buf.push(43),
+            Self::ResetBet => buf.push(44),
+            Self::AddBetFor { choice, beneficiary, amount } => {
+                buf.push(45);
+                buf.push(u8::from(*choice));
+                buf.extend_from_slice(beneficiary.as_ref());
+                buf.extend_from_slice(&amount.to_le_bytes());
+            },
+            Self::SetDelegate { delegate } => {
+                buf.push(46);
+                buf.extend_from_slice(delegate.as_ref());
+            },
+            Self::CollectCommission => buf.push(47),
+            Self::FinalizeResult => buf.push(48),
+            Self::CreateLeaderboard => buf.push(49),
+            Self::AddToAllowlist { wallet } => {
+                buf.push(50);
+                buf.extend_from_slice(wallet.as_ref());
+            },
+            Self::RemoveFromAllowlist { wallet } => {
+                buf.push(51);
+                buf.extend_from_slice(wallet.as_ref());
+            },
+            Self::AddToDenylist { wallet } => {
+                buf.push(52);
+                buf.extend_from_slice(wallet.as_ref());
+            },
+            Self::RemoveFromDenylist { wallet } => {
+                buf.push(53);
+                buf.extend_from_slice(wallet.as_ref());
+            },
+        }
+        buf
+    }
+}
+
+fn unpack_config_params(input: &[u8]) -> Result<ConfigParams, ProgramError> {
+    use std::convert::TryInto;
+    use ProgramError::InvalidInstructionData;
+    let read_u16 = |at: usize| {
+        input
+            .get(at..at + 2)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u16::from_le_bytes)
+            .ok_or(InvalidInstructionData)
+    };
+    let default_commission_bps = read_u16(0)?;
+    let max_commission_bps = read_u16(2)?;
+    let min_deadline_offset_secs = input
+        .get(4..12)
+        .and_then(|slice| slice.try_into().ok())
+        .map(i64::from_le_bytes)
+        .ok_or(InvalidInstructionData)?;
+    let arbiter_share_bps = read_u16(12)?;
+    let (referral_bps, _rest) = unpack_optional_u16(&input[14..], 0)?;
+    Ok(ConfigParams { default_commission_bps, max_commission_bps, min_deadline_offset_secs, arbiter_share_bps, referral_bps })
+}
+
+fn pack_config_params(params: &ConfigParams, buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&params.default_commission_bps.to_le_bytes());
+    buf.extend_from_slice(&params.max_commission_bps.to_le_bytes());
+    buf.extend_from_slice(&params.min_deadline_offset_secs.to_le_bytes());
+    buf.extend_from_slice(&params.arbiter_share_bps.to_le_bytes());
+    buf.extend_from_slice(&params.referral_bps.to_le_bytes());
+}
+
+// Reads an optional trailing field, older clients simply don't send it.
+fn unpack_optional<const N: usize>(input: &[u8]) -> Result<(Option<[u8; N]>, &[u8]), ProgramError> {
+    use std::convert::TryInto;
+    if input.is_empty() {
+        return Ok((None, input));
+    }
+    let value = input
+        .get(..N)
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    Ok((Some(value), &input[N..]))
+}
+
+fn unpack_optional_i64(input: &[u8], default: i64) -> Result<(i64, &[u8]), ProgramError> {
+    let (value, rest) = unpack_optional::<8>(input)?;
+    Ok((value.map(i64::from_le_bytes).unwrap_or(default), rest))
+}
+
+fn unpack_optional_u64(input: &[u8], default: u64) -> Result<(u64, &[u8]), ProgramError> {
+    let (value, rest) = unpack_optional::<8>(input)?;
+    Ok((value.map(u64::from_le_bytes).unwrap_or(default), rest))
+}
+
+fn unpack_optional_u16(input: &[u8], default: u16) -> Result<(u16, &[u8]), ProgramError> {
+    let (value, rest) = unpack_optional::<2>(input)?;
+    Ok((value.map(u16::from_le_bytes).unwrap_or(default), rest))
+}
+
+fn unpack_optional_bool(input: &[u8], default: bool) -> Result<(bool, &[u8]), ProgramError> {
+    let (value, rest) = unpack_optional::<1>(input)?;
+    let value = match value {
+        None => default,
+        Some([0]) => false,
+        Some([1]) => true,
+        Some(_) => return Err(ProgramError::InvalidInstructionData),
+    };
+    Ok((value, rest))
+}
+
+fn unpack_optional_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+    let (value, rest) = unpack_optional::<PUBKEY_BYTES>(input)?;
+    Ok((value.map(Pubkey::new_from_array).unwrap_or_default(), rest))
+}
+
+// A length byte followed by that many bytes of UTF-8.
+fn unpack_string(input: &[u8]) -> Result<(String, &[u8]), ProgramError> {
+    let (&len, rest) = input.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+    let bytes = rest.get(..len as usize).ok_or(ProgramError::InvalidInstructionData)?;
+    let string = String::from_utf8(bytes.to_vec()).map_err(|_| ProgramError::InvalidInstructionData)?;
+    Ok((string, &rest[len as usize..]))
+}
+
+fn unpack_event_params(rest: &[u8]) -> Result<EventParams, ProgramError> {
+    use std::convert::TryInto;
+    use ProgramError::InvalidInstructionData;
+    let bets_accepted_until = rest
+        .get(..8)
+        .and_then(|slice| slice.try_into().ok())
+        .map(UnixTimestamp::from_le_bytes)
+        .ok_or(InvalidInstructionData)?;
+    let defaults = EventParams::new(bets_accepted_until);
+    let (dispute_window_secs, rest) = unpack_optional_i64(&rest[8..], defaults.dispute_window_secs)?;
+    let (finality_delay_secs, rest) = unpack_optional_i64(rest, defaults.finality_delay_secs)?;
+    let (bond_lamports, rest) = unpack_optional_u64(rest, defaults.bond_lamports)?;
+    let (challenger, rest) = unpack_optional_pubkey(rest)?;
+    let (commission_bps, rest) = unpack_optional_u16(rest, defaults.commission_bps)?;
+    let (min_bet_lamports, rest) = unpack_optional_u64(rest, defaults.min_bet_lamports)?;
+    let (max_stake_per_wallet, rest) = unpack_optional_u64(rest, defaults.max_stake_per_wallet)?;
+    let (max_imbalance_ratio, rest) = unpack_optional_u16(rest, defaults.max_imbalance_ratio)?;
+    let (bonus_commission_free, rest) = unpack_optional_bool(rest, defaults.bonus_commission_free)?;
+    let (gate_mint, rest) = unpack_optional_pubkey(rest)?;
+    let (gate_min_amount, rest) = unpack_optional_u64(rest, defaults.gate_min_amount)?;
+    let (mint, rest) = unpack_optional_pubkey(rest)?;
+    let (metadata_hash, rest) = unpack_optional::<METADATA_HASH_BYTES>(rest)?;
+    let (fixed_odds, rest) = unpack_optional_bool(rest, defaults.fixed_odds)?;
+    let (market_kind, rest) = unpack_optional::<1>(rest)?;
+    let (handicap, rest) = unpack_optional::<2>(rest)?;
+    let (correction_window_secs, rest) = unpack_optional_i64(rest, defaults.correction_window_secs)?;
+    let (commission_recipient, rest) = unpack_optional_pubkey(rest)?;
+    let (max_bets_per_wallet, rest) = unpack_optional::<1>(rest)?;
+    let (arbiter_may_bet, rest) = unpack_optional_bool(rest, defaults.arbiter_may_bet)?;
+    let (correction_cooldown_secs, _rest) = unpack_optional_i64(rest, defaults.correction_cooldown_secs)?;
+    Ok(EventParams {
+        bets_accepted_until,
+        dispute_window_secs,
+        finality_delay_secs,
+        bond_lamports,
+        challenger,
+        commission_bps,
+        min_bet_lamports,
+        max_stake_per_wallet,
+        max_imbalance_ratio,
+        bonus_commission_free,
+        gate_mint,
+        gate_min_amount,
+        mint,
+        metadata_hash: metadata_hash.unwrap_or(defaults.metadata_hash),
+        fixed_odds,
+        market_kind: market_kind.map_or(defaults.market_kind, |[kind]| kind),
+        handicap: handicap.map(i16::from_le_bytes).unwrap_or(defaults.handicap),
+        correction_window_secs,
+        commission_recipient,
+        max_bets_per_wallet: max_bets_per_wallet.map_or(defaults.max_bets_per_wallet, |[max]| max),
+        arbiter_may_bet,
+        correction_cooldown_secs,
+    })
+}
+
+fn pack_event_params(params: &EventParams, buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&params.bets_accepted_until.to_le_bytes());
+    buf.extend_from_slice(&params.dispute_window_secs.to_le_bytes());
+    buf.extend_from_slice(&params.finality_delay_secs.to_le_bytes());
+    buf.extend_from_slice(&params.bond_lamports.to_le_bytes());
+    buf.extend_from_slice(params.challenger.as_ref());
+    buf.extend_from_slice(&params.commission_bps.to_le_bytes());
+    buf.extend_from_slice(&params.min_bet_lamports.to_le_bytes());
+    buf.extend_from_slice(&params.max_stake_per_wallet.to_le_bytes());
+    buf.extend_from_slice(&params.max_imbalance_ratio.to_le_bytes());
+    buf.push(params.bonus_commission_free as u8);
+    buf.extend_from_slice(params.gate_mint.as_ref());
+    buf.extend_from_slice(&params.gate_min_amount.to_le_bytes());
+    buf.extend_from_slice(params.mint.as_ref());
+    buf.extend_from_slice(&params.metadata_hash);
+    buf.push(params.fixed_odds as u8);
+    buf.push(params.market_kind);
+    buf.extend_from_slice(&params.handicap.to_le_bytes());
+    buf.extend_from_slice(&params.correction_window_secs.to_le_bytes());
+    buf.extend_from_slice(params.commission_recipient.as_ref());
+    buf.push(params.max_bets_per_wallet);
+    buf.push(params.arbiter_may_bet as u8);
+    buf.extend_from_slice(&params.correction_cooldown_secs.to_le_bytes());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ProgramError::{InvalidAccountData, InvalidInstructionData};
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn test_every_tag_round_trips() {
+        let instructions = crate::test_utils::every_instruction();
+        let tags: Vec<u8> = instructions.iter().map(|instruction| instruction.pack()[0]).collect();
+        assert_eq!(tags, (0..=53).collect::<Vec<u8>>());
+        for instruction in instructions {
+            assert_eq!(Instruction::unpack(&instruction.pack()), Ok(instruction));
+        }
+    }
+
+    #[test]
+    fn test_short_add_bet_and_withdraw_use_defaults() {
+        assert_eq!(
+            Instruction::unpack(&[1, 2]),
+            Ok(Instruction::AddBet { choice: MatchOutcome::TeamB, referrer: Pubkey::default(), amount: 0, memo: [0; MEMO_BYTES] })
+        );
+        assert_eq!(Instruction::unpack(&[3]), Ok(Instruction::Withdraw { amount: 0 }));
+    }
+
+    #[test]
+    fn test_malformed_data_is_refused() {
+        let cases: Vec<Vec<u8>> = vec![
+            vec![],
+            vec![1],
+            vec![1, 1, 0, 0, 0],
+            [&[1, 1][..], &[0; PUBKEY_BYTES], &[0; 3]].concat(),
+            vec![2],
+            [&[2, 1][..], &[0; METADATA_HASH_BYTES - 1]].concat(),
+            vec![3, 1, 2],
+            vec![4, 1],
+            [&[4, 1][..], &[0; PUBKEY_BYTES], &[0; 10]].concat(),
+            vec![8, 0],
+            vec![10, 1, 2, 3],
+            vec![12],
+            [&[18][..], &[0; PUBKEY_BYTES - 1]].concat(),
+            vec![19, 1],
+            vec![20],
+            vec![24],
+            vec![29, 0, 0],
+            vec![31],
+            vec![32, 0, 0, 0, 0, 0, 0, 0, 0],
+            vec![33],
+            vec![34],
+            vec![36, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            vec![38, 2, 1],
+            vec![40, 3, b'a', b'b'],
+            vec![40, 1, 0xff, 0],
+            vec![45, 1],
+            [&[45, 1][..], &[0; PUBKEY_BYTES - 1]].concat(),
+            [&[45, 1][..], &[0; PUBKEY_BYTES], &[0; 3]].concat(),
+            [&[46][..], &[0; PUBKEY_BYTES - 1]].concat(),
+        ];
+        for data in cases {
+            assert_eq!(Instruction::unpack(&data), Err(InvalidInstructionData), "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn test_unknown_tags_are_refused() {
+        for tag in 54..=u8::MAX {
+            assert_eq!(Instruction::unpack(&[tag]), Err(InvalidInstructionData));
+            assert_eq!(Instruction::unpack(&[tag, 1, 2, 3]), Err(InvalidInstructionData));
+        }
+    }
+
+    #[test]
+    fn test_outcome_bytes_are_checked() {
+        // Not an outcome, or a closed bet's.
+        for data in &[[2, 4], [2, 255], [1, 254], [5, 9], [16, 200]] {
+            assert_eq!(Instruction::unpack(data), Err(InvalidAccountData), "{:?}", data);
+        }
+        assert_eq!(Instruction::unpack(&[38, 1, 255]), Err(InvalidAccountData));
+    }
+}
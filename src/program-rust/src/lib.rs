@@ -1,465 +1,251 @@
 use solana_program::{
-    account_info::{next_account_info, AccountInfo},
-    entrypoint,
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
+    program_memory::sol_memcmp,
     pubkey::{Pubkey, PUBKEY_BYTES},
-    clock::{UnixTimestamp, Clock},
-    program_memory::{sol_memcmp},
-    sysvar::{rent::Rent, Sysvar},
 };
-use borsh::{BorshDeserialize, BorshSerialize};
 
-const COMISSION: u8 = 3;
-
-
-use borsh::maybestd::{
-    io::{Error, ErrorKind, Result as BorshResult, Write},
-};
-
-#[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum MatchOutcome {
-    Unknown,
-    TeamA,
-    TeamB,
-    Draw,
-    Withdrawn,
+// msg! for progress logs, compiled out without the default verbose-logs feature. Formatting a pubkey
+// costs thousands of compute units on-chain, error paths keep their plain msg!.
+macro_rules! verbose_msg {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "verbose-logs") {
+            msg!($($arg)*);
+        }
+    };
 }
 
-impl Default for MatchOutcome {
-    fn default() -> Self {
-        MatchOutcome::Unknown
+// Instruction builders for off-chain code
+pub mod client;
+// Program entrypoint, process_instruction
+pub mod entrypoint;
+// BetError, the program's custom errors
+pub mod error;
+// Structured events logged for indexers
+pub mod events;
+// JSON IDL for client codegen
+#[cfg(feature = "idl")]
+pub mod idl;
+// Instruction and its wire format
+pub mod instruction;
+// Payout math on plain state
+pub mod math;
+// Zero-copy views of EventBets and Bet
+pub mod pod;
+// Processor and the instruction handlers
+pub mod processor;
+// Borsh schemas of the state and the instructions
+pub mod schema;
+// EventBets, Bet, MatchOutcome and the other accounts' layouts
+pub mod state;
+// Event and bet accounts for tests
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+
+pub use entrypoint::process_instruction;
+pub use error::BetError;
+pub use instruction::*;
+pub use math::{calculate_market_payout, calculate_parlay_payout, calculate_payout, cash_out_value, settlement_commission};
+pub use processor::{quote_payout, referral_of, split_commission, Processor};
+pub use state::*;
+
+// Default commission in basis points, for events initialized without an explicit one.
+const COMISSION_BPS: u16 = 300;
+const MAX_COMISSION_BPS: u16 = 1_000;
+// EventParams::commission_bps value asking for the default commission.
+pub const USE_DEFAULT_COMISSION: u16 = u16::MAX;
+
+// Address the program is deployed at, process_instruction refuses to run under any other one
+// unless built with the any-id feature.
+solana_program::declare_id!("3qVLgui3qavneXQ9ijH6ksoCZjDKKtDeRDonBpRZYVEp");
+
+pub fn check_program_account(program_id: &Pubkey) -> ProgramResult {
+    if !cmp_pubkeys(program_id, &id()) {
+        msg!("Instruction: check_program_account: running as {} instead of {}", program_id, id());
+        return Err(ProgramError::IncorrectProgramId);
     }
+    Ok(())
 }
 
-impl BorshSerialize for MatchOutcome {
-    fn serialize<W: Write>(&self, writer: &mut W) -> BorshResult<()> {
-        match self {
-            MatchOutcome::Unknown => 0u8.serialize(writer),
-            MatchOutcome::TeamA => 1u8.serialize(writer),
-            MatchOutcome::TeamB => 2u8.serialize(writer),
-            MatchOutcome::Draw => 3u8.serialize(writer),
-            MatchOutcome::Withdrawn => 255u8.serialize(writer),
-        }
-    }
+// The only key allowed to create the global config.
+pub mod admin {
+    #[cfg(not(feature = "test-admin"))]
+    solana_program::declare_id!("BhJf7K8Toe1QwpYEy32UDR4spAekb2JkK7S6h7ZJJkcs");
+    // Public key of tests/fixtures/test-admin.json, never deploy a build with this feature.
+    #[cfg(feature = "test-admin")]
+    solana_program::declare_id!("AX6xHScmo3aP938CeqEEjgUezKyaGZwGbuESyhFTuDKX");
 }
 
-impl BorshDeserialize for MatchOutcome {
-    fn deserialize(buf: &mut &[u8]) -> BorshResult<Self> {
-        if buf.len() != 1 {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "Unexpected length of input",
-            ));
-        }
-        match buf[0] {
-            0 => Ok(Self::Unknown),
-            1 => Ok(Self::TeamA),
-            2 => Ok(Self::TeamB),
-            3 => Ok(Self::Draw),
-            255 => Ok(Self::Withdrawn),
-            _ => Err(Error::new(ErrorKind::InvalidInput, "MatchOutcome_bad_input"))
-        }
-    }
-}
+pub const CONFIG_SEED: &[u8] = b"config";
 
-#[derive(BorshSerialize, BorshDeserialize)]
-pub struct EventBets {
-    pub is_initialized: bool,
-    pub arbiter: Pubkey,
-    pub bets_allowed_until_ts: UnixTimestamp,
-    pub outcome: u8,
-    pub balance_a: u64,
-    pub balance_b: u64,
+pub fn config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
 }
 
-#[derive(BorshSerialize, BorshDeserialize)]
-pub struct Bet {
-    pub is_initialized: bool,
-    pub betor: Pubkey,
-    pub event: Pubkey,
-    pub amount: u64,
-    pub outcome: u8,
+// Program-owned account collecting the protocol's part of the commission.
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+pub fn treasury_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], program_id)
 }
 
-const BETS_RENT_EXCEMPTION: u64 = 1405920;
+// Per (event, betor) account tracking the wallet's total stake on the event.
+pub const LIMIT_SEED: &[u8] = b"limit";
 
-fn pack_match_outcome(value: MatchOutcome) -> u8{
-    match value {
-        MatchOutcome::Unknown => 0,
-        MatchOutcome::TeamA => 1,
-        MatchOutcome::TeamB => 2,
-        MatchOutcome::Draw => 3,
-        MatchOutcome::Withdrawn => 255,
-    }
+pub fn limit_address(program_id: &Pubkey, event: &Pubkey, betor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LIMIT_SEED, event.as_ref(), betor.as_ref()], program_id)
 }
-fn unpack_match_outcome(src: u8) -> Result<MatchOutcome, ProgramError> {
-    match src {
-        0 => Ok(MatchOutcome::Unknown),
-        1 => Ok(MatchOutcome::TeamA),
-        2 => Ok(MatchOutcome::TeamB),
-        3 => Ok(MatchOutcome::Draw),
-        _ => Err(ProgramError::InvalidAccountData),
-    }
+
+// Per (event, betor) marker, empty, created by the first AddBet that passes it.
+pub const SEEN_SEED: &[u8] = b"seen";
+
+pub fn seen_address(program_id: &Pubkey, event: &Pubkey, betor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SEEN_SEED, event.as_ref(), betor.as_ref()], program_id)
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub enum Instruction {
-    // Checks and initializes an empty account.
-    // Accepted accounts:
-    //    [readable, signed] - owner account, signed, mostly to avoid fat finger errors.
-    //    [writable] - bets account
-    Initialize{
-        bets_accepted_until: UnixTimestamp,
-    },
-
-    // Adds a bet
-    // Accepted accounts:
-    //    [writable] - betor
-    //    [writable] - bets account
-    //    [writable] - tmp account with SOLs to deposit
-    //    [writable] - bet info
-    AddBet{
-        choice: MatchOutcome,
-    },
-
-    // Sets a winner
-    // Accepted accounts
-    //    [readable, signer] - owner account
-    //    [writable] - bets account
-    SetWinner{
-        result: MatchOutcome,
-    },
-
-    // Withdraw your win
-    //    [readable] - betor (no need to be signed, bc. it's ok if someone else decides to withdraw for you)
-    //    [writable] - bets account
-    //    [writable] - bet info
-    Withdraw,
+// Per event WalletList of the wallets allowed to bet, see AddToAllowlist.
+pub const ALLOWLIST_SEED: &[u8] = b"allowlist";
+
+pub fn allowlist_address(program_id: &Pubkey, event: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ALLOWLIST_SEED, event.as_ref()], program_id)
 }
 
-impl Instruction {
-    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
-        use std::convert::TryInto;
-        use ProgramError::InvalidInstructionData;
-        let (&tag, rest) = input.split_first().ok_or(InvalidInstructionData)?;
-        Ok(match tag {
-            0 => {
-                let bets_accepted_until = rest
-                    .get(..8)
-                    .and_then(|slice| slice.try_into().ok())
-                    .map(UnixTimestamp::from_le_bytes)
-                    .ok_or(InvalidInstructionData)?;
-                Self::Initialize { bets_accepted_until }
-            },
-            1 => {
-                let (&choice, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
-                Self::AddBet { choice: unpack_match_outcome(choice)? }
-            },
-            2 => {
-                let (&result, rest) = rest.split_first().ok_or(InvalidInstructionData)?;
-                Self::SetWinner { result: unpack_match_outcome(result)? }
-            },
-            3 => Self::Withdraw,
-            _ => unreachable!()
-        })
-    }
+// Per event WalletList of the wallets refused new bets, see AddToDenylist.
+pub const DENYLIST_SEED: &[u8] = b"denylist";
+
+pub fn denylist_address(program_id: &Pubkey, event: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DENYLIST_SEED, event.as_ref()], program_id)
 }
 
-pub fn cmp_pubkeys(a: &Pubkey, b: &Pubkey) -> bool {
-    sol_memcmp(a.as_ref(), b.as_ref(), PUBKEY_BYTES) == 0
+pub const WALLET_LIST_CAPACITY: usize = 128;
+
+// Per event account ranking its biggest winners, see CreateLeaderboard.
+pub const LEADERBOARD_SEED: &[u8] = b"leaderboard";
+
+pub fn leaderboard_address(program_id: &Pubkey, event: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LEADERBOARD_SEED, event.as_ref()], program_id)
 }
 
-fn _process_initialize(program_id: &Pubkey, bets_accepted_until: UnixTimestamp, accounts: &[AccountInfo]) -> ProgramResult {
-    let account_info_iter = &mut accounts.iter();
-    let owner = next_account_info(account_info_iter)?;
-    if !owner.is_signer {
-        msg!("Instruction: _process_initialize: wrong signer");
-        return Err(ProgramError::MissingRequiredSignature)
-    }
-    
-    let bets_info = next_account_info(account_info_iter)?;
-    let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
-    if !rent.is_exempt(bets_info.lamports(), bets_info.data_len()) {
-        msg!("Instruction: _process_initialize: no exempt, size={}", bets_info.data_len());
-        return Err(ProgramError::InvalidAccountData)
-    }
+// Per betor account with their lifetime record across SOL events, kept only when they pass it.
+pub const STATS_SEED: &[u8] = b"stats";
 
-    if !cmp_pubkeys(program_id, bets_info.owner) {
-        msg!("Instruction: _process_initialize: wrong owner");
-        return Err(ProgramError::InvalidAccountData)
-    }
-    
-    let mut bets = EventBets::deserialize(&mut &bets_info.data.borrow()[..])?;
-    if bets.is_initialized {
-        return Err(ProgramError::AccountAlreadyInitialized);
-    }
+pub fn stats_address(program_id: &Pubkey, betor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STATS_SEED, betor.as_ref()], program_id)
+}
 
-    if bets_accepted_until < Clock::get()?.unix_timestamp {
-        msg!("Bets accepted until {} but now it is {}", bets_accepted_until, Clock::get()?.unix_timestamp);
-        return Err(ProgramError::InvalidInstructionData);
-    }
+// Pages listing the events in the order Initialize registered them, REGISTRY_PAGE_CAPACITY per page.
+pub const REGISTRY_SEED: &[u8] = b"registry";
+pub const REGISTRY_PAGE_CAPACITY: usize = 256;
 
-    bets.is_initialized = true;
-    bets.arbiter = *owner.key;
-    bets.outcome = 0u8;
-    bets.bets_allowed_until_ts = bets_accepted_until;
-    bets.balance_a = 0;
-    bets.balance_b = 0;
+pub fn registry_address(program_id: &Pubkey, page: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTRY_SEED, &page.to_le_bytes()], program_id)
+}
 
-    bets.serialize(&mut &mut bets_info.data.borrow_mut()[..])?;
-    Ok(())
+// Events created by InitializeIndexed live at event_address(arbiter, index), the index coming
+// from the arbiter's counter at arbiter_counter_address(arbiter).
+pub const EVENT_SEED: &[u8] = b"event";
+pub const ARBITER_SEED: &[u8] = b"arbiter";
+
+pub fn event_address(program_id: &Pubkey, arbiter: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_SEED, arbiter.as_ref(), &index.to_le_bytes()], program_id)
 }
 
-fn _process_add_bet(program_id: &Pubkey, accounts: &[AccountInfo], choice: MatchOutcome) -> ProgramResult {
-    // What can go wrong?
-    // `bets_info_acc` does not belong to our program, and someone scams our users.
-    // `this_bet_acc` does not belong to our program, again possible scam, but actually don't think it is achievable.
-    // `this_bet_acc` does not have enough funds to be rent excepmpted. Pretty bad, users may be disappointed.
-    // `bets_info` is wrong, uninitnalized - users can be scammed by betting to something else.
-    // `bets_info.bets_allowed_until_ts` is in the past.
-    // `bets_info.outcome` is not yet set (it should not, but just in case)...
-
-    let account_info_iter = &mut accounts.iter();
-    let betor = next_account_info(account_info_iter)?; 
-    let bets_info_acc = next_account_info(account_info_iter)?;
-    let this_bet_acc = next_account_info(account_info_iter)?;
-
-    msg!("betor = {}, bets_info = {}, this_bet_acc = {}", betor.key, bets_info_acc.key, this_bet_acc.key);
-    if !cmp_pubkeys(program_id, bets_info_acc.owner) {
-        msg!("Instruction: _process_add_bet: wrong owner for event {}", bets_info_acc.owner);
-        return Err(ProgramError::InvalidAccountData)
-    }
-    if !cmp_pubkeys(program_id, this_bet_acc.owner) {
-        msg!("Instruction: _process_add_bet: wrong owner for event {}", this_bet_acc.owner);
-        return Err(ProgramError::InvalidAccountData)
-    }
-    
-    let mut bets = EventBets::deserialize(&mut &bets_info_acc.data.borrow()[..])?;
-    let mut this_bet = Bet::deserialize(&mut &this_bet_acc.data.borrow()[..])?;
-    if !bets.is_initialized {
-        msg!("Instruction: _process_add_bet: BetInfo should be Initialized...");
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if this_bet.is_initialized {
-        msg!("Instruction: _process_add_bet: Bet is already Initialized...");
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if Clock::get()?.unix_timestamp > bets.bets_allowed_until_ts {
-        msg!("Instruction: _process_add_bet: too late, bets are no longer accepted");
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if unpack_match_outcome(bets.outcome)? != MatchOutcome::Unknown {
-        msg!("Betting on completed match");
-        return Err(ProgramError::InvalidAccountData);
-    }
+// Events created by InitializeSeeded live at seeded_event_address(arbiter, seed) instead.
+pub const EVENT_SEED_BYTES: usize = 32;
 
-    msg!("Adding {} for resolution {}", this_bet_acc.lamports(), pack_match_outcome(choice));
-    this_bet.is_initialized = true;
-    this_bet.outcome = pack_match_outcome(choice);
-    this_bet.betor = *betor.key;
-    this_bet.amount = this_bet_acc.lamports() - BETS_RENT_EXCEMPTION;
-    this_bet.event = *bets_info_acc.key;
-
-    match choice {
-        MatchOutcome::TeamA => { bets.balance_a += this_bet.amount; },
-        MatchOutcome::TeamB => { bets.balance_b += this_bet.amount; },
-        _ => { return Err(ProgramError::InvalidAccountData); },
-    };
+pub fn seeded_event_address(program_id: &Pubkey, arbiter: &Pubkey, seed: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_SEED, arbiter.as_ref(), seed.as_bytes()], program_id)
+}
 
-    msg!("Sending funds from {} to {}", this_bet_acc.key, bets_info_acc.key);
-    **bets_info_acc.try_borrow_mut_lamports()? += this_bet.amount;
-    **this_bet_acc.try_borrow_mut_lamports()? = BETS_RENT_EXCEMPTION;
+// Tournaments live at tournament_address(arbiter, slug), the slug following the event seed rules.
+pub const TOURNAMENT_SEED: &[u8] = b"tournament";
 
-    bets.serialize(&mut &mut bets_info_acc.data.borrow_mut()[..])?;
-    this_bet.serialize(&mut &mut this_bet_acc.data.borrow_mut()[..])?;
-    Ok(())
+pub fn tournament_address(program_id: &Pubkey, arbiter: &Pubkey, slug: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TOURNAMENT_SEED, arbiter.as_ref(), slug.as_bytes()], program_id)
 }
 
-fn _process_set_winner(program_id: &Pubkey, accounts: &[AccountInfo], result: MatchOutcome) -> ProgramResult {
-    let account_info_iter = &mut accounts.iter();
-    let owner = next_account_info(account_info_iter)?; 
-    let bets_info = next_account_info(account_info_iter)?;
-    if !owner.is_signer {
-        msg!("Instruction: _process_set_winner: wrong signer");
-        return Err(ProgramError::MissingRequiredSignature)
-    }
-    let mut bets = EventBets::deserialize(&mut &bets_info.data.borrow()[..])?;
-    if !bets.is_initialized {
-        msg!("Instruction: _process_set_winner: not Initialized...");
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if Clock::get()?.unix_timestamp < bets.bets_allowed_until_ts {
-        msg!("Instruction: _process_set_winner: too early");
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if !cmp_pubkeys(&bets.arbiter, owner.key) {
-        msg!("Instruction: _process_set_winner: you are not an arbiter");
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if result == MatchOutcome::Unknown {
-        msg!("Can not set result back to Unknown");
-        return Err(ProgramError::InvalidAccountData);
-    }
-    
-    if unpack_match_outcome(bets.outcome)? == MatchOutcome::Unknown {
-        msg!("Sending funds from {} to {}", bets_info.key, owner.key);
-        let comission: u64 = bets_info.lamports() * (COMISSION as u64) / 100u64;
-        **bets_info.try_borrow_mut_lamports()? -= comission;
-        **owner.try_borrow_mut_lamports()? += comission;
-    }
-    bets.outcome = pack_match_outcome(result);
-    bets.serialize(&mut &mut bets_info.data.borrow_mut()[..])?;
+pub fn arbiter_counter_address(program_id: &Pubkey, arbiter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ARBITER_SEED, arbiter.as_ref()], program_id)
+}
 
-    Ok(())
+// Signs for the token vaults of SPL token events, one per event.
+pub const VAULT_SEED: &[u8] = b"vault";
+
+pub fn vault_authority(program_id: &Pubkey, event: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, event.as_ref()], program_id)
 }
 
-fn _process_withdraw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
-    let account_info_iter = &mut accounts.iter();
-    let betor = next_account_info(account_info_iter)?; 
-    let bets_info = next_account_info(account_info_iter)?;
-    let this_bet_acc = next_account_info(account_info_iter)?;
-
-    msg!("betor = {}, bets_info = {}, this_bet_acc = {}", betor.key, bets_info.key, this_bet_acc.key);
-    
-    if !cmp_pubkeys(program_id, bets_info.owner) {
-        msg!("Instruction: _process_add_bet: wrong owner for event {}", bets_info.owner);
-        return Err(ProgramError::InvalidAccountData)
-    }
-    
-    let bets = EventBets::deserialize(&mut &bets_info.data.borrow()[..])?;
-    let mut this_bet = Bet::deserialize(&mut &this_bet_acc.data.borrow()[..])?;
+// Associated token account of vault_authority(), holding the pools of an SPL token event.
+// Derived by hand since the token program is part of the seeds for Token-2022 mints.
+pub fn vault_address(program_id: &Pubkey, event: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    let authority = vault_authority(program_id, event).0;
+    Pubkey::find_program_address(&[authority.as_ref(), token_program.as_ref(), mint.as_ref()], &spl_associated_token_account::id()).0
+}
 
-    if !cmp_pubkeys(bets_info.key, &this_bet.event) {
-        msg!("Bet does not match event");
-        return Err(ProgramError::InvalidAccountData)
-    }
-    if this_bet.betor != *betor.key {
-        msg!("Withdrawing to foreigner account");
-        return Err(ProgramError::InvalidAccountData);
-    }
-    if unpack_match_outcome(bets.outcome)? != MatchOutcome::Unknown {
-        msg!("Betting on completed match");
-        return Err(ProgramError::InvalidAccountData);
-    }
+// Mint of the supply-1 SPL token standing for a bet after TokenizeBet, also its own mint authority
+// until the one token is minted.
+pub const RECEIPT_SEED: &[u8] = b"receipt";
 
-    let withdraw_balance = match (unpack_match_outcome(bets.outcome)?, unpack_match_outcome(this_bet.outcome)?) {
-        (MatchOutcome::TeamA, MatchOutcome::TeamA) => {
-            let mut result = 1u128;
-            result *= bets.balance_b as u128;
-            result *= this_bet.amount as u128;
-            result /= bets.balance_a as u128;
-            result += this_bet.amount as u128;
-            result *= (100-COMISSION) as u128;
-            result /= 100u128;
-            result
-        },
-        (MatchOutcome::TeamB, MatchOutcome::TeamB) => {
-            let mut result = 1u128;
-            result *= bets.balance_a as u128;
-            result *= this_bet.amount as u128;
-            result /= bets.balance_b as u128;
-            result += this_bet.amount as u128;
-            result *= (100-COMISSION) as u128;
-            result /= 100u128;
-            result
-        },
-        (MatchOutcome::Draw, MatchOutcome::TeamA) | (MatchOutcome::Draw, MatchOutcome::TeamB)=> {
-            let mut result = 0u128;
-            result += this_bet.amount as u128;
-            result *= (100-COMISSION) as u128;
-            result /= 100u128;
-            result
-        },
-        _ => 0
-    };
+pub fn receipt_mint_address(program_id: &Pubkey, bet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_SEED, bet.as_ref()], program_id)
+}
 
-    if withdraw_balance > bets_info.lamports().into() {
-        msg!("Withdrawing too much: {}", withdraw_balance);
-        return Err(ProgramError::InvalidAccountData);
-    }
+// Program-owned account escrowing every parlay stake and paying the winning parlays. Anyone may
+// fund it with a plain transfer on top of the stakes, SettleParlay never takes it below its rent exemption.
+pub const PARLAY_POOL_SEED: &[u8] = b"parlay_pool";
 
-    this_bet.outcome = pack_match_outcome(MatchOutcome::Withdrawn);
-    msg!("Sending {} lamports from {} to {}", withdraw_balance, bets_info.key, betor.key);
-    **bets_info.try_borrow_mut_lamports()? -= withdraw_balance as u64;
-    **betor.try_borrow_mut_lamports()? += withdraw_balance as u64;
+pub fn parlay_pool_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PARLAY_POOL_SEED], program_id)
+}
 
-    bets.serialize(&mut &mut bets_info.data.borrow_mut()[..])?;
-    this_bet.serialize(&mut &mut this_bet_acc.data.borrow_mut()[..])?;
+// SPL Token-2022, accepted next to SPL Token for event mints.
+pub mod token_2022 {
+    solana_program::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
 
-    Ok(())
+pub fn is_token_program(id: &Pubkey) -> bool {
+    cmp_pubkeys(id, &spl_token::id()) || cmp_pubkeys(id, &token_2022::id())
 }
 
+// Token-2022 mint extensions that would let someone else freeze, take or block the vault's tokens:
+// NonTransferable, PermanentDelegate and TransferHook.
+const REJECTED_MINT_EXTENSIONS: [u16; 3] = [9, 12, 14];
 
-// Declare and export the program's entrypoint
-entrypoint!(process_instruction);
+const BPS_DENOMINATOR: u64 = 10_000;
 
-// Program entrypoint's implementation
-pub fn process_instruction(
-    program_id: &Pubkey, // Public key of the account the hello world program was loaded into
-    accounts: &[AccountInfo], // The account to say hello to
-    _instruction_data: &[u8], // Ignored, all helloworld instructions are hellos
-) -> ProgramResult {
-    let instruction = Instruction::unpack(_instruction_data)?;
-    msg!("UNpacked");
+// For the imbalance check a pool counts as holding at least that much,
+// so the first bets on an empty event are accepted up to max_imbalance_ratio of it.
+pub const IMBALANCE_FLOOR_LAMPORTS: u64 = 1_000_000_000;
 
-    match instruction {
-        Instruction::Initialize{bets_accepted_until} => _process_initialize(program_id, bets_accepted_until, accounts),
-        Instruction::AddBet{choice} => _process_add_bet(program_id, accounts, choice),
-        Instruction::SetWinner{result} => _process_set_winner(program_id, accounts, result),
-        Instruction::Withdraw => _process_withdraw(program_id, accounts),
-    }
+// Commission taken from `amount`. Rounds down, same as net_of_commission,
+// so the skim and the payouts together never exceed what was staked.
+pub fn commission_of(amount: u64, commission_bps: u16) -> u64 {
+    (amount as u128 * commission_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+// What's left of `amount` after the commission, rounded down.
+pub fn net_of_commission(amount: u128, commission_bps: u16) -> u128 {
+    amount * (BPS_DENOMINATOR - commission_bps as u64) as u128 / BPS_DENOMINATOR as u128
+}
+
+pub fn cmp_pubkeys(a: &Pubkey, b: &Pubkey) -> bool {
+    sol_memcmp(a.as_ref(), b.as_ref(), PUBKEY_BYTES) == 0
 }
 
-// Sanity tests
 #[cfg(test)]
 mod test {
     use super::*;
-    use solana_program::clock::Epoch;
-    use std::mem;
 
     #[test]
-    fn test_sanity() {
-        let program_id = Pubkey::default();
-        let key = Pubkey::default();
-        let mut lamports = 0;
-        let mut data = vec![0; mem::size_of::<u32>()];
-        let owner = Pubkey::default();
-        let account = AccountInfo::new(
-            &key,
-            false,
-            true,
-            &mut lamports,
-            &mut data,
-            &owner,
-            false,
-            Epoch::default(),
-        );
-        let instruction_data: Vec<u8> = Vec::new();
-
-        let accounts = vec![account];
-
-        assert_eq!(
-            GreetingAccount::try_from_slice(&accounts[0].data.borrow())
-                .unwrap()
-                .counter,
-            0
-        );
-        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
-        assert_eq!(
-            GreetingAccount::try_from_slice(&accounts[0].data.borrow())
-                .unwrap()
-                .counter,
-            1
-        );
-        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
-        assert_eq!(
-            GreetingAccount::try_from_slice(&accounts[0].data.borrow())
-                .unwrap()
-                .counter,
-            2
-        );
+    fn test_cmp_pubkeys() {
+        let key = Pubkey::new_unique();
+        assert!(cmp_pubkeys(&key, &Pubkey::new(key.as_ref())));
+        assert!(!cmp_pubkeys(&key, &Pubkey::new_unique()));
+        // The last byte counts as much as the first.
+        let mut bytes = key.to_bytes();
+        bytes[PUBKEY_BYTES - 1] ^= 1;
+        assert!(!cmp_pubkeys(&key, &Pubkey::new_from_array(bytes)));
+        assert!(cmp_pubkeys(&Pubkey::default(), &Pubkey::new_from_array([0; PUBKEY_BYTES])));
     }
 }
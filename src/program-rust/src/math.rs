@@ -0,0 +1,144 @@
+// Payout math on plain state, usable off-chain without any accounts.
+
+use crate::{commission_of, net_of_commission, Bet, BetError, EventBets, Market, Parlay, BPS_DENOMINATOR, MARKET_UNSETTLED};
+use std::convert::TryFrom;
+
+// Most commission a lamport event can charge: `commission_bps` of the pools and of a bonus that isn't
+// commission free, rounded down. Withdraw charges each winning bet the commission of its own payout,
+// which add up to at most this. The rent exemption and the bond are the account's, not the pot's.
+// With every winner paid calculate_payout, this plus the payouts never exceeds the pools and bonus.
+pub fn settlement_commission(event: &EventBets) -> Result<u64, BetError> {
+    let charged_bonus = if event.bonus_commission_free { 0 } else { event.bonus_lamports };
+    let base = event
+        .balance_a
+        .checked_add(event.balance_b)
+        .and_then(|pools| pools.checked_add(charged_bonus))
+        .ok_or(BetError::ArithmeticOverflow)?;
+    Ok(commission_of(base, event.commission_bps))
+}
+
+/// Stake payout of `bet` under the event's result, net of `commission_bps`.
+/// 0 while the result is Unknown and for a losing bet, the stake itself back on a Draw.
+/// A fixed-odds bet wins stake * its locked odds. Bond and bonus shares are not included.
+///
+/// Rounding: a winning pool bet is owed exactly `stake * (pool + other_pool) / pool * (1 - commission)`.
+/// That is computed in u128 with the commission applied once and a single round down at the end,
+/// so every bet gets less than a lamport under its exact share and the same effective fee.
+/// The floors of the shares never sum to more than the floor of the whole, so the winners together
+/// are paid at most `net_of_commission` of both pools and, with `settlement_commission`, the pot
+/// is never overdrawn. The dust left behind is under one lamport per winning bet.
+pub fn calculate_payout(event: &EventBets, bet: &Bet, commission_bps: u16) -> Result<u64, BetError> {
+    // Bets are only ever placed on TeamA or TeamB.
+    let (pool, other_pool) = match bet.outcome {
+        1 => (event.balance_a, event.balance_b),
+        2 => (event.balance_b, event.balance_a),
+        _ => return Err(BetError::InvalidOutcome),
+    };
+    let gross = match event.outcome {
+        0 => 0,
+        3 => bet.amount as u128,
+        // Fixed-odds bets are paid at their own odds whatever the pools look like.
+        result if result == bet.outcome && bet.locked_odds.is_set() => {
+            bet.locked_odds.payout(bet.amount).ok_or(BetError::ArithmeticOverflow)? as u128
+        },
+        result if result == bet.outcome => {
+            if pool == 0 {
+                return Ok(0);
+            }
+            return pro_rata_payout(bet.amount, pool, other_pool, commission_bps);
+        },
+        1 | 2 => 0,
+        _ => return Err(BetError::InvalidOutcome),
+    };
+    u64::try_from(net_of_commission(gross, commission_bps)).map_err(|_| BetError::ArithmeticOverflow)
+}
+
+// floor(stake * (pool + other_pool) / pool * keep / BPS_DENOMINATOR) without rounding in between.
+// With stake * other_pool = q * pool + r the exact share is q + r / pool on top of the stake,
+// and floor((n + x) / d) == floor((n + floor(x)) / d) for whole n and d lets r / pool be floored
+// after scaling by keep, where it still fits: r < pool and keep <= BPS_DENOMINATOR.
+fn pro_rata_payout(stake: u64, pool: u64, other_pool: u64, commission_bps: u16) -> Result<u64, BetError> {
+    let keep = BPS_DENOMINATOR.checked_sub(commission_bps as u64).ok_or(BetError::ArithmeticOverflow)? as u128;
+    let winnings = other_pool as u128 * stake as u128;
+    let gross = stake as u128 + winnings / pool as u128;
+    let fraction = winnings % pool as u128 * keep / pool as u128;
+    let net = gross
+        .checked_mul(keep)
+        .and_then(|scaled| scaled.checked_add(fraction))
+        .ok_or(BetError::ArithmeticOverflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(net).map_err(|_| BetError::ArithmeticOverflow)
+}
+
+// What CashOut pays for `bet` before the result, net of `fee_bps`, rounded down.
+// The stake is discounted by its share of both pools times the opposite pool's share, so a large
+// position against a large opposite pool gets less, and a bet with no opposite pool gets its stake.
+// Never more than the stake, so the pools left behind are always covered.
+pub fn cash_out_value(event: &EventBets, bet: &Bet, fee_bps: u16) -> Result<u64, BetError> {
+    if event.outcome != 0 {
+        return Err(BetError::InvalidOutcome);
+    }
+    let (pool, other_pool) = match bet.outcome {
+        1 => (event.balance_a, event.balance_b),
+        2 => (event.balance_b, event.balance_a),
+        _ => return Err(BetError::InvalidOutcome),
+    };
+    let stake = bet.amount as u128;
+    let pools = pool as u128 + other_pool as u128;
+    if stake > pool as u128 {
+        return Err(BetError::ArithmeticOverflow);
+    }
+    if pools == 0 {
+        return Ok(0);
+    }
+    let discount = stake * stake / pools * other_pool as u128 / pools;
+    Ok(net_of_commission(stake - discount, fee_bps) as u64)
+}
+
+// Payout of `bet` in an N-outcome market: its share of all pools net of the market's commission when
+// it picked the winner, 0 otherwise or while unsettled. With nobody on the winner every stake is refunded.
+pub fn calculate_market_payout(market: &Market, bet: &Bet) -> Result<u64, BetError> {
+    if bet.outcome >= market.num_outcomes {
+        return Err(BetError::InvalidOutcome);
+    }
+    if market.winner == MARKET_UNSETTLED {
+        return Ok(0);
+    }
+    let winning_pool = *market.pools.get(market.winner as usize).ok_or(BetError::InvalidOutcome)?;
+    if winning_pool == 0 {
+        return Ok(bet.amount);
+    }
+    if bet.outcome != market.winner {
+        return Ok(0);
+    }
+    let gross = bet.amount as u128 * market.total_pool() / winning_pool as u128;
+    u64::try_from(net_of_commission(gross, market.commission_bps)).map_err(|_| BetError::ArithmeticOverflow)
+}
+
+// Payout of `parlay`, `events` being its legs' events in order, all with a result.
+// Each winning leg multiplies the stake by its event's pools net of commission over the winning
+// pool, rounding down after every leg. A Draw cancels the leg, which then counts as 1, and so does
+// a winning side nobody bet on. A single losing leg loses the whole parlay.
+pub fn calculate_parlay_payout(parlay: &Parlay, events: &[EventBets]) -> Result<u64, BetError> {
+    let legs = parlay.legs.get(..parlay.num_legs as usize).ok_or(BetError::InvalidOutcome)?;
+    if legs.len() != events.len() {
+        return Err(BetError::InvalidOutcome);
+    }
+    let mut payout = parlay.stake as u128;
+    for (leg, event) in legs.iter().zip(events) {
+        let pool = match (leg.outcome, event.outcome) {
+            (1, 1) => event.balance_a,
+            (2, 2) => event.balance_b,
+            (1, 2) | (2, 1) => return Ok(0),
+            (1, 3) | (2, 3) => continue,
+            (1, 0) | (2, 0) => return Err(BetError::ResultNotFinal),
+            _ => return Err(BetError::InvalidOutcome),
+        };
+        if pool == 0 {
+            continue;
+        }
+        let pools = net_of_commission(event.balance_a as u128 + event.balance_b as u128, event.commission_bps);
+        payout = payout.checked_mul(pools).ok_or(BetError::ArithmeticOverflow)? / pool as u128;
+    }
+    u64::try_from(payout).map_err(|_| BetError::ArithmeticOverflow)
+}
@@ -0,0 +1,334 @@
+// Zero-copy views of EventBets and Bet over the account data, for handlers touching a few fields
+// in place instead of a Borsh round trip. Fields are in the Borsh order with alignment 1 and no
+// padding, so a view reads and writes exactly the bytes of the Borsh encoding.
+
+use crate::{EVENT_SEED_BYTES, MEMO_BYTES, METADATA_HASH_BYTES};
+use bytemuck::{Pod, Zeroable};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+macro_rules! pod_int {
+    ($name:ident, $int:ty) => {
+        // Little-endian integer, unaligned.
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+        #[repr(transparent)]
+        pub struct $name([u8; std::mem::size_of::<$int>()]);
+
+        impl $name {
+            pub fn get(&self) -> $int {
+                <$int>::from_le_bytes(self.0)
+            }
+
+            pub fn set(&mut self, value: $int) {
+                self.0 = value.to_le_bytes();
+            }
+        }
+
+        impl From<$int> for $name {
+            fn from(value: $int) -> Self {
+                Self(value.to_le_bytes())
+            }
+        }
+    };
+}
+
+pod_int!(PodU16, u16);
+pod_int!(PodI16, i16);
+pod_int!(PodU32, u32);
+pod_int!(PodU64, u64);
+pod_int!(PodI64, i64);
+
+// Borsh bool, 0 or 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+#[repr(transparent)]
+pub struct PodBool(u8);
+
+impl PodBool {
+    pub fn get(&self) -> bool {
+        self.0 != 0
+    }
+
+    pub fn set(&mut self, value: bool) {
+        self.0 = value as u8;
+    }
+}
+
+impl From<bool> for PodBool {
+    fn from(value: bool) -> Self {
+        Self(value as u8)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct PodOdds {
+    pub numerator: PodU32,
+    pub denominator: PodU32,
+}
+
+impl From<crate::Odds> for PodOdds {
+    fn from(odds: crate::Odds) -> Self {
+        Self { numerator: odds.numerator.into(), denominator: odds.denominator.into() }
+    }
+}
+
+impl From<PodOdds> for crate::Odds {
+    fn from(odds: PodOdds) -> Self {
+        Self { numerator: odds.numerator.get(), denominator: odds.denominator.get() }
+    }
+}
+
+// EventBets, field for field, see there. Byte offsets on the right.
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct EventBetsPod {
+    pub version: u8, // 0
+    pub arbiter: Pubkey, // 1
+    pub bets_allowed_until_ts: PodI64, // 33
+    pub outcome: u8, // 41
+    pub balance_a: PodU64, // 42
+    pub balance_b: PodU64, // 50
+    pub dispute_window_secs: PodI64, // 58
+    pub winner_set_at: PodI64, // 66
+    pub finality_delay_secs: PodI64, // 74
+    pub bond_lamports: PodU64, // 82
+    pub challenger: Pubkey, // 90
+    pub bond_slashed: PodBool, // 122
+    pub commission_bps: PodU16, // 123
+    pub min_bet_lamports: PodU64, // 125
+    pub max_stake_per_wallet: PodU64, // 133
+    pub max_imbalance_ratio: PodU16, // 141
+    pub bonus_lamports: PodU64, // 143
+    pub bonus_commission_free: PodBool, // 151
+    pub referred_a: PodU64, // 152
+    pub referred_b: PodU64, // 160
+    pub referral_bps: PodU16, // 168
+    pub referral_reserve: PodU64, // 170
+    pub gate_mint: Pubkey, // 178
+    pub gate_min_amount: PodU64, // 210
+    pub mint: Pubkey, // 218
+    pub token_program: Pubkey, // 250
+    pub metadata_hash: [u8; METADATA_HASH_BYTES], // 282
+    pub bets_count_a: PodU32, // 314
+    pub bets_count_b: PodU32, // 318
+    pub total_paid_out: PodU64, // 322
+    pub paused: PodBool, // 330
+    pub bump: u8, // 331
+    pub seed: [u8; EVENT_SEED_BYTES], // 332
+    pub fixed_odds: PodBool, // 364
+    pub odds_a: PodOdds, // 365
+    pub odds_b: PodOdds, // 373
+    pub liability_a: PodU64, // 381
+    pub liability_b: PodU64, // 389
+    pub worst_case_liability: PodU64, // 397
+    pub tournament: Pubkey, // 405
+    pub market_kind: u8, // 437
+    pub handicap: PodI16, // 438
+    pub outstanding_bets: PodU32, // 440
+    pub commission_accrued: PodU64, // 444
+    pub correction_window_secs: PodI64, // 452
+    pub claims_count: PodU32, // 460
+    pub commission_recipient: Pubkey, // 464
+    pub finalized: PodBool, // 496
+    pub unique_bettors: PodU32, // 497
+    pub max_bets_per_wallet: u8, // 501
+    pub allowlisted: PodBool, // 502
+    pub denylisted: PodBool, // 503
+    pub arbiter_may_bet: PodBool, // 504
+    pub correction_cooldown_secs: PodI64, // 505
+    pub last_result_change_at: PodI64, // 513
+}
+
+// Bet, field for field, see there. Byte offsets on the right.
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct BetPod {
+    pub version: u8, // 0
+    pub betor: Pubkey, // 1
+    pub event: Pubkey, // 33
+    pub amount: PodU64, // 65
+    pub outcome: u8, // 73
+    pub referrer: Pubkey, // 74
+    pub memo: [u8; MEMO_BYTES], // 106
+    pub placed_at: PodI64, // 138
+    pub claimed: PodU64, // 146
+    pub for_sale: PodBool, // 154
+    pub ask_lamports: PodU64, // 155
+    pub receipt_mint: Pubkey, // 163
+    pub locked_odds: PodOdds, // 195
+    pub delegate: Pubkey, // 203
+}
+
+impl From<&crate::EventBets> for EventBetsPod {
+    fn from(state: &crate::EventBets) -> Self {
+        Self {
+            version: state.version,
+            arbiter: state.arbiter,
+            bets_allowed_until_ts: state.bets_allowed_until_ts.into(),
+            outcome: state.outcome,
+            balance_a: state.balance_a.into(),
+            balance_b: state.balance_b.into(),
+            dispute_window_secs: state.dispute_window_secs.into(),
+            winner_set_at: state.winner_set_at.into(),
+            finality_delay_secs: state.finality_delay_secs.into(),
+            bond_lamports: state.bond_lamports.into(),
+            challenger: state.challenger,
+            bond_slashed: state.bond_slashed.into(),
+            commission_bps: state.commission_bps.into(),
+            min_bet_lamports: state.min_bet_lamports.into(),
+            max_stake_per_wallet: state.max_stake_per_wallet.into(),
+            max_imbalance_ratio: state.max_imbalance_ratio.into(),
+            bonus_lamports: state.bonus_lamports.into(),
+            bonus_commission_free: state.bonus_commission_free.into(),
+            referred_a: state.referred_a.into(),
+            referred_b: state.referred_b.into(),
+            referral_bps: state.referral_bps.into(),
+            referral_reserve: state.referral_reserve.into(),
+            gate_mint: state.gate_mint,
+            gate_min_amount: state.gate_min_amount.into(),
+            mint: state.mint,
+            token_program: state.token_program,
+            metadata_hash: state.metadata_hash,
+            bets_count_a: state.bets_count_a.into(),
+            bets_count_b: state.bets_count_b.into(),
+            total_paid_out: state.total_paid_out.into(),
+            paused: state.paused.into(),
+            bump: state.bump,
+            seed: state.seed,
+            fixed_odds: state.fixed_odds.into(),
+            odds_a: state.odds_a.into(),
+            odds_b: state.odds_b.into(),
+            liability_a: state.liability_a.into(),
+            liability_b: state.liability_b.into(),
+            worst_case_liability: state.worst_case_liability.into(),
+            tournament: state.tournament,
+            market_kind: state.market_kind,
+            handicap: state.handicap.into(),
+            outstanding_bets: state.outstanding_bets.into(),
+            commission_accrued: state.commission_accrued.into(),
+            correction_window_secs: state.correction_window_secs.into(),
+            claims_count: state.claims_count.into(),
+            commission_recipient: state.commission_recipient,
+            finalized: state.finalized.into(),
+            unique_bettors: state.unique_bettors.into(),
+            max_bets_per_wallet: state.max_bets_per_wallet,
+            allowlisted: state.allowlisted.into(),
+            denylisted: state.denylisted.into(),
+            arbiter_may_bet: state.arbiter_may_bet.into(),
+            correction_cooldown_secs: state.correction_cooldown_secs.into(),
+            last_result_change_at: state.last_result_change_at.into(),
+        }
+    }
+}
+
+impl From<&EventBetsPod> for crate::EventBets {
+    fn from(view: &EventBetsPod) -> Self {
+        Self {
+            version: view.version,
+            arbiter: view.arbiter,
+            bets_allowed_until_ts: view.bets_allowed_until_ts.get(),
+            outcome: view.outcome,
+            balance_a: view.balance_a.get(),
+            balance_b: view.balance_b.get(),
+            dispute_window_secs: view.dispute_window_secs.get(),
+            winner_set_at: view.winner_set_at.get(),
+            finality_delay_secs: view.finality_delay_secs.get(),
+            bond_lamports: view.bond_lamports.get(),
+            challenger: view.challenger,
+            bond_slashed: view.bond_slashed.get(),
+            commission_bps: view.commission_bps.get(),
+            min_bet_lamports: view.min_bet_lamports.get(),
+            max_stake_per_wallet: view.max_stake_per_wallet.get(),
+            max_imbalance_ratio: view.max_imbalance_ratio.get(),
+            bonus_lamports: view.bonus_lamports.get(),
+            bonus_commission_free: view.bonus_commission_free.get(),
+            referred_a: view.referred_a.get(),
+            referred_b: view.referred_b.get(),
+            referral_bps: view.referral_bps.get(),
+            referral_reserve: view.referral_reserve.get(),
+            gate_mint: view.gate_mint,
+            gate_min_amount: view.gate_min_amount.get(),
+            mint: view.mint,
+            token_program: view.token_program,
+            metadata_hash: view.metadata_hash,
+            bets_count_a: view.bets_count_a.get(),
+            bets_count_b: view.bets_count_b.get(),
+            total_paid_out: view.total_paid_out.get(),
+            paused: view.paused.get(),
+            bump: view.bump,
+            seed: view.seed,
+            fixed_odds: view.fixed_odds.get(),
+            odds_a: view.odds_a.into(),
+            odds_b: view.odds_b.into(),
+            liability_a: view.liability_a.get(),
+            liability_b: view.liability_b.get(),
+            worst_case_liability: view.worst_case_liability.get(),
+            tournament: view.tournament,
+            market_kind: view.market_kind,
+            handicap: view.handicap.get(),
+            outstanding_bets: view.outstanding_bets.get(),
+            commission_accrued: view.commission_accrued.get(),
+            correction_window_secs: view.correction_window_secs.get(),
+            claims_count: view.claims_count.get(),
+            commission_recipient: view.commission_recipient,
+            finalized: view.finalized.get(),
+            unique_bettors: view.unique_bettors.get(),
+            max_bets_per_wallet: view.max_bets_per_wallet,
+            allowlisted: view.allowlisted.get(),
+            denylisted: view.denylisted.get(),
+            arbiter_may_bet: view.arbiter_may_bet.get(),
+            correction_cooldown_secs: view.correction_cooldown_secs.get(),
+            last_result_change_at: view.last_result_change_at.get(),
+        }
+    }
+}
+
+impl From<&crate::Bet> for BetPod {
+    fn from(state: &crate::Bet) -> Self {
+        Self {
+            version: state.version,
+            betor: state.betor,
+            event: state.event,
+            amount: state.amount.into(),
+            outcome: state.outcome,
+            referrer: state.referrer,
+            memo: state.memo,
+            placed_at: state.placed_at.into(),
+            claimed: state.claimed.into(),
+            for_sale: state.for_sale.into(),
+            ask_lamports: state.ask_lamports.into(),
+            receipt_mint: state.receipt_mint,
+            locked_odds: state.locked_odds.into(),
+            delegate: state.delegate,
+        }
+    }
+}
+
+impl From<&BetPod> for crate::Bet {
+    fn from(view: &BetPod) -> Self {
+        Self {
+            version: view.version,
+            betor: view.betor,
+            event: view.event,
+            amount: view.amount.get(),
+            outcome: view.outcome,
+            referrer: view.referrer,
+            memo: view.memo,
+            placed_at: view.placed_at.get(),
+            claimed: view.claimed.get(),
+            for_sale: view.for_sale.get(),
+            ask_lamports: view.ask_lamports.get(),
+            receipt_mint: view.receipt_mint,
+            locked_odds: view.locked_odds.into(),
+            delegate: view.delegate,
+        }
+    }
+}
+
+// View of the start of an account's data, which may be longer than T.
+pub fn load<T: Pod>(data: &[u8]) -> Result<&T, ProgramError> {
+    data.get(..std::mem::size_of::<T>()).map(bytemuck::from_bytes).ok_or(ProgramError::InvalidAccountData)
+}
+
+pub fn load_mut<T: Pod>(data: &mut [u8]) -> Result<&mut T, ProgramError> {
+    data.get_mut(..std::mem::size_of::<T>()).map(bytemuck::from_bytes_mut).ok_or(ProgramError::InvalidAccountData)
+}
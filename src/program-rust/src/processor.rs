@@ -0,0 +1,3593 @@
+use crate::{
+    admin, arbiter_counter_address, cmp_pubkeys, commission_of, config_address,
+    error::BetError,
+    event_address, events,
+    instruction::{result_message, ConfigParams, EventParams, Instruction, MEMO_BYTES, SIGNATURE_BYTES},
+    is_token_program, limit_address,
+    math::{calculate_market_payout, calculate_parlay_payout, calculate_payout, cash_out_value},
+    net_of_commission, parlay_pool_address, pod, receipt_mint_address, registry_address,
+    leaderboard_address, seeded_event_address, seen_address, stats_address,
+    state::{
+        _check_version, result_outcome, ArbiterCounter, Bet, BettorStats, Config, EventBets, Leaderboard, LeaderboardEntry, Market,
+        MatchOutcome, Odds, Parlay, ParlayLeg,
+        RegistryPage, Tournament, WalletList, WalletStake, MARKET_KIND_HANDICAP, MARKET_KIND_MONEYLINE, MARKET_KIND_TOTALS,
+        LEADERBOARD_SIZE, MARKET_UNSETTLED, MAX_HANDICAP, METADATA_HASH_BYTES, MAX_OUTCOMES, MAX_PARLAY_LEGS, MAX_TOTALS_LINE, STATE_VERSION, STATE_VERSION_V1,
+        TOURNAMENT_NAME_BYTES,
+    },
+    tournament_address, treasury_address, vault_address, vault_authority, ALLOWLIST_SEED, ARBITER_SEED, BPS_DENOMINATOR, COMISSION_BPS,
+    CONFIG_SEED, DENYLIST_SEED, EVENT_SEED, EVENT_SEED_BYTES, IMBALANCE_FLOOR_LAMPORTS, LEADERBOARD_SEED, LIMIT_SEED, MAX_COMISSION_BPS, PARLAY_POOL_SEED,
+    RECEIPT_SEED, REGISTRY_PAGE_CAPACITY, REGISTRY_SEED, REJECTED_MINT_EXTENSIONS, SEEN_SEED, STATS_SEED, TOURNAMENT_SEED, TREASURY_SEED,
+    USE_DEFAULT_COMISSION, VAULT_SEED, WALLET_LIST_CAPACITY,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::{Clock, UnixTimestamp},
+    ed25519_program,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed, set_return_data},
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack},
+    pubkey::{Pubkey, PUBKEY_BYTES},
+    system_instruction, system_program,
+    sysvar::{self, rent::Rent, Sysvar},
+};
+use std::convert::TryFrom;
+
+// Runs an unpacked instruction, the handlers behind it check their accounts themselves.
+pub struct Processor;
+
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+        let instruction = Instruction::unpack(instruction_data)?;
+        verbose_msg!("UNpacked");
+
+        match instruction {
+            Instruction::Initialize(params) => _process_initialize(program_id, &params, accounts),
+            Instruction::AddBet{choice, referrer, amount, memo} => _process_add_bet(program_id, accounts, choice, referrer, amount, memo),
+            Instruction::SetWinner{result, metadata_hash} => _process_set_winner(program_id, accounts, result, metadata_hash),
+            Instruction::Withdraw{amount} => _process_withdraw(program_id, accounts, amount),
+            Instruction::SetWinnerSigned{result, event, signature} => _process_set_winner_signed(program_id, accounts, result, event, signature),
+            Instruction::CorrectResult{result} => _process_correct_result(program_id, accounts, result),
+            Instruction::SlashBond => _process_slash_bond(program_id, accounts),
+            Instruction::ReleaseBond => _process_release_bond(program_id, accounts),
+            Instruction::InitializeConfig(params) => _process_initialize_config(program_id, accounts, &params),
+            Instruction::UpdateConfig(params) => _process_update_config(program_id, accounts, &params),
+            Instruction::WithdrawTreasury{amount} => _process_withdraw_treasury(program_id, accounts, amount),
+            Instruction::SeedLiquidity => _process_seed_liquidity(program_id, accounts),
+            Instruction::TopUpPot{amount} => _process_top_up_pot(program_id, accounts, amount),
+            Instruction::AuditEvent => _process_audit_event(program_id, accounts),
+            Instruction::QuoteOdds => _process_quote_odds(program_id, accounts),
+            Instruction::QuotePayout => _process_quote_payout(program_id, accounts),
+            Instruction::IncreaseBet{choice, amount} => _process_increase_bet(program_id, accounts, choice, amount),
+            Instruction::CashOut => _process_cash_out(program_id, accounts),
+            Instruction::TransferBet{new_betor} => _process_transfer_bet(program_id, accounts, new_betor),
+            Instruction::ListBet{price} => _process_list_bet(program_id, accounts, price),
+            Instruction::BuyBet{price} => _process_buy_bet(program_id, accounts, price),
+            Instruction::TokenizeBet => _process_tokenize_bet(program_id, accounts),
+            Instruction::WithdrawMany => _process_withdraw_many(program_id, accounts),
+            Instruction::SettleBets => _process_settle_bets(program_id, accounts),
+            Instruction::VoidBet{reason} => _process_void_bet(program_id, accounts, reason),
+            Instruction::PauseEvent => _process_set_paused(program_id, accounts, true),
+            Instruction::UnpauseEvent => _process_set_paused(program_id, accounts, false),
+            Instruction::HaltProgram => _process_set_halted(program_id, accounts, true),
+            Instruction::ResumeProgram => _process_set_halted(program_id, accounts, false),
+            Instruction::CreateRegistryPage{page} => _process_create_registry_page(program_id, accounts, page),
+            Instruction::InitializeIndexed(params) => _process_initialize_indexed(program_id, &params, accounts),
+            Instruction::InitializeSeeded{seed, params} => _process_initialize_seeded(program_id, &seed, &params, accounts),
+            Instruction::InitializeMarket{bets_accepted_until, num_outcomes, commission_bps} => _process_initialize_market(program_id, accounts, bets_accepted_until, num_outcomes, commission_bps),
+            Instruction::AddMarketBet{outcome} => _process_add_market_bet(program_id, accounts, outcome),
+            Instruction::SetMarketWinner{winner} => _process_set_market_winner(program_id, accounts, winner),
+            Instruction::WithdrawMarketBet => _process_withdraw_market_bet(program_id, accounts),
+            Instruction::SetOdds{odds_a, odds_b} => _process_set_odds(program_id, accounts, odds_a, odds_b),
+            Instruction::CreateParlayPool => _process_create_parlay_pool(program_id, accounts),
+            Instruction::PlaceParlay{choices} => _process_place_parlay(program_id, accounts, &choices),
+            Instruction::SettleParlay => _process_settle_parlay(program_id, accounts),
+            Instruction::CreateTournament{slug, name} => _process_create_tournament(program_id, accounts, &slug, &name),
+            Instruction::AddEventToTournament => _process_add_event_to_tournament(program_id, accounts),
+            Instruction::MigrateEvent => _process_migrate_event(program_id, accounts),
+            Instruction::SweepDust => _process_sweep_dust(program_id, accounts),
+            Instruction::ResetBet => _process_reset_bet(program_id, accounts),
+            Instruction::AddBetFor{choice, beneficiary, amount} => _process_add_bet_for(program_id, accounts, choice, beneficiary, amount),
+            Instruction::SetDelegate{delegate} => _process_set_delegate(program_id, accounts, delegate),
+            Instruction::CollectCommission => _process_collect_commission(program_id, accounts),
+            Instruction::FinalizeResult => _process_finalize_result(program_id, accounts),
+            Instruction::CreateLeaderboard => _process_create_leaderboard(program_id, accounts),
+            Instruction::AddToAllowlist { wallet } => _process_add_to_list(program_id, accounts, wallet, false),
+            Instruction::RemoveFromAllowlist { wallet } => _process_remove_from_list(program_id, accounts, wallet, false),
+            Instruction::AddToDenylist { wallet } => _process_add_to_list(program_id, accounts, wallet, true),
+            Instruction::RemoveFromDenylist { wallet } => _process_remove_from_list(program_id, accounts, wallet, true),
+        }
+    }
+}
+
+fn _process_initialize(program_id: &Pubkey, params: &EventParams, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_initialize: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    
+    let bets_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    _initialize_event(program_id, params, owner, bets_info, rent_info, 0, &[], account_info_iter)
+}
+
+fn _process_initialize_indexed(program_id: &Pubkey, params: &EventParams, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let counter_info = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_initialize_indexed: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+        msg!("Instruction: _process_initialize_indexed: wrong system program");
+        return Err(ProgramError::IncorrectProgramId)
+    }
+    let (counter_address, counter_bump) = arbiter_counter_address(program_id, owner.key);
+    if !cmp_pubkeys(&counter_address, counter_info.key) {
+        msg!("Instruction: _process_initialize_indexed: counter must live at {}", counter_address);
+        return Err(ProgramError::InvalidArgument)
+    }
+    if counter_info.data_len() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                owner.key,
+                counter_info.key,
+                Rent::get()?.minimum_balance(ArbiterCounter::LEN),
+                ArbiterCounter::LEN as u64,
+                program_id,
+            ),
+            &[owner.clone(), counter_info.clone(), system_program_info.clone()],
+            &[&[ARBITER_SEED, owner.key.as_ref(), &[counter_bump]]],
+        )?;
+    } else if !cmp_pubkeys(program_id, counter_info.owner) {
+        msg!("Instruction: _process_initialize_indexed: wrong owner for counter {}", counter_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let mut counter = ArbiterCounter::deserialize(&mut &counter_info.data.borrow()[..])?;
+    let index = counter.next_event_index;
+    let (address, bump) = event_address(program_id, owner.key, index);
+    if !cmp_pubkeys(&address, bets_info.key) {
+        msg!("Instruction: _process_initialize_indexed: event #{} must live at {}", index, address);
+        return Err(ProgramError::InvalidArgument)
+    }
+
+    _create_event_account(program_id, owner, bets_info, system_program_info, params, &[EVENT_SEED, owner.key.as_ref(), &index.to_le_bytes(), &[bump]])?;
+    verbose_msg!("Event #{} of {} created at {}", index, owner.key, address);
+    counter.is_initialized = true;
+    counter.next_event_index = index.checked_add(1).ok_or(BetError::ArithmeticOverflow)?;
+    counter.serialize(&mut &mut counter_info.data.borrow_mut()[..])?;
+
+    _initialize_event(program_id, params, owner, bets_info, rent_info, bump, &[], account_info_iter)
+}
+
+// 1 to EVENT_SEED_BYTES of ASCII letters, digits, '-' and '_', for event seeds and tournament slugs.
+fn is_valid_seed(seed: &str) -> bool {
+    !seed.is_empty() && seed.len() <= EVENT_SEED_BYTES && seed.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_')
+}
+
+fn _process_initialize_seeded(program_id: &Pubkey, seed: &str, params: &EventParams, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_initialize_seeded: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+        msg!("Instruction: _process_initialize_seeded: wrong system program");
+        return Err(ProgramError::IncorrectProgramId)
+    }
+    if !is_valid_seed(seed) {
+        msg!("Instruction: _process_initialize_seeded: bad seed {:?}", seed);
+        return Err(ProgramError::InvalidInstructionData)
+    }
+    let (address, bump) = seeded_event_address(program_id, owner.key, seed);
+    if !cmp_pubkeys(&address, bets_info.key) {
+        msg!("Instruction: _process_initialize_seeded: event {:?} must live at {}", seed, address);
+        return Err(ProgramError::InvalidArgument)
+    }
+    if cmp_pubkeys(program_id, bets_info.owner) || bets_info.data_len() > 0 {
+        msg!("Instruction: _process_initialize_seeded: {} already has an event {:?}", owner.key, seed);
+        return Err(BetError::EventSeedTaken.into())
+    }
+
+    _create_event_account(program_id, owner, bets_info, system_program_info, params, &[EVENT_SEED, owner.key.as_ref(), seed.as_bytes(), &[bump]])?;
+    verbose_msg!("Event {:?} of {} created at {}", seed, owner.key, address);
+    _initialize_event(program_id, params, owner, bets_info, rent_info, bump, seed.as_bytes(), account_info_iter)
+}
+
+// Creates the program-derived event account, funded with rent exemption and the bond by the arbiter.
+fn _create_event_account<'a>(
+    program_id: &Pubkey,
+    owner: &AccountInfo<'a>,
+    bets_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    params: &EventParams,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            bets_info.key,
+            Rent::get()?.minimum_balance(EventBets::LEN) + params.bond_lamports,
+            EventBets::LEN as u64,
+            program_id,
+        ),
+        &[owner.clone(), bets_info.clone(), system_program_info.clone()],
+        &[seeds],
+    )
+}
+
+// Initializes the empty event account `bets_info`, `account_info_iter` holding the optional accounts of Initialize.
+fn _initialize_event(
+    program_id: &Pubkey,
+    params: &EventParams,
+    owner: &AccountInfo,
+    bets_info: &AccountInfo,
+    rent_info: &AccountInfo,
+    bump: u8,
+    seed: &[u8],
+    account_info_iter: &mut std::slice::Iter<AccountInfo>,
+) -> ProgramResult {
+    let rent = &Rent::from_account_info(rent_info)?;
+    let token_program = if params.mint != Pubkey::default() {
+        let vault = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        if !cmp_pubkeys(&params.mint, mint_info.key) {
+            msg!("Instruction: _process_initialize: mint must be {}", params.mint);
+            return Err(ProgramError::InvalidArgument)
+        }
+        _check_mint(mint_info)?;
+        let address = vault_address(program_id, bets_info.key, &params.mint, mint_info.owner);
+        if !cmp_pubkeys(&address, vault.key) {
+            msg!("Instruction: _process_initialize: vault must be {}", address);
+            return Err(ProgramError::InvalidArgument)
+        }
+        _check_token_account(vault, &params.mint, &vault_authority(program_id, bets_info.key).0)?;
+        *mint_info.owner
+    } else {
+        Pubkey::default()
+    };
+    // The config is recognized by its address, a registry page may come without it.
+    let config_info = match account_info_iter.as_slice().first() {
+        Some(info) if cmp_pubkeys(info.key, &config_address(program_id).0) => account_info_iter.next(),
+        _ => None,
+    };
+    let config = _load_config(program_id, config_info)?;
+    _check_not_halted(config.as_ref())?;
+    if !rent.is_exempt(bets_info.lamports(), bets_info.data_len()) {
+        msg!("Instruction: _process_initialize: no exempt, size={}", bets_info.data_len());
+        return Err(ProgramError::InvalidAccountData)
+    }
+    if bets_info.lamports() < rent.minimum_balance(bets_info.data_len()) + params.bond_lamports {
+        msg!("Instruction: _process_initialize: bond of {} is not deposited", params.bond_lamports);
+        return Err(ProgramError::InsufficientFunds)
+    }
+
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_initialize: wrong owner");
+        return Err(ProgramError::InvalidAccountData)
+    }
+    
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    if bets.is_initialized() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let min_deadline_offset_secs = config.as_ref().map_or(0, |config| config.min_deadline_offset_secs);
+    _check_deadline_ahead(params.bets_accepted_until, _now()?, min_deadline_offset_secs)?;
+    if params.dispute_window_secs < 0 || params.finality_delay_secs < 0 || params.correction_window_secs < 0 || params.correction_cooldown_secs < 0 {
+        msg!("Instruction: _process_initialize: negative dispute window, finality delay, correction window or cooldown");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (default_commission_bps, max_commission_bps) = config
+        .as_ref()
+        .map_or((COMISSION_BPS, MAX_COMISSION_BPS), |config| (config.default_commission_bps, config.max_commission_bps));
+    // The margin of a fixed-odds event is in its odds, it takes no commission.
+    let commission_bps = if params.fixed_odds {
+        0
+    } else if params.commission_bps == USE_DEFAULT_COMISSION {
+        default_commission_bps
+    } else {
+        params.commission_bps
+    };
+    if commission_bps > max_commission_bps {
+        msg!("Instruction: _process_initialize: commission {} bps is above {} bps", commission_bps, max_commission_bps);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let line_ok = match params.market_kind {
+        MARKET_KIND_MONEYLINE => params.handicap == 0,
+        MARKET_KIND_HANDICAP => params.handicap.checked_abs().map_or(false, |line| line <= MAX_HANDICAP),
+        MARKET_KIND_TOTALS => params.handicap > 0 && params.handicap <= MAX_TOTALS_LINE && params.handicap % 2 == 1,
+        _ => false,
+    };
+    if !line_ok {
+        msg!("Instruction: _process_initialize: market kind {} with line {}", params.market_kind, params.handicap);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if params.fixed_odds && params.mint != Pubkey::default() {
+        msg!("Instruction: _process_initialize: fixed odds are only for SOL events");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    // An empty token account must not pass the gate.
+    if params.gate_mint != Pubkey::default() && params.gate_min_amount == 0 {
+        msg!("Instruction: _process_initialize: gate_min_amount must be at least 1");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    bets.version = STATE_VERSION;
+    bets.arbiter = *owner.key;
+    bets.outcome = 0u8;
+    bets.bets_allowed_until_ts = params.bets_accepted_until;
+    bets.balance_a = 0;
+    bets.balance_b = 0;
+    bets.dispute_window_secs = params.dispute_window_secs;
+    bets.winner_set_at = 0;
+    bets.finality_delay_secs = params.finality_delay_secs;
+    bets.bond_lamports = params.bond_lamports;
+    bets.challenger = params.challenger;
+    bets.bond_slashed = false;
+    bets.commission_bps = commission_bps;
+    bets.min_bet_lamports = params.min_bet_lamports;
+    bets.max_stake_per_wallet = params.max_stake_per_wallet;
+    bets.max_bets_per_wallet = params.max_bets_per_wallet;
+    bets.max_imbalance_ratio = params.max_imbalance_ratio;
+    bets.bonus_lamports = 0;
+    bets.bonus_commission_free = params.bonus_commission_free;
+    bets.gate_mint = params.gate_mint;
+    bets.gate_min_amount = params.gate_min_amount;
+    bets.mint = params.mint;
+    bets.token_program = token_program;
+    bets.metadata_hash = params.metadata_hash;
+    bets.bets_count_a = 0;
+    bets.bets_count_b = 0;
+    bets.total_paid_out = 0;
+    bets.bump = bump;
+    bets.seed = [0; EVENT_SEED_BYTES];
+    bets.seed[..seed.len()].copy_from_slice(seed);
+    bets.fixed_odds = params.fixed_odds;
+    bets.odds_a = Odds::default();
+    bets.odds_b = Odds::default();
+    bets.liability_a = 0;
+    bets.liability_b = 0;
+    bets.worst_case_liability = 0;
+    bets.tournament = Pubkey::default();
+    bets.market_kind = params.market_kind;
+    bets.handicap = params.handicap;
+    bets.outstanding_bets = 0;
+    bets.commission_accrued = 0;
+    bets.correction_window_secs = params.correction_window_secs;
+    bets.claims_count = 0;
+    bets.finalized = false;
+    bets.unique_bettors = 0;
+    bets.allowlisted = false;
+    bets.denylisted = false;
+    bets.arbiter_may_bet = params.arbiter_may_bet;
+    bets.correction_cooldown_secs = params.correction_cooldown_secs;
+    bets.last_result_change_at = 0;
+    bets.commission_recipient = if params.commission_recipient == Pubkey::default() { *owner.key } else { params.commission_recipient };
+
+    if let Some(page_info) = account_info_iter.next() {
+        _register_event(program_id, page_info, bets_info.key)?;
+    }
+
+    _store_state(&bets, bets_info)?;
+    events::emit(&events::ProgramEvent::EventInitialized(events::EventInitialized {
+        event: *bets_info.key,
+        arbiter: bets.arbiter,
+        deadline: bets.bets_allowed_until_ts,
+    }))
+}
+
+fn _register_event(program_id: &Pubkey, page_info: &AccountInfo, event: &Pubkey) -> ProgramResult {
+    if !cmp_pubkeys(program_id, page_info.owner) {
+        msg!("Instruction: _register_event: wrong owner for registry page {}", page_info.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let mut page = RegistryPage::deserialize(&mut &page_info.data.borrow()[..])?;
+    if !page.is_initialized || !cmp_pubkeys(&registry_address(program_id, page.page).0, page_info.key) {
+        msg!("Instruction: _register_event: {} is not a registry page", page_info.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    let slot = page.count as usize;
+    if slot >= REGISTRY_PAGE_CAPACITY {
+        msg!("Instruction: _register_event: page {} is full, create page {}", page.page, page.page + 1);
+        return Err(BetError::RegistryPageFull.into());
+    }
+
+    verbose_msg!("Event {} registered at index {}", event, page.page as u64 * REGISTRY_PAGE_CAPACITY as u64 + slot as u64);
+    let start = RegistryPage::HEADER_LEN + slot * PUBKEY_BYTES;
+    page_info.data.borrow_mut()[start..start + PUBKEY_BYTES].copy_from_slice(event.as_ref());
+    page.count += 1;
+    page.serialize(&mut &mut page_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _process_create_registry_page(program_id: &Pubkey, accounts: &[AccountInfo], page: u32) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_info = next_account_info(account_info_iter)?;
+    let page_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let (address, bump) = registry_address(program_id, page);
+    if !cmp_pubkeys(&address, page_info.key) {
+        msg!("Instruction: _process_create_registry_page: page {} must live at {}", page, address);
+        return Err(ProgramError::InvalidArgument)
+    }
+    if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+        msg!("Instruction: _process_create_registry_page: wrong system program");
+        return Err(ProgramError::IncorrectProgramId)
+    }
+    if page > 0 {
+        let previous_info = next_account_info(account_info_iter)?;
+        if !cmp_pubkeys(&registry_address(program_id, page - 1).0, previous_info.key) || !cmp_pubkeys(program_id, previous_info.owner) {
+            msg!("Instruction: _process_create_registry_page: {} is not page {}", previous_info.key, page - 1);
+            return Err(ProgramError::InvalidArgument)
+        }
+        let previous = RegistryPage::deserialize(&mut &previous_info.data.borrow()[..])?;
+        if (previous.count as usize) < REGISTRY_PAGE_CAPACITY {
+            msg!("Instruction: _process_create_registry_page: page {} still has room", page - 1);
+            return Err(ProgramError::InvalidArgument)
+        }
+    }
+
+    // Fails on its own if the page already exists.
+    invoke_signed(
+        &system_instruction::create_account(
+            payer_info.key,
+            page_info.key,
+            Rent::get()?.minimum_balance(RegistryPage::LEN),
+            RegistryPage::LEN as u64,
+            program_id,
+        ),
+        &[payer_info.clone(), page_info.clone(), system_program_info.clone()],
+        &[&[REGISTRY_SEED, &page.to_le_bytes(), &[bump]]],
+    )?;
+    let header = RegistryPage { is_initialized: true, page, count: 0 };
+    header.serialize(&mut &mut page_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// Reads the global config when the optional account is passed, making sure it's the real one.
+fn _load_config(program_id: &Pubkey, config_info: Option<&AccountInfo>) -> Result<Option<Config>, ProgramError> {
+    let config_info = match config_info {
+        Some(config_info) => config_info,
+        None => return Ok(None),
+    };
+    if !cmp_pubkeys(&config_address(program_id).0, config_info.key) || !cmp_pubkeys(program_id, config_info.owner) {
+        msg!("Instruction: _load_config: {} is not the config account", config_info.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    let config = Config::deserialize(&mut &config_info.data.borrow()[..])?;
+    if !config.is_initialized {
+        msg!("Instruction: _load_config: config is not Initialized...");
+        return Err(ProgramError::UninitializedAccount);
+    }
+    Ok(Some(config))
+}
+
+fn _validate_config_params(params: &ConfigParams) -> ProgramResult {
+    if params.max_commission_bps as u64 >= BPS_DENOMINATOR || params.default_commission_bps > params.max_commission_bps {
+        msg!("Instruction: _validate_config_params: bad commission bounds {} / {} bps", params.default_commission_bps, params.max_commission_bps);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if params.min_deadline_offset_secs < 0 {
+        msg!("Instruction: _validate_config_params: negative deadline offset");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if params.arbiter_share_bps as u64 > BPS_DENOMINATOR {
+        msg!("Instruction: _validate_config_params: arbiter share above 100%");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if params.referral_bps as u64 > BPS_DENOMINATOR {
+        msg!("Instruction: _validate_config_params: referral share above 100%");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(())
+}
+
+// Makes sure `treasury_info` is the program-owned, rent-exempt treasury PDA.
+fn _check_treasury<'a, 'b>(program_id: &Pubkey, treasury_info: &'a AccountInfo<'b>) -> Result<&'a AccountInfo<'b>, ProgramError> {
+    if !cmp_pubkeys(&treasury_address(program_id).0, treasury_info.key) || !cmp_pubkeys(program_id, treasury_info.owner) {
+        msg!("Instruction: _check_treasury: {} is not the treasury account", treasury_info.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    if !Rent::get()?.is_exempt(treasury_info.lamports(), treasury_info.data_len()) {
+        msg!("Instruction: _check_treasury: treasury is not rent exempt");
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    Ok(treasury_info)
+}
+
+fn _process_initialize_config(program_id: &Pubkey, accounts: &[AccountInfo], params: &ConfigParams) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    if !admin_info.is_signer || !cmp_pubkeys(admin_info.key, &admin::id()) {
+        msg!("Instruction: _process_initialize_config: only {} can create the config", admin::id());
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    let (address, bump) = config_address(program_id);
+    if !cmp_pubkeys(&address, config_info.key) {
+        msg!("Instruction: _process_initialize_config: config must live at {}", address);
+        return Err(ProgramError::InvalidArgument)
+    }
+    let (treasury, treasury_bump) = treasury_address(program_id);
+    if !cmp_pubkeys(&treasury, treasury_info.key) {
+        msg!("Instruction: _process_initialize_config: treasury must live at {}", treasury);
+        return Err(ProgramError::InvalidArgument)
+    }
+    if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+        msg!("Instruction: _process_initialize_config: wrong system program");
+        return Err(ProgramError::IncorrectProgramId)
+    }
+    _validate_config_params(params)?;
+
+    // Fails on its own if the config already exists.
+    invoke_signed(
+        &system_instruction::create_account(
+            admin_info.key,
+            config_info.key,
+            Rent::get()?.minimum_balance(Config::LEN),
+            Config::LEN as u64,
+            program_id,
+        ),
+        &[admin_info.clone(), config_info.clone(), system_program_info.clone()],
+        &[&[CONFIG_SEED, &[bump]]],
+    )?;
+    invoke_signed(
+        &system_instruction::create_account(
+            admin_info.key,
+            treasury_info.key,
+            Rent::get()?.minimum_balance(0),
+            0,
+            program_id,
+        ),
+        &[admin_info.clone(), treasury_info.clone(), system_program_info.clone()],
+        &[&[TREASURY_SEED, &[treasury_bump]]],
+    )?;
+
+    let config = Config {
+        is_initialized: true,
+        admin: *admin_info.key,
+        default_commission_bps: params.default_commission_bps,
+        max_commission_bps: params.max_commission_bps,
+        min_deadline_offset_secs: params.min_deadline_offset_secs,
+        arbiter_share_bps: params.arbiter_share_bps,
+        referral_bps: params.referral_bps,
+        halted: false,
+    };
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _process_update_config(program_id: &Pubkey, accounts: &[AccountInfo], params: &ConfigParams) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mut config = _load_config(program_id, Some(config_info))?.ok_or(ProgramError::UninitializedAccount)?;
+    if !admin_info.is_signer || !cmp_pubkeys(admin_info.key, &config.admin) {
+        msg!("Instruction: _process_update_config: only {} can update the config", config.admin);
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    _validate_config_params(params)?;
+
+    config.default_commission_bps = params.default_commission_bps;
+    config.max_commission_bps = params.max_commission_bps;
+    config.min_deadline_offset_secs = params.min_deadline_offset_secs;
+    config.arbiter_share_bps = params.arbiter_share_bps;
+    config.referral_bps = params.referral_bps;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _process_set_halted(program_id: &Pubkey, accounts: &[AccountInfo], halted: bool) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mut config = _load_config(program_id, Some(config_info))?.ok_or(ProgramError::UninitializedAccount)?;
+    if !admin_info.is_signer || !cmp_pubkeys(admin_info.key, &config.admin) {
+        msg!("Instruction: _process_set_halted: only {} can halt the program", config.admin);
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+
+    verbose_msg!("Program halted: {}", halted);
+    config.halted = halted;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _check_not_halted(config: Option<&Config>) -> ProgramResult {
+    if config.map_or(false, |config| config.halted) {
+        msg!("Instruction: _check_not_halted: the program is halted");
+        return Err(BetError::ProgramHalted.into());
+    }
+    Ok(())
+}
+
+fn _process_withdraw_treasury(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let treasury_info = _check_treasury(program_id, next_account_info(account_info_iter)?)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+    let config = _load_config(program_id, Some(config_info))?.ok_or(ProgramError::UninitializedAccount)?;
+    if !admin_info.is_signer || !cmp_pubkeys(admin_info.key, &config.admin) {
+        msg!("Instruction: _process_withdraw_treasury: only {} can withdraw", config.admin);
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if amount > treasury_info.lamports() {
+        msg!("Instruction: _process_withdraw_treasury: {} is above the balance of {}", amount, treasury_info.lamports());
+        return Err(ProgramError::InsufficientFunds)
+    }
+    let available = treasury_info.lamports().saturating_sub(Rent::get()?.minimum_balance(treasury_info.data_len()));
+    if amount > available {
+        msg!("Instruction: _process_withdraw_treasury: only {} is available above rent exemption", available);
+        return Err(BetError::TreasuryBelowRentExemption.into())
+    }
+
+    verbose_msg!("Sending {} lamports from {} to {}", amount, treasury_info.key, recipient_info.key);
+    _move_lamports(treasury_info, recipient_info, amount)
+}
+
+fn _process_add_bet(program_id: &Pubkey, accounts: &[AccountInfo], choice: MatchOutcome, referrer: Pubkey, amount: u64, memo: [u8; MEMO_BYTES]) -> ProgramResult {
+    // What can go wrong?
+    // `bets_info_acc` does not belong to our program, and someone scams our users.
+    // `this_bet_acc` does not belong to our program, again possible scam, but actually don't think it is achievable.
+    // `this_bet_acc` does not have enough funds to be rent excepmpted. Pretty bad, users may be disappointed.
+    // `bets_info` is wrong, uninitnalized - users can be scammed by betting to something else.
+    // `bets_info.bets_allowed_until_ts` is in the past.
+    // `bets_info.outcome` is not yet set (it should not, but just in case)...
+
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?; 
+    _add_bet(program_id, betor, betor.key, account_info_iter, choice, referrer, amount, memo)
+}
+
+fn _process_add_bet_for(program_id: &Pubkey, accounts: &[AccountInfo], choice: MatchOutcome, beneficiary: Pubkey, amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let funder = next_account_info(account_info_iter)?;
+    // Unlike AddBet the betor doesn't sign, whoever pays for the bet does.
+    if !funder.is_signer {
+        msg!("Instruction: _process_add_bet_for: funder must sign");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    verbose_msg!("Bet funded by {} for {}", funder.key, beneficiary);
+    _add_bet(program_id, funder, &beneficiary, account_info_iter, choice, Pubkey::default(), amount, [0; MEMO_BYTES])
+}
+
+// AddBet and AddBetFor from the bets account on. `payer` signs for the tokens of an SPL token event
+// and gets back the lamports left on the bet account above rent and the stake in SOL events,
+// the bet is `betor`'s, who also has to pass the token gate and is held to the stake cap.
+#[allow(clippy::too_many_arguments)]
+fn _add_bet<'a, 'b>(
+    program_id: &Pubkey,
+    payer: &'a AccountInfo<'b>,
+    betor: &Pubkey,
+    account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    choice: MatchOutcome,
+    referrer: Pubkey,
+    amount: u64,
+    memo: [u8; MEMO_BYTES],
+) -> ProgramResult {
+    let bets_info_acc = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+
+    verbose_msg!("betor = {}, bets_info = {}, this_bet_acc = {}", betor, bets_info_acc.key, this_bet_acc.key);
+    if !cmp_pubkeys(program_id, bets_info_acc.owner) {
+        msg!("Instruction: _process_add_bet: wrong owner for event {}", bets_info_acc.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    if !cmp_pubkeys(program_id, this_bet_acc.owner) {
+        msg!("Instruction: _process_add_bet: wrong owner for event {}", this_bet_acc.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    
+    if cmp_pubkeys(&referrer, betor) {
+        msg!("Instruction: _process_add_bet: betor can't be their own referrer");
+        return Err(ProgramError::InvalidArgument)
+    }
+    _check_writable_state(bets_info_acc, EventBets::LEN)?;
+    _check_writable_state(this_bet_acc, Bet::LEN)?;
+    // The first byte is Bet::version, a placed bet is refused before deserializing anything.
+    if this_bet_acc.data.borrow()[0] != 0 {
+        msg!("Instruction: _process_add_bet: Bet is already Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut bets = _load_initialized::<EventBets>(bets_info_acc)?;
+    _check_bets_open(&bets, _now()?)?;
+    _check_arbiter_may_bet(&bets, betor)?;
+    let token_stake = if bets.mint != Pubkey::default() {
+        Some(_deposit_tokens(program_id, bets_info_acc.key, &bets, payer, account_info_iter, amount)?)
+    } else {
+        None
+    };
+    _check_betor_admitted(program_id, bets_info_acc.key, &bets, betor, account_info_iter)?;
+    let stake = _open_bet(bets_info_acc, this_bet_acc, betor, choice, referrer, memo, amount, token_stake, &mut bets)?;
+    if token_stake.is_none() {
+        let rent_exemption = Rent::get()?.minimum_balance(this_bet_acc.data_len());
+        let surplus = this_bet_acc.lamports().checked_sub(rent_exemption).ok_or(BetError::ArithmeticOverflow)?;
+        if surplus > 0 {
+            msg!("Instruction: _process_add_bet: refunding {} lamports above the stake to {}", surplus, payer.key);
+            _move_lamports(this_bet_acc, payer, surplus)?;
+        }
+    }
+    if stake < bets.min_bet_lamports {
+        msg!("Instruction: _process_add_bet: stake {} is below the minimum of {}", stake, bets.min_bet_lamports);
+        return Err(BetError::BelowMinimumBet.into());
+    }
+    if bets.caps_wallets() {
+        _track_wallet_stake(program_id, bets_info_acc.key, betor, account_info_iter, stake, 1, &bets)?;
+    }
+    // The seen marker and the stats accounts come before the optional config, told apart by their addresses.
+    if let Some(next_info) = account_info_iter.as_slice().first() {
+        let (address, bump) = seen_address(program_id, bets_info_acc.key, betor);
+        if cmp_pubkeys(&address, next_info.key) && _mark_seen(program_id, bets_info_acc.key, betor, payer, bump, account_info_iter)? {
+            bets.unique_bettors = bets.unique_bettors.checked_add(1).ok_or(BetError::ArithmeticOverflow)?;
+        }
+    }
+    if let Some(next_info) = account_info_iter.as_slice().first() {
+        let (address, bump) = stats_address(program_id, betor);
+        if cmp_pubkeys(&address, next_info.key) {
+            if token_stake.is_some() {
+                msg!("Instruction: _process_add_bet: stats only count SOL events");
+                return Err(ProgramError::InvalidArgument);
+            }
+            let stats_info = _create_bettor_stats(program_id, betor, bump, account_info_iter)?;
+            _update_bettor_stats(program_id, stats_info, |stats| {
+                stats.staked_total = stats.staked_total.checked_add(stake)?;
+                stats.bets_placed = stats.bets_placed.checked_add(1)?;
+                Some(())
+            })?;
+        }
+    }
+    _check_not_halted(_load_config(program_id, account_info_iter.next())?.as_ref())?;
+    _check_imbalance(&bets)?;
+    if !bets.liability_covered() {
+        msg!("Instruction: _process_add_bet: liability of {} is not covered", bets.worst_case_liability);
+        return Err(BetError::ExposureExceeded.into());
+    }
+    let count = match choice {
+        MatchOutcome::TeamA => &mut bets.bets_count_a,
+        _ => &mut bets.bets_count_b,
+    };
+    *count = count.checked_add(1).ok_or(BetError::ArithmeticOverflow)?;
+
+    _store_pools(&bets, bets_info_acc)?;
+    _emit_bet_placed(bets_info_acc.key, this_bet_acc.key, betor, choice, stake)
+}
+
+fn _check_arbiter_may_bet(bets: &EventBets, betor: &Pubkey) -> ProgramResult {
+    if !bets.arbiter_may_bet && (cmp_pubkeys(betor, &bets.arbiter) || cmp_pubkeys(betor, &bets.commission_recipient)) {
+        msg!("Instruction: _process_add_bet: {} settles or earns from the event and can't bet on it", betor);
+        return Err(BetError::ArbiterCannotBet.into());
+    }
+    Ok(())
+}
+
+// Checks the token gate, the allowlist and the denylist of the event, whose accounts follow in that order.
+fn _check_betor_admitted<'a, 'b>(
+    program_id: &Pubkey,
+    event: &Pubkey,
+    bets: &EventBets,
+    betor: &Pubkey,
+    account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+) -> ProgramResult {
+    if bets.gate_mint != Pubkey::default() {
+        _check_token_gate(bets, betor, next_account_info(account_info_iter)?)?;
+    }
+    if bets.allowlisted && !_is_listed(program_id, event, ALLOWLIST_SEED, betor, next_account_info(account_info_iter)?)? {
+        msg!("Instruction: _process_add_bet: {} is not on the allowlist", betor);
+        return Err(BetError::NotAllowlisted.into());
+    }
+    if bets.denylisted && _is_listed(program_id, event, DENYLIST_SEED, betor, next_account_info(account_info_iter)?)? {
+        msg!("Instruction: _process_add_bet: {} is on the denylist", betor);
+        return Err(BetError::BettorDenied.into());
+    }
+    Ok(())
+}
+
+fn _emit_bet_placed(event: &Pubkey, bet: &Pubkey, betor: &Pubkey, choice: MatchOutcome, amount: u64) -> ProgramResult {
+    events::emit(&events::ProgramEvent::BetPlaced(events::BetPlaced {
+        event: *event,
+        bet: *bet,
+        betor: *betor,
+        outcome: u8::from(choice),
+        amount,
+    }))
+}
+
+fn _process_increase_bet(program_id: &Pubkey, accounts: &[AccountInfo], choice: MatchOutcome, amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let bets_info_acc = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    // Only the betor, a bigger stake moves their wallet cap and their exposure.
+    if !betor.is_signer {
+        msg!("Instruction: _process_increase_bet: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    _check_writable_state(bets_info_acc, EventBets::LEN)?;
+    _check_writable_state(this_bet_acc, Bet::LEN)?;
+
+    let (mut bets, mut this_bet) = _load_owned_bet(program_id, bets_info_acc, this_bet_acc, betor.key)?;
+    _check_bets_open(&bets, _now()?)?;
+    if bets.fixed_odds {
+        msg!("Instruction: _process_increase_bet: odds are locked with the stake, place another bet");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if this_bet.outcome != u8::from(choice) {
+        msg!("Instruction: _process_increase_bet: bet is on {}, not {}", this_bet.outcome, u8::from(choice));
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    _check_arbiter_may_bet(&bets, betor.key)?;
+    let token_stake = if bets.mint != Pubkey::default() {
+        Some(_deposit_tokens(program_id, bets_info_acc.key, &bets, betor, account_info_iter, amount)?)
+    } else {
+        None
+    };
+    _check_betor_admitted(program_id, bets_info_acc.key, &bets, betor.key, account_info_iter)?;
+    let added = match token_stake {
+        Some(added) => added,
+        None => {
+            // Like AddBet: `amount` lamports of the bet account, all above rent for 0, the surplus goes back to the betor.
+            let rent_exemption = Rent::get()?.minimum_balance(this_bet_acc.data_len());
+            let available = this_bet_acc.lamports().saturating_sub(rent_exemption);
+            let added = if amount == 0 { available } else { amount };
+            if added > available {
+                msg!("Instruction: _process_increase_bet: bet account holds {} above rent, {} declared", available, amount);
+                return Err(ProgramError::InsufficientFunds);
+            }
+            verbose_msg!("Sending {} from {} to {}", added, this_bet_acc.key, bets_info_acc.key);
+            _move_lamports(this_bet_acc, bets_info_acc, added)?;
+            if available > added {
+                msg!("Instruction: _process_increase_bet: refunding {} lamports above the stake to {}", available - added, betor.key);
+                _move_lamports(this_bet_acc, betor, available - added)?;
+            }
+            added
+        },
+    };
+    if added == 0 {
+        msg!("Instruction: _process_increase_bet: nothing to add");
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    this_bet.amount = this_bet.amount.checked_add(added).ok_or(BetError::ArithmeticOverflow)?;
+    let (pool, referred) = match choice {
+        MatchOutcome::TeamA => (&mut bets.balance_a, &mut bets.referred_a),
+        _ => (&mut bets.balance_b, &mut bets.referred_b),
+    };
+    *pool = pool.checked_add(added).ok_or(BetError::ArithmeticOverflow)?;
+    if this_bet.referrer != Pubkey::default() {
+        *referred = referred.checked_add(added).ok_or(BetError::ArithmeticOverflow)?;
+    }
+    if bets.caps_wallets() {
+        _track_wallet_stake(program_id, bets_info_acc.key, betor.key, account_info_iter, added, 0, &bets)?;
+    }
+    _check_not_halted(_load_config(program_id, account_info_iter.next())?.as_ref())?;
+    _check_imbalance(&bets)?;
+
+    _store_state(&this_bet, this_bet_acc)?;
+    _store_state(&bets, bets_info_acc)?;
+    Ok(())
+}
+
+fn _process_cash_out(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let bets_info_acc = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    if !betor.is_signer {
+        msg!("Instruction: _process_cash_out: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    for info in &[bets_info_acc, this_bet_acc] {
+        if !cmp_pubkeys(program_id, info.owner) {
+            msg!("Instruction: _process_cash_out: wrong owner for {}", info.key);
+            return Err(ProgramError::InvalidAccountData)
+        }
+    }
+    _check_writable_state(bets_info_acc, EventBets::LEN)?;
+    _check_writable_state(this_bet_acc, Bet::LEN)?;
+
+    let mut bets = _load_state::<EventBets>(bets_info_acc)?;
+    let mut this_bet = _load_state::<Bet>(this_bet_acc)?;
+    // Only while bets are taken, once the match is over the losing side would cash out of the winners' pot.
+    _check_bets_open(&bets, _now()?)?;
+    if bets.mint != Pubkey::default() || bets.fixed_odds {
+        msg!("Instruction: _process_cash_out: not available in SPL token and fixed-odds events");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if !this_bet.is_initialized() || !cmp_pubkeys(bets_info_acc.key, &this_bet.event) {
+        msg!("Instruction: _process_cash_out: not a bet on {}", bets_info_acc.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !cmp_pubkeys(&this_bet.betor, betor.key) || this_bet.receipt_mint != Pubkey::default() {
+        msg!("Instruction: _process_cash_out: bet doesn't belong to {}", betor.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let value = cash_out_value(&bets, &this_bet, bets.commission_bps)?;
+    let stake = this_bet.amount;
+    let (pool, referred, count) = match MatchOutcome::try_from(this_bet.outcome)? {
+        MatchOutcome::TeamA => (&mut bets.balance_a, &mut bets.referred_a, &mut bets.bets_count_a),
+        MatchOutcome::TeamB => (&mut bets.balance_b, &mut bets.referred_b, &mut bets.bets_count_b),
+        _ => {
+            msg!("Instruction: _process_cash_out: bet {} is closed", this_bet_acc.key);
+            return Err(ProgramError::InvalidAccountData);
+        },
+    };
+    *pool = pool.checked_sub(stake).ok_or(BetError::ArithmeticOverflow)?;
+    if this_bet.referrer != Pubkey::default() {
+        *referred = referred.checked_sub(stake).ok_or(BetError::ArithmeticOverflow)?;
+    }
+    *count = count.saturating_sub(1);
+    bets.outstanding_bets = bets.outstanding_bets.saturating_sub(1);
+    bets.bonus_lamports = bets.bonus_lamports.checked_add(stake - value).ok_or(BetError::ArithmeticOverflow)?;
+    if bets.caps_wallets() {
+        _release_wallet_stake(program_id, bets_info_acc.key, betor.key, next_account_info(account_info_iter)?, stake, 1)?;
+    }
+
+    verbose_msg!("Cashing out {} of {} from {} to {}", value, stake, bets_info_acc.key, betor.key);
+    _move_lamports(bets_info_acc, betor, value)?;
+    this_bet.outcome = u8::from(MatchOutcome::Withdrawn);
+    this_bet.claimed = value;
+
+    _store_state(&this_bet, this_bet_acc)?;
+    _store_state(&bets, bets_info_acc)?;
+    Ok(())
+}
+
+fn _process_void_bet(program_id: &Pubkey, accounts: &[AccountInfo], reason: u8) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let arbiter = next_account_info(account_info_iter)?;
+    let bets_info_acc = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    let betor = next_account_info(account_info_iter)?;
+    if !arbiter.is_signer {
+        msg!("Instruction: _process_void_bet: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    for info in &[bets_info_acc, this_bet_acc] {
+        if !cmp_pubkeys(program_id, info.owner) {
+            msg!("Instruction: _process_void_bet: wrong owner for {}", info.key);
+            return Err(ProgramError::InvalidAccountData)
+        }
+    }
+    _check_writable_state(bets_info_acc, EventBets::LEN)?;
+    _check_writable_state(this_bet_acc, Bet::LEN)?;
+
+    let mut bets = _load_state::<EventBets>(bets_info_acc)?;
+    let mut this_bet = _load_state::<Bet>(this_bet_acc)?;
+    if !bets.is_initialized() || !cmp_pubkeys(&bets.arbiter, arbiter.key) {
+        msg!("Instruction: _process_void_bet: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if result_outcome(bets.outcome)? != MatchOutcome::Unknown {
+        msg!("Instruction: _process_void_bet: only before the result is set");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !this_bet.is_initialized() || !cmp_pubkeys(bets_info_acc.key, &this_bet.event) {
+        msg!("Instruction: _process_void_bet: not a bet on {}", bets_info_acc.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !cmp_pubkeys(&this_bet.betor, betor.key) {
+        msg!("Instruction: _process_void_bet: bet belongs to {}", this_bet.betor);
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let stake = this_bet.amount;
+    let (pool, referred, count, liability) = match MatchOutcome::try_from(this_bet.outcome) {
+        Ok(MatchOutcome::TeamA) => (&mut bets.balance_a, &mut bets.referred_a, &mut bets.bets_count_a, &mut bets.liability_a),
+        Ok(MatchOutcome::TeamB) => (&mut bets.balance_b, &mut bets.referred_b, &mut bets.bets_count_b, &mut bets.liability_b),
+        _ => {
+            msg!("Instruction: _process_void_bet: bet {} is withdrawn or voided already", this_bet_acc.key);
+            return Err(ProgramError::InvalidAccountData);
+        },
+    };
+    *pool = pool.checked_sub(stake).ok_or(BetError::ArithmeticOverflow)?;
+    if let Some(payout) = this_bet.locked_odds.payout(stake) {
+        *liability = liability.checked_sub(payout).ok_or(BetError::ArithmeticOverflow)?;
+        bets.worst_case_liability = std::cmp::max(bets.liability_a, bets.liability_b);
+    }
+    if this_bet.referrer != Pubkey::default() {
+        *referred = referred.checked_sub(stake).ok_or(BetError::ArithmeticOverflow)?;
+    }
+    *count = count.saturating_sub(1);
+    bets.outstanding_bets = bets.outstanding_bets.saturating_sub(1);
+
+    verbose_msg!("Voiding bet {} of {} with reason {}, refunding {}", this_bet_acc.key, betor.key, reason, stake);
+    if bets.mint == Pubkey::default() {
+        _move_lamports(bets_info_acc, betor, stake)?;
+    } else {
+        let betor_token_info = next_account_info(account_info_iter)?;
+        _check_token_account(betor_token_info, &bets.mint, betor.key)?;
+        let vault = _load_vault(program_id, bets_info_acc.key, &bets, account_info_iter)?;
+        _pay_from_vault(&vault, bets_info_acc.key, betor_token_info, stake)?;
+    }
+    // Like a cash out, the voided stake and its slot are the betor's to use again.
+    if bets.caps_wallets() {
+        _release_wallet_stake(program_id, bets_info_acc.key, betor.key, next_account_info(account_info_iter)?, stake, 1)?;
+    }
+    this_bet.outcome = u8::from(MatchOutcome::Voided);
+
+    _store_state(&this_bet, this_bet_acc)?;
+    _store_state(&bets, bets_info_acc)?;
+    Ok(())
+}
+
+fn _process_reset_bet(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    if !betor.is_signer {
+        msg!("Instruction: _process_reset_bet: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, this_bet_acc.owner) {
+        msg!("Instruction: _process_reset_bet: wrong owner for bet {}", this_bet_acc.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_writable_state(this_bet_acc, Bet::LEN)?;
+
+    let this_bet = _load_initialized::<Bet>(this_bet_acc)?;
+    if !cmp_pubkeys(&this_bet.betor, betor.key) {
+        msg!("Instruction: _process_reset_bet: bet belongs to {}", this_bet.betor);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // Only closed bets, the event still counts any other one in outstanding_bets.
+    if !MatchOutcome::try_from(this_bet.outcome)?.is_closed_bet() {
+        msg!("Instruction: _process_reset_bet: bet {} on {} is not withdrawn or voided", this_bet_acc.key, this_bet.event);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    verbose_msg!("Resetting bet {} of {}", this_bet_acc.key, betor.key);
+    // All zero is what AddBet expects of a new bet account.
+    this_bet_acc.data.borrow_mut()[..Bet::LEN].fill(0);
+    Ok(())
+}
+
+fn _process_transfer_bet(program_id: &Pubkey, accounts: &[AccountInfo], new_betor: Pubkey) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let bets_info_acc = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    if !betor.is_signer {
+        msg!("Instruction: _process_transfer_bet: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    let (bets, mut this_bet) = _load_owned_bet(program_id, bets_info_acc, this_bet_acc, betor.key)?;
+    _reassign_bet(program_id, &bets, bets_info_acc.key, &mut this_bet, new_betor, account_info_iter)?;
+    _store_state(&this_bet, this_bet_acc)?;
+    Ok(())
+}
+
+fn _process_set_delegate(program_id: &Pubkey, accounts: &[AccountInfo], delegate: Pubkey) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let bets_info_acc = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    if !betor.is_signer {
+        msg!("Instruction: _process_set_delegate: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    _check_writable_state(this_bet_acc, Bet::LEN)?;
+    let (_bets, mut this_bet) = _load_owned_bet(program_id, bets_info_acc, this_bet_acc, betor.key)?;
+
+    verbose_msg!("Bet {} of {} is delegated to {}", this_bet_acc.key, betor.key, delegate);
+    this_bet.delegate = delegate;
+    _store_state(&this_bet, this_bet_acc)?;
+    Ok(())
+}
+
+// Loads a bet of `betor` on the event that is not withdrawn yet.
+fn _load_owned_bet(program_id: &Pubkey, bets_info_acc: &AccountInfo, this_bet_acc: &AccountInfo, betor: &Pubkey) -> Result<(EventBets, Bet), ProgramError> {
+    for info in &[bets_info_acc, this_bet_acc] {
+        if !cmp_pubkeys(program_id, info.owner) {
+            msg!("Instruction: _load_owned_bet: wrong owner for {}", info.key);
+            return Err(ProgramError::InvalidAccountData)
+        }
+    }
+    let bets = _load_state::<EventBets>(bets_info_acc)?;
+    let this_bet = _load_state::<Bet>(this_bet_acc)?;
+    if !this_bet.is_initialized() || !cmp_pubkeys(bets_info_acc.key, &this_bet.event) {
+        msg!("Instruction: _load_owned_bet: not a bet on {}", bets_info_acc.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !cmp_pubkeys(&this_bet.betor, betor) {
+        msg!("Instruction: _load_owned_bet: bet belongs to {}", this_bet.betor);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if this_bet.outcome == u8::from(MatchOutcome::Withdrawn) || this_bet.outcome == u8::from(MatchOutcome::Voided) {
+        msg!("Instruction: _load_owned_bet: bet is already withdrawn or voided");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if this_bet.receipt_mint != Pubkey::default() {
+        msg!("Instruction: _load_owned_bet: bet is tokenized, it belongs to the holder of {}", this_bet.receipt_mint);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok((bets, this_bet))
+}
+
+// Makes `new_betor` the owner of the bet, moving its stake between the wallets' allowances
+// on capped events. Any listing and delegate are dropped, the new owner didn't ask for them.
+fn _reassign_bet(program_id: &Pubkey, bets: &EventBets, event: &Pubkey, this_bet: &mut Bet, new_betor: Pubkey, account_info_iter: &mut std::slice::Iter<AccountInfo>) -> ProgramResult {
+    if cmp_pubkeys(&new_betor, &this_bet.betor) {
+        msg!("Instruction: _reassign_bet: bet already belongs to {}", new_betor);
+        return Err(ProgramError::InvalidArgument);
+    }
+    if bets.caps_wallets() {
+        _release_wallet_stake(program_id, event, &this_bet.betor, next_account_info(account_info_iter)?, this_bet.amount, 1)?;
+        _track_wallet_stake(program_id, event, &new_betor, account_info_iter, this_bet.amount, 1, bets)?;
+    }
+    verbose_msg!("Bet goes from {} to {}", this_bet.betor, new_betor);
+    this_bet.betor = new_betor;
+    this_bet.for_sale = false;
+    this_bet.ask_lamports = 0;
+    this_bet.delegate = Pubkey::default();
+    Ok(())
+}
+
+fn _process_list_bet(program_id: &Pubkey, accounts: &[AccountInfo], price: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let bets_info_acc = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    if !betor.is_signer {
+        msg!("Instruction: _process_list_bet: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    let (bets, mut this_bet) = _load_owned_bet(program_id, bets_info_acc, this_bet_acc, betor.key)?;
+    if price > 0 && result_outcome(bets.outcome)? != MatchOutcome::Unknown {
+        msg!("Instruction: _process_list_bet: the result is already set");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    this_bet.for_sale = price > 0;
+    this_bet.ask_lamports = price;
+    _store_state(&this_bet, this_bet_acc)?;
+    Ok(())
+}
+
+fn _process_buy_bet(program_id: &Pubkey, accounts: &[AccountInfo], price: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let buyer = next_account_info(account_info_iter)?;
+    let seller = next_account_info(account_info_iter)?;
+    let bets_info_acc = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    if !buyer.is_signer {
+        msg!("Instruction: _process_buy_bet: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    let (bets, mut this_bet) = _load_owned_bet(program_id, bets_info_acc, this_bet_acc, seller.key)?;
+    // Once the result is known the price no longer matters, a winning bet would be free money.
+    if result_outcome(bets.outcome)? != MatchOutcome::Unknown {
+        msg!("Instruction: _process_buy_bet: the result is already set");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !this_bet.for_sale || this_bet.ask_lamports != price {
+        msg!("Instruction: _process_buy_bet: bet is not for sale at {}", price);
+        return Err(ProgramError::InvalidArgument);
+    }
+    if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+        msg!("Instruction: _process_buy_bet: wrong system program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    verbose_msg!("Sending {} lamports from {} to {}", price, buyer.key, seller.key);
+    invoke(
+        &system_instruction::transfer(buyer.key, seller.key, price),
+        &[buyer.clone(), seller.clone(), system_program_info.clone()],
+    )?;
+    _reassign_bet(program_id, &bets, bets_info_acc.key, &mut this_bet, *buyer.key, account_info_iter)?;
+    _store_state(&this_bet, this_bet_acc)?;
+    Ok(())
+}
+
+fn _process_tokenize_bet(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let bets_info_acc = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let holder_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let ata_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    if !betor.is_signer {
+        msg!("Instruction: _process_tokenize_bet: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    let (_bets, mut this_bet) = _load_owned_bet(program_id, bets_info_acc, this_bet_acc, betor.key)?;
+
+    let (mint, bump) = receipt_mint_address(program_id, this_bet_acc.key);
+    if !cmp_pubkeys(&mint, mint_info.key) {
+        msg!("Instruction: _process_tokenize_bet: receipt mint must be {}", mint);
+        return Err(ProgramError::InvalidArgument);
+    }
+    if !cmp_pubkeys(&spl_associated_token_account::get_associated_token_address(betor.key, &mint), holder_info.key) {
+        msg!("Instruction: _process_tokenize_bet: {} is not the betor's associated token account", holder_info.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    if !cmp_pubkeys(token_program_info.key, &spl_token::id())
+        || !cmp_pubkeys(ata_program_info.key, &spl_associated_token_account::id())
+        || !cmp_pubkeys(system_program_info.key, &system_program::id())
+    {
+        msg!("Instruction: _process_tokenize_bet: wrong program account");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let seeds: &[&[u8]] = &[RECEIPT_SEED, this_bet_acc.key.as_ref(), &[bump]];
+    let mint_len = spl_token::state::Mint::LEN;
+    invoke_signed(
+        &system_instruction::create_account(betor.key, &mint, Rent::from_account_info(rent_info)?.minimum_balance(mint_len), mint_len as u64, &spl_token::id()),
+        &[betor.clone(), mint_info.clone(), system_program_info.clone()],
+        &[seeds],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_mint(&spl_token::id(), &mint, &mint, None, 0)?,
+        &[mint_info.clone(), rent_info.clone(), token_program_info.clone()],
+    )?;
+    invoke(
+        &spl_associated_token_account::create_associated_token_account(betor.key, betor.key, &mint),
+        &[betor.clone(), holder_info.clone(), mint_info.clone(), system_program_info.clone(), token_program_info.clone(), rent_info.clone(), ata_program_info.clone()],
+    )?;
+    invoke_signed(
+        &spl_token::instruction::mint_to(&spl_token::id(), &mint, holder_info.key, &mint, &[], 1)?,
+        &[mint_info.clone(), holder_info.clone(), token_program_info.clone()],
+        &[seeds],
+    )?;
+    // Nobody can mint a second one.
+    invoke_signed(
+        &spl_token::instruction::set_authority(&spl_token::id(), &mint, None, spl_token::instruction::AuthorityType::MintTokens, &mint, &[])?,
+        &[mint_info.clone(), token_program_info.clone()],
+        &[seeds],
+    )?;
+
+    verbose_msg!("Bet {} is now held through {}", this_bet_acc.key, mint);
+    this_bet.receipt_mint = mint;
+    // The token's holder withdraws from now on.
+    this_bet.delegate = Pubkey::default();
+    _store_state(&this_bet, this_bet_acc)?;
+    Ok(())
+}
+
+// Burns the bet's receipt token held by `holder`, who must sign.
+fn _burn_receipt<'a, 'b>(this_bet: &Bet, holder: &'a AccountInfo<'b>, account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>) -> ProgramResult {
+    let token_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    if !holder.is_signer {
+        msg!("Instruction: _burn_receipt: the holder must sign");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !cmp_pubkeys(mint_info.key, &this_bet.receipt_mint) || !cmp_pubkeys(token_program_info.key, &spl_token::id()) {
+        msg!("Instruction: _burn_receipt: receipt mint must be {}", this_bet.receipt_mint);
+        return Err(ProgramError::InvalidArgument);
+    }
+    _check_token_account(token_info, &this_bet.receipt_mint, holder.key)?;
+    verbose_msg!("Burning the receipt of {} held by {}", this_bet.receipt_mint, holder.key);
+    invoke(
+        &spl_token::instruction::burn(&spl_token::id(), token_info.key, mint_info.key, holder.key, &[], 1)?,
+        &[token_info.clone(), mint_info.clone(), holder.clone(), token_program_info.clone()],
+    )
+}
+
+fn _check_token_gate(bets: &EventBets, betor: &Pubkey, token_info: &AccountInfo) -> ProgramResult {
+    let token_account = _unpack_token_account(token_info)?;
+    if !cmp_pubkeys(&token_account.mint, &bets.gate_mint) || !cmp_pubkeys(&token_account.owner, betor) {
+        msg!("Instruction: _check_token_gate: token account must be {}'s account of {}", betor, bets.gate_mint);
+        return Err(BetError::TokenGateNotPassed.into());
+    }
+    if token_account.amount < bets.gate_min_amount {
+        msg!("Instruction: _check_token_gate: holds {}, {} required", token_account.amount, bets.gate_min_amount);
+        return Err(BetError::TokenGateNotPassed.into());
+    }
+    Ok(())
+}
+
+// Reads the base state of an SPL Token or Token-2022 account, ignoring Token-2022 extensions.
+fn _unpack_token_account(token_info: &AccountInfo) -> Result<spl_token::state::Account, ProgramError> {
+    if !is_token_program(token_info.owner) {
+        msg!("Instruction: _unpack_token_account: {} is not a token account", token_info.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = token_info.data.borrow();
+    let token_account = spl_token::state::Account::unpack_from_slice(data.get(..spl_token::state::Account::LEN).ok_or(ProgramError::InvalidAccountData)?)?;
+    if !token_account.is_initialized() {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    Ok(token_account)
+}
+
+// Checks the mint belongs to a token program and has no extension that could trap the vault, returns its decimals.
+fn _check_mint(mint_info: &AccountInfo) -> Result<u8, ProgramError> {
+    use spl_token::state::{Account, Mint};
+    if !is_token_program(mint_info.owner) {
+        msg!("Instruction: _check_mint: {} is not a mint", mint_info.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = mint_info.data.borrow();
+    let mint = Mint::unpack_from_slice(data.get(..Mint::LEN).ok_or(ProgramError::InvalidAccountData)?)?;
+    if !mint.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    // Token-2022 extensions follow the account type byte, as u16 type / u16 length / value entries.
+    let mut extensions = data.get(Account::LEN + 1..).unwrap_or(&[]);
+    while extensions.len() >= 4 {
+        let extension_type = u16::from_le_bytes([extensions[0], extensions[1]]);
+        let length = u16::from_le_bytes([extensions[2], extensions[3]]) as usize;
+        if REJECTED_MINT_EXTENSIONS.contains(&extension_type) {
+            msg!("Instruction: _check_mint: mint extension {} is not supported", extension_type);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        extensions = extensions.get(4 + length..).unwrap_or(&[]);
+    }
+    Ok(mint.decimals)
+}
+
+// Makes sure `token_info` is a token account of `mint` owned by `owner`.
+fn _check_token_account(token_info: &AccountInfo, mint: &Pubkey, owner: &Pubkey) -> ProgramResult {
+    let token_account = _unpack_token_account(token_info)?;
+    if !cmp_pubkeys(&token_account.mint, mint) || !cmp_pubkeys(&token_account.owner, owner) {
+        msg!("Instruction: _check_token_account: {} must be {}'s account of {}", token_info.key, owner, mint);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+// The vault of an SPL token event and what it takes to move tokens out of it.
+struct Vault<'a, 'b> {
+    vault: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    mint: &'a AccountInfo<'b>,
+    token_program: &'a AccountInfo<'b>,
+    bump: u8,
+    decimals: u8,
+}
+
+fn _load_vault<'a, 'b>(program_id: &Pubkey, event: &Pubkey, bets: &EventBets, account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>) -> Result<Vault<'a, 'b>, ProgramError> {
+    let vault = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let mint = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let (authority_address, bump) = vault_authority(program_id, event);
+    if !cmp_pubkeys(&authority_address, authority.key) || !cmp_pubkeys(&vault_address(program_id, event, &bets.mint, &bets.token_program), vault.key) {
+        msg!("Instruction: _load_vault: wrong vault or vault authority");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if !cmp_pubkeys(&bets.mint, mint.key) || !cmp_pubkeys(&bets.token_program, token_program.key) {
+        msg!("Instruction: _load_vault: wrong mint or token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let decimals = _check_mint(mint)?;
+    Ok(Vault { vault, authority, mint, token_program, bump, decimals })
+}
+
+// TransferChecked is the one transfer Token-2022 accepts for every mint, the spl_token
+// builder refuses other program ids so the instruction is put together here.
+fn _transfer_checked(vault: &Vault, source: &Pubkey, destination: &Pubkey, authority: &Pubkey, amount: u64) -> solana_program::instruction::Instruction {
+    use solana_program::instruction::{AccountMeta, Instruction as TokenProgramInstruction};
+    TokenProgramInstruction {
+        program_id: *vault.token_program.key,
+        accounts: vec![
+            AccountMeta::new(*source, false),
+            AccountMeta::new_readonly(*vault.mint.key, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data: spl_token::instruction::TokenInstruction::TransferChecked { amount, decimals: vault.decimals }.pack(),
+    }
+}
+
+fn _pay_from_vault(vault: &Vault, event: &Pubkey, to: &AccountInfo, amount: u64) -> ProgramResult {
+    verbose_msg!("Sending {} tokens from {} to {}", amount, vault.vault.key, to.key);
+    invoke_signed(
+        &_transfer_checked(vault, vault.vault.key, to.key, vault.authority.key, amount),
+        &[vault.vault.clone(), vault.mint.clone(), to.clone(), vault.authority.clone(), vault.token_program.clone()],
+        &[&[VAULT_SEED, event.as_ref(), &[vault.bump]]],
+    )
+}
+
+// Moves `amount` tokens from the betor's token account to the vault. The stake is what the vault
+// actually received, Token-2022 transfer fees are withheld from it.
+fn _deposit_tokens(program_id: &Pubkey, event: &Pubkey, bets: &EventBets, betor: &AccountInfo, account_info_iter: &mut std::slice::Iter<AccountInfo>, amount: u64) -> Result<u64, ProgramError> {
+    let source = next_account_info(account_info_iter)?;
+    let vault = _load_vault(program_id, event, bets, account_info_iter)?;
+    if !betor.is_signer {
+        msg!("Instruction: _deposit_tokens: betor must sign the token transfer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let before = _unpack_token_account(vault.vault)?.amount;
+    verbose_msg!("Sending {} tokens from {} to {}", amount, source.key, vault.vault.key);
+    invoke(
+        &_transfer_checked(&vault, source.key, vault.vault.key, betor.key, amount),
+        &[source.clone(), vault.mint.clone(), vault.vault.clone(), betor.clone(), vault.token_program.clone()],
+    )?;
+    let after = _unpack_token_account(vault.vault)?.amount;
+    after.checked_sub(before).ok_or_else(|| BetError::ArithmeticOverflow.into())
+}
+
+// Unpacks the state at the start of `info`'s data, which may be longer.
+fn _load_state<T: Pack>(info: &AccountInfo) -> Result<T, ProgramError> {
+    let data = info.data.borrow();
+    T::unpack_from_slice(data.get(..T::LEN).ok_or(ProgramError::InvalidAccountData)?)
+}
+
+// Pack::unpack, an account nothing was written to yet is refused with UninitializedAccount.
+fn _load_initialized<T: Pack + IsInitialized>(info: &AccountInfo) -> Result<T, ProgramError> {
+    let data = info.data.borrow();
+    T::unpack(data.get(..T::LEN).ok_or(ProgramError::InvalidAccountData)?)
+}
+
+fn _store_state<T: Pack>(state: &T, info: &AccountInfo) -> ProgramResult {
+    let mut data = info.data.borrow_mut();
+    state.pack_into_slice(data.get_mut(..T::LEN).ok_or(ProgramError::InvalidAccountData)?);
+    Ok(())
+}
+
+// Narrow writes of what a handler changed, leaving the other bytes of the account as they are.
+
+// The totals AddBet changes.
+fn _store_pools(bets: &EventBets, bets_info: &AccountInfo) -> ProgramResult {
+    let mut data = bets_info.data.borrow_mut();
+    let view = pod::load_mut::<pod::EventBetsPod>(&mut data[..])?;
+    view.balance_a.set(bets.balance_a);
+    view.balance_b.set(bets.balance_b);
+    view.referred_a.set(bets.referred_a);
+    view.referred_b.set(bets.referred_b);
+    view.liability_a.set(bets.liability_a);
+    view.liability_b.set(bets.liability_b);
+    view.worst_case_liability.set(bets.worst_case_liability);
+    view.bets_count_a.set(bets.bets_count_a);
+    view.bets_count_b.set(bets.bets_count_b);
+    view.outstanding_bets.set(bets.outstanding_bets);
+    view.unique_bettors.set(bets.unique_bettors);
+    Ok(())
+}
+
+// The totals a withdrawal changes.
+fn _store_payout_totals(bets: &EventBets, bets_info: &AccountInfo) -> ProgramResult {
+    let mut data = bets_info.data.borrow_mut();
+    let view = pod::load_mut::<pod::EventBetsPod>(&mut data[..])?;
+    view.total_paid_out.set(bets.total_paid_out);
+    view.referral_reserve.set(bets.referral_reserve);
+    view.outstanding_bets.set(bets.outstanding_bets);
+    view.commission_accrued.set(bets.commission_accrued);
+    view.claims_count.set(bets.claims_count);
+    view.finalized.set(bets.finalized);
+    Ok(())
+}
+
+// The bet fields a withdrawal changes, the owner ones for a redeemed receipt.
+fn _store_claim(this_bet: &Bet, this_bet_acc: &AccountInfo) -> ProgramResult {
+    let mut data = this_bet_acc.data.borrow_mut();
+    let view = pod::load_mut::<pod::BetPod>(&mut data[..])?;
+    view.betor = this_bet.betor;
+    view.receipt_mint = this_bet.receipt_mint;
+    view.claimed.set(this_bet.claimed);
+    view.outcome = this_bet.outcome;
+    Ok(())
+}
+
+// The checks on one of our state accounts that need no deserialization, run before it:
+// the account is writable and long enough to hold `len` bytes of state.
+fn _check_writable_state(info: &AccountInfo, len: usize) -> ProgramResult {
+    if !info.is_writable {
+        msg!("Instruction: _check_writable_state: {} is not writable", info.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    if info.data_len() < len {
+        msg!("Instruction: _check_writable_state: {} holds {} bytes, needs {}", info.key, info.data_len(), len);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+// The cluster's unix time. The deadline checks below take it as `now` instead of reading the sysvar
+// themselves, so that unit tests can run them at any timestamp without a bank.
+fn _now() -> Result<UnixTimestamp, ProgramError> {
+    Ok(Clock::get()?.unix_timestamp)
+}
+
+// An event's deadline is at least `min_deadline_offset_secs` ahead, exactly that far is enough.
+fn _check_deadline_ahead(bets_accepted_until: UnixTimestamp, now: UnixTimestamp, min_deadline_offset_secs: i64) -> ProgramResult {
+    if bets_accepted_until < now.saturating_add(min_deadline_offset_secs) {
+        msg!("Bets accepted until {} but now it is {}, minimal offset {}", bets_accepted_until, now, min_deadline_offset_secs);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(())
+}
+
+// The result can be set from the deadline's second on, while bets are still taken in that second.
+fn _check_deadline_passed(bets: &EventBets, now: UnixTimestamp) -> ProgramResult {
+    if now < bets.bets_allowed_until_ts {
+        msg!("Instruction: _check_deadline_passed: too early, bets are accepted until {}", bets.bets_allowed_until_ts);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+fn _check_bets_open(bets: &EventBets, now: UnixTimestamp) -> ProgramResult {
+    if !bets.is_initialized() {
+        msg!("Instruction: _process_add_bet: BetInfo should be Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if now > bets.bets_allowed_until_ts {
+        msg!("Instruction: _process_add_bet: too late, bets are no longer accepted");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if result_outcome(bets.outcome)? != MatchOutcome::Unknown {
+        msg!("Betting on completed match");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if bets.paused {
+        msg!("Instruction: _process_add_bet: event is paused");
+        return Err(BetError::EventPaused.into());
+    }
+    Ok(())
+}
+
+// Records a bet of `betor`. Its stake is `token_stake` when the tokens are already in the vault,
+// otherwise `amount` lamports of `this_bet_acc` (all above rent for 0), which are moved to the event.
+// The caller still has to serialize `bets`.
+#[allow(clippy::too_many_arguments)]
+fn _open_bet(bets_info_acc: &AccountInfo, this_bet_acc: &AccountInfo, betor: &Pubkey, choice: MatchOutcome, referrer: Pubkey, memo: [u8; MEMO_BYTES], amount: u64, token_stake: Option<u64>, bets: &mut EventBets) -> Result<u64, ProgramError> {
+    // Written in place, the rest of the bet is zero until someone sets it.
+    let mut bet_data = this_bet_acc.data.borrow_mut();
+    let this_bet = pod::load_mut::<pod::BetPod>(&mut bet_data[..])?;
+    if this_bet.version != 0 {
+        msg!("Instruction: _process_add_bet: Bet is already Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let rent_exemption = Rent::get()?.minimum_balance(bet_data.len());
+    if this_bet_acc.lamports() < rent_exemption {
+        msg!("Instruction: _process_add_bet: bet account is not rent exempt");
+        return Err(ProgramError::InsufficientFunds);
+    }
+    let available = this_bet_acc.lamports() - rent_exemption;
+    let stake = match token_stake {
+        Some(stake) => stake,
+        None if amount == 0 => available,
+        None if amount <= available => amount,
+        None => {
+            msg!("Instruction: _process_add_bet: bet account holds {} above rent, {} declared", available, amount);
+            return Err(ProgramError::InsufficientFunds);
+        },
+    };
+
+    verbose_msg!("Adding {} for resolution {}", this_bet_acc.lamports(), u8::from(choice));
+    this_bet.version = STATE_VERSION;
+    this_bet.outcome = u8::from(choice);
+    this_bet.betor = *betor;
+    this_bet.amount.set(stake);
+    this_bet.event = *bets_info_acc.key;
+    this_bet.referrer = referrer;
+    this_bet.memo = memo;
+    this_bet.placed_at.set(_now()?);
+
+    let referred = if referrer == Pubkey::default() { 0 } else { stake };
+    let (pool, referred_pool, odds, liability) = match choice {
+        MatchOutcome::TeamA => (&mut bets.balance_a, &mut bets.referred_a, bets.odds_a, &mut bets.liability_a),
+        MatchOutcome::TeamB => (&mut bets.balance_b, &mut bets.referred_b, bets.odds_b, &mut bets.liability_b),
+        _ => { return Err(ProgramError::InvalidAccountData); },
+    };
+    *pool = pool.checked_add(stake).ok_or(BetError::ArithmeticOverflow)?;
+    *referred_pool = referred_pool.checked_add(referred).ok_or(BetError::ArithmeticOverflow)?;
+    if bets.fixed_odds {
+        if !odds.is_set() {
+            msg!("Instruction: _process_add_bet: no odds posted for {}", u8::from(choice));
+            return Err(ProgramError::InvalidArgument);
+        }
+        this_bet.locked_odds = odds.into();
+        *liability = odds.payout(stake)
+            .and_then(|payout| liability.checked_add(payout))
+            .ok_or(BetError::ArithmeticOverflow)?;
+        bets.worst_case_liability = std::cmp::max(bets.liability_a, bets.liability_b);
+    }
+    bets.outstanding_bets = bets.outstanding_bets.checked_add(1).ok_or(BetError::ArithmeticOverflow)?;
+
+    if token_stake.is_none() {
+        verbose_msg!("Sending funds from {} to {}", this_bet_acc.key, bets_info_acc.key);
+        // Leaves the bet at its rent exemption and any surplus over `amount`.
+        _move_lamports(this_bet_acc, bets_info_acc, stake)?;
+    }
+    Ok(stake)
+}
+
+fn _process_seed_liquidity(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    let bet_a_acc = next_account_info(account_info_iter)?;
+    let bet_b_acc = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_seed_liquidity: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    for info in &[bets_info, bet_a_acc, bet_b_acc] {
+        if !cmp_pubkeys(program_id, info.owner) {
+            msg!("Instruction: _process_seed_liquidity: wrong owner for {}", info.key);
+            return Err(ProgramError::InvalidAccountData)
+        }
+    }
+
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    _check_bets_open(&bets, _now()?)?;
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_seed_liquidity: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if bets.mint != Pubkey::default() || bets.fixed_odds {
+        msg!("Instruction: _process_seed_liquidity: only for parimutuel SOL events, use TopUpPot for fixed odds");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let seed_a = _open_bet(bets_info, bet_a_acc, owner.key, MatchOutcome::TeamA, Pubkey::default(), [0; MEMO_BYTES], 0, None, &mut bets)?;
+    let seed_b = _open_bet(bets_info, bet_b_acc, owner.key, MatchOutcome::TeamB, Pubkey::default(), [0; MEMO_BYTES], 0, None, &mut bets)?;
+    verbose_msg!("Seeded {} on TeamA and {} on TeamB", seed_a, seed_b);
+    _check_imbalance(&bets)?;
+
+    _store_state(&bets, bets_info)?;
+    _emit_bet_placed(bets_info.key, bet_a_acc.key, owner.key, MatchOutcome::TeamA, seed_a)?;
+    _emit_bet_placed(bets_info.key, bet_b_acc.key, owner.key, MatchOutcome::TeamB, seed_b)
+}
+
+// There is no cancellation yet. bonus_lamports also holds the fees CashOut left in the pot, so a cancel
+// path could not simply send all of it back to bets.arbiter.
+fn _process_top_up_pot(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_top_up_pot: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_top_up_pot: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+        msg!("Instruction: _process_top_up_pot: wrong system program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    if !bets.is_initialized() {
+        msg!("Instruction: _process_top_up_pot: not Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_top_up_pot: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if bets.mint != Pubkey::default() {
+        msg!("Instruction: _process_top_up_pot: only for SOL events");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // Winners take their share of bonus_lamports as they withdraw, a later top-up would skip the ones already paid.
+    if result_outcome(bets.outcome)? != MatchOutcome::Unknown {
+        msg!("Instruction: _process_top_up_pot: result is already set");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    bets.bonus_lamports = bets.bonus_lamports.checked_add(amount).ok_or(BetError::ArithmeticOverflow)?;
+    verbose_msg!("Sending {} lamports from {} to {}", amount, owner.key, bets_info.key);
+    invoke(
+        &system_instruction::transfer(owner.key, bets_info.key, amount),
+        &[owner.clone(), bets_info.clone(), system_program_info.clone()],
+    )?;
+    _store_state(&bets, bets_info)?;
+    Ok(())
+}
+
+fn _process_set_odds(program_id: &Pubkey, accounts: &[AccountInfo], odds_a: Odds, odds_b: Odds) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_set_odds: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_set_odds: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    _check_bets_open(&bets, _now()?)?;
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_set_odds: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !bets.fixed_odds {
+        msg!("Instruction: _process_set_odds: not a fixed-odds event");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // Locked odds keep the liability as it is, but a VoidBet refunding a stake that backed the
+    // other side can leave it uncovered. No new odds until the arbiter tops up.
+    if !bets.liability_covered() {
+        msg!("Instruction: _process_set_odds: liability of {} is not covered, top up first", bets.worst_case_liability);
+        return Err(BetError::ExposureExceeded.into());
+    }
+    for odds in &[odds_a, odds_b] {
+        // Below 1 a winning bet would get less than its stake back.
+        if *odds != Odds::default() && (!odds.is_set() || odds.numerator < odds.denominator) {
+            msg!("Instruction: _process_set_odds: invalid odds {}/{}", odds.numerator, odds.denominator);
+            return Err(ProgramError::InvalidArgument);
+        }
+    }
+
+    verbose_msg!("Odds of {} are {}/{} and {}/{}", bets_info.key, odds_a.numerator, odds_a.denominator, odds_b.numerator, odds_b.denominator);
+    bets.odds_a = odds_a;
+    bets.odds_b = odds_b;
+    _store_state(&bets, bets_info)?;
+    Ok(())
+}
+
+fn _process_audit_event(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bets_info = next_account_info(account_info_iter)?;
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_audit_event: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let bets = _load_state::<EventBets>(bets_info)?;
+    if !bets.is_initialized() {
+        msg!("Instruction: _process_audit_event: not Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let outcome = match MatchOutcome::try_from(bets.outcome) {
+        Ok(MatchOutcome::Withdrawn) | Ok(MatchOutcome::Voided) | Err(_) => {
+            msg!("Audit: outcome {} is not a valid event result", bets.outcome);
+            return Err(BetError::InvalidOutcome.into());
+        },
+        Ok(outcome) => outcome,
+    };
+    verbose_msg!("Audit: outcome ok");
+
+    let settled = outcome != MatchOutcome::Unknown;
+    if bets.dispute_window_secs < 0
+        || bets.finality_delay_secs < 0
+        || bets.correction_window_secs < 0
+        || bets.correction_cooldown_secs < 0
+        || settled != (bets.winner_set_at != 0)
+        || (settled && bets.winner_set_at < bets.bets_allowed_until_ts)
+    {
+        verbose_msg!("Audit: deadline {}, result set at {}, dispute window {}, finality delay {}, correction window {}, correction cooldown {}",
+            bets.bets_allowed_until_ts, bets.winner_set_at, bets.dispute_window_secs, bets.finality_delay_secs,
+            bets.correction_window_secs, bets.correction_cooldown_secs);
+        return Err(BetError::DeadlinesOutOfOrder.into());
+    }
+    verbose_msg!("Audit: deadlines ok");
+
+    let pools = bets.balance_a as u128 + bets.balance_b as u128;
+    let owed = if settled {
+        // Bond and bonus shares are not tracked once paid, only the stake payouts can be checked.
+        if bets.total_paid_out as u128 > bets.payout_cap() {
+            msg!("Audit: paid out {} of {}", bets.total_paid_out, bets.payout_cap());
+            return Err(BetError::PayoutExceedsPools.into());
+        }
+        // In SOL events the pools net of commission are held until the winners withdraw them, unless every
+        // bet is closed and SweepDust may have taken the rounding left over. The commission charged so far
+        // is held until CollectCommission.
+        let unclaimed = if bets.mint == Pubkey::default() && bets.outstanding_bets > 0 {
+            bets.payout_cap() - bets.total_paid_out as u128
+        } else {
+            0
+        };
+        // A shared bonus goes out with the stake payouts, in proportion to them, so the unclaimed part
+        // of the payouts tells the part of the bonus still held.
+        let unclaimed_bonus = if !bets.fixed_odds && unclaimed > 0 {
+            bets.bonus_lamports as u128 * unclaimed / bets.payout_cap()
+        } else {
+            0
+        };
+        // A bond neither slashed nor released is the arbiter's until ReleaseBond.
+        let bond = if bets.bond_slashed { 0 } else { bets.bond_lamports as u128 };
+        unclaimed + unclaimed_bonus + bond + bets.commission_accrued as u128
+    } else {
+        if bets.total_paid_out != 0 {
+            msg!("Audit: paid out {} before the result", bets.total_paid_out);
+            return Err(BetError::PayoutExceedsPools.into());
+        }
+        // Token pools sit in the vault, only rent, bond and bonus are in lamports.
+        let lamport_pools = if bets.mint == Pubkey::default() { pools } else { 0 };
+        lamport_pools + bets.bond_lamports as u128 + bets.bonus_lamports as u128
+    };
+    verbose_msg!("Audit: payouts ok");
+
+    let required = Rent::get()?.minimum_balance(bets_info.data_len()) as u128 + owed;
+    if (bets_info.lamports() as u128) < required {
+        msg!("Audit: holds {} lamports, needs {}", bets_info.lamports(), required);
+        return Err(BetError::EventUnderfunded.into());
+    }
+    verbose_msg!("Audit: funds ok");
+    Ok(())
+}
+
+fn _process_quote_odds(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bets_info = next_account_info(account_info_iter)?;
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_quote_odds: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let bets = _load_state::<EventBets>(bets_info)?;
+    if !bets.is_initialized() {
+        msg!("Instruction: _process_quote_odds: not Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    set_return_data(&bets.quote_odds().try_to_vec()?);
+    Ok(())
+}
+
+// Checks the post-bet pools against max_imbalance_ratio, both ways.
+fn _check_imbalance(bets: &EventBets) -> ProgramResult {
+    if bets.max_imbalance_ratio == 0 {
+        return Ok(());
+    }
+    let limit = |pool: u64| bets.max_imbalance_ratio as u128 * std::cmp::max(pool, IMBALANCE_FLOOR_LAMPORTS) as u128;
+    if bets.balance_a as u128 > limit(bets.balance_b) || bets.balance_b as u128 > limit(bets.balance_a) {
+        msg!("Instruction: _check_imbalance: pools {} / {} are beyond the ratio of {}", bets.balance_a, bets.balance_b, bets.max_imbalance_ratio);
+        return Err(BetError::PoolImbalance.into());
+    }
+    Ok(())
+}
+
+// Whether `betor` is on the event's list at the PDA of `seed`.
+fn _is_listed(program_id: &Pubkey, event: &Pubkey, seed: &[u8], betor: &Pubkey, list_info: &AccountInfo) -> Result<bool, ProgramError> {
+    let (address, _) = Pubkey::find_program_address(&[seed, event.as_ref()], program_id);
+    if !cmp_pubkeys(&address, list_info.key) || !cmp_pubkeys(program_id, list_info.owner) {
+        msg!("Instruction: _is_listed: wrong list {}", list_info.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    let data = list_info.data.borrow();
+    Ok(WalletList::deserialize(&mut &data[..])?.position(&data, betor).is_some())
+}
+
+// Adds `stake` and `bets_added` to the betor's running totals on the event, creating the limit account
+// on the first bet. Betors don't sign AddBet, so anyone paying for a bet on someone's behalf also uses their allowance.
+#[allow(clippy::too_many_arguments)]
+fn _track_wallet_stake(
+    program_id: &Pubkey,
+    event: &Pubkey,
+    betor: &Pubkey,
+    account_info_iter: &mut std::slice::Iter<AccountInfo>,
+    stake: u64,
+    bets_added: u32,
+    bets: &EventBets,
+) -> ProgramResult {
+    let limit_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    let (address, bump) = limit_address(program_id, event, betor);
+    if !cmp_pubkeys(&address, limit_info.key) {
+        msg!("Instruction: _track_wallet_stake: limit account must be {}", address);
+        return Err(ProgramError::InvalidArgument);
+    }
+    if limit_info.data_len() == 0 {
+        if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+            msg!("Instruction: _track_wallet_stake: wrong system program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        invoke_signed(
+            &system_instruction::create_account(
+                payer_info.key,
+                limit_info.key,
+                Rent::get()?.minimum_balance(WalletStake::LEN),
+                WalletStake::LEN as u64,
+                program_id,
+            ),
+            &[payer_info.clone(), limit_info.clone(), system_program_info.clone()],
+            &[&[LIMIT_SEED, event.as_ref(), betor.as_ref(), &[bump]]],
+        )?;
+    } else if !cmp_pubkeys(program_id, limit_info.owner) {
+        msg!("Instruction: _track_wallet_stake: wrong owner for limit account {}", limit_info.owner);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut wallet_stake = WalletStake::deserialize(&mut &limit_info.data.borrow()[..])?;
+    let total_staked = wallet_stake.total_staked.checked_add(stake).ok_or(BetError::ArithmeticOverflow)?;
+    if bets.max_stake_per_wallet > 0 && total_staked > bets.max_stake_per_wallet {
+        msg!("Instruction: _track_wallet_stake: {} staked in total would be above the cap of {}", total_staked, bets.max_stake_per_wallet);
+        return Err(BetError::StakeCapExceeded.into());
+    }
+    let bets_open = wallet_stake.bets_open.checked_add(bets_added).ok_or(BetError::ArithmeticOverflow)?;
+    if bets.max_bets_per_wallet > 0 && bets_open > u32::from(bets.max_bets_per_wallet) {
+        msg!("Instruction: _track_wallet_stake: {} bets would be above the cap of {}", bets_open, bets.max_bets_per_wallet);
+        return Err(BetError::TooManyBets.into());
+    }
+    wallet_stake.is_initialized = true;
+    wallet_stake.total_staked = total_staked;
+    wallet_stake.bets_open = bets_open;
+    wallet_stake.serialize(&mut &mut limit_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// The seen marker followed by the system program, `payer` funds it. True if this call created it.
+fn _mark_seen<'a, 'b>(
+    program_id: &Pubkey,
+    event: &Pubkey,
+    betor: &Pubkey,
+    payer: &AccountInfo<'b>,
+    bump: u8,
+    account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+) -> Result<bool, ProgramError> {
+    let seen_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+        msg!("Instruction: _mark_seen: wrong system program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if cmp_pubkeys(program_id, seen_info.owner) {
+        return Ok(false);
+    }
+    invoke_signed(
+        &system_instruction::create_account(payer.key, seen_info.key, Rent::get()?.minimum_balance(0), 0, program_id),
+        &[payer.clone(), seen_info.clone(), system_program_info.clone()],
+        &[&[SEEN_SEED, event.as_ref(), betor.as_ref(), &[bump]]],
+    )?;
+    Ok(true)
+}
+
+// The betor's stats account, followed by a payer and the system program to create it on first use.
+fn _create_bettor_stats<'a, 'b>(program_id: &Pubkey, betor: &Pubkey, bump: u8, account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>) -> Result<&'a AccountInfo<'b>, ProgramError> {
+    let stats_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    if stats_info.data_len() == 0 {
+        if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+            msg!("Instruction: _create_bettor_stats: wrong system program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        invoke_signed(
+            &system_instruction::create_account(
+                payer_info.key,
+                stats_info.key,
+                Rent::get()?.minimum_balance(BettorStats::LEN),
+                BettorStats::LEN as u64,
+                program_id,
+            ),
+            &[payer_info.clone(), stats_info.clone(), system_program_info.clone()],
+            &[&[STATS_SEED, betor.as_ref(), &[bump]]],
+        )?;
+    }
+    Ok(stats_info)
+}
+
+// Applies `update` to a stats account the caller found at stats_address, None from it is an overflow.
+fn _update_bettor_stats(program_id: &Pubkey, stats_info: &AccountInfo, update: impl FnOnce(&mut BettorStats) -> Option<()>) -> ProgramResult {
+    if !cmp_pubkeys(program_id, stats_info.owner) || stats_info.data_len() != BettorStats::LEN {
+        msg!("Instruction: _update_bettor_stats: wrong stats account {}", stats_info.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let mut stats = BettorStats::deserialize(&mut &stats_info.data.borrow()[..])?;
+    stats.is_initialized = true;
+    update(&mut stats).ok_or(BetError::ArithmeticOverflow)?;
+    stats.serialize(&mut &mut stats_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// Takes `stake` and `bets_removed` back off the betor's running totals on the event.
+fn _release_wallet_stake(program_id: &Pubkey, event: &Pubkey, betor: &Pubkey, limit_info: &AccountInfo, stake: u64, bets_removed: u32) -> ProgramResult {
+    if !cmp_pubkeys(&limit_address(program_id, event, betor).0, limit_info.key) || !cmp_pubkeys(program_id, limit_info.owner) {
+        msg!("Instruction: _release_wallet_stake: wrong limit account {}", limit_info.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut wallet_stake = WalletStake::deserialize(&mut &limit_info.data.borrow()[..])?;
+    wallet_stake.total_staked = wallet_stake.total_staked.checked_sub(stake).ok_or(BetError::ArithmeticOverflow)?;
+    wallet_stake.bets_open = wallet_stake.bets_open.checked_sub(bets_removed).ok_or(BetError::ArithmeticOverflow)?;
+    wallet_stake.serialize(&mut &mut limit_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _process_set_winner(program_id: &Pubkey, accounts: &[AccountInfo], result: MatchOutcome, metadata_hash: [u8; METADATA_HASH_BYTES]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?; 
+    let bets_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_set_winner: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_set_winner: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_writable_state(bets_info, EventBets::LEN)?;
+    if result == MatchOutcome::Unknown {
+        msg!("Can not set result back to Unknown");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    if !bets.is_initialized() {
+        msg!("Instruction: _process_set_winner: not Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    _check_deadline_passed(&bets, _now()?)?;
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_set_winner: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // A zero hash on either side is a legacy event or a caller not confirming.
+    let unset = [0; METADATA_HASH_BYTES];
+    if bets.metadata_hash != unset && metadata_hash != unset && metadata_hash != bets.metadata_hash {
+        msg!("Instruction: _process_set_winner: metadata hash doesn't match event {}", bets_info.key);
+        return Err(BetError::EventMismatch.into());
+    }
+    _check_result_kind(&bets, result)?;
+    if result_outcome(bets.outcome)? != MatchOutcome::Unknown {
+        msg!("Instruction: _process_set_winner: result is already set, use CorrectResult");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let payout = _load_commission_payout(program_id, bets_info.key, &bets, account_info_iter)?;
+
+    _settle(bets_info, payout, &mut bets, result)
+}
+
+// Optional trailing config + treasury accounts of SetWinner and SetWinnerSigned.
+fn _load_commission_split<'a, 'b>(program_id: &Pubkey, account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>) -> Result<Option<(Config, &'a AccountInfo<'b>)>, ProgramError> {
+    match _load_config(program_id, account_info_iter.next())? {
+        Some(config) => {
+            let treasury_info = _check_treasury(program_id, next_account_info(account_info_iter)?)?;
+            Ok(Some((config, treasury_info)))
+        },
+        None => Ok(None),
+    }
+}
+
+// Where the commission goes at settlement.
+enum CommissionPayout<'a, 'b> {
+    // SOL events, with the config and treasury when passed.
+    Lamports(Option<(Config, &'a AccountInfo<'b>)>),
+    // SPL token events, all of it to the commission recipient's token account.
+    Tokens(&'a AccountInfo<'b>, Vault<'a, 'b>),
+}
+
+// Optional trailing accounts of SetWinner and SetWinnerSigned, depending on the event's mint.
+fn _load_commission_payout<'a, 'b>(program_id: &Pubkey, event: &Pubkey, bets: &EventBets, account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>) -> Result<CommissionPayout<'a, 'b>, ProgramError> {
+    if bets.mint == Pubkey::default() {
+        return Ok(CommissionPayout::Lamports(_load_commission_split(program_id, account_info_iter)?));
+    }
+    // SetWinnerSigned is relayed by anyone, the commission must reach the recipient's own account.
+    let recipient_token_info = next_account_info(account_info_iter)?;
+    _check_token_account(recipient_token_info, &bets.mint, &bets.commission_recipient)?;
+    let vault = _load_vault(program_id, event, bets, account_info_iter)?;
+    Ok(CommissionPayout::Tokens(recipient_token_info, vault))
+}
+
+// Referrer's part of `comission`, rounded down.
+pub fn referral_of(comission: u64, referral_bps: u16) -> u64 {
+    (comission as u128 * referral_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+// Splits the commission between the arbiter and the treasury. The arbiter's part
+// is rounded down, so the odd lamport always ends up in the treasury.
+pub fn split_commission(comission: u64, arbiter_share_bps: u16) -> (u64, u64) {
+    let arbiter_part = (comission as u128 * arbiter_share_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    (arbiter_part, comission - arbiter_part)
+}
+
+// The balances of `from` and `to` once `amount` moved between them. Moving all of `from` is fine,
+// more than that is InsufficientPot and a `to` that would go past u64::MAX ArithmeticOverflow.
+fn _lamport_balances_after(from: u64, to: u64, amount: u64) -> Result<(u64, u64), BetError> {
+    let from_balance = from.checked_sub(amount).ok_or(BetError::InsufficientPot)?;
+    let to_balance = to.checked_add(amount).ok_or(BetError::ArithmeticOverflow)?;
+    Ok((from_balance, to_balance))
+}
+
+fn _move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    let (from_balance, to_balance) = _lamport_balances_after(from.lamports(), to.lamports(), amount)?;
+    **from.try_borrow_mut_lamports()? = from_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
+    Ok(())
+}
+
+// Pays the commission of an SPL token event to commission_recipient, sets aside the referrers' reserve of a SOL
+// event, whose commission is charged by Withdraw, and stores the first result.
+fn _settle(bets_info: &AccountInfo, payout: CommissionPayout, bets: &mut EventBets, result: MatchOutcome) -> ProgramResult {
+    let commission = match payout {
+        CommissionPayout::Lamports(split) => {
+            // Referrers are only paid with a config, their part stays in the event until Withdraw.
+            bets.referral_bps = split.as_ref().map_or(0, |(config, _)| config.referral_bps);
+            bets.referral_reserve = referral_of(commission_of(bets.referred_gross(result), bets.commission_bps), bets.referral_bps);
+            0
+        },
+        CommissionPayout::Tokens(recipient_token_info, vault) => {
+            // Only the pools are in tokens, there is no bonus, referral or treasury part.
+            let pools = bets.balance_a.checked_add(bets.balance_b).ok_or(BetError::ArithmeticOverflow)?;
+            let commission = commission_of(pools, bets.commission_bps);
+            _pay_from_vault(&vault, bets_info.key, recipient_token_info, commission)?;
+            bets.referral_bps = 0;
+            bets.referral_reserve = 0;
+            commission
+        },
+    };
+
+    let line = || format!("{}.{}", bets.handicap.abs() / 2, bets.handicap.abs() % 2 * 5);
+    if bets.market_kind == MARKET_KIND_HANDICAP {
+        let line = format!("{}{}", if bets.handicap < 0 { "-" } else { "+" }, line());
+        match result {
+            MatchOutcome::TeamA => verbose_msg!("TeamA {} covered", line),
+            MatchOutcome::TeamB => verbose_msg!("TeamB covered against TeamA {}", line),
+            _ => verbose_msg!("Push on TeamA {}", line),
+        }
+    } else if bets.market_kind == MARKET_KIND_TOTALS {
+        verbose_msg!("{} {}", if result == MatchOutcome::OVER { "Over" } else { "Under" }, line());
+    }
+    bets.outcome = u8::from(result);
+    bets.winner_set_at = _now()?;
+    bets.last_result_change_at = bets.winner_set_at;
+    _store_state(bets, bets_info)?;
+
+    events::emit(&events::ProgramEvent::WinnerSet(events::WinnerSet { event: *bets_info.key, outcome: bets.outcome, commission }))
+}
+
+// Pays `comission` from the event to the commission recipient, and to the treasury if configured.
+fn _pay_lamport_commission(bets_info: &AccountInfo, recipient: &AccountInfo, split: Option<(Config, &AccountInfo)>, comission: u64) -> ProgramResult {
+    match split {
+        Some((config, treasury_info)) => {
+            let (arbiter_part, treasury_part) = split_commission(comission, config.arbiter_share_bps);
+            verbose_msg!("Sending {} to {} and {} to {}", arbiter_part, recipient.key, treasury_part, treasury_info.key);
+            _move_lamports(bets_info, recipient, arbiter_part)?;
+            _move_lamports(bets_info, treasury_info, treasury_part)?;
+        },
+        None => {
+            verbose_msg!("Sending funds from {} to {}", bets_info.key, recipient.key);
+            _move_lamports(bets_info, recipient, comission)?;
+        },
+    }
+    Ok(())
+}
+
+fn _process_collect_commission(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_collect_commission: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_collect_commission: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_writable_state(bets_info, EventBets::LEN)?;
+    let mut bets = _load_initialized::<EventBets>(bets_info)?;
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_collect_commission: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if bets.mint != Pubkey::default() {
+        msg!("Instruction: _process_collect_commission: SPL token events are paid their commission by SetWinner");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if result_outcome(bets.outcome)? == MatchOutcome::Unknown || _now()? < bets.result_final_at() {
+        msg!("Instruction: _process_collect_commission: result is final only at {}", bets.result_final_at());
+        return Err(BetError::ResultNotFinal.into());
+    }
+    let recipient = next_account_info(account_info_iter)?;
+    if !cmp_pubkeys(&bets.commission_recipient, recipient.key) {
+        msg!("Instruction: _process_collect_commission: commission recipient must be {}", bets.commission_recipient);
+        return Err(ProgramError::InvalidArgument);
+    }
+    let split = _load_commission_split(program_id, account_info_iter)?;
+
+    verbose_msg!("Collecting {} lamports of commission from {}", bets.commission_accrued, bets_info.key);
+    _pay_lamport_commission(bets_info, recipient, split, bets.commission_accrued)?;
+    bets.commission_accrued = 0;
+    _store_payout_totals(&bets, bets_info)?;
+    Ok(())
+}
+
+fn _process_finalize_result(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bets_info = next_account_info(account_info_iter)?;
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_finalize_result: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_writable_state(bets_info, EventBets::LEN)?;
+    let mut bets = _load_initialized::<EventBets>(bets_info)?;
+    if bets.finalized {
+        msg!("Instruction: _process_finalize_result: already final");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if result_outcome(bets.outcome)? == MatchOutcome::Unknown || _now()? < bets.correction_closes_at() {
+        msg!("Instruction: _process_finalize_result: the result can be corrected until {}", bets.correction_closes_at());
+        return Err(BetError::ResultNotFinal.into());
+    }
+
+    verbose_msg!("Result {} of {} is final", bets.outcome, bets_info.key);
+    bets.finalized = true;
+    _store_state(&bets, bets_info)?;
+
+    events::emit(&events::ProgramEvent::ResultFinalized(events::ResultFinalized { event: *bets_info.key, outcome: bets.outcome }))
+}
+
+fn _check_result_kind(bets: &EventBets, result: MatchOutcome) -> ProgramResult {
+    if bets.market_kind == MARKET_KIND_TOTALS && result == MatchOutcome::Draw {
+        msg!("Instruction: _check_result_kind: a totals market ends Over or Under");
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+fn _process_correct_result(program_id: &Pubkey, accounts: &[AccountInfo], result: MatchOutcome) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_correct_result: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_correct_result: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_writable_state(bets_info, EventBets::LEN)?;
+    if result == MatchOutcome::Unknown {
+        msg!("Can not set result back to Unknown");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    if !bets.is_initialized() {
+        msg!("Instruction: _process_correct_result: not Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_correct_result: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    _check_result_kind(&bets, result)?;
+    if result_outcome(bets.outcome)? == MatchOutcome::Unknown {
+        msg!("Instruction: _process_correct_result: nothing to correct, use SetWinner");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // Bets already paid out or marked withdrawn under the current result can't be redone.
+    if bets.claims_count > 0 {
+        msg!("Instruction: _process_correct_result: {} bets were withdrawn already", bets.claims_count);
+        return Err(BetError::ResultAlreadyClaimed.into());
+    }
+    let now = _now()?;
+    if bets.finalized || now >= bets.correction_closes_at() {
+        msg!("Instruction: _process_correct_result: result is final, the correction window closed at {}", bets.correction_closes_at());
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let cooldown_ends_at = bets.last_result_change_at.saturating_add(bets.correction_cooldown_secs);
+    if now < cooldown_ends_at {
+        msg!("Instruction: _process_correct_result: the result changed at {}, next correction from {}", bets.last_result_change_at, cooldown_ends_at);
+        return Err(BetError::CorrectionTooSoon.into());
+    }
+
+    verbose_msg!("Correcting result from {} to {}", bets.outcome, u8::from(result));
+    bets.outcome = u8::from(result);
+    // The referrers of the new winning side are owed instead, SetWinner set the referral_bps.
+    bets.referral_reserve = referral_of(commission_of(bets.referred_gross(result), bets.commission_bps), bets.referral_bps);
+    bets.last_result_change_at = now;
+    _store_state(&bets, bets_info)?;
+
+    events::emit(&events::ProgramEvent::WinnerSet(events::WinnerSet { event: *bets_info.key, outcome: bets.outcome, commission: 0 }))
+}
+
+// Checks that the instruction right before the current one is an ed25519_program
+// instruction which verified `signature` of `message` by `signer`.
+fn _verify_ed25519_instruction(instructions_info: &AccountInfo, signer: &Pubkey, message: &[u8], signature: &[u8; SIGNATURE_BYTES]) -> ProgramResult {
+    use sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+
+    if !cmp_pubkeys(instructions_info.key, &sysvar::instructions::id()) {
+        msg!("Instruction: _verify_ed25519_instruction: wrong instructions sysvar");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let current_index = load_current_index_checked(instructions_info)? as usize;
+    if current_index == 0 {
+        msg!("Instruction: _verify_ed25519_instruction: no ed25519 instruction before");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let ed25519_ix = load_instruction_at_checked(current_index - 1, instructions_info)?;
+    if !cmp_pubkeys(&ed25519_ix.program_id, &ed25519_program::id()) {
+        msg!("Instruction: _verify_ed25519_instruction: previous instruction is not ed25519");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Layout: num_signatures: u8, padding: u8, then a single Ed25519SignatureOffsets
+    // of seven u16: signature_offset, signature_instruction_index, public_key_offset,
+    // public_key_instruction_index, message_data_offset, message_data_size, message_instruction_index.
+    let data = &ed25519_ix.data;
+    if data.len() < 16 || data[0] != 1 {
+        msg!("Instruction: _verify_ed25519_instruction: expected exactly one signature");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let (signature_offset, signature_ix) = (read_u16(2), read_u16(4));
+    let (public_key_offset, public_key_ix) = (read_u16(6), read_u16(8));
+    let (message_offset, message_size, message_ix) = (read_u16(10), read_u16(12), read_u16(14));
+    // All the data must live inside the ed25519 instruction itself.
+    let this_ix = u16::MAX as usize;
+    if signature_ix != this_ix || public_key_ix != this_ix || message_ix != this_ix {
+        msg!("Instruction: _verify_ed25519_instruction: signature data in foreign instruction");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let signed_key = data.get(public_key_offset..public_key_offset + PUBKEY_BYTES);
+    let signed_signature = data.get(signature_offset..signature_offset + SIGNATURE_BYTES);
+    let signed_message = data.get(message_offset..message_offset + message_size);
+    if signed_key != Some(signer.as_ref()) {
+        msg!("Instruction: _verify_ed25519_instruction: signed by someone else");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if signed_signature != Some(&signature[..]) || signed_message != Some(message) {
+        msg!("Instruction: _verify_ed25519_instruction: signed message does not match");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+fn _process_set_winner_signed(program_id: &Pubkey, accounts: &[AccountInfo], result: MatchOutcome, event: Pubkey, signature: [u8; SIGNATURE_BYTES]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let arbiter = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    let instructions_info = next_account_info(account_info_iter)?;
+
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_set_winner_signed: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    if !cmp_pubkeys(&event, bets_info.key) {
+        msg!("Instruction: _process_set_winner_signed: result is signed for another event {}", event);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_writable_state(bets_info, EventBets::LEN)?;
+    if result == MatchOutcome::Unknown {
+        msg!("Can not set result back to Unknown");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    if !bets.is_initialized() {
+        msg!("Instruction: _process_set_winner_signed: not Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    _check_deadline_passed(&bets, _now()?)?;
+    if !cmp_pubkeys(&bets.arbiter, arbiter.key) {
+        msg!("Instruction: _process_set_winner_signed: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    _check_result_kind(&bets, result)?;
+    // The signed message is not bound to a nonce, so a result can be applied only once.
+    if result_outcome(bets.outcome)? != MatchOutcome::Unknown {
+        msg!("Instruction: _process_set_winner_signed: result is already set");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let payout = _load_commission_payout(program_id, bets_info.key, &bets, account_info_iter)?;
+
+    _verify_ed25519_instruction(instructions_info, &bets.arbiter, &result_message(&event, result), &signature)?;
+
+    _settle(bets_info, payout, &mut bets, result)
+}
+
+fn _process_slash_bond(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let challenger = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    if !challenger.is_signer {
+        msg!("Instruction: _process_slash_bond: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_slash_bond: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    if !bets.is_initialized() {
+        msg!("Instruction: _process_slash_bond: not Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if bets.challenger == Pubkey::default() || !cmp_pubkeys(&bets.challenger, challenger.key) {
+        msg!("Instruction: _process_slash_bond: you are not a challenger");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if bets.bond_lamports == 0 || bets.bond_slashed {
+        msg!("Instruction: _process_slash_bond: nothing to slash");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if result_outcome(bets.outcome)? == MatchOutcome::Unknown {
+        msg!("Instruction: _process_slash_bond: no result to dispute");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if _now()? >= bets.winner_set_at.saturating_add(bets.dispute_window_secs) {
+        msg!("Instruction: _process_slash_bond: dispute window is over");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    verbose_msg!("Slashing bond of {} lamports", bets.bond_lamports);
+    bets.bond_slashed = true;
+    _store_state(&bets, bets_info)?;
+
+    Ok(())
+}
+
+fn _process_set_paused(program_id: &Pubkey, accounts: &[AccountInfo], paused: bool) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_set_paused: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_set_paused: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_writable_state(bets_info, EventBets::LEN)?;
+    let mut data = bets_info.data.borrow_mut();
+    let bets = pod::load_mut::<pod::EventBetsPod>(&mut data[..])?;
+    if bets.version == 0 {
+        msg!("Instruction: _process_set_paused: not Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    _check_version(bets.version)?;
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_set_paused: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if paused && result_outcome(bets.outcome)? != MatchOutcome::Unknown {
+        msg!("Instruction: _process_set_paused: result is already set");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if bets.paused.get() == paused {
+        msg!("Instruction: _process_set_paused: event is already {}", if paused { "paused" } else { "unpaused" });
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    verbose_msg!("Event {} paused: {}", bets_info.key, paused);
+    bets.paused.set(paused);
+
+    Ok(())
+}
+
+// Loads the market, telling it apart from EventBets by its length.
+fn _load_market(program_id: &Pubkey, market_info: &AccountInfo) -> Result<Market, ProgramError> {
+    if !cmp_pubkeys(program_id, market_info.owner) || market_info.data_len() != Market::LEN {
+        msg!("Instruction: _load_market: {} is not a market", market_info.key);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    Ok(Market::deserialize(&mut &market_info.data.borrow()[..])?)
+}
+
+fn _process_initialize_market(program_id: &Pubkey, accounts: &[AccountInfo], bets_accepted_until: UnixTimestamp, num_outcomes: u8, commission_bps: u16) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let market_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_initialize_market: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    let mut market = _load_market(program_id, market_info)?;
+    if market.is_initialized {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    if !Rent::get()?.is_exempt(market_info.lamports(), market_info.data_len()) {
+        msg!("Instruction: _process_initialize_market: no exempt, size={}", market_info.data_len());
+        return Err(ProgramError::InvalidAccountData)
+    }
+    if num_outcomes < 2 || num_outcomes as usize > MAX_OUTCOMES {
+        msg!("Instruction: _process_initialize_market: {} outcomes, must be 2 to {}", num_outcomes, MAX_OUTCOMES);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if commission_bps > MAX_COMISSION_BPS || bets_accepted_until < _now()? {
+        msg!("Instruction: _process_initialize_market: commission {} bps or deadline {} out of range", commission_bps, bets_accepted_until);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    market.is_initialized = true;
+    market.arbiter = *owner.key;
+    market.bets_allowed_until_ts = bets_accepted_until;
+    market.commission_bps = commission_bps;
+    market.num_outcomes = num_outcomes;
+    market.winner = MARKET_UNSETTLED;
+    market.pools = [0; MAX_OUTCOMES];
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _process_add_market_bet(program_id: &Pubkey, accounts: &[AccountInfo], outcome: u8) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let market_info = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    if !cmp_pubkeys(program_id, this_bet_acc.owner) {
+        msg!("Instruction: _process_add_market_bet: wrong owner for bet {}", this_bet_acc.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let mut market = _load_market(program_id, market_info)?;
+    if !market.is_initialized || market.winner != MARKET_UNSETTLED || _now()? > market.bets_allowed_until_ts {
+        msg!("Instruction: _process_add_market_bet: market is not taking bets");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if outcome >= market.num_outcomes {
+        msg!("Instruction: _process_add_market_bet: outcome {} of {}", outcome, market.num_outcomes);
+        return Err(BetError::InvalidOutcome.into());
+    }
+    let mut this_bet = _load_state::<Bet>(this_bet_acc)?;
+    if this_bet.is_initialized() {
+        msg!("Instruction: _process_add_market_bet: Bet is already Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let rent_exemption = Rent::get()?.minimum_balance(this_bet_acc.data_len());
+    let stake = this_bet_acc.lamports().checked_sub(rent_exemption).ok_or(ProgramError::InsufficientFunds)?;
+    let pool = &mut market.pools[outcome as usize];
+    *pool = pool.checked_add(stake).ok_or(BetError::ArithmeticOverflow)?;
+
+    verbose_msg!("Adding {} on outcome {} of {}", stake, outcome, market_info.key);
+    this_bet.version = STATE_VERSION;
+    this_bet.betor = *betor.key;
+    this_bet.event = *market_info.key;
+    this_bet.amount = stake;
+    this_bet.outcome = outcome;
+    this_bet.placed_at = _now()?;
+    _move_lamports(this_bet_acc, market_info, stake)?;
+
+    _store_state(&this_bet, this_bet_acc)?;
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _process_set_market_winner(program_id: &Pubkey, accounts: &[AccountInfo], winner: u8) -> ProgramResult {
+    use std::convert::TryFrom;
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let market_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_set_market_winner: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    let mut market = _load_market(program_id, market_info)?;
+    if !market.is_initialized || !cmp_pubkeys(&market.arbiter, owner.key) {
+        msg!("Instruction: _process_set_market_winner: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if market.winner != MARKET_UNSETTLED {
+        msg!("Instruction: _process_set_market_winner: winner is already set");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if _now()? < market.bets_allowed_until_ts {
+        msg!("Instruction: _process_set_market_winner: too early");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if winner >= market.num_outcomes {
+        msg!("Instruction: _process_set_market_winner: outcome {} of {}", winner, market.num_outcomes);
+        return Err(BetError::InvalidOutcome.into());
+    }
+
+    market.winner = winner;
+    // Nothing is taken when everybody gets refunded.
+    if market.pools[winner as usize] > 0 {
+        let commission = commission_of(u64::try_from(market.total_pool()).map_err(|_| BetError::ArithmeticOverflow)?, market.commission_bps);
+        verbose_msg!("Sending commission {} from {} to {}", commission, market_info.key, owner.key);
+        _move_lamports(market_info, owner, commission)?;
+    }
+    market.serialize(&mut &mut market_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _process_withdraw_market_bet(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let market_info = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    if !cmp_pubkeys(program_id, this_bet_acc.owner) {
+        msg!("Instruction: _process_withdraw_market_bet: wrong owner for bet {}", this_bet_acc.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let market = _load_market(program_id, market_info)?;
+    let mut this_bet = _load_state::<Bet>(this_bet_acc)?;
+    if !this_bet.is_initialized() || !cmp_pubkeys(&this_bet.event, market_info.key) || !cmp_pubkeys(&this_bet.betor, betor.key) {
+        msg!("Instruction: _process_withdraw_market_bet: not a bet of {} on {}", betor.key, market_info.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if this_bet.outcome == u8::from(MatchOutcome::Withdrawn) {
+        msg!("Bet is already withdrawn");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if market.winner == MARKET_UNSETTLED {
+        msg!("Withdrawing from not completed market");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let payout = calculate_market_payout(&market, &this_bet)?;
+    verbose_msg!("Sending {} lamports from {} to {}", payout, market_info.key, betor.key);
+    _move_lamports(market_info, betor, payout)?;
+    this_bet.outcome = u8::from(MatchOutcome::Withdrawn);
+    this_bet.claimed = payout;
+    _store_state(&this_bet, this_bet_acc)?;
+    Ok(())
+}
+
+fn _check_parlay_pool(program_id: &Pubkey, pool_info: &AccountInfo) -> ProgramResult {
+    if !cmp_pubkeys(&parlay_pool_address(program_id).0, pool_info.key) || !cmp_pubkeys(program_id, pool_info.owner) {
+        msg!("Instruction: _check_parlay_pool: {} is not the parlay pool", pool_info.key);
+        return Err(ProgramError::InvalidArgument)
+    }
+    Ok(())
+}
+
+fn _process_create_parlay_pool(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let (address, bump) = parlay_pool_address(program_id);
+    if !cmp_pubkeys(&address, pool_info.key) {
+        msg!("Instruction: _process_create_parlay_pool: pool must live at {}", address);
+        return Err(ProgramError::InvalidArgument)
+    }
+    if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+        msg!("Instruction: _process_create_parlay_pool: wrong system program");
+        return Err(ProgramError::IncorrectProgramId)
+    }
+
+    // Fails on its own if the pool already exists.
+    invoke_signed(
+        &system_instruction::create_account(payer_info.key, pool_info.key, Rent::get()?.minimum_balance(0), 0, program_id),
+        &[payer_info.clone(), pool_info.clone(), system_program_info.clone()],
+        &[&[PARLAY_POOL_SEED, &[bump]]],
+    )
+}
+
+fn _process_place_parlay(program_id: &Pubkey, accounts: &[AccountInfo], choices: &[MatchOutcome]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let parlay_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    if !cmp_pubkeys(program_id, parlay_info.owner) {
+        msg!("Instruction: _process_place_parlay: wrong owner for parlay {}", parlay_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_parlay_pool(program_id, pool_info)?;
+    if choices.len() < 2 || choices.len() > MAX_PARLAY_LEGS {
+        msg!("Instruction: _process_place_parlay: {} legs, expected 2 to {}", choices.len(), MAX_PARLAY_LEGS);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut parlay = Parlay::deserialize(&mut &parlay_info.data.borrow()[..])?;
+    if parlay.is_initialized {
+        msg!("Instruction: _process_place_parlay: parlay is already Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    for (index, &choice) in choices.iter().enumerate() {
+        let bets_info = next_account_info(account_info_iter)?;
+        if !cmp_pubkeys(program_id, bets_info.owner) {
+            msg!("Instruction: _process_place_parlay: wrong owner for event {}", bets_info.key);
+            return Err(ProgramError::InvalidAccountData)
+        }
+        let bets = _load_state::<EventBets>(bets_info)?;
+        _check_bets_open(&bets, _now()?)?;
+        if bets.mint != Pubkey::default() || bets.fixed_odds {
+            msg!("Instruction: _process_place_parlay: {} is not a parimutuel SOL event", bets_info.key);
+            return Err(ProgramError::InvalidArgument);
+        }
+        if choice != MatchOutcome::TeamA && choice != MatchOutcome::TeamB {
+            msg!("Instruction: _process_place_parlay: can't pick {} on {}", u8::from(choice), bets_info.key);
+            return Err(ProgramError::InvalidArgument);
+        }
+        if parlay.legs[..index].iter().any(|leg| cmp_pubkeys(&leg.event, bets_info.key)) {
+            msg!("Instruction: _process_place_parlay: {} is picked twice", bets_info.key);
+            return Err(ProgramError::InvalidArgument);
+        }
+        parlay.legs[index] = ParlayLeg { event: *bets_info.key, outcome: u8::from(choice) };
+    }
+    let stake = parlay_info.lamports().saturating_sub(Rent::get()?.minimum_balance(parlay_info.data_len()));
+    if stake == 0 {
+        msg!("Instruction: _process_place_parlay: nothing staked");
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    verbose_msg!("Escrowing {} from {} in {}", stake, parlay_info.key, pool_info.key);
+    _move_lamports(parlay_info, pool_info, stake)?;
+    parlay.is_initialized = true;
+    parlay.betor = *betor.key;
+    parlay.stake = stake;
+    parlay.num_legs = choices.len() as u8;
+    parlay.serialize(&mut &mut parlay_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _process_settle_parlay(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let parlay_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    if !cmp_pubkeys(program_id, parlay_info.owner) {
+        msg!("Instruction: _process_settle_parlay: wrong owner for parlay {}", parlay_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_parlay_pool(program_id, pool_info)?;
+
+    let mut parlay = Parlay::deserialize(&mut &parlay_info.data.borrow()[..])?;
+    if !parlay.is_initialized || parlay.settled {
+        msg!("Instruction: _process_settle_parlay: parlay {} is not open", parlay_info.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !cmp_pubkeys(&parlay.betor, betor.key) {
+        msg!("Instruction: _process_settle_parlay: parlay belongs to {}", parlay.betor);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let now = _now()?;
+    let mut events = Vec::with_capacity(parlay.num_legs as usize);
+    for leg in &parlay.legs[..parlay.num_legs as usize] {
+        let bets_info = next_account_info(account_info_iter)?;
+        if !cmp_pubkeys(&leg.event, bets_info.key) || !cmp_pubkeys(program_id, bets_info.owner) {
+            msg!("Instruction: _process_settle_parlay: expected event {}", leg.event);
+            return Err(ProgramError::InvalidArgument)
+        }
+        let bets = _load_state::<EventBets>(bets_info)?;
+        if bets.paused {
+            msg!("Instruction: _process_settle_parlay: event {} is paused", leg.event);
+            return Err(BetError::EventPaused.into());
+        }
+        if result_outcome(bets.outcome)? == MatchOutcome::Unknown || now < bets.claimable_at() {
+            msg!("Instruction: _process_settle_parlay: result of {} is not final", leg.event);
+            return Err(BetError::ResultNotFinal.into());
+        }
+        events.push(bets);
+    }
+
+    let payout = calculate_parlay_payout(&parlay, &events)?;
+    let available = pool_info.lamports().saturating_sub(Rent::get()?.minimum_balance(pool_info.data_len()));
+    if payout > available {
+        msg!("Instruction: _process_settle_parlay: pool holds {} of {}", available, payout);
+        return Err(ProgramError::InsufficientFunds);
+    }
+    verbose_msg!("Sending {} lamports from {} to {}", payout, pool_info.key, betor.key);
+    _move_lamports(pool_info, betor, payout)?;
+    parlay.settled = true;
+    parlay.payout = payout;
+    parlay.serialize(&mut &mut parlay_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _process_create_tournament(program_id: &Pubkey, accounts: &[AccountInfo], slug: &str, name: &str) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let tournament_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_create_tournament: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+        msg!("Instruction: _process_create_tournament: wrong system program");
+        return Err(ProgramError::IncorrectProgramId)
+    }
+    if !is_valid_seed(slug) || name.len() > TOURNAMENT_NAME_BYTES {
+        msg!("Instruction: _process_create_tournament: bad slug {:?} or name {:?}", slug, name);
+        return Err(ProgramError::InvalidInstructionData)
+    }
+    let (address, bump) = tournament_address(program_id, owner.key, slug);
+    if !cmp_pubkeys(&address, tournament_info.key) {
+        msg!("Instruction: _process_create_tournament: tournament {:?} must live at {}", slug, address);
+        return Err(ProgramError::InvalidArgument)
+    }
+
+    // Fails on its own if the tournament already exists.
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            tournament_info.key,
+            Rent::get()?.minimum_balance(Tournament::LEN),
+            Tournament::LEN as u64,
+            program_id,
+        ),
+        &[owner.clone(), tournament_info.clone(), system_program_info.clone()],
+        &[&[TOURNAMENT_SEED, owner.key.as_ref(), slug.as_bytes(), &[bump]]],
+    )?;
+    let mut tournament = Tournament {
+        is_initialized: true,
+        arbiter: *owner.key,
+        slug: [0; EVENT_SEED_BYTES],
+        name: [0; TOURNAMENT_NAME_BYTES],
+        event_count: 0,
+    };
+    tournament.slug[..slug.len()].copy_from_slice(slug.as_bytes());
+    tournament.name[..name.len()].copy_from_slice(name.as_bytes());
+    tournament.serialize(&mut &mut tournament_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// The event `owner` signs for as its arbiter.
+fn _load_arbiter_event(program_id: &Pubkey, owner: &AccountInfo, bets_info: &AccountInfo) -> Result<EventBets, ProgramError> {
+    if !owner.is_signer {
+        msg!("Instruction: _load_arbiter_event: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _load_arbiter_event: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let bets = _load_initialized::<EventBets>(bets_info)?;
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _load_arbiter_event: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(bets)
+}
+
+// AddToAllowlist and AddToDenylist, creating the list on the first call and marking the event as having it.
+fn _process_add_to_list(program_id: &Pubkey, accounts: &[AccountInfo], wallet: Pubkey, denylist: bool) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    let list_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    _load_arbiter_event(program_id, owner, bets_info)?;
+    _check_writable_state(bets_info, EventBets::LEN)?;
+    let (seed, full) = if denylist { (DENYLIST_SEED, BetError::DenylistFull) } else { (ALLOWLIST_SEED, BetError::AllowlistFull) };
+    let (address, bump) = Pubkey::find_program_address(&[seed, bets_info.key.as_ref()], program_id);
+    if !cmp_pubkeys(&address, list_info.key) {
+        msg!("Instruction: _process_add_to_list: list must live at {}", address);
+        return Err(ProgramError::InvalidArgument)
+    }
+    if list_info.data_len() == 0 {
+        if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+            msg!("Instruction: _process_add_to_list: wrong system program");
+            return Err(ProgramError::IncorrectProgramId)
+        }
+        invoke_signed(
+            &system_instruction::create_account(
+                owner.key,
+                list_info.key,
+                Rent::get()?.minimum_balance(WalletList::LEN),
+                WalletList::LEN as u64,
+                program_id,
+            ),
+            &[owner.clone(), list_info.clone(), system_program_info.clone()],
+            &[&[seed, bets_info.key.as_ref(), &[bump]]],
+        )?;
+        WalletList { is_initialized: true, event: *bets_info.key, count: 0 }.serialize(&mut &mut list_info.data.borrow_mut()[..])?;
+        let mut data = bets_info.data.borrow_mut();
+        let view = pod::load_mut::<pod::EventBetsPod>(&mut data[..])?;
+        if denylist {
+            view.denylisted.set(true);
+        } else {
+            view.allowlisted.set(true);
+        }
+    } else if !cmp_pubkeys(program_id, list_info.owner) {
+        msg!("Instruction: _process_add_to_list: wrong owner for list {}", list_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+
+    let mut data = list_info.data.borrow_mut();
+    let mut list = WalletList::deserialize(&mut &data[..])?;
+    if list.position(&data, &wallet).is_some() {
+        verbose_msg!("{} is on the list already", wallet);
+        return Ok(());
+    }
+    let slot = list.count as usize;
+    if slot >= WALLET_LIST_CAPACITY {
+        msg!("Instruction: _process_add_to_list: list holds {} wallets already", WALLET_LIST_CAPACITY);
+        return Err(full.into());
+    }
+    let start = WalletList::HEADER_LEN + slot * PUBKEY_BYTES;
+    data[start..start + PUBKEY_BYTES].copy_from_slice(wallet.as_ref());
+    list.count += 1;
+    list.serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+// RemoveFromAllowlist and RemoveFromDenylist. Taking a wallet that is not on it off an allowlist is refused,
+// off a denylist it changes nothing.
+fn _process_remove_from_list(program_id: &Pubkey, accounts: &[AccountInfo], wallet: Pubkey, denylist: bool) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    let list_info = next_account_info(account_info_iter)?;
+    _load_arbiter_event(program_id, owner, bets_info)?;
+    let seed = if denylist { DENYLIST_SEED } else { ALLOWLIST_SEED };
+    let (address, _) = Pubkey::find_program_address(&[seed, bets_info.key.as_ref()], program_id);
+    if !cmp_pubkeys(&address, list_info.key) || !cmp_pubkeys(program_id, list_info.owner) {
+        msg!("Instruction: _process_remove_from_list: wrong list {}", list_info.key);
+        return Err(ProgramError::InvalidArgument)
+    }
+
+    let mut data = list_info.data.borrow_mut();
+    let mut list = WalletList::deserialize(&mut &data[..])?;
+    let index = match list.position(&data, &wallet) {
+        Some(index) => index,
+        None if denylist => {
+            verbose_msg!("{} is not on the denylist", wallet);
+            return Ok(());
+        },
+        None => {
+            msg!("Instruction: _process_remove_from_list: {} is not on the allowlist", wallet);
+            return Err(ProgramError::InvalidArgument);
+        },
+    };
+    // The last wallet takes the removed one's slot.
+    let last = WalletList::HEADER_LEN + (list.count as usize - 1) * PUBKEY_BYTES;
+    let start = WalletList::HEADER_LEN + index * PUBKEY_BYTES;
+    data.copy_within(last..last + PUBKEY_BYTES, start);
+    data[last..last + PUBKEY_BYTES].fill(0);
+    list.count -= 1;
+    list.serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+fn _process_create_leaderboard(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    let leaderboard_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_create_leaderboard: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_create_leaderboard: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    if !cmp_pubkeys(system_program_info.key, &system_program::id()) {
+        msg!("Instruction: _process_create_leaderboard: wrong system program");
+        return Err(ProgramError::IncorrectProgramId)
+    }
+    let bets = _load_initialized::<EventBets>(bets_info)?;
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_create_leaderboard: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let (address, bump) = leaderboard_address(program_id, bets_info.key);
+    if !cmp_pubkeys(&address, leaderboard_info.key) {
+        msg!("Instruction: _process_create_leaderboard: leaderboard must live at {}", address);
+        return Err(ProgramError::InvalidArgument)
+    }
+
+    // Fails on its own if the leaderboard already exists.
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            leaderboard_info.key,
+            Rent::get()?.minimum_balance(Leaderboard::LEN),
+            Leaderboard::LEN as u64,
+            program_id,
+        ),
+        &[owner.clone(), leaderboard_info.clone(), system_program_info.clone()],
+        &[&[LEADERBOARD_SEED, bets_info.key.as_ref(), &[bump]]],
+    )?;
+    let leaderboard = Leaderboard { is_initialized: true, event: *bets_info.key, entries: [LeaderboardEntry::default(); LEADERBOARD_SIZE] };
+    leaderboard.serialize(&mut &mut leaderboard_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn _process_add_event_to_tournament(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let tournament_info = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_add_event_to_tournament: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    for info in &[tournament_info, bets_info] {
+        if !cmp_pubkeys(program_id, info.owner) {
+            msg!("Instruction: _process_add_event_to_tournament: wrong owner for {}", info.key);
+            return Err(ProgramError::InvalidAccountData)
+        }
+    }
+    if tournament_info.data_len() != Tournament::LEN {
+        msg!("Instruction: _process_add_event_to_tournament: {} is not a tournament", tournament_info.key);
+        return Err(ProgramError::InvalidAccountData)
+    }
+
+    let mut tournament = Tournament::deserialize(&mut &tournament_info.data.borrow()[..])?;
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    if !tournament.is_initialized || !cmp_pubkeys(&tournament.arbiter, owner.key) {
+        msg!("Instruction: _process_add_event_to_tournament: tournament belongs to {}", tournament.arbiter);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !bets.is_initialized() || !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_add_event_to_tournament: event belongs to {}", bets.arbiter);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if bets.tournament != Pubkey::default() {
+        msg!("Instruction: _process_add_event_to_tournament: event is in {} already", bets.tournament);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    bets.tournament = *tournament_info.key;
+    tournament.event_count = tournament.event_count.checked_add(1).ok_or(BetError::ArithmeticOverflow)?;
+    tournament.serialize(&mut &mut tournament_info.data.borrow_mut()[..])?;
+    _store_state(&bets, bets_info)?;
+    Ok(())
+}
+
+// Rewrites a written account to STATE_VERSION. Version 1 only differs in the first byte, which was
+// is_initialized and 1. Returns whether anything changed.
+fn _migrate_state(data: &mut [u8]) -> Result<bool, ProgramError> {
+    match data[0] {
+        0 | STATE_VERSION => Ok(false),
+        STATE_VERSION_V1 => {
+            data[0] = STATE_VERSION;
+            Ok(true)
+        },
+        version => {
+            msg!("Instruction: _migrate_state: no migration from version {}", version);
+            Err(BetError::VersionMismatch.into())
+        },
+    }
+}
+
+fn _process_migrate_event(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_migrate_event: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_migrate_event: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let is_market = bets_info.data_len() == Market::LEN;
+    let arbiter = if is_market {
+        _load_market(program_id, bets_info)?.arbiter
+    } else {
+        _check_writable_state(bets_info, EventBets::LEN)?;
+        let data = bets_info.data.borrow();
+        if data[0] == 0 {
+            msg!("Instruction: _process_migrate_event: not Initialized...");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        // Every version so far keeps the arbiter where EventBetsPod has it.
+        pod::load::<pod::EventBetsPod>(&data[..])?.arbiter
+    };
+    if !cmp_pubkeys(&arbiter, owner.key) {
+        msg!("Instruction: _process_migrate_event: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !is_market && _migrate_state(&mut bets_info.data.borrow_mut()[..EventBets::LEN])? {
+        verbose_msg!("Migrated event {} to version {}", bets_info.key, STATE_VERSION);
+    }
+
+    for this_bet_acc in account_info_iter {
+        if !cmp_pubkeys(program_id, this_bet_acc.owner) {
+            msg!("Instruction: _process_migrate_event: wrong owner for bet {}", this_bet_acc.owner);
+            return Err(ProgramError::InvalidAccountData)
+        }
+        _check_writable_state(this_bet_acc, Bet::LEN)?;
+        let mut data = this_bet_acc.data.borrow_mut();
+        if !cmp_pubkeys(&pod::load::<pod::BetPod>(&data[..])?.event, bets_info.key) {
+            msg!("Instruction: _process_migrate_event: {} is not a bet on {}", this_bet_acc.key, bets_info.key);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if _migrate_state(&mut data[..Bet::LEN])? {
+            verbose_msg!("Migrated bet {} to version {}", this_bet_acc.key, STATE_VERSION);
+        }
+    }
+    Ok(())
+}
+
+fn _process_release_bond(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_release_bond: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_release_bond: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    if !bets.is_initialized() {
+        msg!("Instruction: _process_release_bond: not Initialized...");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_release_bond: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if bets.bond_lamports == 0 || bets.bond_slashed {
+        msg!("Instruction: _process_release_bond: no bond to release");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if result_outcome(bets.outcome)? == MatchOutcome::Unknown {
+        msg!("Instruction: _process_release_bond: event is not settled");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // Held as long as the result may still be corrected, a released bond could no longer be slashed.
+    if _now()? < bets.claimable_at() {
+        msg!("Instruction: _process_release_bond: result is final only at {}", bets.claimable_at());
+        return Err(BetError::ResultNotFinal.into());
+    }
+
+    verbose_msg!("Releasing bond of {} lamports to {}", bets.bond_lamports, owner.key);
+    _move_lamports(bets_info, owner, bets.bond_lamports)?;
+    bets.bond_lamports = 0;
+    _store_state(&bets, bets_info)?;
+
+    Ok(())
+}
+
+fn _process_sweep_dust(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+    if !owner.is_signer {
+        msg!("Instruction: _process_sweep_dust: wrong signer");
+        return Err(ProgramError::MissingRequiredSignature)
+    }
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_sweep_dust: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_writable_state(bets_info, EventBets::LEN)?;
+    let mut bets = _load_initialized::<EventBets>(bets_info)?;
+    if !cmp_pubkeys(&bets.arbiter, owner.key) {
+        msg!("Instruction: _process_sweep_dust: you are not an arbiter");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if result_outcome(bets.outcome)? == MatchOutcome::Unknown || _now()? < bets.result_final_at() {
+        msg!("Instruction: _process_sweep_dust: result is final only at {}", bets.result_final_at());
+        return Err(BetError::ResultNotFinal.into());
+    }
+    if bets.outstanding_bets > 0 {
+        msg!("Instruction: _process_sweep_dust: {} bets are still to be claimed", bets.outstanding_bets);
+        return Err(BetError::BetsOutstanding.into());
+    }
+
+    // A slashed bond no bet won a share of goes to the challenger, not back to the slashed arbiter.
+    if bets.bond_slashed && bets.bond_lamports > 0 && bets.winning_stakes() == 0 {
+        let challenger = next_account_info(account_info_iter)?;
+        if !cmp_pubkeys(&bets.challenger, challenger.key) {
+            msg!("Instruction: _process_sweep_dust: the slashed bond goes to the challenger {}", bets.challenger);
+            return Err(ProgramError::InvalidArgument);
+        }
+        verbose_msg!("Sending the slashed bond of {} lamports to {}", bets.bond_lamports, challenger.key);
+        _move_lamports(bets_info, challenger, bets.bond_lamports)?;
+        bets.bond_lamports = 0;
+        pod::load_mut::<pod::EventBetsPod>(&mut bets_info.data.borrow_mut()[..])?.bond_lamports.set(0);
+    }
+    // A bond that wasn't slashed or released yet is still the arbiter's, through ReleaseBond,
+    // and the commission goes through CollectCommission, which may split it with the treasury.
+    let bond = if bets.bond_slashed { 0 } else { bets.bond_lamports };
+    let keep = Rent::get()?
+        .minimum_balance(bets_info.data_len())
+        .checked_add(bond)
+        .and_then(|keep| keep.checked_add(bets.commission_accrued))
+        .ok_or(BetError::ArithmeticOverflow)?;
+    let dust = bets_info.lamports().saturating_sub(keep);
+    verbose_msg!("Sweeping {} lamports from {} to {}", dust, bets_info.key, owner.key);
+    _move_lamports(bets_info, owner, dust)?;
+    // With every bet closed nobody can claim what is left of the referral reserve.
+    bets.referral_reserve = 0;
+    _store_payout_totals(&bets, bets_info)?;
+
+    Ok(())
+}
+
+// What Withdraw pays for a bet once the result is set.
+struct Entitlement {
+    // Payout before commission, in the pools' unit.
+    gross: u128,
+    // Payout net of commission, in the pools' unit.
+    stake_payout: u128,
+    // Bond and bonus shares, lamports even in SPL token events.
+    lamport_extras: u128,
+    // commission_bps of the gross payout and of a charged bonus share, charged by the first claim
+    // of a SOL event.
+    commission: u128,
+}
+
+fn _entitlement(bets: &EventBets, this_bet: &Bet) -> Result<Entitlement, ProgramError> {
+    let gross = calculate_payout(bets, this_bet, 0)? as u128;
+    let stake_payout = calculate_payout(bets, this_bet, bets.commission_bps)? as u128;
+    // A slashed bond and the bonus go to the winners pro rata.
+    let winning_pool = match (result_outcome(bets.outcome)?, MatchOutcome::try_from(this_bet.outcome)?) {
+        (MatchOutcome::TeamA, MatchOutcome::TeamA) => bets.balance_a as u128,
+        (MatchOutcome::TeamB, MatchOutcome::TeamB) => bets.balance_b as u128,
+        (MatchOutcome::Draw, MatchOutcome::TeamA) | (MatchOutcome::Draw, MatchOutcome::TeamB) => bets.balance_a as u128 + bets.balance_b as u128,
+        _ => 0,
+    };
+    let pro_rata = |lamports: u64| if winning_pool > 0 { lamports as u128 * this_bet.amount as u128 / winning_pool } else { 0 };
+    let mut lamport_extras = 0u128;
+    if bets.bond_slashed {
+        lamport_extras += pro_rata(bets.bond_lamports);
+    }
+    let mut commission = commission_of(u64::try_from(gross).map_err(|_| BetError::ArithmeticOverflow)?, bets.commission_bps) as u128;
+    // The top-ups of a fixed-odds event back its odds, they are not shared.
+    if !bets.fixed_odds {
+        let bonus_share = pro_rata(bets.bonus_lamports);
+        let net_bonus_share = if bets.bonus_commission_free { bonus_share } else { net_of_commission(bonus_share, bets.commission_bps) };
+        lamport_extras += net_bonus_share;
+        commission += bonus_share - net_bonus_share;
+    }
+    Ok(Entitlement { gross, stake_payout, lamport_extras, commission })
+}
+
+impl Entitlement {
+    // The part claimed with Withdraw amounts, the extras are included in SOL events only.
+    fn payout(&self, bets: &EventBets) -> u128 {
+        if bets.mint == Pubkey::default() {
+            self.stake_payout + self.lamport_extras
+        } else {
+            self.stake_payout
+        }
+    }
+}
+
+fn _process_quote_payout(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bets_info = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+    for info in &[bets_info, this_bet_acc] {
+        if !cmp_pubkeys(program_id, info.owner) {
+            msg!("Instruction: _process_quote_payout: wrong owner for {}", info.key);
+            return Err(ProgramError::InvalidAccountData)
+        }
+    }
+    let bets = _load_state::<EventBets>(bets_info)?;
+    let this_bet = _load_state::<Bet>(this_bet_acc)?;
+    if !cmp_pubkeys(bets_info.key, &this_bet.event) {
+        msg!("Bet does not match event");
+        return Err(ProgramError::InvalidAccountData)
+    }
+    if result_outcome(bets.outcome)? == MatchOutcome::Unknown {
+        msg!("Instruction: _process_quote_payout: result is not set");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    set_return_data(&quote_payout(&bets, &this_bet)?.to_le_bytes());
+    Ok(())
+}
+
+// What Withdraw would pay the betor of `this_bet` for the rest of its claim, see Instruction::QuotePayout.
+pub fn quote_payout(bets: &EventBets, this_bet: &Bet) -> Result<u64, ProgramError> {
+    let payout = _entitlement(bets, this_bet)?.payout(bets);
+    Ok(payout.saturating_sub(this_bet.claimed as u128) as u64)
+}
+
+fn _process_withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?; 
+    let bets_info = next_account_info(account_info_iter)?;
+    let this_bet_acc = next_account_info(account_info_iter)?;
+
+    verbose_msg!("betor = {}, bets_info = {}, this_bet_acc = {}", betor.key, bets_info.key, this_bet_acc.key);
+    
+    for info in &[bets_info, this_bet_acc] {
+        if !cmp_pubkeys(program_id, info.owner) {
+            msg!("Instruction: _process_withdraw: wrong owner for {}", info.key);
+            return Err(ProgramError::InvalidAccountData)
+        }
+    }
+    _check_writable_state(bets_info, EventBets::LEN)?;
+    _check_writable_state(this_bet_acc, Bet::LEN)?;
+
+    let mut bets = _load_initialized::<EventBets>(bets_info)?;
+    let this_bet = _load_initialized::<Bet>(this_bet_acc)?;
+    let won = this_bet.outcome == bets.outcome;
+    let first_claim = this_bet.claimed == 0;
+    let paid = _withdraw_bet(program_id, bets_info, &mut bets, betor, this_bet_acc, amount, account_info_iter)?;
+    _store_payout_totals(&bets, bets_info)?;
+
+    // The optional accounts after all the others, the betor's stats then the leaderboard, either may be left out.
+    let mut optional_info = account_info_iter.next();
+    if optional_info.is_some() && bets.mint != Pubkey::default() {
+        msg!("Instruction: _process_withdraw: stats and leaderboards only count SOL events");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if let Some(stats_info) = optional_info.filter(|info| cmp_pubkeys(&stats_address(program_id, betor.key).0, info.key)) {
+        optional_info = account_info_iter.next();
+        _update_bettor_stats(program_id, stats_info, |stats| {
+            if won {
+                stats.won_total = stats.won_total.checked_add(paid)?;
+                if first_claim {
+                    stats.bets_won = stats.bets_won.checked_add(1)?;
+                }
+            }
+            Some(())
+        })?;
+    }
+    if let Some(leaderboard_info) = optional_info {
+        if !cmp_pubkeys(&leaderboard_address(program_id, bets_info.key).0, leaderboard_info.key) || !cmp_pubkeys(program_id, leaderboard_info.owner) {
+            msg!("Instruction: _process_withdraw: {} is neither the betor's stats nor the event's leaderboard", leaderboard_info.key);
+            return Err(ProgramError::InvalidArgument);
+        }
+        if won {
+            let mut leaderboard = Leaderboard::deserialize(&mut &leaderboard_info.data.borrow()[..])?;
+            leaderboard.record(betor.key, paid).ok_or(BetError::ArithmeticOverflow)?;
+            leaderboard.serialize(&mut &mut leaderboard_info.data.borrow_mut()[..])?;
+        }
+    }
+    Ok(())
+}
+
+fn _process_withdraw_many(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let betor = next_account_info(account_info_iter)?;
+    let bets_info = next_account_info(account_info_iter)?;
+
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_withdraw_many: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    _check_writable_state(bets_info, EventBets::LEN)?;
+
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    while let Some(this_bet_acc) = account_info_iter.next() {
+        if !cmp_pubkeys(program_id, this_bet_acc.owner) {
+            msg!("Instruction: _process_withdraw_many: wrong owner for bet {}", this_bet_acc.key);
+            return Err(ProgramError::InvalidAccountData)
+        }
+        _check_writable_state(this_bet_acc, Bet::LEN)?;
+        let outcome = _load_state::<Bet>(this_bet_acc)?.outcome;
+        if outcome == u8::from(MatchOutcome::Withdrawn) || outcome == u8::from(MatchOutcome::Voided) {
+            verbose_msg!("Instruction: _process_withdraw_many: skipping {}, already withdrawn or voided", this_bet_acc.key);
+            continue;
+        }
+        _withdraw_bet(program_id, bets_info, &mut bets, betor, this_bet_acc, 0, account_info_iter)?;
+    }
+    _store_payout_totals(&bets, bets_info)?;
+
+    Ok(())
+}
+
+fn _process_settle_bets(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let bets_info = next_account_info(account_info_iter)?;
+
+    if !cmp_pubkeys(program_id, bets_info.owner) {
+        msg!("Instruction: _process_settle_bets: wrong owner for event {}", bets_info.owner);
+        return Err(ProgramError::InvalidAccountData)
+    }
+    let mut bets = _load_state::<EventBets>(bets_info)?;
+    if bets.mint != Pubkey::default() {
+        msg!("Instruction: _process_settle_bets: SPL token events are settled by Withdraw only");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if result_outcome(bets.outcome)? == MatchOutcome::Unknown || _now()? < bets.claimable_at() {
+        msg!("Instruction: _process_settle_bets: result can be claimed only from {}", bets.claimable_at());
+        return Err(BetError::ResultNotFinal.into());
+    }
+
+    let no_accounts: &[AccountInfo] = &[];
+    while let Some(this_bet_acc) = account_info_iter.next() {
+        let betor = next_account_info(account_info_iter)?;
+        if !cmp_pubkeys(program_id, this_bet_acc.owner) {
+            verbose_msg!("Instruction: _process_settle_bets: skipping {}, not a bet", this_bet_acc.key);
+            continue;
+        }
+        let this_bet = _load_state::<Bet>(this_bet_acc)?;
+        if !cmp_pubkeys(&this_bet.event, bets_info.key) || !cmp_pubkeys(&this_bet.betor, betor.key) {
+            verbose_msg!("Instruction: _process_settle_bets: skipping {}, it doesn't belong to {}", this_bet_acc.key, betor.key);
+            continue;
+        }
+        if this_bet.outcome == u8::from(MatchOutcome::Withdrawn)
+            || this_bet.outcome == u8::from(MatchOutcome::Voided)
+            || this_bet.receipt_mint != Pubkey::default()
+            || this_bet.delegate != Pubkey::default()
+            || (this_bet.referrer != Pubkey::default() && this_bet.claimed == 0)
+        {
+            verbose_msg!("Instruction: _process_settle_bets: skipping {}, it is withdrawn or takes more accounts", this_bet_acc.key);
+            continue;
+        }
+        _withdraw_bet(program_id, bets_info, &mut bets, betor, this_bet_acc, 0, &mut no_accounts.iter())?;
+    }
+    _store_payout_totals(&bets, bets_info)?;
+
+    Ok(())
+}
+
+// Claims `amount` (0 for all that is left) of `this_bet_acc` for `betor`, taking the bet's optional Withdraw
+// accounts from `account_info_iter`. The caller stores `bets`. Returns the lamports sent to `betor`.
+fn _withdraw_bet<'a, 'b>(
+    program_id: &Pubkey,
+    bets_info: &'a AccountInfo<'b>,
+    bets: &mut EventBets,
+    betor: &'a AccountInfo<'b>,
+    this_bet_acc: &'a AccountInfo<'b>,
+    amount: u64,
+    account_info_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+) -> Result<u64, ProgramError> {
+    use std::convert::TryFrom;
+    let mut this_bet = _load_initialized::<Bet>(this_bet_acc)?;
+    // Nothing is left to pay on a closed bet, so this fails before anything else is looked at.
+    if this_bet.outcome == u8::from(MatchOutcome::Withdrawn) || this_bet.outcome == u8::from(MatchOutcome::Voided) {
+        msg!("Bet {} is already withdrawn or voided", this_bet_acc.key);
+        return Err(BetError::AlreadyWithdrawn.into());
+    }
+    if bets.paused {
+        msg!("Instruction: _process_withdraw: event is paused");
+        return Err(BetError::EventPaused.into());
+    }
+
+    if !cmp_pubkeys(bets_info.key, &this_bet.event) {
+        msg!("Bet does not match event");
+        return Err(ProgramError::InvalidAccountData)
+    }
+    if this_bet.receipt_mint == Pubkey::default() && this_bet.betor != *betor.key {
+        msg!("Withdrawing to foreigner account");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if result_outcome(bets.outcome)? == MatchOutcome::Unknown {
+        msg!("Withdrawing from not completed match");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if _now()? < bets.claimable_at() {
+        msg!("Instruction: _process_withdraw: result can be claimed only from {}", bets.claimable_at());
+        return Err(BetError::ResultNotFinal.into());
+    }
+
+    if this_bet.receipt_mint != Pubkey::default() {
+        _burn_receipt(&this_bet, betor, account_info_iter)?;
+        this_bet.betor = *betor.key;
+        this_bet.receipt_mint = Pubkey::default();
+    } else if this_bet.delegate != Pubkey::default() {
+        let authority = next_account_info(account_info_iter)?;
+        if !authority.is_signer || !(cmp_pubkeys(authority.key, &this_bet.betor) || cmp_pubkeys(authority.key, &this_bet.delegate)) {
+            msg!("Instruction: _process_withdraw: bet is delegated, it takes the betor's or {}'s signature", this_bet.delegate);
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+    }
+
+    let entitlement = _entitlement(bets, &this_bet)?;
+    let payout = entitlement.payout(bets);
+    let remaining = payout.saturating_sub(this_bet.claimed as u128);
+    let claim = if amount == 0 { remaining } else { amount as u128 };
+    if claim > remaining {
+        msg!("Instruction: _process_withdraw: claiming {} with {} left", claim, remaining);
+        return Err(BetError::ClaimExceedsPayout.into());
+    }
+    let first_claim = this_bet.claimed == 0;
+    let claimed = this_bet.claimed as u128 + claim;
+    // The stake payout is claimed before the extras.
+    let stake_paid = std::cmp::min(claimed, entitlement.stake_payout) - std::cmp::min(this_bet.claimed as u128, entitlement.stake_payout);
+
+    let total_paid_out = u64::try_from(stake_paid).ok()
+        .and_then(|payout| bets.total_paid_out.checked_add(payout))
+        .ok_or(BetError::ArithmeticOverflow)?;
+    if total_paid_out as u128 > bets.payout_cap() {
+        msg!("Instruction: _process_withdraw: paying {} on top of {} exceeds the pools", stake_paid, bets.total_paid_out);
+        return Err(BetError::PayoutExceedsPools.into());
+    }
+    bets.total_paid_out = total_paid_out;
+    let as_u64 = |amount: u128| u64::try_from(amount).map_err(|_| BetError::ArithmeticOverflow);
+    let (token_payout, withdraw_balance) = if bets.mint == Pubkey::default() {
+        (0, as_u64(claim)?)
+    } else if first_claim {
+        (as_u64(claim)?, as_u64(entitlement.lamport_extras)?)
+    } else {
+        (as_u64(claim)?, 0)
+    };
+
+    if withdraw_balance > bets_info.lamports() {
+        msg!("Withdrawing too much: {}", withdraw_balance);
+        return Err(BetError::InsufficientPot.into());
+    }
+
+    let mut referral_paid = 0;
+    if this_bet.referrer != Pubkey::default() && first_claim {
+        let referrer = next_account_info(account_info_iter)?;
+        if !cmp_pubkeys(&this_bet.referrer, referrer.key) {
+            msg!("Instruction: _process_withdraw: referrer must be {}", this_bet.referrer);
+            return Err(ProgramError::InvalidArgument);
+        }
+        let referral = std::cmp::min(
+            referral_of(commission_of(entitlement.gross as u64, bets.commission_bps), bets.referral_bps),
+            bets.referral_reserve,
+        );
+        verbose_msg!("Sending {} lamports from {} to referrer {}", referral, bets_info.key, referrer.key);
+        _move_lamports(bets_info, referrer, referral)?;
+        bets.referral_reserve -= referral;
+        referral_paid = referral;
+    }
+    if bets.mint == Pubkey::default() && first_claim {
+        // Stays in the event for CollectCommission, less what the referrer was just paid out of it.
+        let commission = as_u64(entitlement.commission)?.checked_sub(referral_paid).ok_or(BetError::ArithmeticOverflow)?;
+        bets.commission_accrued = bets.commission_accrued.checked_add(commission).ok_or(BetError::ArithmeticOverflow)?;
+    }
+    if first_claim {
+        bets.claims_count = bets.claims_count.checked_add(1).ok_or(BetError::ArithmeticOverflow)?;
+        // Past the correction window, so the result can no longer change. Finalizes it lazily
+        // when nobody sent FinalizeResult.
+        if !bets.finalized {
+            bets.finalized = true;
+            events::emit(&events::ProgramEvent::ResultFinalized(events::ResultFinalized { event: *bets_info.key, outcome: bets.outcome }))?;
+        }
+    }
+    if bets.mint != Pubkey::default() {
+        let betor_token_info = next_account_info(account_info_iter)?;
+        _check_token_account(betor_token_info, &bets.mint, betor.key)?;
+        let vault = _load_vault(program_id, bets_info.key, bets, account_info_iter)?;
+        if token_payout > 0 {
+            _pay_from_vault(&vault, bets_info.key, betor_token_info, token_payout)?;
+        }
+    }
+
+    this_bet.claimed = claimed as u64;
+    if claimed == payout {
+        this_bet.outcome = u8::from(MatchOutcome::Withdrawn);
+        bets.outstanding_bets = bets.outstanding_bets.saturating_sub(1);
+    }
+    verbose_msg!("Sending {} lamports from {} to {}", withdraw_balance, bets_info.key, betor.key);
+    _move_lamports(bets_info, betor, withdraw_balance)?;
+
+    _store_claim(&this_bet, this_bet_acc)?;
+
+    events::emit(&events::ProgramEvent::BetWithdrawn(events::BetWithdrawn {
+        event: *bets_info.key,
+        bet: *this_bet_acc.key,
+        amount: claim as u64,
+    }))?;
+    Ok(withdraw_balance)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DEADLINE: UnixTimestamp = 1_700_000_000;
+
+    fn open_event() -> EventBets {
+        let mut bets = EventBets::unpack_unchecked(&[0; EventBets::LEN]).unwrap();
+        bets.version = STATE_VERSION;
+        bets.bets_allowed_until_ts = DEADLINE;
+        bets
+    }
+
+    #[test]
+    fn test_initialize_deadline_boundaries() {
+        assert_eq!(_check_deadline_ahead(DEADLINE, DEADLINE, 0), Ok(()));
+        assert_eq!(_check_deadline_ahead(DEADLINE, DEADLINE + 1, 0), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(_check_deadline_ahead(DEADLINE, DEADLINE - 60, 60), Ok(()));
+        assert_eq!(_check_deadline_ahead(DEADLINE, DEADLINE - 59, 60), Err(ProgramError::InvalidInstructionData));
+        // The offset saturates instead of overflowing.
+        assert_eq!(_check_deadline_ahead(UnixTimestamp::MAX, UnixTimestamp::MAX, 60), Ok(()));
+    }
+
+    #[test]
+    fn test_add_bet_deadline_boundaries() {
+        let bets = open_event();
+        assert_eq!(_check_bets_open(&bets, DEADLINE - 1), Ok(()));
+        assert_eq!(_check_bets_open(&bets, DEADLINE), Ok(()));
+        assert_eq!(_check_bets_open(&bets, DEADLINE + 1), Err(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn test_lamport_transfer_boundaries() {
+        const POT: u64 = 1_940_000;
+        // The pot pays exactly the payout and is left empty.
+        assert_eq!(_lamport_balances_after(POT, 0, POT), Ok((0, POT)));
+        assert_eq!(_lamport_balances_after(POT, 7, POT - 1), Ok((1, POT + 6)));
+        assert_eq!(_lamport_balances_after(POT - 1, 0, POT), Err(BetError::InsufficientPot));
+        assert_eq!(_lamport_balances_after(0, 0, 0), Ok((0, 0)));
+        // A receiver already at the top of u64.
+        assert_eq!(_lamport_balances_after(POT, u64::MAX - POT, POT), Ok((0, u64::MAX)));
+        assert_eq!(_lamport_balances_after(POT, u64::MAX - POT + 1, POT), Err(BetError::ArithmeticOverflow));
+        assert_eq!(_lamport_balances_after(u64::MAX, u64::MAX, 1), Err(BetError::ArithmeticOverflow));
+    }
+
+    #[test]
+    fn test_set_winner_deadline_boundaries() {
+        let bets = open_event();
+        assert_eq!(_check_deadline_passed(&bets, DEADLINE - 1), Err(ProgramError::InvalidAccountData));
+        assert_eq!(_check_deadline_passed(&bets, DEADLINE), Ok(()));
+        assert_eq!(_check_deadline_passed(&bets, DEADLINE + 1), Ok(()));
+    }
+}
@@ -0,0 +1,203 @@
+// Borsh schemas of the state and the instructions, for client generators and explorers.
+//
+// MatchOutcome is not a Borsh enum on the wire, Voided and Withdrawn are 254 and 255 rather than
+// their variant index. Its schema declares a one byte struct instead and MATCH_OUTCOME_VALUES maps
+// the byte to the outcomes. Instructions aren't Borsh either: WireInstruction encodes one byte for
+// byte as Instruction::pack, with strings and parlay choices as ShortVec, a sequence with a u8
+// length where Borsh's own have a u32 one.
+
+use crate::{Bet, ConfigParams, EventBets, EventParams, Instruction, MatchOutcome, Odds, MEMO_BYTES, METADATA_HASH_BYTES, SIGNATURE_BYTES};
+use borsh::{
+    schema::{BorshSchemaContainer, Declaration, Definition, Fields},
+    BorshSchema, BorshSerialize,
+};
+use solana_program::{clock::UnixTimestamp, pubkey::Pubkey};
+use std::{collections::HashMap, io::Write};
+
+// The byte of each MatchOutcome.
+pub const MATCH_OUTCOME_VALUES: &[(&str, u8)] = &[("Unknown", 0), ("TeamA", 1), ("TeamB", 2), ("Draw", 3), ("Voided", 254), ("Withdrawn", 255)];
+
+impl BorshSchema for MatchOutcome {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        let definition = Definition::Struct { fields: Fields::UnnamedFields(vec![u8::declaration()]) };
+        Self::add_definition(Self::declaration(), definition, definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "MatchOutcome".to_string()
+    }
+}
+
+// A sequence with a u8 length. Strings are ShortVec<u8> of their UTF-8.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShortVec<T>(pub Vec<T>);
+
+impl<T: BorshSerialize> BorshSerialize for ShortVec<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        (self.0.len() as u8).serialize(writer)?;
+        for item in &self.0 {
+            item.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: BorshSchema> BorshSchema for ShortVec<T> {
+    fn add_definitions_recursively(definitions: &mut HashMap<Declaration, Definition>) {
+        Self::add_definition(Self::declaration(), Definition::Sequence { elements: T::declaration() }, definitions);
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        format!("ShortVec<{}>", T::declaration())
+    }
+}
+
+impl From<&str> for ShortVec<u8> {
+    fn from(string: &str) -> Self {
+        ShortVec(string.as_bytes().to_vec())
+    }
+}
+
+// Instruction as it is on the wire, variants in tag order. The trailing fields older clients may
+// leave out are always there, as Instruction::pack writes them.
+#[derive(BorshSerialize, BorshSchema, Clone, Debug, PartialEq)]
+pub enum WireInstruction {
+    Initialize(EventParams),
+    AddBet { choice: MatchOutcome, referrer: Pubkey, amount: u64, memo: [u8; MEMO_BYTES] },
+    SetWinner { result: MatchOutcome, metadata_hash: [u8; METADATA_HASH_BYTES] },
+    Withdraw { amount: u64 },
+    SetWinnerSigned { result: MatchOutcome, event: Pubkey, signature: [u8; SIGNATURE_BYTES] },
+    CorrectResult { result: MatchOutcome },
+    SlashBond,
+    ReleaseBond,
+    InitializeConfig(ConfigParams),
+    UpdateConfig(ConfigParams),
+    WithdrawTreasury { amount: u64 },
+    SeedLiquidity,
+    TopUpPot { amount: u64 },
+    AuditEvent,
+    QuoteOdds,
+    QuotePayout,
+    IncreaseBet { choice: MatchOutcome, amount: u64 },
+    CashOut,
+    TransferBet { new_betor: Pubkey },
+    ListBet { price: u64 },
+    BuyBet { price: u64 },
+    TokenizeBet,
+    WithdrawMany,
+    SettleBets,
+    VoidBet { reason: u8 },
+    PauseEvent,
+    UnpauseEvent,
+    HaltProgram,
+    ResumeProgram,
+    CreateRegistryPage { page: u32 },
+    InitializeIndexed(EventParams),
+    InitializeSeeded { seed: ShortVec<u8>, params: EventParams },
+    InitializeMarket { bets_accepted_until: UnixTimestamp, num_outcomes: u8, commission_bps: u16 },
+    AddMarketBet { outcome: u8 },
+    SetMarketWinner { winner: u8 },
+    WithdrawMarketBet,
+    SetOdds { odds_a: Odds, odds_b: Odds },
+    CreateParlayPool,
+    PlaceParlay { choices: ShortVec<MatchOutcome> },
+    SettleParlay,
+    CreateTournament { slug: ShortVec<u8>, name: ShortVec<u8> },
+    AddEventToTournament,
+    MigrateEvent,
+    SweepDust,
+    ResetBet,
+    AddBetFor { choice: MatchOutcome, beneficiary: Pubkey, amount: u64 },
+    SetDelegate { delegate: Pubkey },
+    CollectCommission,
+    FinalizeResult,
+    CreateLeaderboard,
+    AddToAllowlist { wallet: Pubkey },
+    RemoveFromAllowlist { wallet: Pubkey },
+    AddToDenylist { wallet: Pubkey },
+    RemoveFromDenylist { wallet: Pubkey },
+}
+
+impl From<&Instruction> for WireInstruction {
+    fn from(instruction: &Instruction) -> Self {
+        use Instruction as I;
+        match instruction.clone() {
+            I::Initialize(params) => Self::Initialize(params),
+            I::AddBet { choice, referrer, amount, memo } => Self::AddBet { choice, referrer, amount, memo },
+            I::SetWinner { result, metadata_hash } => Self::SetWinner { result, metadata_hash },
+            I::Withdraw { amount } => Self::Withdraw { amount },
+            I::SetWinnerSigned { result, event, signature } => Self::SetWinnerSigned { result, event, signature },
+            I::CorrectResult { result } => Self::CorrectResult { result },
+            I::SlashBond => Self::SlashBond,
+            I::ReleaseBond => Self::ReleaseBond,
+            I::InitializeConfig(params) => Self::InitializeConfig(params),
+            I::UpdateConfig(params) => Self::UpdateConfig(params),
+            I::WithdrawTreasury { amount } => Self::WithdrawTreasury { amount },
+            I::SeedLiquidity => Self::SeedLiquidity,
+            I::TopUpPot { amount } => Self::TopUpPot { amount },
+            I::AuditEvent => Self::AuditEvent,
+            I::QuoteOdds => Self::QuoteOdds,
+            I::QuotePayout => Self::QuotePayout,
+            I::IncreaseBet { choice, amount } => Self::IncreaseBet { choice, amount },
+            I::CashOut => Self::CashOut,
+            I::TransferBet { new_betor } => Self::TransferBet { new_betor },
+            I::ListBet { price } => Self::ListBet { price },
+            I::BuyBet { price } => Self::BuyBet { price },
+            I::TokenizeBet => Self::TokenizeBet,
+            I::WithdrawMany => Self::WithdrawMany,
+            I::SettleBets => Self::SettleBets,
+            I::VoidBet { reason } => Self::VoidBet { reason },
+            I::PauseEvent => Self::PauseEvent,
+            I::UnpauseEvent => Self::UnpauseEvent,
+            I::HaltProgram => Self::HaltProgram,
+            I::ResumeProgram => Self::ResumeProgram,
+            I::CreateRegistryPage { page } => Self::CreateRegistryPage { page },
+            I::InitializeIndexed(params) => Self::InitializeIndexed(params),
+            I::InitializeSeeded { seed, params } => Self::InitializeSeeded { seed: seed.as_str().into(), params },
+            I::InitializeMarket { bets_accepted_until, num_outcomes, commission_bps } => Self::InitializeMarket { bets_accepted_until, num_outcomes, commission_bps },
+            I::AddMarketBet { outcome } => Self::AddMarketBet { outcome },
+            I::SetMarketWinner { winner } => Self::SetMarketWinner { winner },
+            I::WithdrawMarketBet => Self::WithdrawMarketBet,
+            I::SetOdds { odds_a, odds_b } => Self::SetOdds { odds_a, odds_b },
+            I::CreateParlayPool => Self::CreateParlayPool,
+            I::PlaceParlay { choices } => Self::PlaceParlay { choices: ShortVec(choices) },
+            I::SettleParlay => Self::SettleParlay,
+            I::CreateTournament { slug, name } => Self::CreateTournament { slug: slug.as_str().into(), name: name.as_str().into() },
+            I::AddEventToTournament => Self::AddEventToTournament,
+            I::MigrateEvent => Self::MigrateEvent,
+            I::SweepDust => Self::SweepDust,
+            I::ResetBet => Self::ResetBet,
+            I::AddBetFor { choice, beneficiary, amount } => Self::AddBetFor { choice, beneficiary, amount },
+            I::SetDelegate { delegate } => Self::SetDelegate { delegate },
+            I::CollectCommission => Self::CollectCommission,
+            I::FinalizeResult => Self::FinalizeResult,
+            I::CreateLeaderboard => Self::CreateLeaderboard,
+            I::AddToAllowlist { wallet } => Self::AddToAllowlist { wallet },
+            I::RemoveFromAllowlist { wallet } => Self::RemoveFromAllowlist { wallet },
+            I::AddToDenylist { wallet } => Self::AddToDenylist { wallet },
+            I::RemoveFromDenylist { wallet } => Self::RemoveFromDenylist { wallet },
+        }
+    }
+}
+
+pub fn match_outcome() -> BorshSchemaContainer {
+    MatchOutcome::schema_container()
+}
+
+pub fn event_bets() -> BorshSchemaContainer {
+    EventBets::schema_container()
+}
+
+pub fn bet() -> BorshSchemaContainer {
+    Bet::schema_container()
+}
+
+pub fn instruction() -> BorshSchemaContainer {
+    WireInstruction::schema_container()
+}
+
+// Every container above, e.g. for a build script to write out with try_to_vec.
+pub fn all() -> Vec<BorshSchemaContainer> {
+    vec![match_outcome(), event_bets(), bet(), instruction()]
+}
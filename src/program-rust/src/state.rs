@@ -0,0 +1,855 @@
+use crate::{error::BetError, net_of_commission, pod, BPS_DENOMINATOR, EVENT_SEED_BYTES, REGISTRY_PAGE_CAPACITY, WALLET_LIST_CAPACITY};
+use borsh::{
+    maybestd::io::{Error, ErrorKind, Result as BorshResult, Write},
+    BorshDeserialize, BorshSchema, BorshSerialize,
+};
+use solana_program::{
+    clock::UnixTimestamp,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::{Pubkey, PUBKEY_BYTES},
+};
+use std::convert::TryFrom;
+
+// Pubkeys as base58 strings in serde formats, as explorers show them.
+#[cfg(feature = "serde")]
+mod serde_pubkey {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_program::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        Pubkey::from_str(&string).map_err(|_| D::Error::custom(format!("{} is not a base58 pubkey", string)))
+    }
+}
+
+// EventBets::market_kind values.
+pub const MARKET_KIND_MONEYLINE: u8 = 0;
+pub const MARKET_KIND_HANDICAP: u8 = 1;
+pub const MARKET_KIND_TOTALS: u8 = 2;
+// Largest handicap either way, in half points.
+pub const MAX_HANDICAP: i16 = 200;
+// Largest totals line, in half points.
+pub const MAX_TOTALS_LINE: i16 = 400;
+// How long after SetWinner the arbiter may still CorrectResult, unless a bet was withdrawn already.
+pub const DEFAULT_CORRECTION_WINDOW_SECS: i64 = 60 * 60;
+pub const METADATA_HASH_BYTES: usize = 32;
+pub const MEMO_BYTES: usize = 32;
+
+// Serialized by name in serde formats, MatchOutcome::as_u8 gives the byte.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MatchOutcome {
+    Unknown,
+    TeamA,
+    TeamB,
+    Draw,
+    Withdrawn,
+    // Annulled by the arbiter with VoidBet, the stake went back to the betor.
+    Voided,
+}
+
+// A totals market's sides, wire compatible with TeamA and TeamB.
+impl MatchOutcome {
+    pub const OVER: MatchOutcome = MatchOutcome::TeamA;
+    pub const UNDER: MatchOutcome = MatchOutcome::TeamB;
+}
+
+impl MatchOutcome {
+    pub const ALL: [MatchOutcome; 6] = [MatchOutcome::Unknown, MatchOutcome::TeamA, MatchOutcome::TeamB, MatchOutcome::Draw, MatchOutcome::Withdrawn, MatchOutcome::Voided];
+
+    // The byte in accounts and instructions.
+    pub fn as_u8(self) -> u8 {
+        self.into()
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::try_from(value).ok()
+    }
+
+    // Withdrawn and Voided only mark bets that are closed, no event has them as its result.
+    pub fn is_closed_bet(self) -> bool {
+        matches!(self, MatchOutcome::Withdrawn | MatchOutcome::Voided)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MatchOutcome::Unknown => "Unknown",
+            MatchOutcome::TeamA => "TeamA",
+            MatchOutcome::TeamB => "TeamB",
+            MatchOutcome::Draw => "Draw",
+            MatchOutcome::Withdrawn => "Withdrawn",
+            MatchOutcome::Voided => "Voided",
+        }
+    }
+}
+
+// The one mapping of outcomes to bytes, Borsh included.
+impl From<MatchOutcome> for u8 {
+    fn from(outcome: MatchOutcome) -> Self {
+        match outcome {
+            MatchOutcome::Unknown => 0,
+            MatchOutcome::TeamA => 1,
+            MatchOutcome::TeamB => 2,
+            MatchOutcome::Draw => 3,
+            MatchOutcome::Withdrawn => 255,
+            MatchOutcome::Voided => 254,
+        }
+    }
+}
+
+impl TryFrom<u8> for MatchOutcome {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MatchOutcome::Unknown),
+            1 => Ok(MatchOutcome::TeamA),
+            2 => Ok(MatchOutcome::TeamB),
+            3 => Ok(MatchOutcome::Draw),
+            255 => Ok(MatchOutcome::Withdrawn),
+            254 => Ok(MatchOutcome::Voided),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+impl Default for MatchOutcome {
+    fn default() -> Self {
+        MatchOutcome::Unknown
+    }
+}
+
+impl std::fmt::Display for MatchOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            MatchOutcome::Unknown => "Unknown",
+            MatchOutcome::TeamA => "Team A",
+            MatchOutcome::TeamB => "Team B",
+            MatchOutcome::Draw => "Draw",
+            MatchOutcome::Withdrawn => "Withdrawn",
+            MatchOutcome::Voided => "Voided",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseMatchOutcomeError(pub String);
+
+impl std::fmt::Display for ParseMatchOutcomeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "\"{}\" is not a match outcome, expected Unknown, TeamA, TeamB, Draw, Withdrawn, Voided or their byte", self.0)
+    }
+}
+
+impl std::error::Error for ParseMatchOutcomeError {}
+
+// The name or the Display text in any case, or the byte in decimal.
+impl std::str::FromStr for MatchOutcome {
+    type Err = ParseMatchOutcomeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let trimmed = input.trim();
+        let outcome = match trimmed.parse::<u8>() {
+            Ok(value) => Self::from_u8(value),
+            Err(_) => Self::ALL.iter().copied().find(|outcome| {
+                outcome.name().eq_ignore_ascii_case(trimmed) || outcome.to_string().eq_ignore_ascii_case(trimmed)
+            }),
+        };
+        outcome.ok_or_else(|| ParseMatchOutcomeError(input.to_string()))
+    }
+}
+
+impl BorshSerialize for MatchOutcome {
+    fn serialize<W: Write>(&self, writer: &mut W) -> BorshResult<()> {
+        u8::from(*self).serialize(writer)
+    }
+}
+
+impl BorshDeserialize for MatchOutcome {
+    fn deserialize(buf: &mut &[u8]) -> BorshResult<Self> {
+        if buf.len() != 1 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unexpected length of input",
+            ));
+        }
+        Self::try_from(buf[0]).map_err(|_| Error::new(ErrorKind::InvalidInput, "MatchOutcome_bad_input"))
+    }
+}
+
+// Decimal odds of a fixed-odds event as numerator/denominator, a winning stake is paid
+// stake * numerator / denominator, the stake included. 0/0 means no odds posted.
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Odds {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl Odds {
+    pub const LEN: usize = 4 + 4;
+
+    pub fn is_set(&self) -> bool {
+        self.denominator != 0
+    }
+
+    // What a winning `stake` is paid at these odds, rounded down.
+    pub fn payout(&self, stake: u64) -> Option<u64> {
+        use std::convert::TryFrom;
+        if !self.is_set() {
+            return None;
+        }
+        u64::try_from(stake as u128 * self.numerator as u128 / self.denominator as u128).ok()
+    }
+}
+
+// First byte of EventBets and Bet. 0 is an account nothing was written to yet. Version 1 accounts
+// are the layout from before the byte was a version, when it was is_initialized and 1 once written.
+// They load again after MigrateEvent.
+pub const STATE_VERSION: u8 = 2;
+pub const STATE_VERSION_V1: u8 = 1;
+
+#[derive(BorshSchema)]
+#[cfg_attr(feature = "borsh-state", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventBets {
+    // STATE_VERSION once initialized.
+    pub version: u8,
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub arbiter: Pubkey,
+    pub bets_allowed_until_ts: UnixTimestamp,
+    pub outcome: u8,
+    pub balance_a: u64,
+    pub balance_b: u64,
+    // For how long after the first SetWinner the arbiter may still correct the result.
+    pub dispute_window_secs: i64,
+    // When the first SetWinner happened, 0 while the outcome is Unknown. CorrectResult keeps it.
+    pub winner_set_at: UnixTimestamp,
+    // For how long after the first SetWinner withdrawals are not allowed, may be 0.
+    pub finality_delay_secs: i64,
+    // Lamports the arbiter locked on top of the rent exemption, never part of balance_a/balance_b.
+    pub bond_lamports: u64,
+    // Who may slash the bond during the dispute window, default pubkey means nobody.
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub challenger: Pubkey,
+    // A slashed bond is shared between the winners instead of returning to the arbiter.
+    pub bond_slashed: bool,
+    // Commission in basis points, deducted from every winning payout. SOL events accrue it per claim into
+    // commission_accrued, SPL token events pay all of it to commission_recipient's token account at SetWinner.
+    pub commission_bps: u16,
+    // Smallest accepted stake, 0 means no minimum.
+    pub min_bet_lamports: u64,
+    // Largest total stake of a single betor, 0 means unlimited.
+    pub max_stake_per_wallet: u64,
+    // A pool may hold at most that many times the other one, 0 means no limit.
+    pub max_imbalance_ratio: u16,
+    // Arbiter's promotional lamports and the part of cashed out stakes that stayed in the pot,
+    // shared by the winners only, never part of balance_a/balance_b.
+    pub bonus_lamports: u64,
+    // Whether the bonus is paid out without the commission.
+    pub bonus_commission_free: bool,
+    // Stakes placed with a referrer, per side.
+    pub referred_a: u64,
+    pub referred_b: u64,
+    // Referrers' part of the commission, copied from the config at SetWinner.
+    pub referral_bps: u16,
+    // Commission kept for the referrers of the winning bets at SetWinner and CorrectResult, paid out at Withdraw.
+    pub referral_reserve: u64,
+    // Only holders of at least gate_min_amount of this mint may bet, default pubkey means anyone.
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub gate_mint: Pubkey,
+    pub gate_min_amount: u64,
+    // Token the pools are denominated in, default pubkey means native SOL.
+    // Pools, stakes, commission and payouts are then in the token's base units, rent and bond stay in lamports.
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub mint: Pubkey,
+    // Owner of the mint, SPL Token or Token-2022.
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub token_program: Pubkey,
+    // Hash of the off-chain fixture description (e.g. SHA-256 of a canonical JSON), set once at Initialize.
+    pub metadata_hash: [u8; METADATA_HASH_BYTES],
+    // Number of bets placed through AddBet per side, for display only and never used in payouts.
+    pub bets_count_a: u32,
+    pub bets_count_b: u32,
+    // Sum of the stake payouts made by Withdraw, in the pools' unit, net of commission.
+    // Bond and bonus shares are not included.
+    pub total_paid_out: u64,
+    // Set by PauseEvent while the match is under review.
+    pub paused: bool,
+    // Bump of the event's address for program-derived events, 0 for keypair ones.
+    pub bump: u8,
+    // InitializeSeeded's seed, zero padded, all zeroes for other events.
+    pub seed: [u8; EVENT_SEED_BYTES],
+    // Bets are paid at the odds posted by SetOdds when they were placed, not from the pools.
+    // The arbiter is the counterparty and funds the difference with TopUpPot.
+    pub fixed_odds: bool,
+    pub odds_a: Odds,
+    pub odds_b: Odds,
+    // Sum of the locked payouts of the bets per side, what the event owes if that side wins.
+    pub liability_a: u64,
+    pub liability_b: u64,
+    // Larger of liability_a and liability_b, never above the stakes plus the arbiter's top-ups.
+    pub worst_case_liability: u64,
+    // Set once by AddEventToTournament, default pubkey means none. At TOURNAMENT_OFFSET for memcmp filters.
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub tournament: Pubkey,
+    // MARKET_KIND_MONEYLINE, MARKET_KIND_HANDICAP or MARKET_KIND_TOTALS, the arbiter decides either way.
+    pub market_kind: u8,
+    // TeamA's handicap in half points, e.g. -3 for TeamA -1.5. TeamA wins when it covers, TeamB when
+    // it doesn't, Draw is a push on whole lines. Always 0 in moneyline events.
+    // In totals markets the line instead, e.g. 5 for 2.5, always a half so there is no Draw: TeamA is
+    // MatchOutcome::OVER and TeamB MatchOutcome::UNDER.
+    pub handicap: i16,
+    // Bets placed and not yet withdrawn, voided or cashed out. SweepDust waits for it to reach 0.
+    pub outstanding_bets: u32,
+    // Commission of the payouts withdrawn so far in a SOL event, held by the event until the arbiter
+    // takes it with CollectCommission.
+    pub commission_accrued: u64,
+    // CorrectResult is allowed for this long after SetWinner, or the dispute window if longer.
+    pub correction_window_secs: i64,
+    // Bets withdrawn after the result, the first claim of each. CorrectResult needs it to be 0.
+    pub claims_count: u32,
+    // Gets the commission instead of the arbiter, who still signs settlement. The arbiter unless
+    // Initialize names another key.
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub commission_recipient: Pubkey,
+    // Set by FinalizeResult or the first withdrawal after the correction window, the result can't be
+    // corrected from then on.
+    pub finalized: bool,
+    // Wallets that bet on the event, counted when AddBet creates their seen_address marker.
+    pub unique_bettors: u32,
+    // Most bets a single betor may hold open on the event, 0 means unlimited.
+    pub max_bets_per_wallet: u8,
+    // Set by the first AddToAllowlist, only wallets on allowlist_address(event) can bet from then on.
+    pub allowlisted: bool,
+    // Set by the first AddToDenylist, wallets on denylist_address(event) can't bet from then on.
+    pub denylisted: bool,
+    // The arbiter and the commission recipient may bet, see EventParams.
+    pub arbiter_may_bet: bool,
+    // CorrectResult waits this long after the last change of the result.
+    pub correction_cooldown_secs: i64,
+    // When SetWinner or the last CorrectResult changed the result, 0 while the outcome is Unknown.
+    pub last_result_change_at: UnixTimestamp,
+}
+
+#[derive(BorshSchema)]
+#[cfg_attr(feature = "borsh-state", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bet {
+    // STATE_VERSION once placed.
+    pub version: u8,
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub betor: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub event: Pubkey,
+    pub amount: u64,
+    pub outcome: u8,
+    // Gets a share of the commission on this bet's payout, default pubkey means no referrer.
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub referrer: Pubkey,
+    // Caller's reference for the bet, stored as is and never read by the program.
+    pub memo: [u8; MEMO_BYTES],
+    // Clock time when the bet was added, never changed afterwards.
+    pub placed_at: UnixTimestamp,
+    // Part of the payout already withdrawn, in the payout's unit.
+    pub claimed: u64,
+    // Listed by the betor with ListBet, anyone may take it over with BuyBet for ask_lamports.
+    pub for_sale: bool,
+    pub ask_lamports: u64,
+    // Set by TokenizeBet, whoever holds the token is paid by Withdraw. Default pubkey means not tokenized.
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub receipt_mint: Pubkey,
+    // Odds of a fixed-odds event when the bet was placed, 0/0 in parimutuel events.
+    pub locked_odds: Odds,
+    // Set by the betor with SetDelegate, may then sign Withdraw in the betor's place. Withdrawals of a bet
+    // with a delegate need the betor's or the delegate's signature. Default pubkey means no delegate.
+    #[cfg_attr(feature = "serde", serde(with = "serde_pubkey"))]
+    pub delegate: Pubkey,
+}
+
+impl EventBets {
+    // Offsets for memcmp filters, see client::events_by_arbiter.
+    pub const ARBITER_OFFSET: usize = 1;
+    pub const OUTCOME_OFFSET: usize = Self::ARBITER_OFFSET + PUBKEY_BYTES + 8;
+    pub const TOURNAMENT_OFFSET: usize = 1 + PUBKEY_BYTES + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + PUBKEY_BYTES + 1 + 2 + 8 + 8 + 2 + 8 + 1 + 8 + 8 + 2 + 8 + PUBKEY_BYTES + 8 + PUBKEY_BYTES + PUBKEY_BYTES + METADATA_HASH_BYTES + 4 + 4 + 8 + 1 + 1 + EVENT_SEED_BYTES + 1 + Odds::LEN + Odds::LEN + 8 + 8 + 8;
+    pub const LEN: usize = Self::TOURNAMENT_OFFSET + PUBKEY_BYTES + 1 + 2 + 4 + 8 + 8 + 4 + PUBKEY_BYTES + 1 + 4 + 1 + 1 + 1 + 1 + 8 + 8;
+
+    // Withdrawals open once both the dispute window and the finality delay are over.
+    pub fn result_final_at(&self) -> UnixTimestamp {
+        self.winner_set_at.saturating_add(std::cmp::max(self.dispute_window_secs, self.finality_delay_secs))
+    }
+
+    // CorrectResult is refused from then on.
+    pub fn correction_closes_at(&self) -> UnixTimestamp {
+        self.winner_set_at.saturating_add(std::cmp::max(self.dispute_window_secs, self.correction_window_secs))
+    }
+
+    // Withdraw and the other claims wait for the result to be final and out of the correction window,
+    // or finalized by FinalizeResult.
+    pub fn claimable_at(&self) -> UnixTimestamp {
+        if self.finalized {
+            self.result_final_at()
+        } else {
+            std::cmp::max(self.result_final_at(), self.correction_closes_at())
+        }
+    }
+
+    pub fn quote_odds(&self) -> OddsQuote {
+        let pools = self.balance_a as u128 + self.balance_b as u128;
+        let implied = |pool: u64| if pools > 0 { (pool as u128 * BPS_DENOMINATOR as u128 / pools) as u16 } else { 0 };
+        OddsQuote {
+            balance_a: self.balance_a,
+            balance_b: self.balance_b,
+            implied_a_bps: implied(self.balance_a),
+            implied_b_bps: implied(self.balance_b),
+            commission_bps: self.commission_bps,
+            market_kind: self.market_kind,
+            handicap: self.handicap,
+        }
+    }
+
+    // Whether betors pass their limit_address account, to be held to the stake or the bets cap.
+    pub fn caps_wallets(&self) -> bool {
+        self.max_stake_per_wallet > 0 || self.max_bets_per_wallet > 0
+    }
+
+    // Whether the stakes and the arbiter's top-ups pay every fixed-odds bet of the side that wins.
+    // A Draw refunds the stakes, which are always there.
+    pub fn liability_covered(&self) -> bool {
+        let funds = self.balance_a as u128 + self.balance_b as u128 + self.bonus_lamports as u128;
+        self.worst_case_liability as u128 <= funds
+    }
+
+    // Stakes sharing a slashed bond and the bonus under the result, 0 when no bet backed it.
+    pub fn winning_stakes(&self) -> u128 {
+        match MatchOutcome::try_from(self.outcome) {
+            Ok(MatchOutcome::TeamA) => self.balance_a as u128,
+            Ok(MatchOutcome::TeamB) => self.balance_b as u128,
+            Ok(MatchOutcome::Draw) => self.balance_a as u128 + self.balance_b as u128,
+            _ => 0,
+        }
+    }
+
+    // Most Withdraw may pay out in stake payouts: the pools net of commission, and in fixed-odds
+    // events the arbiter's top-ups on top.
+    pub fn payout_cap(&self) -> u128 {
+        let net_pools = net_of_commission(self.balance_a as u128 + self.balance_b as u128, self.commission_bps);
+        if self.fixed_odds {
+            net_pools + self.bonus_lamports as u128
+        } else {
+            net_pools
+        }
+    }
+
+    // Total payout, before commission, of the referred bets winning under `result`.
+    // Never below the sum of the per-bet payouts, which are rounded down one by one.
+    pub fn referred_gross(&self, result: MatchOutcome) -> u64 {
+        let won = |referred: u64, pool: u64, other_pool: u64| {
+            if pool == 0 {
+                return 0;
+            }
+            (referred as u128 * other_pool as u128 / pool as u128) as u64 + referred
+        };
+        match result {
+            MatchOutcome::TeamA => won(self.referred_a, self.balance_a, self.balance_b),
+            MatchOutcome::TeamB => won(self.referred_b, self.balance_b, self.balance_a),
+            MatchOutcome::Draw => self.referred_a + self.referred_b,
+            _ => 0,
+        }
+    }
+}
+
+// Program-wide knobs, lives at config_address().
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Config {
+    pub is_initialized: bool,
+    pub admin: Pubkey,
+    pub default_commission_bps: u16,
+    pub max_commission_bps: u16,
+    // Events must accept bets for at least that long after Initialize.
+    pub min_deadline_offset_secs: i64,
+    // Part of the commission going to the arbiter, the rest goes to the treasury.
+    pub arbiter_share_bps: u16,
+    // Part of a referred bet's commission going to its referrer.
+    pub referral_bps: u16,
+    // Emergency switch set by HaltProgram, refused by Initialize and AddBet when they get the config.
+    pub halted: bool,
+}
+
+impl Config {
+    pub const LEN: usize = 1 + PUBKEY_BYTES + 2 + 2 + 8 + 2 + 2 + 1;
+}
+
+// Lives at limit_address(event, betor).
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct WalletStake {
+    pub is_initialized: bool,
+    pub total_staked: u64,
+    // Bets of the betor on the event not cashed out, held to max_bets_per_wallet.
+    pub bets_open: u32,
+}
+
+impl WalletStake {
+    pub const LEN: usize = 1 + 8 + 4;
+}
+
+pub const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct LeaderboardEntry {
+    pub betor: Pubkey,
+    // Lamports withdrawn from the event's winning bets so far.
+    pub won: u64,
+}
+
+// Lives at leaderboard_address(event). Sorted by `won`, largest first, unused entries are zeroed at the end.
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+pub struct Leaderboard {
+    pub is_initialized: bool,
+    pub event: Pubkey,
+    pub entries: [LeaderboardEntry; LEADERBOARD_SIZE],
+}
+
+impl Leaderboard {
+    pub const LEN: usize = 1 + PUBKEY_BYTES + LEADERBOARD_SIZE * (PUBKEY_BYTES + 8);
+
+    // Adds `amount` to the betor's entry, or gives them one if that beats the last entry. A full board
+    // evicts its last entry, a tie with it keeps the one already there. None on overflow.
+    pub fn record(&mut self, betor: &Pubkey, amount: u64) -> Option<()> {
+        let last = LEADERBOARD_SIZE - 1;
+        let mut index = match self.entries.iter().position(|entry| entry.won > 0 && entry.betor == *betor) {
+            Some(index) => index,
+            None if amount > self.entries[last].won => {
+                self.entries[last] = LeaderboardEntry { betor: *betor, won: 0 };
+                last
+            },
+            None => return Some(()),
+        };
+        self.entries[index].won = self.entries[index].won.checked_add(amount)?;
+        // Only this entry grew, it moves up past the smaller ones and stays behind equal ones.
+        while index > 0 && self.entries[index].won > self.entries[index - 1].won {
+            self.entries.swap(index, index - 1);
+            index -= 1;
+        }
+        Some(())
+    }
+}
+
+// Lives at stats_address(betor), updated by the AddBet and Withdraw that pass it. In lamports.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq)]
+pub struct BettorStats {
+    pub is_initialized: bool,
+    pub staked_total: u64,
+    // Paid out to winning bets, refunds of a Draw or a push are not counted.
+    pub won_total: u64,
+    pub bets_placed: u32,
+    // Bets on the result, counted on their first claim.
+    pub bets_won: u32,
+}
+
+impl BettorStats {
+    pub const LEN: usize = 1 + 8 + 8 + 4 + 4;
+}
+
+pub const MAX_OUTCOMES: usize = 16;
+// Market::winner until SetMarketWinner.
+pub const MARKET_UNSETTLED: u8 = u8::MAX;
+
+// An event with up to MAX_OUTCOMES outcomes, from InitializeMarket. Its bets are Bet accounts whose
+// outcome is the index of the outcome picked. SOL only.
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+pub struct Market {
+    pub is_initialized: bool,
+    pub arbiter: Pubkey,
+    pub bets_allowed_until_ts: UnixTimestamp,
+    pub commission_bps: u16,
+    pub num_outcomes: u8,
+    pub winner: u8,
+    // One pool per outcome, only the first num_outcomes are used.
+    pub pools: [u64; MAX_OUTCOMES],
+}
+
+impl Market {
+    pub const LEN: usize = 1 + PUBKEY_BYTES + 8 + 2 + 1 + 1 + 8 * MAX_OUTCOMES;
+
+    pub fn total_pool(&self) -> u128 {
+        self.pools.iter().map(|&pool| pool as u128).sum()
+    }
+}
+
+pub const MAX_PARLAY_LEGS: usize = 8;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ParlayLeg {
+    pub event: Pubkey,
+    // TeamA or TeamB, as in Bet::outcome.
+    pub outcome: u8,
+}
+
+// A single stake on 2 to MAX_PARLAY_LEGS events, from PlaceParlay, see calculate_parlay_payout.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq)]
+pub struct Parlay {
+    pub is_initialized: bool,
+    pub betor: Pubkey,
+    pub stake: u64,
+    pub num_legs: u8,
+    // Only the first num_legs are used.
+    pub legs: [ParlayLeg; MAX_PARLAY_LEGS],
+    // Set by SettleParlay, with what it paid.
+    pub settled: bool,
+    pub payout: u64,
+}
+
+impl Parlay {
+    pub const LEN: usize = 1 + PUBKEY_BYTES + 8 + 1 + (PUBKEY_BYTES + 1) * MAX_PARLAY_LEGS + 1 + 8;
+}
+
+pub const TOURNAMENT_NAME_BYTES: usize = 64;
+
+// Groups an arbiter's events, lives at tournament_address(arbiter, slug).
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+pub struct Tournament {
+    pub is_initialized: bool,
+    pub arbiter: Pubkey,
+    // Zero padded, like EventBets::seed.
+    pub slug: [u8; EVENT_SEED_BYTES],
+    // Display name, UTF-8 zero padded.
+    pub name: [u8; TOURNAMENT_NAME_BYTES],
+    // Events added with AddEventToTournament.
+    pub event_count: u32,
+}
+
+impl Tournament {
+    pub const LEN: usize = 1 + PUBKEY_BYTES + EVENT_SEED_BYTES + TOURNAMENT_NAME_BYTES + 4;
+}
+
+// Lives at arbiter_counter_address(arbiter).
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct ArbiterCounter {
+    pub is_initialized: bool,
+    // Index the arbiter's next InitializeIndexed event gets.
+    pub next_event_index: u64,
+}
+
+impl ArbiterCounter {
+    pub const LEN: usize = 1 + 8;
+}
+
+// Header of the page at registry_address(page), followed by `count` event pubkeys and room for the rest.
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct RegistryPage {
+    pub is_initialized: bool,
+    pub page: u32,
+    pub count: u32,
+}
+
+impl RegistryPage {
+    pub const HEADER_LEN: usize = 1 + 4 + 4;
+    pub const LEN: usize = Self::HEADER_LEN + REGISTRY_PAGE_CAPACITY * PUBKEY_BYTES;
+
+    // The `index`th event of the page, `data` being the whole account.
+    pub fn event(data: &[u8], index: usize) -> Option<Pubkey> {
+        let start = Self::HEADER_LEN + index * PUBKEY_BYTES;
+        data.get(start..start + PUBKEY_BYTES).map(Pubkey::new)
+    }
+}
+
+// Header of the allowlist or denylist of `event`, followed by `count` wallets in no particular order
+// and room for the rest.
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct WalletList {
+    pub is_initialized: bool,
+    pub event: Pubkey,
+    pub count: u32,
+}
+
+impl WalletList {
+    pub const HEADER_LEN: usize = 1 + PUBKEY_BYTES + 4;
+    pub const LEN: usize = Self::HEADER_LEN + WALLET_LIST_CAPACITY * PUBKEY_BYTES;
+
+    // The `index`th wallet of the list, `data` being the whole account.
+    pub fn wallet(data: &[u8], index: usize) -> Option<Pubkey> {
+        let start = Self::HEADER_LEN + index * PUBKEY_BYTES;
+        data.get(start..start + PUBKEY_BYTES).map(Pubkey::new)
+    }
+
+    // Where `wallet` is among the first `count` wallets of `data`.
+    pub fn position(&self, data: &[u8], wallet: &Pubkey) -> Option<usize> {
+        (0..self.count as usize).find(|&index| Self::wallet(data, index).as_ref() == Some(wallet))
+    }
+}
+
+// Returned by QuoteOdds. Implied chances are each pool's share of both pools, rounded down,
+// both 0 while nothing is staked. A winning stake gets its share of both pools less commission_bps.
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+pub struct OddsQuote {
+    pub balance_a: u64,
+    pub balance_b: u64,
+    pub implied_a_bps: u16,
+    pub implied_b_bps: u16,
+    pub commission_bps: u16,
+    // The event's market kind and line, see EventBets.
+    pub market_kind: u8,
+    pub handicap: i16,
+}
+
+impl Bet {
+    // Offsets for memcmp filters, see client::bets_by_betor and client::bets_for_event.
+    pub const BETOR_OFFSET: usize = 1;
+    pub const EVENT_OFFSET: usize = Self::BETOR_OFFSET + PUBKEY_BYTES;
+    pub const OUTCOME_OFFSET: usize = Self::EVENT_OFFSET + PUBKEY_BYTES + 8;
+    pub const LEN: usize = 1 + PUBKEY_BYTES + PUBKEY_BYTES + 8 + 1 + PUBKEY_BYTES + MEMO_BYTES + 8 + 8 + 1 + 8 + PUBKEY_BYTES + Odds::LEN + PUBKEY_BYTES;
+}
+
+// EventBets and Bet are packed by hand in the layouts of pod::EventBetsPod and pod::BetPod, which
+// are the bytes of their Borsh encoding, so existing accounts load as they are.
+impl Sealed for EventBets {}
+
+impl IsInitialized for EventBets {
+    fn is_initialized(&self) -> bool {
+        self.version != 0
+    }
+}
+
+impl Pack for EventBets {
+    const LEN: usize = EventBets::LEN;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        *bytemuck::from_bytes_mut::<pod::EventBetsPod>(dst) = self.into();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let view = pod::load::<pod::EventBetsPod>(src)?;
+        _check_version(view.version)?;
+        Ok(view.into())
+    }
+}
+
+impl Sealed for Bet {}
+
+impl IsInitialized for Bet {
+    fn is_initialized(&self) -> bool {
+        self.version != 0
+    }
+}
+
+impl Pack for Bet {
+    const LEN: usize = Bet::LEN;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        *bytemuck::from_bytes_mut::<pod::BetPod>(dst) = self.into();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let view = pod::load::<pod::BetPod>(src)?;
+        _check_version(view.version)?;
+        Ok(view.into())
+    }
+}
+
+// An event's result or a bet's pick, which are never the Withdrawn or Voided of closed bets.
+pub(crate) fn result_outcome(src: u8) -> Result<MatchOutcome, ProgramError> {
+    let outcome = MatchOutcome::try_from(src)?;
+    if outcome.is_closed_bet() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(outcome)
+}
+
+// Accounts nothing was written to yet and current ones load, anything else waits for MigrateEvent.
+pub(crate) fn _check_version(version: u8) -> ProgramResult {
+    if version != 0 && version != STATE_VERSION {
+        msg!("Instruction: _check_version: version {}, expected {}", version, STATE_VERSION);
+        return Err(BetError::VersionMismatch.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_outcome_round_trips() {
+        for &outcome in MatchOutcome::ALL.iter() {
+            let value = u8::from(outcome);
+            assert_eq!(MatchOutcome::try_from(value), Ok(outcome));
+            assert_eq!(MatchOutcome::from_u8(value), Some(outcome));
+            assert_eq!(outcome.to_string().parse(), Ok(outcome));
+            assert_eq!(outcome.name().parse(), Ok(outcome));
+            assert_eq!(outcome.try_to_vec().unwrap(), [value]);
+            assert_eq!(MatchOutcome::try_from_slice(&[value]).unwrap(), outcome);
+        }
+        assert_eq!(MatchOutcome::try_from(4), Err(ProgramError::InvalidAccountData));
+        assert!(MatchOutcome::try_from_slice(&[1, 1]).is_err());
+    }
+
+    // Borsh and Pack write the same bytes, and each reads what the other wrote.
+    #[cfg(feature = "borsh-state")]
+    #[test]
+    fn test_state_borsh_round_trips() {
+        let key = Pubkey::new_from_array([5; PUBKEY_BYTES]);
+        let mut bets = EventBets::unpack_unchecked(&[0; EventBets::LEN]).unwrap();
+        bets.version = STATE_VERSION;
+        bets.arbiter = key;
+        bets.bets_allowed_until_ts = -7;
+        bets.balance_a = u64::MAX;
+        bets.balance_b = 3;
+        bets.commission_bps = 300;
+        bets.outcome = u8::from(MatchOutcome::Draw);
+        let mut packed = vec![0; EventBets::LEN];
+        EventBets::pack(bets, &mut packed).unwrap();
+        assert_eq!(EventBets::try_from_slice(&packed).unwrap().try_to_vec().unwrap(), packed);
+        let bets = EventBets::unpack(&packed).unwrap();
+        assert_eq!((bets.arbiter, bets.bets_allowed_until_ts, bets.balance_a, bets.balance_b), (key, -7, u64::MAX, 3));
+
+        let mut this_bet = Bet::unpack_unchecked(&[0; Bet::LEN]).unwrap();
+        this_bet.version = STATE_VERSION;
+        this_bet.event = key;
+        this_bet.betor = Pubkey::new_from_array([6; PUBKEY_BYTES]);
+        this_bet.amount = 1_000_000;
+        this_bet.outcome = u8::from(MatchOutcome::TeamB);
+        let mut packed = vec![0; Bet::LEN];
+        Bet::pack(this_bet, &mut packed).unwrap();
+        assert_eq!(Bet::try_from_slice(&packed).unwrap().try_to_vec().unwrap(), packed);
+        let this_bet = Bet::unpack(&packed).unwrap();
+        assert_eq!((this_bet.event, this_bet.amount, this_bet.outcome), (key, 1_000_000, 2));
+    }
+
+    #[test]
+    fn test_leaderboard_record() {
+        let key = |i: u8| Pubkey::new_from_array([i; PUBKEY_BYTES]);
+        let mut board = Leaderboard { is_initialized: true, event: key(0), entries: Default::default() };
+        board.record(&key(1), 0).unwrap();
+        assert_eq!(board.entries[0], LeaderboardEntry::default());
+
+        for i in 1..=LEADERBOARD_SIZE as u8 {
+            board.record(&key(i), 10 * i as u64).unwrap();
+        }
+        assert_eq!(board.entries[0], LeaderboardEntry { betor: key(10), won: 100 });
+        assert_eq!(board.entries[LEADERBOARD_SIZE - 1], LeaderboardEntry { betor: key(1), won: 10 });
+
+        // A tie with the last entry doesn't evict it, beating it does.
+        board.record(&key(11), 10).unwrap();
+        assert_eq!(board.entries[LEADERBOARD_SIZE - 1].betor, key(1));
+        board.record(&key(11), 15).unwrap();
+        assert_eq!(board.entries[LEADERBOARD_SIZE - 1], LeaderboardEntry { betor: key(11), won: 15 });
+        assert!(board.entries.iter().all(|entry| entry.betor != key(1)));
+
+        // A repeat win accumulates in place and moves up.
+        board.record(&key(11), 100).unwrap();
+        assert_eq!(board.entries[0], LeaderboardEntry { betor: key(11), won: 115 });
+        assert_eq!(board.entries.iter().filter(|entry| entry.betor == key(11)).count(), 1);
+        assert!(board.entries.windows(2).all(|pair| pair[0].won >= pair[1].won));
+        assert!(board.record(&key(11), u64::MAX).is_none());
+    }
+}
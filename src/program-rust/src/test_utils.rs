@@ -0,0 +1,281 @@
+// Accounts holding an event or a bet for tests of this crate and of programs and clients using it,
+// `--features test-utils`. The (Pubkey, Account) pairs go to ProgramTest::add_account as they are,
+// InstructionAccounts lays them out in an instruction's order for a transaction or for calling
+// process_instruction on AccountInfos directly.
+
+use crate::{
+    id, Bet, ConfigParams, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome, Odds, COMISSION_BPS, MEMO_BYTES,
+    METADATA_HASH_BYTES, SIGNATURE_BYTES, STATE_VERSION,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::UnixTimestamp,
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::{Pubkey, PUBKEY_BYTES},
+    rent::Rent,
+    system_program,
+};
+use solana_sdk::account::Account;
+
+// An account of the program holding `data`, rent exempt under the default Rent plus `extra_lamports`.
+pub fn program_account(data: Vec<u8>, extra_lamports: u64) -> Account {
+    let lamports = Rent::default().minimum_balance(data.len()) + extra_lamports;
+    Account { lamports, data, owner: id(), executable: false, rent_epoch: 0 }
+}
+
+// The event Initialize leaves for EventParams::new(deadline), without a config.
+pub fn new_event_bets(arbiter: &Pubkey, deadline: UnixTimestamp) -> EventBets {
+    let params = EventParams::new(deadline);
+    let mut bets = EventBets::unpack_unchecked(&[0; EventBets::LEN]).unwrap();
+    bets.version = STATE_VERSION;
+    bets.arbiter = *arbiter;
+    bets.bets_allowed_until_ts = params.bets_accepted_until;
+    bets.commission_bps = COMISSION_BPS;
+    bets.market_kind = params.market_kind;
+    bets
+}
+
+// The bet AddBet leaves for `amount` on `outcome`, placed at 0.
+pub fn new_bet(event: &Pubkey, betor: &Pubkey, amount: u64, outcome: MatchOutcome) -> Bet {
+    let mut this_bet = Bet::unpack_unchecked(&[0; Bet::LEN]).unwrap();
+    this_bet.version = STATE_VERSION;
+    this_bet.betor = *betor;
+    this_bet.event = *event;
+    this_bet.amount = amount;
+    this_bet.outcome = u8::from(outcome);
+    this_bet
+}
+
+// The event holds its pools and bond above the rent exemption, as after the bets were placed.
+pub fn event_account(bets: EventBets) -> Account {
+    let mut data = vec![0; EventBets::LEN];
+    let extra_lamports = bets.balance_a + bets.balance_b + bets.bonus_lamports + bets.bond_lamports;
+    EventBets::pack(bets, &mut data).unwrap();
+    program_account(data, extra_lamports)
+}
+
+// The bet keeps its rent exemption only, AddBet moved the stake to the event.
+pub fn bet_account(this_bet: Bet) -> Account {
+    let mut data = vec![0; Bet::LEN];
+    Bet::pack(this_bet, &mut data).unwrap();
+    program_account(data, 0)
+}
+
+// An open event at a new address with `balances` staked on TeamA and TeamB.
+pub fn make_event_account(arbiter: &Pubkey, deadline: UnixTimestamp, balances: (u64, u64)) -> (Pubkey, Account) {
+    let mut bets = new_event_bets(arbiter, deadline);
+    bets.balance_a = balances.0;
+    bets.balance_b = balances.1;
+    (Pubkey::new_unique(), event_account(bets))
+}
+
+// A bet at a new address, `amount` has to be in the event's balances as well.
+pub fn make_bet_account(event: &Pubkey, betor: &Pubkey, amount: u64, outcome: MatchOutcome) -> (Pubkey, Account) {
+    (Pubkey::new_unique(), bet_account(new_bet(event, betor, amount, outcome)))
+}
+
+// One of an instruction's accounts.
+#[derive(Clone, Debug)]
+pub struct TestAccount {
+    pub pubkey: Pubkey,
+    pub account: Account,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// An instruction's accounts in the order the processor reads them.
+#[derive(Clone, Debug, Default)]
+pub struct InstructionAccounts(pub Vec<TestAccount>);
+
+// What a wallet passed as a plain pubkey holds, nothing.
+fn wallet() -> Account {
+    Account { lamports: 0, data: vec![], owner: system_program::id(), executable: false, rent_epoch: 0 }
+}
+
+impl InstructionAccounts {
+    // A signer that may be paid, as SetWinner's arbiter.
+    pub fn signer(mut self, pubkey: &Pubkey) -> Self {
+        self.0.push(TestAccount { pubkey: *pubkey, account: wallet(), is_signer: true, is_writable: true });
+        self
+    }
+
+    pub fn readonly_signer(mut self, pubkey: &Pubkey) -> Self {
+        self.0.push(TestAccount { pubkey: *pubkey, account: wallet(), is_signer: true, is_writable: false });
+        self
+    }
+
+    pub fn wallet(mut self, pubkey: &Pubkey) -> Self {
+        self.0.push(TestAccount { pubkey: *pubkey, account: wallet(), is_signer: false, is_writable: true });
+        self
+    }
+
+    pub fn writable(mut self, (pubkey, account): (Pubkey, Account)) -> Self {
+        self.0.push(TestAccount { pubkey, account, is_signer: false, is_writable: true });
+        self
+    }
+
+    pub fn metas(&self) -> Vec<AccountMeta> {
+        self.0.iter().map(|a| AccountMeta { pubkey: a.pubkey, is_signer: a.is_signer, is_writable: a.is_writable }).collect()
+    }
+
+    pub fn instruction(&self, instruction: &BetInstruction) -> Instruction {
+        Instruction::new_with_bytes(id(), &instruction.pack(), self.metas())
+    }
+
+    // The program's accounts, for ProgramTest::add_account. Wallets are left to the test.
+    pub fn program_accounts(&self) -> Vec<(Pubkey, Account)> {
+        self.0.iter().filter(|a| a.account.owner == id()).map(|a| (a.pubkey, a.account.clone())).collect()
+    }
+
+    pub fn account(&self, pubkey: &Pubkey) -> Option<&Account> {
+        self.0.iter().find(|a| a.pubkey == *pubkey).map(|a| &a.account)
+    }
+
+    // Runs `f` on AccountInfos over the accounts, e.g. to call process_instruction without a runtime,
+    // and keeps what it wrote. Instructions reading sysvars need the runtime.
+    pub fn with_account_infos<R>(&mut self, f: impl FnOnce(&[AccountInfo]) -> R) -> R {
+        let infos: Vec<AccountInfo> = self
+            .0
+            .iter_mut()
+            .map(|a| {
+                let account = &mut a.account;
+                AccountInfo::new(
+                    &a.pubkey,
+                    a.is_signer,
+                    a.is_writable,
+                    &mut account.lamports,
+                    &mut account.data,
+                    &account.owner,
+                    account.executable,
+                    account.rent_epoch,
+                )
+            })
+            .collect();
+        f(&infos)
+    }
+}
+
+pub fn add_bet_accounts(betor: &Pubkey, event: (Pubkey, Account), bet: (Pubkey, Account)) -> InstructionAccounts {
+    InstructionAccounts::default().wallet(betor).writable(event).writable(bet)
+}
+
+pub fn set_winner_accounts(arbiter: &Pubkey, event: (Pubkey, Account)) -> InstructionAccounts {
+    InstructionAccounts::default().signer(arbiter).writable(event)
+}
+
+pub fn withdraw_accounts(betor: &Pubkey, event: (Pubkey, Account), bet: (Pubkey, Account)) -> InstructionAccounts {
+    InstructionAccounts::default().wallet(betor).writable(event).writable(bet)
+}
+
+// PauseEvent and UnpauseEvent.
+pub fn pause_accounts(arbiter: &Pubkey, event: (Pubkey, Account)) -> InstructionAccounts {
+    InstructionAccounts::default().readonly_signer(arbiter).writable(event)
+}
+
+// One of each instruction in tag order, for round trips and as fuzzing seeds.
+pub fn every_instruction() -> Vec<BetInstruction> {
+    let key = Pubkey::new_from_array([7; PUBKEY_BYTES]);
+    let params = EventParams { commission_bps: 250, challenger: key, ..EventParams::new(1_700_000_000) };
+    let config = ConfigParams { default_commission_bps: 300, max_commission_bps: 1_000, min_deadline_offset_secs: 60, arbiter_share_bps: 2_000, referral_bps: 500 };
+    let odds = |numerator| Odds { numerator, denominator: 100 };
+    vec![
+        BetInstruction::Initialize(params),
+        BetInstruction::AddBet { choice: MatchOutcome::TeamB, referrer: key, amount: 5, memo: [3; MEMO_BYTES] },
+        BetInstruction::SetWinner { result: MatchOutcome::Draw, metadata_hash: [6; METADATA_HASH_BYTES] },
+        BetInstruction::Withdraw { amount: 9 },
+        BetInstruction::SetWinnerSigned { result: MatchOutcome::TeamA, event: key, signature: [4; SIGNATURE_BYTES] },
+        BetInstruction::CorrectResult { result: MatchOutcome::TeamB },
+        BetInstruction::SlashBond,
+        BetInstruction::ReleaseBond,
+        BetInstruction::InitializeConfig(config),
+        BetInstruction::UpdateConfig(config),
+        BetInstruction::WithdrawTreasury { amount: u64::MAX },
+        BetInstruction::SeedLiquidity,
+        BetInstruction::TopUpPot { amount: 1 },
+        BetInstruction::AuditEvent,
+        BetInstruction::QuoteOdds,
+        BetInstruction::QuotePayout,
+        BetInstruction::IncreaseBet { choice: MatchOutcome::TeamA, amount: 2 },
+        BetInstruction::CashOut,
+        BetInstruction::TransferBet { new_betor: key },
+        BetInstruction::ListBet { price: 3 },
+        BetInstruction::BuyBet { price: 3 },
+        BetInstruction::TokenizeBet,
+        BetInstruction::WithdrawMany,
+        BetInstruction::SettleBets,
+        BetInstruction::VoidBet { reason: 2 },
+        BetInstruction::PauseEvent,
+        BetInstruction::UnpauseEvent,
+        BetInstruction::HaltProgram,
+        BetInstruction::ResumeProgram,
+        BetInstruction::CreateRegistryPage { page: 70_000 },
+        BetInstruction::InitializeIndexed(params),
+        BetInstruction::InitializeSeeded { seed: "final-2024".to_string(), params },
+        BetInstruction::InitializeMarket { bets_accepted_until: -1, num_outcomes: 5, commission_bps: 100 },
+        BetInstruction::AddMarketBet { outcome: 4 },
+        BetInstruction::SetMarketWinner { winner: 0 },
+        BetInstruction::WithdrawMarketBet,
+        BetInstruction::SetOdds { odds_a: odds(250), odds_b: odds(160) },
+        BetInstruction::CreateParlayPool,
+        BetInstruction::PlaceParlay { choices: vec![MatchOutcome::TeamA, MatchOutcome::Draw] },
+        BetInstruction::SettleParlay,
+        BetInstruction::CreateTournament { slug: "cup".to_string(), name: "World Cup".to_string() },
+        BetInstruction::AddEventToTournament,
+        BetInstruction::MigrateEvent,
+        BetInstruction::SweepDust,
+        BetInstruction::ResetBet,
+        BetInstruction::AddBetFor { choice: MatchOutcome::TeamB, beneficiary: key, amount: 5 },
+        BetInstruction::SetDelegate { delegate: key },
+        BetInstruction::CollectCommission,
+        BetInstruction::FinalizeResult,
+        BetInstruction::CreateLeaderboard,
+        BetInstruction::AddToAllowlist { wallet: key },
+        BetInstruction::RemoveFromAllowlist { wallet: key },
+        BetInstruction::AddToDenylist { wallet: key },
+        BetInstruction::RemoveFromDenylist { wallet: key },
+    ]
+}
+
+// The checks of the fuzz targets in fuzz/ and of tests/fuzz.rs. Any bytes may come in, the decoders
+// have to answer with Ok or an error, and what they accept has to encode back to the same value.
+pub fn fuzz_instruction(data: &[u8]) {
+    if let Ok(instruction) = BetInstruction::unpack(data) {
+        assert_eq!(BetInstruction::unpack(&instruction.pack()), Ok(instruction));
+    }
+}
+
+pub fn fuzz_event(data: &[u8]) {
+    fuzz_state::<EventBets>(data);
+    #[cfg(feature = "borsh-state")]
+    fuzz_borsh::<EventBets>(data);
+}
+
+pub fn fuzz_bet(data: &[u8]) {
+    fuzz_state::<Bet>(data);
+    #[cfg(feature = "borsh-state")]
+    fuzz_borsh::<Bet>(data);
+}
+
+// Packing what was unpacked is stable, any non-zero byte of a bool reads as true and packs as 1.
+fn fuzz_state<T: Pack>(data: &[u8]) {
+    if let Ok(state) = T::unpack_unchecked(data) {
+        let mut packed = vec![0; T::LEN];
+        T::pack(state, &mut packed).unwrap();
+        let mut repacked = vec![0; T::LEN];
+        T::pack(T::unpack_unchecked(&packed).unwrap(), &mut repacked).unwrap();
+        assert_eq!(packed, repacked);
+    }
+    let _ = T::unpack(data);
+}
+
+// Borsh takes exactly LEN bytes with strict bools, which Pack then writes back byte for byte.
+#[cfg(feature = "borsh-state")]
+fn fuzz_borsh<T: Pack + borsh::BorshDeserialize + borsh::BorshSerialize>(data: &[u8]) {
+    if let Ok(state) = T::try_from_slice(data) {
+        assert_eq!(state.try_to_vec().unwrap(), data);
+        let mut packed = vec![0; T::LEN];
+        T::pack(state, &mut packed).unwrap();
+        assert_eq!(packed, data);
+    }
+}
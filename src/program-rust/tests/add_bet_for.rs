@@ -0,0 +1,80 @@
+mod common;
+
+use helloworld::{Bet, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::TransactionError,
+};
+
+const STAKE: u64 = 1_000_000;
+
+// `funder` creates a bet account holding STAKE above rent and places it for `beneficiary`.
+async fn gift_bet(env: &mut common::Env, funder: &Keypair, beneficiary: &Pubkey, event: &Pubkey, choice: MatchOutcome, funder_signs: bool) -> Result<Pubkey, TransactionError> {
+    let bet = Keypair::new();
+    let lamports = env.rent(Bet::LEN).await + STAKE;
+    let create = system_instruction::create_account(&funder.pubkey(), &bet.pubkey(), lamports, Bet::LEN as u64, &env.program_id);
+    let mut add_bet_for = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::AddBetFor { choice, beneficiary: *beneficiary, amount: 0 }.pack(),
+        vec![AccountMeta::new(funder.pubkey(), true), AccountMeta::new(*event, false), AccountMeta::new(bet.pubkey(), false)],
+    );
+    add_bet_for.accounts[0].is_signer = funder_signs;
+    env.send(&[create, add_bet_for], &[funder, &bet]).await?;
+    Ok(bet.pubkey())
+}
+
+#[test]
+fn test_add_bet_for_unpack() {
+    let beneficiary = Pubkey::new_unique();
+    let ix = BetInstruction::AddBetFor { choice: MatchOutcome::TeamA, beneficiary, amount: 0 };
+    assert_eq!(ix.pack(), [&[45, 1][..], beneficiary.as_ref(), &[0; 8]].concat());
+    assert_eq!(BetInstruction::unpack(&[&[45, 1][..], beneficiary.as_ref()].concat()).unwrap(), ix);
+}
+
+#[tokio::test]
+async fn test_beneficiary_withdraws_a_gifted_bet() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let (funder, beneficiary) = (Keypair::new(), Keypair::new().pubkey());
+    env.fund(&funder.pubkey(), 1_000_000_000).await;
+
+    let bet = gift_bet(&mut env, &funder, &beneficiary, &event, MatchOutcome::TeamA, true).await.unwrap();
+    // The funder paid, the bet is the beneficiary's.
+    let this_bet = Bet::unpack(&env.data(&bet).await).unwrap();
+    assert_eq!((this_bet.betor, this_bet.amount), (beneficiary, STAKE));
+    assert_eq!(env.lamports(&funder.pubkey()).await, 1_000_000_000 - env.rent(Bet::LEN).await - STAKE);
+    let state = EventBets::unpack(&env.data(&event).await).unwrap();
+    assert_eq!((state.balance_a, state.outstanding_bets), (STAKE, 1));
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, STAKE).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    let funder_before = env.lamports(&funder.pubkey()).await;
+    assert_eq!(
+        env.withdraw(&funder.pubkey(), &event, &bet).await.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+    env.withdraw(&beneficiary, &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&beneficiary).await, 1_940_000);
+    assert_eq!(env.lamports(&funder.pubkey()).await, funder_before);
+}
+
+#[tokio::test]
+async fn test_add_bet_for_needs_the_funder_signature() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let funder = Keypair::new();
+    env.fund(&funder.pubkey(), 1_000_000_000).await;
+
+    let err = gift_bet(&mut env, &funder, &Keypair::new().pubkey(), &event, MatchOutcome::TeamA, false).await.unwrap_err();
+    assert_eq!(err, TransactionError::InstructionError(1, InstructionError::MissingRequiredSignature));
+    assert_eq!(EventBets::unpack(&env.data(&event).await).unwrap().outstanding_bets, 0);
+}
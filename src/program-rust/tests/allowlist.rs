@@ -0,0 +1,130 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{allowlist_address, BetError, EventBets, Instruction as BetInstruction, MatchOutcome, WALLET_LIST_CAPACITY};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+async fn add_to_allowlist(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, wallet: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::AddToAllowlist { wallet: *wallet }.pack(),
+        vec![
+            AccountMeta::new(arbiter.pubkey(), true),
+            AccountMeta::new(*event, false),
+            AccountMeta::new(allowlist_address(&env.program_id, event).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+async fn remove_from_allowlist(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, wallet: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::RemoveFromAllowlist { wallet: *wallet }.pack(),
+        vec![
+            AccountMeta::new_readonly(arbiter.pubkey(), true),
+            AccountMeta::new_readonly(*event, false),
+            AccountMeta::new(allowlist_address(&env.program_id, event).0, false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+async fn bet(env: &mut common::Env, betor: &Pubkey, event: &Pubkey) -> Result<Pubkey, TransactionError> {
+    let allowlist = vec![AccountMeta::new_readonly(allowlist_address(&env.program_id, event).0, false)];
+    env.try_place_bet_with_accounts(betor, event, MatchOutcome::TeamA, 1_000_000, allowlist).await
+}
+
+async fn private_event(env: &mut common::Env, invited: &[Pubkey]) -> (Keypair, Pubkey) {
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    for wallet in invited {
+        add_to_allowlist(env, &arbiter, &event, wallet).await.unwrap();
+    }
+    (arbiter, event)
+}
+
+#[test]
+fn test_allowlist_unpack() {
+    let wallet = Pubkey::new_unique();
+    for ix in &[BetInstruction::AddToAllowlist { wallet }, BetInstruction::RemoveFromAllowlist { wallet }] {
+        assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), *ix);
+    }
+    assert_eq!(BetInstruction::AddToAllowlist { wallet }.pack()[0], 50);
+    assert!(BetInstruction::unpack(&[51, 1, 2, 3]).is_err());
+}
+
+#[tokio::test]
+async fn test_only_invited_wallets_bet() {
+    let mut env = common::start().await;
+    let alice = Keypair::new().pubkey();
+    let (_, event) = private_event(&mut env, &[alice]).await;
+    assert!(EventBets::unpack(&env.data(&event).await).unwrap().allowlisted);
+
+    bet(&mut env, &alice, &event).await.unwrap();
+    let stranger = Keypair::new().pubkey();
+    assert_eq!(bet(&mut env, &stranger, &event).await.unwrap_err(), bet_error(0, BetError::NotAllowlisted));
+    // Leaving the allowlist out doesn't get around it.
+    assert!(env.try_place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await.is_err());
+}
+
+#[tokio::test]
+async fn test_removal_takes_effect() {
+    let mut env = common::start().await;
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let (arbiter, event) = private_event(&mut env, &[alice, bob]).await;
+    bet(&mut env, &alice, &event).await.unwrap();
+
+    remove_from_allowlist(&mut env, &arbiter, &event, &alice).await.unwrap();
+    assert_eq!(bet(&mut env, &alice, &event).await.unwrap_err(), bet_error(0, BetError::NotAllowlisted));
+    // Bob took Alice's slot and is still on it.
+    bet(&mut env, &bob, &event).await.unwrap();
+    assert!(remove_from_allowlist(&mut env, &arbiter, &event, &alice).await.is_err());
+
+    // The event stays private once the allowlist is empty.
+    remove_from_allowlist(&mut env, &arbiter, &event, &bob).await.unwrap();
+    assert_eq!(bet(&mut env, &bob, &event).await.unwrap_err(), bet_error(0, BetError::NotAllowlisted));
+    add_to_allowlist(&mut env, &arbiter, &event, &bob).await.unwrap();
+    bet(&mut env, &bob, &event).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_allowlist_is_bounded() {
+    let mut env = common::start().await;
+    let (arbiter, event) = private_event(&mut env, &[]).await;
+    let wallets: Vec<Pubkey> = (0..WALLET_LIST_CAPACITY).map(|_| Pubkey::new_unique()).collect();
+    for wallet in &wallets {
+        add_to_allowlist(&mut env, &arbiter, &event, wallet).await.unwrap();
+    }
+    // A wallet already on it changes nothing, a new one doesn't fit.
+    add_to_allowlist(&mut env, &arbiter, &event, &wallets[0]).await.unwrap();
+    assert_eq!(
+        add_to_allowlist(&mut env, &arbiter, &event, &Pubkey::new_unique()).await.unwrap_err(),
+        bet_error(0, BetError::AllowlistFull)
+    );
+    remove_from_allowlist(&mut env, &arbiter, &event, &wallets[7]).await.unwrap();
+    add_to_allowlist(&mut env, &arbiter, &event, &Pubkey::new_unique()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_only_the_arbiter_manages_the_allowlist() {
+    let mut env = common::start().await;
+    let alice = Keypair::new().pubkey();
+    let (_, event) = private_event(&mut env, &[alice]).await;
+    let stranger = Keypair::new();
+    env.fund(&stranger.pubkey(), 1_000_000_000).await;
+    assert!(add_to_allowlist(&mut env, &stranger, &event, &stranger.pubkey()).await.is_err());
+    assert!(remove_from_allowlist(&mut env, &stranger, &event, &alice).await.is_err());
+    bet(&mut env, &alice, &event).await.unwrap();
+}
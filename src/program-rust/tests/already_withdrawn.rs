@@ -0,0 +1,49 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{client, BetError, MatchOutcome};
+use solana_sdk::{
+    instruction::InstructionError,
+    program_error::ProgramError,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn test_second_withdraw_fails_and_moves_nothing() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let (alice, bob) = (Keypair::new().pubkey(), Keypair::new().pubkey());
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamB, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+
+    let accounts = [alice, bob, event, alice_bet, bob_bet];
+    let mut before = vec![];
+    for account in &accounts {
+        before.push(env.lamports(account).await);
+    }
+    // The winner's and the loser's bet alike.
+    assert_eq!(env.withdraw(&alice, &event, &alice_bet).await.unwrap_err(), bet_error(0, BetError::AlreadyWithdrawn));
+    assert_eq!(env.withdraw(&bob, &event, &bob_bet).await.unwrap_err(), bet_error(0, BetError::AlreadyWithdrawn));
+    for (account, lamports) in accounts.iter().zip(before) {
+        assert_eq!(env.lamports(account).await, lamports);
+    }
+}
+
+#[test]
+fn test_error_codes_and_messages() {
+    for (code, error) in BetError::ALL.iter().enumerate() {
+        assert_eq!(*error as usize, code);
+        assert_eq!(BetError::from_code(code as u32), Some(*error));
+        assert_eq!(client::error_message(&InstructionError::Custom(code as u32)), Some(error.message()));
+    }
+    assert_eq!(BetError::from_code(BetError::ALL.len() as u32), None);
+    assert_eq!(ProgramError::from(BetError::AlreadyWithdrawn), ProgramError::Custom(BetError::AlreadyWithdrawn as u32));
+    assert_eq!(client::error_message(&InstructionError::Custom(BetError::AlreadyWithdrawn as u32)), Some("This bet was already paid out"));
+    assert_eq!(client::error_message(&InstructionError::InvalidAccountData), None);
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{BetError, EventParams, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[test]
+fn test_arbiter_may_bet_params_unpack() {
+    let ix = BetInstruction::Initialize(EventParams { arbiter_may_bet: true, ..EventParams::new(42) });
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    let mut legacy = ix.pack();
+    legacy.truncate(legacy.len() - (1 + 8));
+    assert_eq!(BetInstruction::unpack(&legacy).unwrap(), BetInstruction::Initialize(EventParams::new(42)));
+}
+
+#[tokio::test]
+async fn test_arbiter_and_recipient_cannot_bet() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { commission_recipient: recipient, ..EventParams::new(now) }).await;
+
+    for betor in &[arbiter.pubkey(), recipient] {
+        assert_eq!(
+            env.try_place_bet(betor, &event, MatchOutcome::TeamA, 1_000_000).await.unwrap_err(),
+            bet_error(0, BetError::ArbiterCannotBet)
+        );
+    }
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+}
+
+#[tokio::test]
+async fn test_house_participation_allows_it() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { arbiter_may_bet: true, ..EventParams::new(now) }).await;
+    let house_bet = env.place_bet(&arbiter.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&arbiter.pubkey(), &event, &house_bet).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, 1_940_000);
+}
+
+#[tokio::test]
+async fn test_arbiter_withdraws_a_bet_transferred_to_them() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new();
+    let bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    // Only new bets are refused, a bet handed over keeps paying whoever holds it.
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::TransferBet { new_betor: arbiter.pubkey() }.pack(),
+        vec![AccountMeta::new_readonly(alice.pubkey(), true), AccountMeta::new_readonly(event, false), AccountMeta::new(bet, false)],
+    );
+    env.send(&[ix], &[&alice]).await.unwrap();
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&arbiter.pubkey(), &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, 1_940_000);
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use helloworld::{BetError, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+fn audit_ix(env: &common::Env, event: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(env.program_id, &BetInstruction::AuditEvent.pack(), vec![AccountMeta::new_readonly(*event, false)])
+}
+
+async fn audit(env: &mut common::Env, event: &Pubkey) -> Result<(), TransactionError> {
+    let ix = audit_ix(env, event);
+    env.send(&[ix], &[]).await
+}
+
+fn edit_event(account: &mut Account, f: impl FnOnce(&mut EventBets)) {
+    let mut bets = EventBets::try_from_slice(&account.data).unwrap();
+    f(&mut bets);
+    account.data = bets.try_to_vec().unwrap();
+}
+
+async fn open_event(env: &mut common::Env) -> (Keypair, Pubkey) {
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 2_000_000).await;
+    (arbiter, event)
+}
+
+#[test]
+fn test_audit_event_pack() {
+    assert_eq!(BetInstruction::AuditEvent.pack(), vec![13]);
+    assert_eq!(BetInstruction::unpack(&[13]).unwrap(), BetInstruction::AuditEvent);
+}
+
+#[tokio::test]
+async fn test_consistent_event_passes_and_is_untouched() {
+    let mut env = common::start().await;
+    let (arbiter, event) = open_event(&mut env).await;
+    let data = env.data(&event).await;
+    let lamports = env.lamports(&event).await;
+    audit(&mut env, &event).await.unwrap();
+    assert_eq!(env.data(&event).await, data);
+    assert_eq!(env.lamports(&event).await, lamports);
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+    audit(&mut env, &event).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_each_broken_invariant_is_named() {
+    type Corruption = Box<dyn FnOnce(&mut Account)>;
+    let cases: Vec<(Corruption, BetError)> = vec![
+        (Box::new(|a| edit_event(a, |b| b.outcome = 7)), BetError::InvalidOutcome),
+        (Box::new(|a| edit_event(a, |b| b.outcome = 255)), BetError::InvalidOutcome),
+        (Box::new(|a| edit_event(a, |b| b.outcome = 1)), BetError::DeadlinesOutOfOrder),
+        (Box::new(|a| edit_event(a, |b| b.dispute_window_secs = -1)), BetError::DeadlinesOutOfOrder),
+        (Box::new(|a| edit_event(a, |b| b.finality_delay_secs = -1)), BetError::DeadlinesOutOfOrder),
+        (Box::new(|a| edit_event(a, |b| b.correction_window_secs = -1)), BetError::DeadlinesOutOfOrder),
+        (Box::new(|a| edit_event(a, |b| b.correction_cooldown_secs = -1)), BetError::DeadlinesOutOfOrder),
+        (Box::new(|a| edit_event(a, |b| b.total_paid_out = 1)), BetError::PayoutExceedsPools),
+        (Box::new(|a| edit_event(a, |b| b.balance_a += 1)), BetError::EventUnderfunded),
+        (Box::new(|a| a.lamports -= 1), BetError::EventUnderfunded),
+    ];
+    for (corrupt, error) in cases {
+        let mut env = common::start().await;
+        let (_, event) = open_event(&mut env).await;
+        env.corrupt(&event, corrupt).await;
+        assert_eq!(audit(&mut env, &event).await.unwrap_err(), common::bet_error(0, error));
+    }
+}
+
+#[tokio::test]
+async fn test_settled_event_invariants() {
+    let mut env = common::start().await;
+    let (arbiter, event) = open_event(&mut env).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+
+    // A result set before the betting deadline.
+    env.corrupt(&event, |a| edit_event(a, |b| b.bets_allowed_until_ts = b.winner_set_at + 1)).await;
+    assert_eq!(audit(&mut env, &event).await.unwrap_err(), common::bet_error(0, BetError::DeadlinesOutOfOrder));
+    env.corrupt(&event, |a| edit_event(a, |b| b.bets_allowed_until_ts = b.winner_set_at)).await;
+    audit(&mut env, &event).await.unwrap();
+
+    // More paid out than the pools net of commission.
+    env.corrupt(&event, |a| edit_event(a, |b| b.total_paid_out = 3_000_000 * 97 / 100 + 1)).await;
+    assert_eq!(audit(&mut env, &event).await.unwrap_err(), common::bet_error(0, BetError::PayoutExceedsPools));
+    env.corrupt(&event, |a| edit_event(a, |b| b.total_paid_out = 0)).await;
+
+    // Lamports the unclaimed winners are owed gone missing, only the commission may be short.
+    env.corrupt(&event, |a| a.lamports -= 3_000_000 * 3 / 100).await;
+    audit(&mut env, &event).await.unwrap();
+    env.corrupt(&event, |a| a.lamports -= 1).await;
+    assert_eq!(audit(&mut env, &event).await.unwrap_err(), common::bet_error(0, BetError::EventUnderfunded));
+}
+
+#[tokio::test]
+async fn test_settled_event_holds_bond_and_bonus() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { bond_lamports: 5_000_000, ..EventParams::new(now) }).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 2_000_000).await;
+    env.top_up_pot(&arbiter, &event, 4_000_000).await.unwrap();
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+
+    // The bond and the unpaid bonus are owed next to the payouts, only the commission may be short.
+    env.corrupt(&event, |a| a.lamports -= 3_000_000 * 3 / 100).await;
+    audit(&mut env, &event).await.unwrap();
+    env.corrupt(&event, |a| a.lamports -= 1).await;
+    assert_eq!(audit(&mut env, &event).await.unwrap_err(), common::bet_error(0, BetError::EventUnderfunded));
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use helloworld::{client, Bet, EventBets, MatchOutcome};
+use solana_sdk::{
+    instruction::InstructionError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+// Places a bet of `betor` declaring `amount` from a bet account holding `deposit` above rent.
+async fn place_declared(env: &mut common::Env, betor: &Pubkey, event: &Pubkey, deposit: u64, amount: u64) -> Result<Pubkey, TransactionError> {
+    let bet = Keypair::new();
+    let lamports = env.rent(Bet::LEN).await + deposit;
+    env.create_account(&bet, lamports, Bet::LEN).await;
+    let ix = client::add_bet(&env.program_id, betor, event, &bet.pubkey(), MatchOutcome::TeamA, amount);
+    env.send(&[ix], &[]).await?;
+    Ok(bet.pubkey())
+}
+
+#[tokio::test]
+async fn test_surplus_goes_back_to_the_betor() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let betor = Keypair::new().pubkey();
+    env.fund(&betor, 1_000_000_000).await;
+
+    let bet = place_declared(&mut env, &betor, &event, 1_000_000 + 12345, 1_000_000).await.unwrap();
+    assert_eq!(Bet::unpack(&env.data(&bet).await).unwrap().amount, 1_000_000);
+    assert_eq!(EventBets::unpack(&env.data(&event).await).unwrap().balance_a, 1_000_000);
+    assert_eq!(env.lamports(&betor).await, 1_000_000_000 + 12345);
+    assert_eq!(env.lamports(&bet).await, env.rent(Bet::LEN).await);
+
+    // No surplus, nothing to refund.
+    let bet = place_declared(&mut env, &betor, &event, 1_000_000, 1_000_000).await.unwrap();
+    assert_eq!(env.lamports(&betor).await, 1_000_000_000 + 12345);
+    assert_eq!(env.lamports(&bet).await, env.rent(Bet::LEN).await);
+
+    // 0 still stakes everything above rent.
+    let bet = place_declared(&mut env, &betor, &event, 700_000, 0).await.unwrap();
+    assert_eq!(Bet::unpack(&env.data(&bet).await).unwrap().amount, 700_000);
+    assert_eq!(env.lamports(&bet).await, env.rent(Bet::LEN).await);
+}
+
+#[tokio::test]
+async fn test_deposit_short_of_the_amount_is_refused() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+
+    assert_eq!(
+        place_declared(&mut env, &Keypair::new().pubkey(), &event, 999_999, 1_000_000).await.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InsufficientFunds)
+    );
+    assert_eq!(EventBets::unpack(&env.data(&event).await).unwrap().balance_a, 0);
+    place_declared(&mut env, &Keypair::new().pubkey(), &event, 1_000_000, 1_000_000).await.unwrap();
+}
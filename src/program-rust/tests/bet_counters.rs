@@ -0,0 +1,29 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{EventBets, MatchOutcome};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_bets_are_counted_per_side() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    // House liquidity is not somebody backing a side.
+    env.seed_liquidity(&arbiter, &event, 1_000, 1_000).await.unwrap();
+
+    let alice = Keypair::new().pubkey();
+    for _ in 0..3 {
+        env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000).await;
+    }
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 5_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 7_000).await;
+    // A rejected bet is not counted.
+    assert!(env.try_place_bet(&alice, &event, MatchOutcome::Draw, 1_000).await.is_err());
+
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.bets_count_a, bets.bets_count_b), (4, 1));
+    assert_eq!((bets.balance_a, bets.balance_b), (9_000, 8_000));
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{Bet, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+async fn list_bet(env: &mut common::Env, betor: &Keypair, event: &Pubkey, bet: &Pubkey, price: u64) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::ListBet { price }.pack(),
+        vec![AccountMeta::new_readonly(betor.pubkey(), true), AccountMeta::new_readonly(*event, false), AccountMeta::new(*bet, false)],
+    );
+    env.send(&[ix], &[betor]).await
+}
+
+async fn buy_bet(env: &mut common::Env, buyer: &Keypair, seller: &Pubkey, event: &Pubkey, bet: &Pubkey, price: u64) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::BuyBet { price }.pack(),
+        vec![
+            AccountMeta::new(buyer.pubkey(), true),
+            AccountMeta::new(*seller, false),
+            AccountMeta::new_readonly(*event, false),
+            AccountMeta::new(*bet, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    env.send(&[ix], &[buyer]).await
+}
+
+async fn bet_state(env: &mut common::Env, bet: &Pubkey) -> Bet {
+    Bet::deserialize(&mut &env.data(bet).await[..]).unwrap()
+}
+
+#[test]
+fn test_market_instructions_unpack() {
+    for ix in &[BetInstruction::ListBet { price: 5 }, BetInstruction::BuyBet { price: u64::MAX }] {
+        assert_eq!(&BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    }
+    assert!(BetInstruction::unpack(&[19, 1, 2]).is_err());
+}
+
+#[tokio::test]
+async fn test_list_and_buy() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    env.fund(&alice.pubkey(), 1_000_000_000).await;
+    env.fund(&bob.pubkey(), 1_000_000_000).await;
+    let bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    // Only the betor lists.
+    assert!(list_bet(&mut env, &bob, &event, &bet, 1_200_000).await.is_err());
+    // Nothing to buy yet.
+    assert!(buy_bet(&mut env, &bob, &alice.pubkey(), &event, &bet, 0).await.is_err());
+    list_bet(&mut env, &alice, &event, &bet, 1_200_000).await.unwrap();
+    let listed = bet_state(&mut env, &bet).await;
+    assert!(listed.for_sale);
+    assert_eq!(listed.ask_lamports, 1_200_000);
+
+    // The buyer pays the ask, not less.
+    assert!(buy_bet(&mut env, &bob, &alice.pubkey(), &event, &bet, 1_000_000).await.is_err());
+    let alice_before = env.lamports(&alice.pubkey()).await;
+    let bob_before = env.lamports(&bob.pubkey()).await;
+    buy_bet(&mut env, &bob, &alice.pubkey(), &event, &bet, 1_200_000).await.unwrap();
+    assert_eq!(env.lamports(&alice.pubkey()).await - alice_before, 1_200_000);
+    assert_eq!(bob_before - env.lamports(&bob.pubkey()).await, 1_200_000);
+
+    let bought = bet_state(&mut env, &bet).await;
+    assert_eq!(bought.betor, bob.pubkey());
+    assert!(!bought.for_sale);
+    assert_eq!((bought.amount, bought.outcome), (listed.amount, listed.outcome));
+
+    env.set_now(now + 100).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    assert!(env.withdraw(&alice.pubkey(), &event, &bet).await.is_err());
+    let bob_before = env.lamports(&bob.pubkey()).await;
+    env.withdraw(&bob.pubkey(), &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&bob.pubkey()).await - bob_before, 1_940_000);
+}
+
+#[tokio::test]
+async fn test_listing_closes_with_the_result() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    env.fund(&bob.pubkey(), 1_000_000_000).await;
+    let bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    list_bet(&mut env, &alice, &event, &bet, 10).await.unwrap();
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    assert!(buy_bet(&mut env, &bob, &alice.pubkey(), &event, &bet, 10).await.is_err());
+    // No new listings either, taking it off sale is still fine.
+    assert!(list_bet(&mut env, &alice, &event, &bet, 20).await.is_err());
+    list_bet(&mut env, &alice, &event, &bet, 0).await.unwrap();
+    assert!(!bet_state(&mut env, &bet).await.for_sale);
+}
+
+#[tokio::test]
+async fn test_delist() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    env.fund(&bob.pubkey(), 1_000_000_000).await;
+    let bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+
+    list_bet(&mut env, &alice, &event, &bet, 500_000).await.unwrap();
+    list_bet(&mut env, &alice, &event, &bet, 0).await.unwrap();
+    let delisted = bet_state(&mut env, &bet).await;
+    assert_eq!((delisted.for_sale, delisted.ask_lamports), (false, 0));
+    assert!(buy_bet(&mut env, &bob, &alice.pubkey(), &event, &bet, 500_000).await.is_err());
+    assert!(buy_bet(&mut env, &bob, &alice.pubkey(), &event, &bet, 0).await.is_err());
+    assert_eq!(bet_state(&mut env, &bet).await.betor, alice.pubkey());
+}
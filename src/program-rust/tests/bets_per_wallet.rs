@@ -0,0 +1,112 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::bet_error;
+use helloworld::{limit_address, BetError, EventParams, Instruction as BetInstruction, MatchOutcome, WalletStake};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+async fn cash_out(env: &mut common::Env, betor: &Keypair, event: &Pubkey, bet: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::CashOut.pack(),
+        vec![
+            AccountMeta::new(betor.pubkey(), true),
+            AccountMeta::new(*event, false),
+            AccountMeta::new(*bet, false),
+            AccountMeta::new(limit_address(&env.program_id, event, &betor.pubkey()).0, false),
+        ],
+    );
+    env.send(&[ix], &[betor]).await
+}
+
+async fn void_bet(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, bet: &Pubkey, betor: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::VoidBet { reason: 0 }.pack(),
+        vec![
+            AccountMeta::new_readonly(arbiter.pubkey(), true),
+            AccountMeta::new(*event, false),
+            AccountMeta::new(*bet, false),
+            AccountMeta::new(*betor, false),
+            AccountMeta::new(limit_address(&env.program_id, event, betor).0, false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+async fn bets_open(env: &mut common::Env, event: &Pubkey, betor: &Pubkey) -> u32 {
+    WalletStake::deserialize(&mut &env.data(&limit_address(&env.program_id, event, betor).0).await[..]).unwrap().bets_open
+}
+
+#[test]
+fn test_max_bets_params_unpack() {
+    let ix = BetInstruction::Initialize(EventParams { max_bets_per_wallet: 2, ..EventParams::new(42) });
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    let mut legacy = ix.pack();
+    legacy.truncate(legacy.len() - (1 + 1 + 8));
+    assert_eq!(BetInstruction::unpack(&legacy).unwrap(), BetInstruction::Initialize(EventParams::new(42)));
+}
+
+#[tokio::test]
+async fn test_cap_of_two_bets() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { max_bets_per_wallet: 2, ..EventParams::new(now) }).await;
+    let alice = Keypair::new();
+    let accounts = env.limit_accounts(&event, &alice.pubkey());
+    let first = env.try_place_bet_with_accounts(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000, accounts.clone()).await.unwrap();
+    env.try_place_bet_with_accounts(&alice.pubkey(), &event, MatchOutcome::TeamB, 1_000_000, accounts.clone()).await.unwrap();
+    assert_eq!(bets_open(&mut env, &event, &alice.pubkey()).await, 2);
+    assert_eq!(
+        env.try_place_bet_with_accounts(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000, accounts.clone()).await.unwrap_err(),
+        bet_error(0, BetError::TooManyBets)
+    );
+
+    // Cashing out frees a slot, the stake doesn't matter without a stake cap.
+    cash_out(&mut env, &alice, &event, &first).await.unwrap();
+    assert_eq!(bets_open(&mut env, &event, &alice.pubkey()).await, 1);
+    env.try_place_bet_with_accounts(&alice.pubkey(), &event, MatchOutcome::TeamA, 50_000_000, accounts).await.unwrap();
+    assert_eq!(bets_open(&mut env, &event, &alice.pubkey()).await, 2);
+
+    // Other wallets have their own count.
+    let bob = Keypair::new().pubkey();
+    let accounts = env.limit_accounts(&event, &bob);
+    env.try_place_bet_with_accounts(&bob, &event, MatchOutcome::TeamA, 1_000_000, accounts).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_capped_event_requires_limit_account() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { max_bets_per_wallet: 1, ..EventParams::new(now) }).await;
+    let alice = Keypair::new();
+    assert!(env.try_place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await.is_err());
+    let accounts = env.limit_accounts(&event, &alice.pubkey());
+    env.try_place_bet_with_accounts(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000, accounts).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_void_frees_a_slot() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { max_bets_per_wallet: 1, ..EventParams::new(now) }).await;
+    let alice = Keypair::new().pubkey();
+    let accounts = env.limit_accounts(&event, &alice);
+    let bet = env.try_place_bet_with_accounts(&alice, &event, MatchOutcome::TeamA, 1_000_000, accounts.clone()).await.unwrap();
+    assert_eq!(
+        env.try_place_bet_with_accounts(&alice, &event, MatchOutcome::TeamA, 1_000_000, accounts.clone()).await.unwrap_err(),
+        bet_error(0, BetError::TooManyBets)
+    );
+
+    void_bet(&mut env, &arbiter, &event, &bet, &alice).await.unwrap();
+    assert_eq!(bets_open(&mut env, &event, &alice).await, 0);
+    env.try_place_bet_with_accounts(&alice, &event, MatchOutcome::TeamA, 1_000_000, accounts).await.unwrap();
+}
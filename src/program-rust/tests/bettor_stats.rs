@@ -0,0 +1,102 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{stats_address, BettorStats, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+// Trailing AddBet accounts keeping the betor's stats, paid by the test payer.
+fn stats_accounts(env: &common::Env, betor: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(stats_address(&env.program_id, betor).0, false),
+        AccountMeta::new(env.payer().pubkey(), true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]
+}
+
+async fn stats(env: &mut common::Env, betor: &Pubkey) -> BettorStats {
+    BettorStats::try_from_slice(&env.data(&stats_address(&env.program_id, betor).0).await).unwrap()
+}
+
+async fn withdraw_with_stats(env: &mut common::Env, betor: &Pubkey, event: &Pubkey, bet: &Pubkey) -> Result<(), TransactionError> {
+    let mut ix = env.withdraw_ix(betor, event, bet);
+    ix.accounts.push(AccountMeta::new(stats_address(&env.program_id, betor).0, false));
+    env.send(&[ix], &[]).await
+}
+
+#[tokio::test]
+async fn test_two_bets_and_one_win() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let won = env.create_event(&arbiter, now).await;
+    let lost = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let accounts = stats_accounts(&env, &alice);
+    let won_bet = env.try_place_bet_with_accounts(&alice, &won, MatchOutcome::TeamA, 1_000_000, accounts.clone()).await.unwrap();
+    let lost_bet = env.try_place_bet_with_accounts(&alice, &lost, MatchOutcome::TeamB, 500_000, accounts).await.unwrap();
+    assert_eq!(stats(&mut env, &alice).await, BettorStats { is_initialized: true, staked_total: 1_500_000, won_total: 0, bets_placed: 2, bets_won: 0 });
+
+    env.place_bet(&Keypair::new().pubkey(), &won, MatchOutcome::TeamB, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &lost, MatchOutcome::TeamA, 500_000).await;
+    env.set_winner(&arbiter, &won, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&won).await;
+    env.set_winner(&arbiter, &lost, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&lost).await;
+    withdraw_with_stats(&mut env, &alice, &won, &won_bet).await.unwrap();
+    withdraw_with_stats(&mut env, &alice, &lost, &lost_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 1_940_000);
+    assert_eq!(
+        stats(&mut env, &alice).await,
+        BettorStats { is_initialized: true, staked_total: 1_500_000, won_total: 1_940_000, bets_placed: 2, bets_won: 1 }
+    );
+}
+
+#[tokio::test]
+async fn test_stats_are_opt_in() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let accounts = stats_accounts(&env, &alice);
+    env.try_place_bet_with_accounts(&alice, &event, MatchOutcome::TeamA, 1_000_000, accounts).await.unwrap();
+    let before = stats(&mut env, &alice).await;
+
+    // Without the stats accounts the bet and the withdrawal go through as before, the stats stay as they are.
+    let bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 2_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 3_000_000).await;
+    // A betor who never passed them has no stats account at all.
+    let bob = Keypair::new().pubkey();
+    env.place_bet(&bob, &event, MatchOutcome::TeamA, 1_000_000).await;
+    assert_eq!(env.lamports(&stats_address(&env.program_id, &bob).0).await, 0);
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice, &event, &bet).await.unwrap();
+    assert_eq!(stats(&mut env, &alice).await, before);
+}
+
+#[tokio::test]
+async fn test_wrong_stats_account_is_refused() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    let mut ix = env.withdraw_ix(&alice, &event, &bet);
+    ix.accounts.push(AccountMeta::new(stats_address(&env.program_id, &Keypair::new().pubkey()).0, false));
+    assert_eq!(env.send(&[ix], &[]).await.unwrap_err(), TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+    // Stats AddBet didn't create yet are refused too.
+    assert!(withdraw_with_stats(&mut env, &alice, &event, &bet).await.is_err());
+    env.withdraw(&alice, &event, &bet).await.unwrap();
+}
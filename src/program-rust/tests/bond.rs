@@ -0,0 +1,143 @@
+mod common;
+
+use helloworld::{EventBets, EventParams, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    signature::{Keypair, Signer},
+};
+
+const BOND: u64 = 10_000_000;
+
+#[tokio::test]
+async fn test_bond_released_after_clean_settlement() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let challenger = Keypair::new();
+    let now = env.now().await;
+    let event = env
+        .create_event_with(
+            &arbiter,
+            EventParams { dispute_window_secs: 100, bond_lamports: BOND, challenger: challenger.pubkey(), ..EventParams::new(now) },
+        )
+        .await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+
+    // Not before the result is final.
+    assert!(env.release_bond(&arbiter, &event).await.is_err());
+    env.set_now(now + 100).await;
+    assert!(env.slash_bond(&challenger, &event).await.is_err());
+    // Past the dispute window, but the result may still be corrected.
+    assert!(env.release_bond(&arbiter, &event).await.is_err());
+
+    env.close_correction_window(&event).await;
+    assert!(env.release_bond(&Keypair::new(), &event).await.is_err());
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    env.release_bond(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await - arbiter_before, BOND);
+    assert!(env.release_bond(&arbiter, &event).await.is_err());
+
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 970_000);
+    // Neither the bond nor the rent exemption is part of the commission base.
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    env.collect_commission(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await - arbiter_before, 1_000_000 * 3 / 100);
+}
+
+#[tokio::test]
+async fn test_bond_slashed_during_dispute_goes_to_winners() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let challenger = Keypair::new();
+    let now = env.now().await;
+    let event = env
+        .create_event_with(
+            &arbiter,
+            EventParams { dispute_window_secs: 100, bond_lamports: BOND, challenger: challenger.pubkey(), ..EventParams::new(now) },
+        )
+        .await;
+    let alice = Keypair::new().pubkey();
+    let carol = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let carol_bet = env.place_bet(&carol, &event, MatchOutcome::TeamA, 3_000_000).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamB, 4_000_000).await;
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+    // Only the designated challenger may slash.
+    assert!(env.slash_bond(&Keypair::new(), &event).await.is_err());
+    env.slash_bond(&challenger, &event).await.unwrap();
+    assert!(env.slash_bond(&challenger, &event).await.is_err());
+    env.correct_result(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+
+    env.set_now(now + 100).await;
+    assert!(env.release_bond(&arbiter, &event).await.is_err());
+
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    env.withdraw(&carol, &event, &carol_bet).await.unwrap();
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+    // (4_000_000 * 1_000_000 / 4_000_000 + 1_000_000) * 97 / 100 + BOND / 4
+    assert_eq!(env.lamports(&alice).await, 1_940_000 + BOND / 4);
+    // (4_000_000 * 3_000_000 / 4_000_000 + 3_000_000) * 97 / 100 + BOND * 3 / 4
+    assert_eq!(env.lamports(&carol).await, 5_820_000 + BOND * 3 / 4);
+    assert_eq!(env.lamports(&bob).await, 0);
+}
+
+#[tokio::test]
+async fn test_slashed_bond_nobody_won_goes_to_the_challenger() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let challenger = Keypair::new();
+    let now = env.now().await;
+    let event = env
+        .create_event_with(
+            &arbiter,
+            EventParams { dispute_window_secs: 100, bond_lamports: BOND, challenger: challenger.pubkey(), ..EventParams::new(now) },
+        )
+        .await;
+    let bob = Keypair::new().pubkey();
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.slash_bond(&challenger, &event).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+
+    // No winner takes a share, so the challenger's account has to come along.
+    assert!(env.sweep_dust(&arbiter, &event).await.is_err());
+    let sweep = |to: &Keypair| {
+        Instruction::new_with_bytes(
+            env.program_id,
+            &BetInstruction::SweepDust.pack(),
+            vec![AccountMeta::new(arbiter.pubkey(), true), AccountMeta::new(event, false), AccountMeta::new(to.pubkey(), false)],
+        )
+    };
+    let (to_stranger, to_challenger) = (sweep(&Keypair::new()), sweep(&challenger));
+    assert!(env.send(&[to_stranger], &[&arbiter]).await.is_err());
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    env.send(&[to_challenger], &[&arbiter]).await.unwrap();
+    assert_eq!(env.lamports(&challenger.pubkey()).await, BOND);
+    assert!(env.lamports(&arbiter.pubkey()).await - arbiter_before < BOND);
+    assert_eq!(env.lamports(&event).await, env.rent(EventBets::LEN).await);
+}
+
+#[tokio::test]
+async fn test_endless_dispute_window_stays_open() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let challenger = Keypair::new();
+    let now = env.now().await;
+    let event = env
+        .create_event_with(
+            &arbiter,
+            EventParams { dispute_window_secs: i64::MAX, bond_lamports: BOND, challenger: challenger.pubkey(), ..EventParams::new(now) },
+        )
+        .await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.set_now(now + 1_000_000).await;
+    env.slash_bond(&challenger, &event).await.unwrap();
+}
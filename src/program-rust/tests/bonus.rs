@@ -0,0 +1,69 @@
+mod common;
+
+use helloworld::{EventBets, EventParams, MatchOutcome};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const BONUS: u64 = 1_000_000;
+
+// alice 1_000_000 and carol 3_000_000 on TeamA, bob 4_000_000 on TeamB, TeamA wins.
+async fn settle_with_bonus(env: &mut common::Env, bonus_commission_free: bool) -> (Pubkey, Pubkey, Pubkey, Pubkey) {
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 10 * BONUS).await;
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { bonus_commission_free, ..EventParams::new(now) }).await;
+    let alice = Keypair::new().pubkey();
+    let carol = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let carol_bet = env.place_bet(&carol, &event, MatchOutcome::TeamA, 3_000_000).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamB, 4_000_000).await;
+
+    // Only the arbiter funds promotions.
+    let stranger = Keypair::new();
+    env.fund(&stranger.pubkey(), 10 * BONUS).await;
+    assert!(env.top_up_pot(&stranger, &event, BONUS).await.is_err());
+    env.top_up_pot(&arbiter, &event, BONUS / 4).await.unwrap();
+    env.top_up_pot(&arbiter, &event, BONUS * 3 / 4).await.unwrap();
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    // Too late for more.
+    assert!(env.top_up_pot(&arbiter, &event, BONUS).await.is_err());
+    env.close_correction_window(&event).await;
+
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    env.withdraw(&carol, &event, &carol_bet).await.unwrap();
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    env.collect_commission(&arbiter, &event).await.unwrap();
+    let commission = env.lamports(&arbiter.pubkey()).await - arbiter_before;
+    let base = if bonus_commission_free { 8_000_000 } else { 8_000_000 + BONUS };
+    assert_eq!(commission, base * 3 / 100);
+    (event, alice, carol, bob)
+}
+
+#[tokio::test]
+async fn test_commission_free_bonus_is_fully_distributed() {
+    let mut env = common::start().await;
+    let (event, alice, carol, bob) = settle_with_bonus(&mut env, true).await;
+
+    // 1_940_000 + BONUS / 4 and 5_820_000 + BONUS * 3 / 4
+    assert_eq!(env.lamports(&alice).await, 2_190_000);
+    assert_eq!(env.lamports(&carol).await, 6_570_000);
+    assert_eq!(env.lamports(&bob).await, 0);
+    // Everything but the rent has left the event.
+    assert_eq!(env.lamports(&event).await, env.rent(EventBets::LEN).await);
+}
+
+#[tokio::test]
+async fn test_charged_bonus_is_paid_net_of_commission() {
+    let mut env = common::start().await;
+    let (event, alice, carol, _) = settle_with_bonus(&mut env, false).await;
+
+    // (2_000_000 + BONUS / 4) * 97 / 100 and (6_000_000 + BONUS * 3 / 4) * 97 / 100
+    assert_eq!(env.lamports(&alice).await, 2_182_500);
+    assert_eq!(env.lamports(&carol).await, 6_547_500);
+    assert_eq!(env.lamports(&event).await, env.rent(EventBets::LEN).await);
+}
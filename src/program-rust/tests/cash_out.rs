@@ -0,0 +1,178 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{calculate_payout, cash_out_value, Bet, EventBets, Instruction as BetInstruction, MatchOutcome, MEMO_BYTES, Odds, STATE_VERSION};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+fn event(balance_a: u64, balance_b: u64) -> EventBets {
+    let mut event = EventBets::try_from_slice(&[0; EventBets::LEN]).unwrap();
+    event.version = STATE_VERSION;
+    event.balance_a = balance_a;
+    event.balance_b = balance_b;
+    event
+}
+
+fn bet(outcome: u8, amount: u64) -> Bet {
+    Bet {
+        version: STATE_VERSION,
+        betor: Pubkey::new_unique(),
+        event: Pubkey::new_unique(),
+        amount,
+        outcome,
+        referrer: Pubkey::default(),
+        memo: [0; MEMO_BYTES],
+        placed_at: 0,
+        claimed: 0,
+        for_sale: false,
+        ask_lamports: 0,
+        receipt_mint: Pubkey::default(),
+        locked_odds: Odds::default(),
+        delegate: Pubkey::default(),
+    }
+}
+
+async fn cash_out(env: &mut common::Env, betor: &Keypair, event: &Pubkey, bet: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::CashOut.pack(),
+        vec![AccountMeta::new(betor.pubkey(), true), AccountMeta::new(*event, false), AccountMeta::new(*bet, false)],
+    );
+    env.send(&[ix], &[betor]).await
+}
+
+// Whatever the result, the gross payouts of the bets left and the bonus fit in the pot above rent.
+async fn assert_covers_worst_case(env: &mut common::Env, event: &Pubkey, bets: &[Pubkey]) {
+    let state = EventBets::deserialize(&mut &env.data(event).await[..]).unwrap();
+    let available = env.lamports(event).await - env.rent(EventBets::LEN).await;
+    for &result in &[1, 2, 3] {
+        let mut settled = EventBets::deserialize(&mut &env.data(event).await[..]).unwrap();
+        settled.outcome = result;
+        let mut owed = state.bonus_lamports;
+        for bet in bets {
+            let this_bet = Bet::deserialize(&mut &env.data(bet).await[..]).unwrap();
+            if this_bet.outcome != 255 {
+                owed += calculate_payout(&settled, &this_bet, 0).unwrap();
+            }
+        }
+        assert!(owed <= available, "result {} owes {} with {} available", result, owed, available);
+    }
+}
+
+#[test]
+fn test_cash_out_value() {
+    // Balanced pools: 500k * 500k / 2M * 1M / 2M = 62_500 off, then 3% fee.
+    assert_eq!(cash_out_value(&event(1_000_000, 1_000_000), &bet(1, 500_000), 300), Ok(424_375));
+    // Nothing on the other side, the stake comes back less the fee.
+    assert_eq!(cash_out_value(&event(1_000_000, 0), &bet(1, 1_000_000), 300), Ok(970_000));
+    // Favourite and underdog of the same size.
+    assert_eq!(cash_out_value(&event(9_000_000, 1_000_000), &bet(1, 1_000_000), 300), Ok(960_300));
+    assert_eq!(cash_out_value(&event(1_000_000, 9_000_000), &bet(1, 1_000_000), 300), Ok(882_700));
+    // Never more than the stake, even without a fee.
+    assert_eq!(cash_out_value(&event(u64::MAX, u64::MAX), &bet(2, u64::MAX), 0), Ok(u64::MAX - u64::MAX / 4));
+
+    let mut settled = event(1_000, 1_000);
+    settled.outcome = 1;
+    assert!(cash_out_value(&settled, &bet(1, 1_000), 300).is_err());
+    assert!(cash_out_value(&event(1_000, 1_000), &bet(255, 1_000), 300).is_err());
+}
+
+#[tokio::test]
+async fn test_cash_out_balanced_pools() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let alice = Keypair::new();
+    env.fund(&alice.pubkey(), 1_000_000_000).await;
+    let alice_bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 500_000).await;
+    let bob = Keypair::new().pubkey();
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamA, 500_000).await;
+    let carol = Keypair::new().pubkey();
+    let carol_bet = env.place_bet(&carol, &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    let pot = env.lamports(&event).await;
+    let before = env.lamports(&alice.pubkey()).await;
+    cash_out(&mut env, &alice, &event, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice.pubkey()).await - before, 424_375);
+    assert_eq!(env.lamports(&event).await, pot - 424_375);
+
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.balance_a, bets.balance_b, bets.bets_count_a), (500_000, 1_000_000, 1));
+    assert_eq!(bets.bonus_lamports, 500_000 - 424_375);
+    assert_covers_worst_case(&mut env, &event, &[alice_bet, bob_bet, carol_bet]).await;
+
+    // Only once, and the cashed out bet can't win.
+    assert!(cash_out(&mut env, &alice, &event, &alice_bet).await.is_err());
+    env.set_now(now + 100).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    assert!(env.withdraw(&alice.pubkey(), &event, &alice_bet).await.is_err());
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+    env.withdraw(&carol, &event, &carol_bet).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_cash_out_lopsided_pools() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let mut all = vec![];
+    let mut favourites = vec![];
+    for _ in 0..3 {
+        let betor = Keypair::new();
+        env.fund(&betor.pubkey(), 1_000_000_000).await;
+        let bet = env.place_bet(&betor.pubkey(), &event, MatchOutcome::TeamA, 3_000_000).await;
+        all.push(bet);
+        favourites.push((betor, bet));
+    }
+    let underdog = Keypair::new();
+    env.fund(&underdog.pubkey(), 1_000_000_000).await;
+    let underdog_bet = env.place_bet(&underdog.pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+    all.push(underdog_bet);
+
+    let (betor, bet) = &favourites[0];
+    cash_out(&mut env, betor, &event, bet).await.unwrap();
+    assert_covers_worst_case(&mut env, &event, &all).await;
+    cash_out(&mut env, &underdog, &event, &underdog_bet).await.unwrap();
+    assert_covers_worst_case(&mut env, &event, &all).await;
+    let (betor, bet) = &favourites[1];
+    cash_out(&mut env, betor, &event, bet).await.unwrap();
+    assert_covers_worst_case(&mut env, &event, &all).await;
+
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.balance_a, bets.balance_b), (3_000_000, 0));
+    // Last one out with nobody on the other side gets the stake less the fee.
+    let (betor, bet) = &favourites[2];
+    let before = env.lamports(&betor.pubkey()).await;
+    cash_out(&mut env, betor, &event, bet).await.unwrap();
+    assert_eq!(env.lamports(&betor.pubkey()).await - before, 2_910_000);
+    assert_covers_worst_case(&mut env, &event, &all).await;
+}
+
+#[tokio::test]
+async fn test_cash_out_after_the_deadline_is_rejected() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let alice = Keypair::new();
+    env.fund(&alice.pubkey(), 1_000_000_000).await;
+    let alice_bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    // Betting closed, the result is not in yet, but the match may well be over.
+    env.set_now(now + 101).await;
+    let pot = env.lamports(&event).await;
+    let before = env.lamports(&alice.pubkey()).await;
+    assert!(cash_out(&mut env, &alice, &event, &alice_bet).await.is_err());
+    assert_eq!(env.lamports(&event).await, pot);
+    assert_eq!(env.lamports(&alice.pubkey()).await, before);
+    let this_bet = Bet::deserialize(&mut &env.data(&alice_bet).await[..]).unwrap();
+    assert_eq!(this_bet.outcome, u8::from(MatchOutcome::TeamA));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use helloworld::{client, Bet, EventBets, EventParams, MatchOutcome};
+use solana_sdk::{
+    program_pack::Pack,
+    rent::Rent,
+    signature::{Keypair, Signer},
+};
+
+// Each step sends only what the client module builds.
+#[tokio::test]
+async fn test_client_builders_run_a_full_event() {
+    let mut env = common::start().await;
+    let program_id = env.program_id;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+
+    let event = Keypair::new();
+    let lamports = env.rent(EventBets::LEN).await;
+    env.create_account(&event, lamports, EventBets::LEN).await;
+    env.send(&[client::initialize(&program_id, &arbiter.pubkey(), &event.pubkey(), now)], &[&arbiter]).await.unwrap();
+    let event = event.pubkey();
+
+    let (alice, bob) = (Keypair::new().pubkey(), Keypair::new().pubkey());
+    let mut bets = vec![];
+    for (betor, choice) in &[(alice, MatchOutcome::TeamA), (bob, MatchOutcome::TeamB)] {
+        let bet = Keypair::new();
+        let lamports = env.rent(Bet::LEN).await + 1_000_000;
+        env.create_account(&bet, lamports, Bet::LEN).await;
+        env.send(&[client::add_bet(&program_id, betor, &event, &bet.pubkey(), *choice, 1_000_000)], &[]).await.unwrap();
+        bets.push(bet.pubkey());
+    }
+    let state = EventBets::unpack_from_slice(&env.data(&event).await).unwrap();
+    assert_eq!((state.arbiter, state.balance_a, state.balance_b), (arbiter.pubkey(), 1_000_000, 1_000_000));
+
+    env.send(&[client::set_winner(&program_id, &arbiter.pubkey(), &event, MatchOutcome::TeamA)], &[&arbiter]).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.send(&[client::withdraw(&program_id, &alice, &event, &bets[0])], &[]).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 1_940_000);
+    // A second claim finds the bet withdrawn.
+    assert!(env.send(&[client::withdraw(&program_id, &alice, &event, &bets[0])], &[]).await.is_err());
+}
+
+#[test]
+fn test_client_account_flags() {
+    let (program_id, owner, event, bet) = (helloworld::id(), Keypair::new().pubkey(), Keypair::new().pubkey(), Keypair::new().pubkey());
+    let flags = |ix: solana_sdk::instruction::Instruction| ix.accounts.iter().map(|meta| (meta.is_signer, meta.is_writable)).collect::<Vec<_>>();
+    assert_eq!(flags(client::initialize(&program_id, &owner, &event, 0)), [(true, false), (false, true), (false, false)]);
+    assert_eq!(client::initialize(&program_id, &owner, &event, 0).accounts[2].pubkey, solana_sdk::sysvar::rent::id());
+    assert_eq!(flags(client::add_bet(&program_id, &owner, &event, &bet, MatchOutcome::TeamB, 5)), [(false, true); 3]);
+    assert_eq!(flags(client::set_winner(&program_id, &owner, &event, MatchOutcome::Draw)), [(true, true), (false, true)]);
+    assert_eq!(flags(client::withdraw(&program_id, &owner, &event, &bet)), [(false, true); 3]);
+}
+
+// The account creation comes with the instruction, nothing is set up beforehand.
+#[tokio::test]
+async fn test_client_transactions_create_the_accounts() {
+    let mut env = common::start().await;
+    let program_id = env.program_id;
+    let payer = env.payer();
+    let rent: Rent = env.context.banks_client.get_sysvar().await.unwrap();
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+
+    let event = Keypair::new();
+    let instructions = client::create_event_transaction(&program_id, &payer.pubkey(), &arbiter.pubkey(), &event.pubkey(), now, &rent);
+    env.send(&instructions, &[&event, &arbiter]).await.unwrap();
+    let event = event.pubkey();
+    assert_eq!(env.lamports(&event).await, rent.minimum_balance(EventBets::LEN));
+
+    let alice = Keypair::new().pubkey();
+    let alice_bet = Keypair::new();
+    let instructions = client::place_bet_transaction(&program_id, &payer.pubkey(), &alice, &event, &alice_bet.pubkey(), MatchOutcome::TeamA, 1_000_000, &rent);
+    env.send(&instructions, &[&alice_bet]).await.unwrap();
+    let bob_bet = Keypair::new();
+    let instructions = client::place_bet_transaction(&program_id, &payer.pubkey(), &Keypair::new().pubkey(), &event, &bob_bet.pubkey(), MatchOutcome::TeamB, 1_000_000, &rent);
+    env.send(&instructions, &[&bob_bet]).await.unwrap();
+
+    let this_bet = Bet::unpack_from_slice(&env.data(&alice_bet.pubkey()).await).unwrap();
+    assert_eq!((this_bet.betor, this_bet.amount), (alice, 1_000_000));
+    env.send(&[client::set_winner(&program_id, &arbiter.pubkey(), &event, MatchOutcome::TeamA)], &[&arbiter]).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.send(&[client::withdraw(&program_id, &alice, &event, &alice_bet.pubkey())], &[]).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 1_940_000);
+
+    // A bond comes on top of the rent.
+    let bonded = Keypair::new();
+    let params = EventParams { bond_lamports: 5_000, ..EventParams::new(env.now().await + 100) };
+    let instructions = client::create_event_transaction_with(&program_id, &payer.pubkey(), &arbiter.pubkey(), &bonded.pubkey(), params, &rent);
+    env.send(&instructions, &[&bonded, &arbiter]).await.unwrap();
+    assert_eq!(env.lamports(&bonded.pubkey()).await, rent.minimum_balance(EventBets::LEN) + 5_000);
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{commission_of, BetError, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+};
+
+// Stakes that don't divide evenly, so the pro-rata shares and the commission both round.
+const WINNERS: [u64; 3] = [1_000_001, 2_000_003, 333_337];
+const LOSERS: [u64; 2] = [1_000_000, 777_777];
+
+fn accrued(data: &[u8]) -> u64 {
+    EventBets::unpack(data).unwrap().commission_accrued
+}
+
+#[test]
+fn test_collect_commission_unpack() {
+    assert_eq!(BetInstruction::unpack(&[47]).unwrap(), BetInstruction::CollectCommission);
+    assert_eq!(BetInstruction::CollectCommission.pack(), vec![47]);
+}
+
+#[tokio::test]
+async fn test_full_settlement_stays_solvent() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let mut winners = vec![];
+    for &stake in &WINNERS {
+        let betor = Keypair::new().pubkey();
+        winners.push((betor, env.place_bet(&betor, &event, MatchOutcome::TeamA, stake).await, stake));
+    }
+    let mut losers = vec![];
+    for &stake in &LOSERS {
+        let betor = Keypair::new().pubkey();
+        losers.push((betor, env.place_bet(&betor, &event, MatchOutcome::TeamB, stake).await));
+    }
+    let rent = env.rent(EventBets::LEN).await;
+    let winning_pool: u64 = WINNERS.iter().sum();
+    let losing_pool: u64 = LOSERS.iter().sum();
+    assert_eq!(env.lamports(&event).await, rent + winning_pool + losing_pool);
+
+    // The result alone moves nothing.
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    assert_eq!(env.lamports(&event).await, rent + winning_pool + losing_pool);
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, 0);
+
+    let mut paid = 0;
+    let mut fees = 0;
+    for (betor, bet, stake) in &winners {
+        env.withdraw(betor, &event, bet).await.unwrap();
+        paid += env.lamports(betor).await;
+        let gross = (*stake as u128 * losing_pool as u128 / winning_pool as u128) as u64 + stake;
+        fees += commission_of(gross, 300);
+        // Each claim leaves its commission behind, on top of what the others are still owed.
+        assert_eq!(accrued(&env.data(&event).await), fees);
+        assert!(env.lamports(&event).await >= rent + fees);
+    }
+    for (betor, bet) in &losers {
+        env.withdraw(betor, &event, bet).await.unwrap();
+        assert_eq!(env.lamports(betor).await, 0);
+    }
+    // A second claim doesn't charge again.
+    let (betor, bet, _) = winners[0];
+    assert!(env.withdraw(&betor, &event, &bet).await.is_err());
+    assert_eq!(accrued(&env.data(&event).await), fees);
+
+    env.collect_commission(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, fees);
+    assert_eq!(accrued(&env.data(&event).await), 0);
+    // Only the rounding is left, under a lamport per winner for the share and one for the commission.
+    let dust = env.lamports(&event).await - rent;
+    assert!(dust < 2 * WINNERS.len() as u64, "{} lamports of dust", dust);
+    assert_eq!(paid + fees + dust, winning_pool + losing_pool);
+
+    // Collecting again moves nothing.
+    env.collect_commission(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, fees);
+}
+
+#[tokio::test]
+async fn test_no_commission_without_winning_bets() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let betor = Keypair::new().pubkey();
+    let bet = env.place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await;
+
+    // Nobody backed TeamB, so nothing is paid out and nothing is charged.
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&betor, &event, &bet).await.unwrap();
+    env.collect_commission(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, 0);
+    assert_eq!(env.lamports(&event).await, env.rent(EventBets::LEN).await + 1_000_000);
+}
+
+#[tokio::test]
+async fn test_only_the_arbiter_collects_a_final_result() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { dispute_window_secs: 100, ..EventParams::new(now) }).await;
+    let betor = Keypair::new().pubkey();
+    let bet = env.place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    assert_eq!(env.collect_commission(&arbiter, &event).await.unwrap_err(), bet_error(0, BetError::ResultNotFinal));
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&betor, &event, &bet).await.unwrap();
+
+    let stranger = Keypair::new();
+    env.fund(&stranger.pubkey(), 1_000_000_000).await;
+    assert!(env.collect_commission(&stranger, &event).await.is_err());
+    assert_eq!(accrued(&env.data(&event).await), 60_000);
+
+    env.collect_commission(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, 60_000);
+}
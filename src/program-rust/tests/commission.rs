@@ -0,0 +1,84 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{EventBets, EventParams, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    signature::{Keypair, Signer},
+    sysvar,
+};
+
+#[tokio::test]
+async fn test_zero_commission_pays_full_pro_rata() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { commission_bps: 0, ..EventParams::new(now) }).await;
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&bob, &event, MatchOutcome::TeamB, 3_000_000).await;
+
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, arbiter_before);
+
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 4_000_000);
+}
+
+#[tokio::test]
+async fn test_custom_commission_is_used_for_payouts() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { commission_bps: 500, ..EventParams::new(now) }).await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 1_900_000);
+}
+
+#[tokio::test]
+async fn test_commission_above_maximum_is_rejected() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    assert!(env.try_create_event_with(&arbiter, EventParams { commission_bps: 1_001, ..EventParams::new(now) }).await.is_err());
+    env.create_event_with(&arbiter, EventParams { commission_bps: 1_000, ..EventParams::new(now) }).await;
+}
+
+#[tokio::test]
+async fn test_legacy_initialize_payload_gets_default_commission() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+
+    // Tag + deadline only, as sent by clients predating the optional fields.
+    let mut data = vec![0u8];
+    data.extend_from_slice(&now.to_le_bytes());
+    assert_eq!(BetInstruction::unpack(&data).unwrap(), BetInstruction::Initialize(EventParams::new(now)));
+
+    let event = Keypair::new();
+    let lamports = env.rent(EventBets::LEN).await;
+    env.create_account(&event, lamports, EventBets::LEN).await;
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(arbiter.pubkey(), true),
+            AccountMeta::new(event.pubkey(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    );
+    env.send(&[ix], &[&arbiter]).await.unwrap();
+
+    let bets = EventBets::deserialize(&mut &env.data(&event.pubkey()).await[..]).unwrap();
+    assert_eq!(bets.commission_bps, 300);
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use helloworld::{commission_of, net_of_commission, EventParams, MatchOutcome};
+use proptest::prelude::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+// Same gross pro-rata share as _process_withdraw computes for a winning bet.
+fn payout(amount: u64, winning_pool: u64, losing_pool: u64, commission_bps: u16) -> u128 {
+    let gross = losing_pool as u128 * amount as u128 / winning_pool as u128 + amount as u128;
+    net_of_commission(gross, commission_bps)
+}
+
+proptest! {
+    #[test]
+    fn test_payouts_and_commission_never_exceed_pot(
+        winners in prop::collection::vec(1u64..=u64::MAX / 64, 1..16),
+        losers in prop::collection::vec(0u64..=u64::MAX / 64, 0..16),
+        commission_bps in 0u16..=10_000,
+    ) {
+        let winning_pool: u64 = winners.iter().sum();
+        let losing_pool: u64 = losers.iter().sum();
+        let pot = winning_pool as u128 + losing_pool as u128;
+        let commission = commission_of(pot as u64, commission_bps) as u128;
+        let paid: u128 = winners.iter().map(|&amount| payout(amount, winning_pool, losing_pool, commission_bps)).sum();
+        prop_assert!(paid + commission <= pot);
+    }
+
+    #[test]
+    fn test_commission_and_net_add_up_to_at_most_the_amount(amount in any::<u64>(), commission_bps in 0u16..=10_000) {
+        prop_assert!(commission_of(amount, commission_bps) as u128 + net_of_commission(amount as u128, commission_bps) <= amount as u128);
+    }
+}
+
+#[test]
+fn test_default_is_300_bps() {
+    assert_eq!(EventParams::new(0).commission_bps, helloworld::USE_DEFAULT_COMISSION);
+    assert_eq!(commission_of(1_000_000, 300), 30_000);
+    assert_eq!(net_of_commission(1_000_000, 300), 970_000);
+}
+
+#[tokio::test]
+async fn test_fractional_percent_commission() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { commission_bps: 250, ..EventParams::new(now) }).await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_001).await;
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    // (1_000_001 * 1_000_000 / 1_000_000 + 1_000_000) * 9_750 / 10_000
+    assert_eq!(env.lamports(&alice).await, 1_950_000);
+
+    env.collect_commission(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, 2_000_001 * 250 / 10_000);
+}
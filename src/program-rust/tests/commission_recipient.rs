@@ -0,0 +1,61 @@
+mod common;
+
+use helloworld::{EventBets, EventParams, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::InstructionError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+#[test]
+fn test_commission_recipient_params_unpack() {
+    let ix = BetInstruction::Initialize(EventParams { commission_recipient: Pubkey::new_unique(), ..EventParams::new(42) });
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    let mut legacy = ix.pack();
+    legacy.truncate(legacy.len() - (32 + 1 + 1 + 8));
+    assert_eq!(BetInstruction::unpack(&legacy).unwrap(), BetInstruction::Initialize(EventParams::new(42)));
+}
+
+#[tokio::test]
+async fn test_recipient_defaults_to_the_arbiter() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    assert_eq!(EventBets::unpack(&env.data(&event).await).unwrap().commission_recipient, arbiter.pubkey());
+}
+
+#[tokio::test]
+async fn test_fees_go_to_the_recipient() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let cold = Pubkey::new_unique();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { commission_recipient: cold, ..EventParams::new(now) }).await;
+    assert_eq!(EventBets::unpack(&env.data(&event).await).unwrap().commission_recipient, cold);
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+
+    // The arbiter still signs, but can't have the fees paid to itself or anyone else.
+    let err = env.collect_commission(&arbiter, &event).await.unwrap_err();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+    let ix = env.collect_commission_to_ix(&arbiter.pubkey(), &event, &Pubkey::new_unique());
+    assert!(env.send(&[ix], &[&arbiter]).await.is_err());
+    // Nor can anyone else collect for the recipient.
+    let stranger = Keypair::new();
+    let ix = env.collect_commission_to_ix(&stranger.pubkey(), &event, &cold);
+    assert!(env.send(&[ix], &[&stranger]).await.is_err());
+
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    let ix = env.collect_commission_to_ix(&arbiter.pubkey(), &event, &cold);
+    env.send(&[ix], &[&arbiter]).await.unwrap();
+    assert_eq!(env.lamports(&cold).await, 60_000);
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, arbiter_before);
+    assert_eq!(env.lamports(&event).await, env.rent(EventBets::LEN).await);
+}
@@ -0,0 +1,544 @@
+#![allow(dead_code)]
+
+use helloworld::{
+    config_address, limit_address, process_instruction, treasury_address, vault_address, vault_authority, Bet, BetError, ConfigParams, EventBets, EventParams, Instruction as BetInstruction,
+    MatchOutcome,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    clock::{Clock, UnixTimestamp},
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{read_keypair_file, Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::{Transaction, TransactionError},
+};
+
+// Keypair behind helloworld::admin::id() when built with the test-admin feature.
+pub fn test_admin() -> Keypair {
+    read_keypair_file(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/test-admin.json")).unwrap()
+}
+
+pub fn bet_error(instruction_index: u8, error: BetError) -> TransactionError {
+    TransactionError::InstructionError(instruction_index, InstructionError::Custom(error as u32))
+}
+
+pub struct Env {
+    pub context: ProgramTestContext,
+    pub program_id: Pubkey,
+}
+
+pub async fn start() -> Env {
+    start_with_accounts(vec![]).await
+}
+
+// Starts with extra accounts already in place, for states no instruction can produce here.
+pub async fn start_with_accounts(accounts: Vec<(Pubkey, Account)>) -> Env {
+    let program_id = helloworld::id();
+    let mut program_test = ProgramTest::new(
+        "helloworld", // Run the BPF version with `cargo test-bpf`
+        program_id,
+        processor!(process_instruction), // Run the native version with `cargo test`
+    );
+    for (address, account) in accounts {
+        program_test.add_account(address, account);
+    }
+    let context = program_test.start_with_context().await;
+    Env { context, program_id }
+}
+
+impl Env {
+    pub fn payer(&self) -> Keypair {
+        Keypair::from_bytes(&self.context.payer.to_bytes()).unwrap()
+    }
+
+    pub async fn now(&mut self) -> UnixTimestamp {
+        self.clock().await.unix_timestamp
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.context.banks_client.get_sysvar::<Clock>().await.unwrap()
+    }
+
+    pub async fn set_now(&mut self, unix_timestamp: UnixTimestamp) {
+        let mut clock = self.clock().await;
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn lamports(&mut self, pubkey: &Pubkey) -> u64 {
+        self.context.banks_client.get_balance(*pubkey).await.unwrap()
+    }
+
+    pub async fn data(&mut self, pubkey: &Pubkey) -> Vec<u8> {
+        self.context
+            .banks_client
+            .get_account(*pubkey)
+            .await
+            .expect("get_account")
+            .expect("account not found")
+            .data
+    }
+
+    // Rewrites an account in place, for states no instruction can produce.
+    pub async fn corrupt(&mut self, pubkey: &Pubkey, f: impl FnOnce(&mut Account)) {
+        let mut account = self.context.banks_client.get_account(*pubkey).await.unwrap().expect("account not found");
+        f(&mut account);
+        self.context.set_account(pubkey, &account.into());
+    }
+
+    pub async fn rent(&mut self, len: usize) -> u64 {
+        let rent: Rent = self.context.banks_client.get_sysvar().await.unwrap();
+        rent.minimum_balance(len)
+    }
+
+    // Sends the instructions with a fresh blockhash, so that identical transactions are not deduplicated.
+    pub async fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), TransactionError> {
+        let payer = self.payer();
+        let blockhash = self
+            .context
+            .banks_client
+            .get_new_blockhash(&self.context.last_blockhash)
+            .await
+            .unwrap()
+            .0;
+        self.context.last_blockhash = blockhash;
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &all_signers, blockhash);
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|err| err.unwrap())
+    }
+
+    pub async fn create_account(&mut self, account: &Keypair, lamports: u64, space: usize) {
+        let payer = self.payer();
+        let program_id = self.program_id;
+        self.send(
+            &[system_instruction::create_account(&payer.pubkey(), &account.pubkey(), lamports, space as u64, &program_id)],
+            &[account],
+        )
+        .await
+        .unwrap();
+    }
+
+    // Creates a zero-decimals mint with the test payer as the mint authority.
+    pub async fn create_mint(&mut self) -> Pubkey {
+        let payer = self.payer();
+        let mint = Keypair::new();
+        let lamports = self.rent(spl_token::state::Mint::LEN).await;
+        self.send(
+            &[
+                system_instruction::create_account(&payer.pubkey(), &mint.pubkey(), lamports, spl_token::state::Mint::LEN as u64, &spl_token::id()),
+                spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &payer.pubkey(), None, 0).unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+        mint.pubkey()
+    }
+
+    pub async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let payer = self.payer();
+        let account = Keypair::new();
+        let lamports = self.rent(spl_token::state::Account::LEN).await;
+        self.send(
+            &[
+                system_instruction::create_account(&payer.pubkey(), &account.pubkey(), lamports, spl_token::state::Account::LEN as u64, &spl_token::id()),
+                spl_token::instruction::initialize_account(&spl_token::id(), &account.pubkey(), mint, owner).unwrap(),
+            ],
+            &[&account],
+        )
+        .await
+        .unwrap();
+        if amount > 0 {
+            self.send(&[spl_token::instruction::mint_to(&spl_token::id(), mint, &account.pubkey(), &payer.pubkey(), &[], amount).unwrap()], &[])
+                .await
+                .unwrap();
+        }
+        account.pubkey()
+    }
+
+    pub async fn token_balance(&mut self, account: &Pubkey) -> u64 {
+        spl_token::state::Account::unpack(&self.data(account).await).unwrap().amount
+    }
+
+    // Token accounts passed by SetWinner and Withdraw of SPL token events, after the payee's token account.
+    pub fn vault_accounts(&self, event: &Pubkey, mint: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(vault_address(&self.program_id, event, mint, &spl_token::id()), false),
+            AccountMeta::new_readonly(vault_authority(&self.program_id, event).0, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ]
+    }
+
+    // Creates the vault, then the event denominated in `params.mint`.
+    pub async fn create_token_event(&mut self, arbiter: &Keypair, params: EventParams) -> Pubkey {
+        let payer = self.payer();
+        let event = Keypair::new();
+        let authority = vault_authority(&self.program_id, &event.pubkey()).0;
+        self.send(&[spl_associated_token_account::create_associated_token_account(&payer.pubkey(), &authority, &params.mint)], &[])
+            .await
+            .unwrap();
+        let lamports = self.rent(EventBets::LEN).await + params.bond_lamports;
+        self.create_account(&event, lamports, EventBets::LEN).await;
+        let mut ix = self.initialize_ix(&arbiter.pubkey(), &event.pubkey(), BetInstruction::Initialize(params));
+        ix.accounts.push(AccountMeta::new_readonly(vault_address(&self.program_id, &event.pubkey(), &params.mint, &spl_token::id()), false));
+        ix.accounts.push(AccountMeta::new_readonly(params.mint, false));
+        self.send(&[ix], &[arbiter]).await.unwrap();
+        event.pubkey()
+    }
+
+    // Places a bet of `amount` tokens from the betor's `source` token account.
+    pub async fn try_place_token_bet(
+        &mut self,
+        betor: &Keypair,
+        event: &Pubkey,
+        mint: &Pubkey,
+        choice: MatchOutcome,
+        source: &Pubkey,
+        amount: u64,
+    ) -> Result<Pubkey, TransactionError> {
+        let bet = Keypair::new();
+        let lamports = self.rent(Bet::LEN).await;
+        self.create_account(&bet, lamports, Bet::LEN).await;
+        let mut ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::AddBet { choice, referrer: Pubkey::default(), amount, memo: [0; 32] }.pack(),
+            vec![
+                AccountMeta::new(betor.pubkey(), true),
+                AccountMeta::new(*event, false),
+                AccountMeta::new(bet.pubkey(), false),
+                AccountMeta::new(*source, false),
+            ],
+        );
+        ix.accounts.extend(self.vault_accounts(event, mint));
+        self.send(&[ix], &[betor]).await?;
+        Ok(bet.pubkey())
+    }
+
+    pub async fn fund(&mut self, to: &Pubkey, lamports: u64) {
+        let payer = self.payer();
+        self.send(&[system_instruction::transfer(&payer.pubkey(), to, lamports)], &[]).await.unwrap();
+    }
+
+    pub fn initialize_ix(&self, arbiter: &Pubkey, event: &Pubkey, instruction: BetInstruction) -> Instruction {
+        Instruction::new_with_bytes(
+            self.program_id,
+            &instruction.pack(),
+            vec![
+                AccountMeta::new_readonly(*arbiter, true),
+                AccountMeta::new(*event, false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ],
+        )
+    }
+
+    pub async fn create_event(&mut self, arbiter: &Keypair, bets_accepted_until: UnixTimestamp) -> Pubkey {
+        self.create_event_with(arbiter, EventParams::new(bets_accepted_until)).await
+    }
+
+    // Creates the event account funded with rent exemption + bond and initializes it.
+    pub async fn try_create_event_with(&mut self, arbiter: &Keypair, params: EventParams) -> Result<Pubkey, TransactionError> {
+        self.try_create_event_with_accounts(arbiter, params, vec![]).await
+    }
+
+    // Same as try_create_event_with, passing the optional trailing accounts of Initialize.
+    pub async fn try_create_event_with_accounts(
+        &mut self,
+        arbiter: &Keypair,
+        params: EventParams,
+        extra_accounts: Vec<AccountMeta>,
+    ) -> Result<Pubkey, TransactionError> {
+        let event = Keypair::new();
+        let lamports = self.rent(EventBets::LEN).await + params.bond_lamports;
+        self.create_account(&event, lamports, EventBets::LEN).await;
+        let mut ix = self.initialize_ix(&arbiter.pubkey(), &event.pubkey(), BetInstruction::Initialize(params));
+        ix.accounts.extend(extra_accounts);
+        self.send(&[ix], &[arbiter]).await?;
+        Ok(event.pubkey())
+    }
+
+    pub async fn create_event_with(&mut self, arbiter: &Keypair, params: EventParams) -> Pubkey {
+        self.try_create_event_with(arbiter, params).await.unwrap()
+    }
+
+    pub fn add_bet_ix(&self, betor: &Pubkey, event: &Pubkey, bet: &Pubkey, choice: MatchOutcome) -> Instruction {
+        self.add_referred_bet_ix(betor, event, bet, choice, &Pubkey::default())
+    }
+
+    pub fn add_referred_bet_ix(&self, betor: &Pubkey, event: &Pubkey, bet: &Pubkey, choice: MatchOutcome, referrer: &Pubkey) -> Instruction {
+        Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::AddBet { choice, referrer: *referrer, amount: 0, memo: [0; 32] }.pack(),
+            vec![
+                AccountMeta::new(*betor, false),
+                AccountMeta::new(*event, false),
+                AccountMeta::new(*bet, false),
+            ],
+        )
+    }
+
+    // Creates a bet account holding `amount` above the rent exemption and places it.
+    pub async fn try_place_bet(&mut self, betor: &Pubkey, event: &Pubkey, choice: MatchOutcome, amount: u64) -> Result<Pubkey, TransactionError> {
+        self.try_place_bet_with_accounts(betor, event, choice, amount, vec![]).await
+    }
+
+    // Same as try_place_bet, passing the optional trailing accounts of AddBet.
+    pub async fn try_place_bet_with_accounts(
+        &mut self,
+        betor: &Pubkey,
+        event: &Pubkey,
+        choice: MatchOutcome,
+        amount: u64,
+        extra_accounts: Vec<AccountMeta>,
+    ) -> Result<Pubkey, TransactionError> {
+        let bet = Keypair::new();
+        let lamports = self.rent(Bet::LEN).await + amount;
+        self.create_account(&bet, lamports, Bet::LEN).await;
+        let mut ix = self.add_bet_ix(betor, event, &bet.pubkey(), choice);
+        ix.accounts.extend(extra_accounts);
+        self.send(&[ix], &[]).await?;
+        Ok(bet.pubkey())
+    }
+
+    pub async fn try_place_referred_bet(
+        &mut self,
+        betor: &Pubkey,
+        event: &Pubkey,
+        choice: MatchOutcome,
+        amount: u64,
+        referrer: &Pubkey,
+    ) -> Result<Pubkey, TransactionError> {
+        let bet = Keypair::new();
+        let lamports = self.rent(Bet::LEN).await + amount;
+        self.create_account(&bet, lamports, Bet::LEN).await;
+        let ix = self.add_referred_bet_ix(betor, event, &bet.pubkey(), choice, referrer);
+        self.send(&[ix], &[]).await?;
+        Ok(bet.pubkey())
+    }
+
+    // Sends `amount` lamports to the bet account and adds them to the bet in the same transaction.
+    pub async fn try_increase_bet(
+        &mut self,
+        betor: &Keypair,
+        event: &Pubkey,
+        bet: &Pubkey,
+        choice: MatchOutcome,
+        amount: u64,
+        extra_accounts: Vec<AccountMeta>,
+    ) -> Result<(), TransactionError> {
+        let payer = self.payer();
+        let mut ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::IncreaseBet { choice, amount: 0 }.pack(),
+            vec![
+                AccountMeta::new(betor.pubkey(), true),
+                AccountMeta::new(*event, false),
+                AccountMeta::new(*bet, false),
+            ],
+        );
+        ix.accounts.extend(extra_accounts);
+        self.send(&[system_instruction::transfer(&payer.pubkey(), bet, amount), ix], &[betor]).await
+    }
+
+    // Trailing AddBet accounts for events with max_stake_per_wallet, paid by the test payer.
+    pub fn limit_accounts(&self, event: &Pubkey, betor: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(limit_address(&self.program_id, event, betor).0, false),
+            AccountMeta::new(self.context.payer.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]
+    }
+
+    pub async fn place_bet(&mut self, betor: &Pubkey, event: &Pubkey, choice: MatchOutcome, amount: u64) -> Pubkey {
+        self.try_place_bet(betor, event, choice, amount).await.unwrap()
+    }
+
+    // Creates one house bet account per side holding rent + seed and seeds the event with them.
+    pub async fn seed_liquidity(&mut self, arbiter: &Keypair, event: &Pubkey, seed_a: u64, seed_b: u64) -> Result<(Pubkey, Pubkey), TransactionError> {
+        let bet_a = Keypair::new();
+        let bet_b = Keypair::new();
+        let rent = self.rent(Bet::LEN).await;
+        self.create_account(&bet_a, rent + seed_a, Bet::LEN).await;
+        self.create_account(&bet_b, rent + seed_b, Bet::LEN).await;
+        let ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::SeedLiquidity.pack(),
+            vec![
+                AccountMeta::new_readonly(arbiter.pubkey(), true),
+                AccountMeta::new(*event, false),
+                AccountMeta::new(bet_a.pubkey(), false),
+                AccountMeta::new(bet_b.pubkey(), false),
+            ],
+        );
+        self.send(&[ix], &[arbiter]).await?;
+        Ok((bet_a.pubkey(), bet_b.pubkey()))
+    }
+
+    pub async fn top_up_pot(&mut self, arbiter: &Keypair, event: &Pubkey, amount: u64) -> Result<(), TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::TopUpPot { amount }.pack(),
+            vec![
+                AccountMeta::new(arbiter.pubkey(), true),
+                AccountMeta::new(*event, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+        self.send(&[ix], &[arbiter]).await
+    }
+
+    pub fn set_winner_ix(&self, arbiter: &Pubkey, event: &Pubkey, result: MatchOutcome) -> Instruction {
+        Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::SetWinner { result, metadata_hash: [0; 32] }.pack(),
+            vec![AccountMeta::new(*arbiter, true), AccountMeta::new(*event, false)],
+        )
+    }
+
+    pub async fn set_winner(&mut self, arbiter: &Keypair, event: &Pubkey, result: MatchOutcome) -> Result<(), TransactionError> {
+        let ix = self.set_winner_ix(&arbiter.pubkey(), event, result);
+        self.send(&[ix], &[arbiter]).await
+    }
+
+    // Moves the clock to when the result of `event` can be claimed, past its correction window.
+    pub async fn close_correction_window(&mut self, event: &Pubkey) {
+        let claimable_at = EventBets::unpack(&self.data(event).await).unwrap().claimable_at();
+        if self.now().await < claimable_at {
+            self.set_now(claimable_at).await;
+        }
+    }
+
+    pub fn withdraw_ix(&self, betor: &Pubkey, event: &Pubkey, bet: &Pubkey) -> Instruction {
+        self.withdraw_part_ix(betor, event, bet, 0)
+    }
+
+    pub fn withdraw_part_ix(&self, betor: &Pubkey, event: &Pubkey, bet: &Pubkey, amount: u64) -> Instruction {
+        Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::Withdraw { amount }.pack(),
+            vec![
+                AccountMeta::new(*betor, false),
+                AccountMeta::new(*event, false),
+                AccountMeta::new(*bet, false),
+            ],
+        )
+    }
+
+    pub async fn correct_result(&mut self, arbiter: &Keypair, event: &Pubkey, result: MatchOutcome) -> Result<(), TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::CorrectResult { result }.pack(),
+            vec![AccountMeta::new_readonly(arbiter.pubkey(), true), AccountMeta::new(*event, false)],
+        );
+        self.send(&[ix], &[arbiter]).await
+    }
+
+    pub async fn slash_bond(&mut self, challenger: &Keypair, event: &Pubkey) -> Result<(), TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::SlashBond.pack(),
+            vec![AccountMeta::new_readonly(challenger.pubkey(), true), AccountMeta::new(*event, false)],
+        );
+        self.send(&[ix], &[challenger]).await
+    }
+
+    pub async fn release_bond(&mut self, arbiter: &Keypair, event: &Pubkey) -> Result<(), TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::ReleaseBond.pack(),
+            vec![AccountMeta::new(arbiter.pubkey(), true), AccountMeta::new(*event, false)],
+        );
+        self.send(&[ix], &[arbiter]).await
+    }
+
+    pub async fn sweep_dust(&mut self, arbiter: &Keypair, event: &Pubkey) -> Result<(), TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::SweepDust.pack(),
+            vec![AccountMeta::new(arbiter.pubkey(), true), AccountMeta::new(*event, false)],
+        );
+        self.send(&[ix], &[arbiter]).await
+    }
+
+    // Pays to the arbiter, the default commission recipient.
+    pub fn collect_commission_ix(&self, arbiter: &Pubkey, event: &Pubkey) -> Instruction {
+        self.collect_commission_to_ix(arbiter, event, arbiter)
+    }
+
+    pub fn collect_commission_to_ix(&self, arbiter: &Pubkey, event: &Pubkey, recipient: &Pubkey) -> Instruction {
+        Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::CollectCommission.pack(),
+            vec![AccountMeta::new(*arbiter, true), AccountMeta::new(*event, false), AccountMeta::new(*recipient, false)],
+        )
+    }
+
+    pub async fn collect_commission(&mut self, arbiter: &Keypair, event: &Pubkey) -> Result<(), TransactionError> {
+        let ix = self.collect_commission_ix(&arbiter.pubkey(), event);
+        self.send(&[ix], &[arbiter]).await
+    }
+
+    pub fn config(&self) -> Pubkey {
+        config_address(&self.program_id).0
+    }
+
+    pub fn treasury(&self) -> Pubkey {
+        treasury_address(&self.program_id).0
+    }
+
+    pub async fn initialize_config(&mut self, admin: &Keypair, params: ConfigParams) -> Result<(), TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::InitializeConfig(params).pack(),
+            vec![
+                AccountMeta::new(admin.pubkey(), true),
+                AccountMeta::new(self.config(), false),
+                AccountMeta::new(self.treasury(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+        self.send(&[ix], &[admin]).await
+    }
+
+    pub async fn update_config(&mut self, admin: &Keypair, params: ConfigParams) -> Result<(), TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::UpdateConfig(params).pack(),
+            vec![AccountMeta::new_readonly(admin.pubkey(), true), AccountMeta::new(self.config(), false)],
+        );
+        self.send(&[ix], &[admin]).await
+    }
+
+    pub async fn withdraw_treasury(&mut self, admin: &Keypair, recipient: &Pubkey, amount: u64) -> Result<(), TransactionError> {
+        let ix = Instruction::new_with_bytes(
+            self.program_id,
+            &BetInstruction::WithdrawTreasury { amount }.pack(),
+            vec![
+                AccountMeta::new_readonly(admin.pubkey(), true),
+                AccountMeta::new_readonly(self.config(), false),
+                AccountMeta::new(self.treasury(), false),
+                AccountMeta::new(*recipient, false),
+            ],
+        );
+        self.send(&[ix], &[admin]).await
+    }
+
+    pub async fn withdraw(&mut self, betor: &Pubkey, event: &Pubkey, bet: &Pubkey) -> Result<(), TransactionError> {
+        let ix = self.withdraw_ix(betor, event, bet);
+        self.send(&[ix], &[]).await
+    }
+
+    pub async fn withdraw_part(&mut self, betor: &Pubkey, event: &Pubkey, bet: &Pubkey, amount: u64) -> Result<(), TransactionError> {
+        let ix = self.withdraw_part_ix(betor, event, bet, amount);
+        self.send(&[ix], &[]).await
+    }
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use helloworld::{Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::sync::Mutex;
+
+// Keeps the runtime's log lines, "Program <id> consumed <n> of <m> compute units" among them.
+struct Capture {
+    lines: Mutex<Vec<String>>,
+}
+
+impl log::Log for Capture {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.lines.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+impl Capture {
+    // Compute units of the last run of `program` since the previous call.
+    fn consumed(&self, program: &str) -> Option<u64> {
+        let prefix = format!("Program {} consumed ", program);
+        let lines = std::mem::take(&mut *self.lines.lock().unwrap());
+        lines.iter().rev().find_map(|line| line.strip_prefix(&prefix)?.split(' ').next()?.parse().ok())
+    }
+}
+
+// Compute unit ceilings of the BPF build with verbose-logs, the heaviest one. A change that needs more
+// raises its ceiling here, so the cost shows up in review. The runtime's limit is 200_000 per instruction.
+const CEILINGS: &[(&str, u64)] = &[
+    ("Initialize", 30_000),
+    ("AddBet", 40_000),
+    ("IncreaseBet", 40_000),
+    ("PauseEvent", 15_000),
+    ("UnpauseEvent", 15_000),
+    ("QuoteOdds", 15_000),
+    ("AuditEvent", 20_000),
+    ("SetWinner", 40_000),
+    ("QuotePayout", 20_000),
+    ("Withdraw", 40_000),
+];
+
+fn readonly_ix(env: &common::Env, instruction: BetInstruction, accounts: &[&Pubkey]) -> Instruction {
+    let accounts = accounts.iter().map(|pubkey| AccountMeta::new_readonly(**pubkey, false)).collect();
+    Instruction::new_with_bytes(env.program_id, &instruction.pack(), accounts)
+}
+
+fn pause_ix(env: &common::Env, arbiter: &Keypair, event: &Pubkey, instruction: BetInstruction) -> Instruction {
+    Instruction::new_with_bytes(
+        env.program_id,
+        &instruction.pack(),
+        vec![AccountMeta::new_readonly(arbiter.pubkey(), true), AccountMeta::new(*event, false)],
+    )
+}
+
+// Only the BPF build is metered, run with
+//     cargo test-bpf --test compute_units -- --ignored --nocapture
+//     cargo test-bpf --no-default-features --test compute_units -- --ignored --nocapture
+// and compare the two to see what the verbose-logs feature costs.
+#[tokio::test]
+#[ignore]
+async fn bench_every_instruction() {
+    let capture: &'static Capture = Box::leak(Box::new(Capture { lines: Mutex::new(vec![]) }));
+    log::set_logger(capture).unwrap();
+    log::set_max_level(log::LevelFilter::Debug);
+
+    let mut env = common::start().await;
+    let program = env.program_id.to_string();
+    let mut consumed: Vec<(&str, Option<u64>)> = vec![];
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+
+    capture.consumed(&program);
+    let event = env.create_event(&arbiter, now).await;
+    consumed.push(("Initialize", capture.consumed(&program)));
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+    let betor = Keypair::new();
+    capture.consumed(&program);
+    let bet = env.place_bet(&betor.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    consumed.push(("AddBet", capture.consumed(&program)));
+    env.try_increase_bet(&betor, &event, &bet, MatchOutcome::TeamA, 500_000, vec![]).await.unwrap();
+    consumed.push(("IncreaseBet", capture.consumed(&program)));
+
+    for (name, instruction) in vec![("PauseEvent", BetInstruction::PauseEvent), ("UnpauseEvent", BetInstruction::UnpauseEvent)] {
+        let ix = pause_ix(&env, &arbiter, &event, instruction);
+        env.send(&[ix], &[&arbiter]).await.unwrap();
+        consumed.push((name, capture.consumed(&program)));
+    }
+    for (name, instruction) in vec![("QuoteOdds", BetInstruction::QuoteOdds), ("AuditEvent", BetInstruction::AuditEvent)] {
+        let ix = readonly_ix(&env, instruction, &[&event]);
+        env.send(&[ix], &[]).await.unwrap();
+        consumed.push((name, capture.consumed(&program)));
+    }
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    consumed.push(("SetWinner", capture.consumed(&program)));
+    let ix = readonly_ix(&env, BetInstruction::QuotePayout, &[&event, &bet]);
+    env.send(&[ix], &[]).await.unwrap();
+    consumed.push(("QuotePayout", capture.consumed(&program)));
+    env.close_correction_window(&event).await;
+    env.withdraw(&betor.pubkey(), &event, &bet).await.unwrap();
+    consumed.push(("Withdraw", capture.consumed(&program)));
+
+    if consumed.iter().all(|(_, units)| units.is_none()) {
+        println!("no compute units logged, the native build is not metered, use cargo test-bpf");
+    }
+    let logs = if cfg!(feature = "verbose-logs") { "verbose logs" } else { "error logs only" };
+    println!("{:<14} {:>8} {:>8}  ({})", "instruction", "CU", "ceiling", logs);
+    let mut over = vec![];
+    for (name, units) in consumed {
+        let ceiling = CEILINGS.iter().find(|(ceiling_name, _)| *ceiling_name == name).map(|(_, ceiling)| *ceiling).unwrap();
+        match units {
+            Some(units) => {
+                println!("{:<14} {:>8} {:>8}", name, units, ceiling);
+                if units > ceiling {
+                    over.push(format!("{} {} > {}", name, units, ceiling));
+                }
+            },
+            None => println!("{:<14} {:>8} {:>8}", name, "-", ceiling),
+        }
+    }
+    assert!(over.is_empty(), "over the compute unit ceiling: {}", over.join(", "));
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "test-admin")]
+
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{admin, Config, ConfigParams, EventBets, EventParams};
+use solana_sdk::{
+    instruction::AccountMeta,
+    signature::{Keypair, Signer},
+};
+
+const PARAMS: ConfigParams =
+    ConfigParams { default_commission_bps: 200, max_commission_bps: 500, min_deadline_offset_secs: 3600, arbiter_share_bps: 10_000, referral_bps: 0 };
+
+#[tokio::test]
+async fn test_admin_creates_and_updates_config() {
+    let mut env = common::start().await;
+    let admin = common::test_admin();
+    assert_eq!(admin.pubkey(), admin::id());
+    env.fund(&admin.pubkey(), 1_000_000_000).await;
+
+    env.initialize_config(&admin, PARAMS).await.unwrap();
+    let config_pubkey = env.config();
+    let config = Config::deserialize(&mut &env.data(&config_pubkey).await[..]).unwrap();
+    assert!(config.is_initialized);
+    assert_eq!(config.admin, admin.pubkey());
+    assert_eq!((config.default_commission_bps, config.max_commission_bps, config.min_deadline_offset_secs), (200, 500, 3600));
+    // Only once.
+    assert!(env.initialize_config(&admin, PARAMS).await.is_err());
+
+    env.update_config(&admin, ConfigParams { max_commission_bps: 800, ..PARAMS }).await.unwrap();
+    let config = Config::deserialize(&mut &env.data(&config_pubkey).await[..]).unwrap();
+    assert_eq!(config.max_commission_bps, 800);
+    // Bounds are validated on update too.
+    assert!(env.update_config(&admin, ConfigParams { default_commission_bps: 900, ..PARAMS }).await.is_err());
+}
+
+#[tokio::test]
+async fn test_stranger_can_not_create_or_update_config() {
+    let mut env = common::start().await;
+    let stranger = Keypair::new();
+    env.fund(&stranger.pubkey(), 1_000_000_000).await;
+    assert!(env.initialize_config(&stranger, PARAMS).await.is_err());
+
+    let admin = common::test_admin();
+    env.fund(&admin.pubkey(), 1_000_000_000).await;
+    env.initialize_config(&admin, PARAMS).await.unwrap();
+    assert!(env.update_config(&stranger, ConfigParams { max_commission_bps: 5_000, ..PARAMS }).await.is_err());
+}
+
+#[tokio::test]
+async fn test_initialize_respects_config_bounds() {
+    let mut env = common::start().await;
+    let admin = common::test_admin();
+    env.fund(&admin.pubkey(), 1_000_000_000).await;
+    env.initialize_config(&admin, PARAMS).await.unwrap();
+    let config = vec![AccountMeta::new_readonly(env.config(), false)];
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+
+    // Deadline too close.
+    assert!(env.try_create_event_with_accounts(&arbiter, EventParams::new(now + 60), config.clone()).await.is_err());
+    // Commission above the configured maximum, but fine under the built-in one.
+    let params = EventParams { commission_bps: 600, ..EventParams::new(now + 3600) };
+    assert!(env.try_create_event_with_accounts(&arbiter, params, config.clone()).await.is_err());
+    env.try_create_event_with(&arbiter, EventParams { commission_bps: 600, ..EventParams::new(now) }).await.unwrap();
+
+    // Default commission comes from the config.
+    let event = env.try_create_event_with_accounts(&arbiter, EventParams::new(now + 3600), config).await.unwrap();
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!(bets.commission_bps, 200);
+}
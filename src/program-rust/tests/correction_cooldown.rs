@@ -0,0 +1,76 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{BetError, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+};
+
+#[test]
+fn test_correction_cooldown_params_unpack() {
+    let ix = BetInstruction::Initialize(EventParams { correction_cooldown_secs: 300, ..EventParams::new(42) });
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    // Older clients don't send it and get no cooldown.
+    let mut legacy = ix.pack();
+    legacy.truncate(legacy.len() - 8);
+    assert_eq!(BetInstruction::unpack(&legacy).unwrap(), BetInstruction::Initialize(EventParams::new(42)));
+    assert_eq!(EventParams::new(42).correction_cooldown_secs, 0);
+}
+
+#[tokio::test]
+async fn test_correction_waits_for_the_cooldown() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { correction_cooldown_secs: 300, ..EventParams::new(now) }).await;
+    let mut bets = vec![];
+    for choice in &[MatchOutcome::TeamA, MatchOutcome::TeamB] {
+        let betor = Keypair::new().pubkey();
+        bets.push((betor, env.place_bet(&betor, &event, *choice, 1_000_000).await));
+    }
+
+    // The first SetWinner isn't held back, it starts the cooldown.
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    let state = EventBets::unpack(&env.data(&event).await).unwrap();
+    assert_eq!(state.last_result_change_at, state.winner_set_at);
+    assert_eq!(
+        env.correct_result(&arbiter, &event, MatchOutcome::TeamB).await.unwrap_err(),
+        bet_error(0, BetError::CorrectionTooSoon)
+    );
+    env.set_now(state.winner_set_at + 299).await;
+    assert_eq!(
+        env.correct_result(&arbiter, &event, MatchOutcome::TeamB).await.unwrap_err(),
+        bet_error(0, BetError::CorrectionTooSoon)
+    );
+
+    env.set_now(state.winner_set_at + 300).await;
+    env.correct_result(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+    let state = EventBets::unpack(&env.data(&event).await).unwrap();
+    assert_eq!((state.outcome, state.last_result_change_at), (u8::from(MatchOutcome::TeamB), state.winner_set_at + 300));
+
+    // Each correction restarts the cooldown.
+    assert_eq!(
+        env.correct_result(&arbiter, &event, MatchOutcome::TeamA).await.unwrap_err(),
+        bet_error(0, BetError::CorrectionTooSoon)
+    );
+    env.set_now(state.winner_set_at + 600).await;
+    env.correct_result(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+
+    // The last correction decides the payouts.
+    env.close_correction_window(&event).await;
+    let (betor, bet) = bets[0];
+    env.withdraw(&betor, &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&betor).await, 1_940_000);
+    let (betor, bet) = bets[1];
+    env.withdraw(&betor, &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&betor).await, 0);
+}
+
+#[tokio::test]
+async fn test_negative_cooldown_is_refused() {
+    let mut env = common::start().await;
+    let now = env.now().await;
+    let params = EventParams { correction_cooldown_secs: -1, ..EventParams::new(now) };
+    assert!(env.try_create_event_with(&Keypair::new(), params).await.is_err());
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{BetError, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome, DEFAULT_CORRECTION_WINDOW_SECS};
+use solana_sdk::{
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+struct Settled {
+    arbiter: Keypair,
+    event: Pubkey,
+    winner_set_at: i64,
+    bets: Vec<(Pubkey, Pubkey)>,
+}
+
+// Two opposite bets and a TeamA result, no dispute window.
+async fn settled(env: &mut common::Env, params: impl FnOnce(i64) -> EventParams) -> Settled {
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, params(now)).await;
+    let mut bets = vec![];
+    for choice in &[MatchOutcome::TeamA, MatchOutcome::TeamB] {
+        let betor = Keypair::new().pubkey();
+        bets.push((betor, env.place_bet(&betor, &event, *choice, 1_000_000).await));
+    }
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    Settled { arbiter, event, winner_set_at: now, bets }
+}
+
+#[test]
+fn test_correction_window_params_unpack() {
+    let ix = BetInstruction::Initialize(EventParams { correction_window_secs: 600, ..EventParams::new(42) });
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    // Older clients don't send it, nor the fields after it, and get the default.
+    let mut legacy = ix.pack();
+    legacy.truncate(legacy.len() - (8 + 32 + 1 + 1 + 8));
+    assert_eq!(BetInstruction::unpack(&legacy).unwrap(), BetInstruction::Initialize(EventParams::new(42)));
+    assert_eq!(EventParams::new(42).correction_window_secs, DEFAULT_CORRECTION_WINDOW_SECS);
+}
+
+#[tokio::test]
+async fn test_correct_within_the_window() {
+    let mut env = common::start().await;
+    let s = settled(&mut env, EventParams::new).await;
+    let lamports = env.lamports(&s.event).await;
+
+    env.set_now(s.winner_set_at + DEFAULT_CORRECTION_WINDOW_SECS - 1).await;
+    env.correct_result(&s.arbiter, &s.event, MatchOutcome::TeamB).await.unwrap();
+    let bets = EventBets::unpack(&env.data(&s.event).await).unwrap();
+    assert_eq!((bets.outcome, bets.winner_set_at), (u8::from(MatchOutcome::TeamB), s.winner_set_at));
+    assert_eq!(env.lamports(&s.event).await, lamports);
+    assert_eq!(env.lamports(&s.arbiter.pubkey()).await, 0);
+
+    // Payouts follow the corrected result, and the commission is charged once.
+    env.close_correction_window(&s.event).await;
+    let (betor, bet) = s.bets[1];
+    env.withdraw(&betor, &s.event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&betor).await, 1_940_000);
+    env.collect_commission(&s.arbiter, &s.event).await.unwrap();
+    assert_eq!(env.lamports(&s.arbiter.pubkey()).await, 60_000);
+}
+
+#[tokio::test]
+async fn test_correct_after_a_withdrawal_fails() {
+    let mut env = common::start().await;
+    let s = settled(&mut env, EventParams::new).await;
+
+    // Claims wait for the window to close, then even the losing side's withdrawal settles the result.
+    let (betor, bet) = s.bets[1];
+    assert_eq!(env.withdraw(&betor, &s.event, &bet).await.unwrap_err(), bet_error(0, BetError::ResultNotFinal));
+    env.close_correction_window(&s.event).await;
+    env.withdraw(&betor, &s.event, &bet).await.unwrap();
+    assert_eq!(
+        env.correct_result(&s.arbiter, &s.event, MatchOutcome::TeamB).await.unwrap_err(),
+        bet_error(0, BetError::ResultAlreadyClaimed)
+    );
+    let bets = EventBets::unpack(&env.data(&s.event).await).unwrap();
+    assert_eq!((bets.outcome, bets.claims_count), (u8::from(MatchOutcome::TeamA), 1));
+}
+
+#[tokio::test]
+async fn test_correct_after_the_window_fails() {
+    let mut env = common::start().await;
+    let s = settled(&mut env, EventParams::new).await;
+    env.set_now(s.winner_set_at + DEFAULT_CORRECTION_WINDOW_SECS).await;
+    assert!(env.correct_result(&s.arbiter, &s.event, MatchOutcome::TeamB).await.is_err());
+
+    // A window of 0 allows no correction at all.
+    let s = settled(&mut env, |now| EventParams { correction_window_secs: 0, ..EventParams::new(now) }).await;
+    assert!(env.correct_result(&s.arbiter, &s.event, MatchOutcome::TeamB).await.is_err());
+    assert!(env.correct_result(&s.arbiter, &s.event, MatchOutcome::Unknown).await.is_err());
+    let bets = EventBets::unpack(&env.data(&s.event).await).unwrap();
+    assert_eq!(bets.outcome, u8::from(MatchOutcome::TeamA));
+
+    let now = env.now().await;
+    let params = EventParams { correction_window_secs: -1, ..EventParams::new(now) };
+    assert!(env.try_create_event_with(&Keypair::new(), params).await.is_err());
+}
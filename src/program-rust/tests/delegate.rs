@@ -0,0 +1,97 @@
+mod common;
+
+use helloworld::{Bet, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+async fn set_delegate(env: &mut common::Env, betor: &Keypair, event: &Pubkey, bet: &Pubkey, delegate: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::SetDelegate { delegate: *delegate }.pack(),
+        vec![AccountMeta::new_readonly(betor.pubkey(), true), AccountMeta::new_readonly(*event, false), AccountMeta::new(*bet, false)],
+    );
+    env.send(&[ix], &[betor]).await
+}
+
+// Withdraw of `betor`'s bet, signed by `authority`.
+async fn withdraw_as(env: &mut common::Env, authority: &Keypair, betor: &Pubkey, event: &Pubkey, bet: &Pubkey) -> Result<(), TransactionError> {
+    let mut ix = env.withdraw_ix(betor, event, bet);
+    ix.accounts.push(AccountMeta::new_readonly(authority.pubkey(), true));
+    env.send(&[ix], &[authority]).await
+}
+
+// An event won by `betor`'s bet of 1_000_000 against another 1_000_000, with the bet delegated to `delegate`.
+async fn delegated_win(env: &mut common::Env, betor: &Keypair, delegate: &Pubkey) -> (Pubkey, Pubkey) {
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let bet = env.place_bet(&betor.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+    set_delegate(env, betor, &event, &bet, delegate).await.unwrap();
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    (event, bet)
+}
+
+#[test]
+fn test_set_delegate_unpack() {
+    let delegate = Pubkey::new_unique();
+    let ix = BetInstruction::SetDelegate { delegate };
+    assert_eq!(ix.pack(), [&[46][..], delegate.as_ref()].concat());
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+}
+
+#[tokio::test]
+async fn test_delegate_withdraws_to_the_betor() {
+    let mut env = common::start().await;
+    let (betor, delegate) = (Keypair::new(), Keypair::new());
+    let (event, bet) = delegated_win(&mut env, &betor, &delegate.pubkey()).await;
+    assert_eq!(Bet::unpack(&env.data(&bet).await).unwrap().delegate, delegate.pubkey());
+
+    withdraw_as(&mut env, &delegate, &betor.pubkey(), &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&betor.pubkey()).await, 1_940_000);
+    assert_eq!(env.lamports(&delegate.pubkey()).await, 0);
+    assert_eq!(Bet::unpack(&env.data(&bet).await).unwrap().outcome, u8::from(MatchOutcome::Withdrawn));
+}
+
+#[tokio::test]
+async fn test_third_key_cannot_withdraw_a_delegated_bet() {
+    let mut env = common::start().await;
+    let betor = Keypair::new();
+    let (event, bet) = delegated_win(&mut env, &betor, &Keypair::new().pubkey()).await;
+    let refused = TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature);
+
+    assert_eq!(withdraw_as(&mut env, &Keypair::new(), &betor.pubkey(), &event, &bet).await.unwrap_err(), refused);
+    // Nobody signing doesn't do either.
+    assert_eq!(
+        env.withdraw(&betor.pubkey(), &event, &bet).await.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+    );
+    assert_eq!(env.lamports(&betor.pubkey()).await, 0);
+
+    // The betor still can.
+    withdraw_as(&mut env, &betor, &betor.pubkey(), &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&betor.pubkey()).await, 1_940_000);
+}
+
+#[tokio::test]
+async fn test_cleared_delegate_withdraws_like_any_bet() {
+    let mut env = common::start().await;
+    let betor = Keypair::new();
+    let (event, bet) = delegated_win(&mut env, &betor, &Keypair::new().pubkey()).await;
+    // Only the betor sets or clears it.
+    assert_eq!(
+        set_delegate(&mut env, &Keypair::new(), &event, &bet, &Pubkey::default()).await.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+
+    set_delegate(&mut env, &betor, &event, &bet, &Pubkey::default()).await.unwrap();
+    assert_eq!(Bet::unpack(&env.data(&bet).await).unwrap().delegate, Pubkey::default());
+    env.withdraw(&betor.pubkey(), &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&betor.pubkey()).await, 1_940_000);
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{denylist_address, BetError, EventBets, Instruction as BetInstruction, MatchOutcome, WALLET_LIST_CAPACITY};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+async fn add_to_denylist(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, wallet: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::AddToDenylist { wallet: *wallet }.pack(),
+        vec![
+            AccountMeta::new(arbiter.pubkey(), true),
+            AccountMeta::new(*event, false),
+            AccountMeta::new(denylist_address(&env.program_id, event).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+async fn remove_from_denylist(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, wallet: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::RemoveFromDenylist { wallet: *wallet }.pack(),
+        vec![
+            AccountMeta::new_readonly(arbiter.pubkey(), true),
+            AccountMeta::new_readonly(*event, false),
+            AccountMeta::new(denylist_address(&env.program_id, event).0, false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+async fn bet(env: &mut common::Env, betor: &Pubkey, event: &Pubkey, choice: MatchOutcome) -> Result<Pubkey, TransactionError> {
+    let denylist = vec![AccountMeta::new_readonly(denylist_address(&env.program_id, event).0, false)];
+    env.try_place_bet_with_accounts(betor, event, choice, 1_000_000, denylist).await
+}
+
+async fn funded_event(env: &mut common::Env) -> (Keypair, Pubkey) {
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    (arbiter, event)
+}
+
+#[test]
+fn test_denylist_unpack() {
+    let wallet = Pubkey::new_unique();
+    let add = BetInstruction::AddToDenylist { wallet };
+    let remove = BetInstruction::RemoveFromDenylist { wallet };
+    assert_eq!(BetInstruction::unpack(&add.pack()).unwrap(), add);
+    assert_eq!(BetInstruction::unpack(&remove.pack()).unwrap(), remove);
+    assert_eq!((add.pack()[0], remove.pack()[0]), (52, 53));
+}
+
+#[tokio::test]
+async fn test_add_to_denylist() {
+    let mut env = common::start().await;
+    let (arbiter, event) = funded_event(&mut env).await;
+    let mallory = Keypair::new().pubkey();
+    add_to_denylist(&mut env, &arbiter, &event, &mallory).await.unwrap();
+    // Adding twice is fine.
+    add_to_denylist(&mut env, &arbiter, &event, &mallory).await.unwrap();
+    assert!(EventBets::unpack(&env.data(&event).await).unwrap().denylisted);
+
+    assert_eq!(bet(&mut env, &mallory, &event, MatchOutcome::TeamA).await.unwrap_err(), bet_error(0, BetError::BettorDenied));
+    // Leaving the denylist out doesn't get around it.
+    assert!(env.try_place_bet(&mallory, &event, MatchOutcome::TeamA, 1_000_000).await.is_err());
+    // The event is not private, everyone else bets as usual.
+    bet(&mut env, &Keypair::new().pubkey(), &event, MatchOutcome::TeamA).await.unwrap();
+
+    let stranger = Keypair::new();
+    env.fund(&stranger.pubkey(), 1_000_000_000).await;
+    assert!(add_to_denylist(&mut env, &stranger, &event, &Keypair::new().pubkey()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_remove_from_denylist() {
+    let mut env = common::start().await;
+    let (arbiter, event) = funded_event(&mut env).await;
+    let mallory = Keypair::new().pubkey();
+    let alice = Keypair::new().pubkey();
+    add_to_denylist(&mut env, &arbiter, &event, &mallory).await.unwrap();
+    add_to_denylist(&mut env, &arbiter, &event, &alice).await.unwrap();
+
+    remove_from_denylist(&mut env, &arbiter, &event, &mallory).await.unwrap();
+    bet(&mut env, &mallory, &event, MatchOutcome::TeamA).await.unwrap();
+    // Alice took Mallory's slot and is still denied.
+    assert_eq!(bet(&mut env, &alice, &event, MatchOutcome::TeamA).await.unwrap_err(), bet_error(0, BetError::BettorDenied));
+    // A wallet that is not on it changes nothing.
+    remove_from_denylist(&mut env, &arbiter, &event, &mallory).await.unwrap();
+    assert!(remove_from_denylist(&mut env, &Keypair::new(), &event, &alice).await.is_err());
+}
+
+#[tokio::test]
+async fn test_denied_wallet_still_withdraws() {
+    let mut env = common::start().await;
+    let (arbiter, event) = funded_event(&mut env).await;
+    let mallory = Keypair::new();
+    let winning_bet = env.place_bet(&mallory.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    add_to_denylist(&mut env, &arbiter, &event, &mallory.pubkey()).await.unwrap();
+    assert_eq!(bet(&mut env, &mallory.pubkey(), &event, MatchOutcome::TeamA).await.unwrap_err(), bet_error(0, BetError::BettorDenied));
+    // Nor can the bet already placed grow.
+    let denylist = vec![AccountMeta::new_readonly(denylist_address(&env.program_id, &event).0, false)];
+    assert_eq!(
+        env.try_increase_bet(&mallory, &event, &winning_bet, MatchOutcome::TeamA, 1_000_000, denylist).await.unwrap_err(),
+        bet_error(1, BetError::BettorDenied)
+    );
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&mallory.pubkey(), &event, &winning_bet).await.unwrap();
+    assert_eq!(env.lamports(&mallory.pubkey()).await, 1_940_000);
+}
+
+#[tokio::test]
+async fn test_denylist_is_bounded() {
+    let mut env = common::start().await;
+    let (arbiter, event) = funded_event(&mut env).await;
+    for _ in 0..WALLET_LIST_CAPACITY {
+        add_to_denylist(&mut env, &arbiter, &event, &Pubkey::new_unique()).await.unwrap();
+    }
+    assert_eq!(
+        add_to_denylist(&mut env, &arbiter, &event, &Pubkey::new_unique()).await.unwrap_err(),
+        bet_error(0, BetError::DenylistFull)
+    );
+}
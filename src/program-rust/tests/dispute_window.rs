@@ -0,0 +1,87 @@
+mod common;
+
+use helloworld::{EventBets, EventParams, MatchOutcome};
+use solana_sdk::{
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn test_corrected_result_is_used_for_payouts() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env
+        .create_event_with(&arbiter, EventParams { dispute_window_secs: 100, correction_window_secs: 0, ..EventParams::new(now) })
+        .await;
+
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamB, 3_000_000).await;
+
+    // correct -> incorrect -> corrected
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+
+    env.set_now(now + 10).await;
+    env.correct_result(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+    env.set_now(now + 20).await;
+    env.correct_result(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    // SetWinner can not be used to change the result.
+    assert!(env.set_winner(&arbiter, &event, MatchOutcome::TeamB).await.is_err());
+
+    // Withdrawals and the commission are blocked while the result can still change.
+    assert!(env.withdraw(&alice, &event, &alice_bet).await.is_err());
+    assert!(env.collect_commission(&arbiter, &event).await.is_err());
+
+    env.set_now(now + 100).await;
+    assert!(env.correct_result(&arbiter, &event, MatchOutcome::TeamB).await.is_err());
+
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+    // (3_000_000 * 1_000_000 / 1_000_000 + 1_000_000) * 97 / 100
+    assert_eq!(env.lamports(&alice).await, 3_880_000);
+    assert_eq!(env.lamports(&bob).await, 0);
+    env.collect_commission(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await - arbiter_before, 4_000_000 * 3 / 100);
+}
+
+#[tokio::test]
+async fn test_correction_requires_a_result_and_the_arbiter() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env
+        .create_event_with(&arbiter, EventParams { dispute_window_secs: 100, ..EventParams::new(now) })
+        .await;
+
+    assert!(env.correct_result(&arbiter, &event, MatchOutcome::TeamA).await.is_err());
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    assert!(env.correct_result(&Keypair::new(), &event, MatchOutcome::TeamB).await.is_err());
+    assert!(env.correct_result(&arbiter, &event, MatchOutcome::Unknown).await.is_err());
+}
+
+#[tokio::test]
+async fn test_winner_set_at_is_the_clock_of_the_first_result() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env
+        .create_event_with(&arbiter, EventParams { dispute_window_secs: 100, ..EventParams::new(now) })
+        .await;
+    assert_eq!(EventBets::unpack(&env.data(&event).await).unwrap().winner_set_at, 0);
+
+    env.set_now(now + 3 * 3600).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    let settled_at = env.clock().await.unix_timestamp;
+    assert_eq!(settled_at, now + 3 * 3600);
+    assert_eq!(EventBets::unpack(&env.data(&event).await).unwrap().winner_set_at, settled_at);
+
+    // A correction keeps the original time.
+    env.set_now(settled_at + 10).await;
+    env.correct_result(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+    let bets = EventBets::unpack(&env.data(&event).await).unwrap();
+    assert_eq!((bets.outcome, bets.winner_set_at), (u8::from(MatchOutcome::TeamB), settled_at));
+}
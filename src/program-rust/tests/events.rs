@@ -0,0 +1,53 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use helloworld::events::{BetPlaced, BetWithdrawn, EventInitialized, ProgramEvent, ResultFinalized, WinnerSet};
+use solana_sdk::pubkey::Pubkey;
+
+// The runtime logs sol_log_data as "Program data: " followed by each field in base64.
+// solana-program-test 1.8 has no transaction metadata to read these back from, so the tests
+// decode lines in that exact format.
+fn log_line(event: &ProgramEvent) -> String {
+    format!("Program data: {}", base64::encode(event.try_to_vec().unwrap()))
+}
+
+fn decode(line: &str) -> Option<ProgramEvent> {
+    let data = base64::decode(line.strip_prefix("Program data: ")?).ok()?;
+    ProgramEvent::try_from_slice(&data).ok()
+}
+
+fn all_events() -> Vec<ProgramEvent> {
+    let event = Pubkey::new_unique();
+    let bet = Pubkey::new_unique();
+    vec![
+        ProgramEvent::EventInitialized(EventInitialized { event, arbiter: Pubkey::new_unique(), deadline: 1_700_000_000 }),
+        ProgramEvent::BetPlaced(BetPlaced { event, bet, betor: Pubkey::new_unique(), outcome: 1, amount: 1_000_000 }),
+        ProgramEvent::WinnerSet(WinnerSet { event, outcome: 1, commission: 0 }),
+        ProgramEvent::BetWithdrawn(BetWithdrawn { event, bet, amount: 1_940_000 }),
+        ProgramEvent::ResultFinalized(ResultFinalized { event, outcome: 1 }),
+    ]
+}
+
+#[test]
+fn test_events_decode_from_logs() {
+    let logs: Vec<String> = all_events().iter().map(log_line).collect();
+    let decoded: Vec<ProgramEvent> = logs.iter().filter_map(|line| decode(line)).collect();
+    assert_eq!(decoded, all_events());
+    // Plain msg! lines are skipped.
+    assert_eq!(decode("Program log: Sending 10 lamports"), None);
+}
+
+#[test]
+fn test_event_layout() {
+    for (discriminant, event) in all_events().iter().enumerate() {
+        let data = event.try_to_vec().unwrap();
+        assert_eq!(data[0] as usize, discriminant);
+        let len = match event {
+            ProgramEvent::EventInitialized(_) => 32 + 32 + 8,
+            ProgramEvent::BetPlaced(_) => 32 + 32 + 32 + 1 + 8,
+            ProgramEvent::WinnerSet(_) => 32 + 1 + 8,
+            ProgramEvent::BetWithdrawn(_) => 32 + 32 + 8,
+            ProgramEvent::ResultFinalized(_) => 32 + 1,
+        };
+        assert_eq!(data.len(), 1 + len);
+    }
+    assert!(ProgramEvent::try_from_slice(&[5; 42]).is_err());
+}
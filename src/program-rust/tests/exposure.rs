@@ -0,0 +1,94 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::bet_error;
+use helloworld::{BetError, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome, Odds};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+async fn set_odds(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, numerator_a: u32, numerator_b: u32) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::SetOdds {
+            odds_a: Odds { numerator: numerator_a, denominator: 1 },
+            odds_b: Odds { numerator: numerator_b, denominator: 1 },
+        }
+        .pack(),
+        vec![AccountMeta::new_readonly(arbiter.pubkey(), true), AccountMeta::new(*event, false)],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+async fn void_bet(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, bet: &Pubkey, betor: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::VoidBet { reason: 0 }.pack(),
+        vec![
+            AccountMeta::new_readonly(arbiter.pubkey(), true),
+            AccountMeta::new(*event, false),
+            AccountMeta::new(*bet, false),
+            AccountMeta::new(*betor, false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+async fn fixed_odds_event(env: &mut common::Env, arbiter: &Keypair, top_up: u64) -> Pubkey {
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event_with(arbiter, EventParams { fixed_odds: true, ..EventParams::new(now) }).await;
+    env.top_up_pot(arbiter, &event, top_up).await.unwrap();
+    event
+}
+
+#[tokio::test]
+async fn test_bets_up_to_the_cap() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let event = fixed_odds_event(&mut env, &arbiter, 2_000_000).await;
+    set_odds(&mut env, &arbiter, &event, 3, 2).await.unwrap();
+
+    let betor = Keypair::new().pubkey();
+    // Each bet adds 500_000 to the funds and 1_500_000 to what TeamA owes.
+    env.place_bet(&betor, &event, MatchOutcome::TeamA, 500_000).await;
+    env.place_bet(&betor, &event, MatchOutcome::TeamA, 500_000).await;
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!(bets.worst_case_liability, 3_000_000);
+    assert_eq!(bets.balance_a + bets.bonus_lamports, 3_000_000);
+
+    assert_eq!(
+        env.try_place_bet(&betor, &event, MatchOutcome::TeamA, 1).await.unwrap_err(),
+        bet_error(0, BetError::ExposureExceeded)
+    );
+    // The other side still has room.
+    env.place_bet(&betor, &event, MatchOutcome::TeamB, 1_000_000).await;
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.liability_a, bets.liability_b, bets.worst_case_liability), (3_000_000, 2_000_000, 3_000_000));
+}
+
+#[tokio::test]
+async fn test_odds_wait_for_an_uncovered_liability_to_be_topped_up() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let event = fixed_odds_event(&mut env, &arbiter, 2_000_000).await;
+    set_odds(&mut env, &arbiter, &event, 3, 1).await.unwrap();
+
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamB, 1_000_000).await;
+    // Bob's stake makes room for 500_000 more on TeamA.
+    env.place_bet(&alice, &event, MatchOutcome::TeamA, 500_000).await;
+    void_bet(&mut env, &arbiter, &event, &bob_bet, &bob).await.unwrap();
+    // TeamA still owes 4_500_000 but only 3_500_000 are left.
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.liability_b, bets.worst_case_liability), (0, 4_500_000));
+
+    assert_eq!(set_odds(&mut env, &arbiter, &event, 2, 1).await.unwrap_err(), bet_error(0, BetError::ExposureExceeded));
+    env.top_up_pot(&arbiter, &event, 1_000_000).await.unwrap();
+    set_odds(&mut env, &arbiter, &event, 2, 1).await.unwrap();
+}
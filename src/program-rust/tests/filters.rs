@@ -0,0 +1,59 @@
+use helloworld::{Bet, EventBets, STATE_VERSION};
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+
+fn packed<T: Pack>(state: &T) -> Vec<u8> {
+    let mut data = vec![0; T::LEN];
+    state.pack_into_slice(&mut data);
+    data
+}
+
+#[test]
+fn test_event_offsets_point_at_the_fields() {
+    let mut event = EventBets::unpack_from_slice(&[0; EventBets::LEN]).unwrap();
+    event.version = STATE_VERSION;
+    event.arbiter = Pubkey::new_unique();
+    event.outcome = 3;
+    event.tournament = Pubkey::new_unique();
+    // Neighbours of the fields, so an offset off by some bytes reads something else.
+    event.bets_allowed_until_ts = -1;
+    event.balance_a = u64::MAX;
+    let data = packed(&event);
+    assert_eq!(&data[EventBets::ARBITER_OFFSET..][..32], event.arbiter.as_ref());
+    assert_eq!(data[EventBets::OUTCOME_OFFSET], 3);
+    assert_eq!(&data[EventBets::TOURNAMENT_OFFSET..][..32], event.tournament.as_ref());
+}
+
+#[test]
+fn test_bet_offsets_point_at_the_fields() {
+    let mut bet = Bet::unpack_from_slice(&[0; Bet::LEN]).unwrap();
+    bet.version = STATE_VERSION;
+    bet.betor = Pubkey::new_unique();
+    bet.event = Pubkey::new_unique();
+    bet.amount = u64::MAX;
+    bet.outcome = 2;
+    bet.referrer = Pubkey::new_unique();
+    let data = packed(&bet);
+    assert_eq!(&data[Bet::BETOR_OFFSET..][..32], bet.betor.as_ref());
+    assert_eq!(&data[Bet::EVENT_OFFSET..][..32], bet.event.as_ref());
+    assert_eq!(data[Bet::OUTCOME_OFFSET], 2);
+}
+
+#[cfg(feature = "rpc-filters")]
+#[test]
+fn test_filters() {
+    use helloworld::client;
+    use solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
+
+    let key = Pubkey::new_unique();
+    let memcmp = |offset: usize| RpcFilterType::Memcmp(Memcmp { offset, bytes: MemcmpEncodedBytes::Binary(key.to_string()), encoding: None });
+    assert_eq!(client::events_by_arbiter(&key), [RpcFilterType::DataSize(EventBets::LEN as u64), memcmp(EventBets::ARBITER_OFFSET)]);
+    assert_eq!(client::bets_by_betor(&key), [RpcFilterType::DataSize(Bet::LEN as u64), memcmp(Bet::BETOR_OFFSET)]);
+    assert_eq!(client::bets_for_event(&key), [RpcFilterType::DataSize(Bet::LEN as u64), memcmp(Bet::EVENT_OFFSET)]);
+    // A filter matches the account bytes.
+    let mut bet = Bet::unpack_from_slice(&[0; Bet::LEN]).unwrap();
+    bet.event = key;
+    match &client::bets_for_event(&key)[1] {
+        RpcFilterType::Memcmp(memcmp) => assert!(memcmp.bytes_match(&packed(&bet))),
+        filter => panic!("{:?}", filter),
+    }
+}
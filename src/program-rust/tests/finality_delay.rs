@@ -0,0 +1,77 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{BetError, EventParams, MatchOutcome};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_withdraw_waits_for_finality_delay() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env
+        .create_event_with(&arbiter, EventParams { finality_delay_secs: 60, correction_window_secs: 0, ..EventParams::new(now) })
+        .await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+
+    // Inside the delay.
+    env.set_now(now + 59).await;
+    assert_eq!(env.withdraw(&alice, &event, &alice_bet).await.unwrap_err(), bet_error(0, BetError::ResultNotFinal));
+    assert_eq!(env.lamports(&alice).await, 0);
+
+    // Exactly at the boundary the result is final.
+    env.set_now(now + 60).await;
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 970_000);
+}
+
+#[tokio::test]
+async fn test_withdraw_after_finality_delay() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env
+        .create_event_with(&arbiter, EventParams { finality_delay_secs: 60, ..EventParams::new(now) })
+        .await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamB, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+
+    env.set_now(now + 3600).await;
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 970_000);
+}
+
+#[tokio::test]
+async fn test_zero_delay_allows_immediate_withdraw() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { correction_window_secs: 0, ..EventParams::new(now) }).await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 970_000);
+}
+
+#[tokio::test]
+async fn test_endless_windows_never_open_claims() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env
+        .create_event_with(&arbiter, EventParams { finality_delay_secs: i64::MAX, correction_window_secs: i64::MAX, ..EventParams::new(now) })
+        .await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+
+    // The end of the windows saturates instead of wrapping into the past.
+    env.set_now(now + 1_000_000).await;
+    assert_eq!(env.withdraw(&alice, &event, &alice_bet).await.unwrap_err(), bet_error(0, BetError::ResultNotFinal));
+    env.correct_result(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{BetError, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome, DEFAULT_CORRECTION_WINDOW_SECS};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+// Permissionless, the payer of the test environment sends it.
+async fn finalize(env: &mut common::Env, event: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(env.program_id, &BetInstruction::FinalizeResult.pack(), vec![AccountMeta::new(*event, false)]);
+    env.send(&[ix], &[]).await
+}
+
+async fn finalized(env: &mut common::Env, event: &Pubkey) -> bool {
+    EventBets::unpack(&env.data(event).await).unwrap().finalized
+}
+
+#[test]
+fn test_finalize_result_unpack() {
+    assert_eq!(BetInstruction::unpack(&[48]).unwrap(), BetInstruction::FinalizeResult);
+    assert_eq!(BetInstruction::FinalizeResult.pack(), vec![48]);
+}
+
+#[tokio::test]
+async fn test_finalize_after_the_correction_window() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { dispute_window_secs: 100, ..EventParams::new(now) }).await;
+    assert_eq!(finalize(&mut env, &event).await.unwrap_err(), bet_error(0, BetError::ResultNotFinal));
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    // Past the dispute window, but still within the longer correction window.
+    env.set_now(now + DEFAULT_CORRECTION_WINDOW_SECS - 1).await;
+    assert_eq!(finalize(&mut env, &event).await.unwrap_err(), bet_error(0, BetError::ResultNotFinal));
+    assert!(!finalized(&mut env, &event).await);
+
+    env.set_now(now + DEFAULT_CORRECTION_WINDOW_SECS).await;
+    finalize(&mut env, &event).await.unwrap();
+    assert!(finalized(&mut env, &event).await);
+    assert!(finalize(&mut env, &event).await.is_err());
+    assert!(env.correct_result(&arbiter, &event, MatchOutcome::TeamB).await.is_err());
+    let bets = EventBets::unpack(&env.data(&event).await).unwrap();
+    assert_eq!(bets.outcome, u8::from(MatchOutcome::TeamA));
+}
+
+#[tokio::test]
+async fn test_withdrawal_waits_for_the_correction_window() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+
+    // Inside the window a claim neither pays nor finalizes, the arbiter can still correct.
+    assert_eq!(env.withdraw(&alice, &event, &alice_bet).await.unwrap_err(), bet_error(0, BetError::ResultNotFinal));
+    env.set_now(now + DEFAULT_CORRECTION_WINDOW_SECS - 1).await;
+    assert_eq!(env.withdraw(&alice, &event, &alice_bet).await.unwrap_err(), bet_error(0, BetError::ResultNotFinal));
+    assert!(!finalized(&mut env, &event).await);
+    assert_eq!(env.lamports(&alice).await, 0);
+
+    // Past it the first claim finalizes the result if nobody sent FinalizeResult.
+    env.set_now(now + DEFAULT_CORRECTION_WINDOW_SECS).await;
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    assert!(finalized(&mut env, &event).await);
+    assert!(finalize(&mut env, &event).await.is_err());
+    assert!(env.correct_result(&arbiter, &event, MatchOutcome::TeamB).await.is_err());
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{calculate_payout, Bet, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome, Odds};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+fn odds(numerator: u32, denominator: u32) -> Odds {
+    Odds { numerator, denominator }
+}
+
+async fn set_odds(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, odds_a: Odds, odds_b: Odds) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::SetOdds { odds_a, odds_b }.pack(),
+        vec![AccountMeta::new_readonly(arbiter.pubkey(), true), AccountMeta::new(*event, false)],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+#[test]
+fn test_fixed_odds_unpack() {
+    for ix in &[
+        BetInstruction::SetOdds { odds_a: odds(5, 2), odds_b: Odds::default() },
+        BetInstruction::Initialize(EventParams { fixed_odds: true, ..EventParams::new(42) }),
+    ] {
+        assert_eq!(&BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    }
+}
+
+#[test]
+fn test_fixed_odds_payout() {
+    let mut event = EventBets::try_from_slice(&[0; EventBets::LEN]).unwrap();
+    event.fixed_odds = true;
+    // The pools don't matter.
+    event.balance_a = 100;
+    event.balance_b = 1;
+    let mut bet = Bet::try_from_slice(&[0; Bet::LEN]).unwrap();
+    bet.amount = 100;
+    bet.outcome = 1;
+    bet.locked_odds = odds(5, 2);
+    assert_eq!(calculate_payout(&event, &bet, 0), Ok(0));
+    event.outcome = 1;
+    assert_eq!(calculate_payout(&event, &bet, 0), Ok(250));
+    event.outcome = 2;
+    assert_eq!(calculate_payout(&event, &bet, 0), Ok(0));
+    event.outcome = 3;
+    assert_eq!(calculate_payout(&event, &bet, 0), Ok(100));
+}
+
+#[tokio::test]
+async fn test_bets_pay_their_locked_odds() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { fixed_odds: true, ..EventParams::new(now) }).await;
+
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let carol = Keypair::new().pubkey();
+    // No odds posted yet.
+    assert!(env.try_place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await.is_err());
+    assert!(set_odds(&mut env, &Keypair::new(), &event, odds(2, 1), odds(3, 2)).await.is_err());
+    assert!(set_odds(&mut env, &arbiter, &event, odds(1, 2), odds(3, 2)).await.is_err());
+    set_odds(&mut env, &arbiter, &event, odds(2, 1), odds(3, 2)).await.unwrap();
+    // 2_000_000 owed on a 1_000_000 stake, the house has not put anything in.
+    assert!(env.try_place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await.is_err());
+
+    env.top_up_pot(&arbiter, &event, 3_000_000).await.unwrap();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    set_odds(&mut env, &arbiter, &event, odds(3, 1), odds(3, 2)).await.unwrap();
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let carol_bet = env.place_bet(&carol, &event, MatchOutcome::TeamB, 1_000_000).await;
+    // TeamA would owe 8_000_000 out of 7_000_000.
+    assert!(env.try_place_bet(&carol, &event, MatchOutcome::TeamA, 1_000_000).await.is_err());
+
+    let bet = Bet::deserialize(&mut &env.data(&alice_bet).await[..]).unwrap();
+    assert_eq!(bet.locked_odds, odds(2, 1));
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.liability_a, bets.liability_b, bets.commission_bps), (5_000_000, 1_500_000, 0));
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+    env.withdraw(&carol, &event, &carol_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 2_000_000);
+    assert_eq!(env.lamports(&bob).await, 3_000_000);
+    assert_eq!(env.lamports(&carol).await, 0);
+}
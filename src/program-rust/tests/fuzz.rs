@@ -0,0 +1,82 @@
+// A bounded run of the fuzz targets in fuzz/, mutating the seeds with a fixed xorshift so that every
+// `cargo test --features test-utils` covers a few thousand malformed inputs without the fuzz toolchain.
+#![cfg(feature = "test-utils")]
+
+use helloworld::{
+    test_utils::{every_instruction, fuzz_bet, fuzz_event, fuzz_instruction, new_bet, new_event_bets},
+    Bet, EventBets, MatchOutcome,
+};
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+
+const ITERATIONS: usize = 5_000;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+// Flips, truncates, extends or overwrites a seed.
+fn mutate(rng: &mut XorShift, seed: &[u8]) -> Vec<u8> {
+    let mut data = seed.to_vec();
+    for _ in 0..1 + rng.below(4) {
+        match rng.below(4) {
+            0 if !data.is_empty() => {
+                let at = rng.below(data.len());
+                data[at] ^= 1 << rng.below(8);
+            },
+            1 => data.truncate(rng.below(data.len() + 1)),
+            2 => data.extend((0..1 + rng.below(8)).map(|_| rng.next() as u8)),
+            _ if !data.is_empty() => {
+                let at = rng.below(data.len());
+                data[at] = rng.next() as u8;
+            },
+            _ => data.push(rng.next() as u8),
+        }
+    }
+    data
+}
+
+fn run(seeds: &[Vec<u8>], target: fn(&[u8])) {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+    for seed in seeds {
+        target(seed);
+    }
+    for _ in 0..ITERATIONS {
+        let seed = &seeds[rng.below(seeds.len())];
+        target(&mutate(&mut rng, seed));
+    }
+}
+
+#[test]
+fn test_instruction_unpack_never_panics() {
+    let mut seeds: Vec<Vec<u8>> = every_instruction().iter().map(|instruction| instruction.pack()).collect();
+    seeds.push(vec![]);
+    seeds.extend((54..=u8::MAX).map(|tag| vec![tag]));
+    run(&seeds, fuzz_instruction);
+}
+
+#[test]
+fn test_event_decoding_never_panics() {
+    let mut packed = vec![0; EventBets::LEN];
+    EventBets::pack(new_event_bets(&Pubkey::new_unique(), 1_700_000_000), &mut packed).unwrap();
+    let seeds = vec![packed, include_bytes!("fixtures/event_bets.bin").to_vec(), vec![0; EventBets::LEN]];
+    run(&seeds, fuzz_event);
+}
+
+#[test]
+fn test_bet_decoding_never_panics() {
+    let mut packed = vec![0; Bet::LEN];
+    Bet::pack(new_bet(&Pubkey::new_unique(), &Pubkey::new_unique(), 1_000_000, MatchOutcome::TeamA), &mut packed).unwrap();
+    let seeds = vec![packed, include_bytes!("fixtures/bet.bin").to_vec(), vec![0; Bet::LEN]];
+    run(&seeds, fuzz_bet);
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "test-admin")]
+
+mod common;
+
+use borsh::BorshDeserialize;
+use common::bet_error;
+use helloworld::{BetError, Config, ConfigParams, EventParams, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const PARAMS: ConfigParams =
+    ConfigParams { default_commission_bps: 300, max_commission_bps: 1_000, min_deadline_offset_secs: 0, arbiter_share_bps: 10_000, referral_bps: 0 };
+
+async fn set_halted(env: &mut common::Env, admin: &Keypair, halted: bool) -> Result<(), TransactionError> {
+    let instruction = if halted { BetInstruction::HaltProgram } else { BetInstruction::ResumeProgram };
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &instruction.pack(),
+        vec![AccountMeta::new_readonly(admin.pubkey(), true), AccountMeta::new(env.config(), false)],
+    );
+    env.send(&[ix], &[admin]).await
+}
+
+#[test]
+fn test_halt_unpack() {
+    for ix in &[BetInstruction::HaltProgram, BetInstruction::ResumeProgram] {
+        assert_eq!(&BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    }
+}
+
+#[tokio::test]
+async fn test_halted_program_refuses_new_events_and_bets() {
+    let mut env = common::start().await;
+    let admin = common::test_admin();
+    env.fund(&admin.pubkey(), 1_000_000_000).await;
+    env.initialize_config(&admin, PARAMS).await.unwrap();
+    let config = vec![AccountMeta::new_readonly(env.config(), false)];
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.try_create_event_with_accounts(&arbiter, EventParams::new(now), config.clone()).await.unwrap();
+    let alice = Keypair::new();
+    let bet = env.try_place_bet_with_accounts(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000, config.clone()).await.unwrap();
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    // Only the admin.
+    let stranger = Keypair::new();
+    assert!(set_halted(&mut env, &stranger, true).await.is_err());
+    set_halted(&mut env, &admin, true).await.unwrap();
+    assert!(Config::deserialize(&mut &env.data(&env.config()).await[..]).unwrap().halted);
+
+    assert_eq!(
+        env.try_create_event_with_accounts(&arbiter, EventParams::new(now), config.clone()).await.unwrap_err(),
+        bet_error(0, BetError::ProgramHalted)
+    );
+    assert_eq!(
+        env.try_place_bet_with_accounts(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 1_000_000, config.clone()).await.unwrap_err(),
+        bet_error(0, BetError::ProgramHalted)
+    );
+    assert_eq!(
+        env.try_increase_bet(&alice, &event, &bet, MatchOutcome::TeamA, 1_000_000, config.clone()).await.unwrap_err(),
+        bet_error(1, BetError::ProgramHalted)
+    );
+
+    // Funds already in keep moving.
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice.pubkey(), &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&alice.pubkey()).await, 1_940_000);
+
+    set_halted(&mut env, &admin, false).await.unwrap();
+    let now = env.now().await;
+    env.try_create_event_with_accounts(&arbiter, EventParams::new(now), config).await.unwrap();
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{EventBets, EventParams, Instruction as BetInstruction, MatchOutcome, MARKET_KIND_HANDICAP, MARKET_KIND_MONEYLINE, MAX_HANDICAP};
+use solana_sdk::signature::{Keypair, Signer};
+
+fn handicap(now: i64, line: i16) -> EventParams {
+    EventParams { market_kind: MARKET_KIND_HANDICAP, handicap: line, ..EventParams::new(now) }
+}
+
+#[test]
+fn test_handicap_params_unpack() {
+    let ix = BetInstruction::Initialize(handicap(42, -3));
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    // Older clients stop before the new fields, the line and everything after it.
+    let mut data = BetInstruction::Initialize(EventParams::new(42)).pack();
+    data.truncate(data.len() - (3 + 8 + 32 + 1 + 1 + 8));
+    assert_eq!(BetInstruction::unpack(&data).unwrap(), BetInstruction::Initialize(EventParams::new(42)));
+    // Half of the line is missing.
+    data.extend_from_slice(&[MARKET_KIND_HANDICAP, 0xfd]);
+    assert!(BetInstruction::unpack(&data).is_err());
+}
+
+#[tokio::test]
+async fn test_line_is_stored_and_quoted() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, handicap(now, -3)).await;
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.market_kind, bets.handicap), (MARKET_KIND_HANDICAP, -3));
+    let quote = bets.quote_odds();
+    assert_eq!((quote.market_kind, quote.handicap), (MARKET_KIND_HANDICAP, -3));
+
+    // Same two outcomes, TeamA covering wins.
+    let betor = Keypair::new().pubkey();
+    let bet = env.place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&betor, &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&betor).await, 1_940_000);
+}
+
+#[tokio::test]
+async fn test_bad_lines_are_rejected() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    for params in &[
+        handicap(now, MAX_HANDICAP + 1),
+        handicap(now, -MAX_HANDICAP - 1),
+        handicap(now, i16::MIN),
+        EventParams { handicap: 3, ..EventParams::new(now) },
+        EventParams { market_kind: 2, ..EventParams::new(now) },
+    ] {
+        assert!(env.try_create_event_with(&arbiter, *params).await.is_err(), "{:?}", params);
+    }
+    env.create_event_with(&arbiter, handicap(now, MAX_HANDICAP)).await;
+    env.create_event_with(&arbiter, handicap(now, -MAX_HANDICAP)).await;
+    let moneyline = env.create_event(&arbiter, now).await;
+    let bets = EventBets::deserialize(&mut &env.data(&moneyline).await[..]).unwrap();
+    assert_eq!((bets.market_kind, bets.handicap), (MARKET_KIND_MONEYLINE, 0));
+}
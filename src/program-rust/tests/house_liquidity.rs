@@ -0,0 +1,68 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{EventBets, MatchOutcome};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+async fn seeded_event(env: &mut common::Env, arbiter: &Keypair) -> (Pubkey, Pubkey, Pubkey) {
+    let now = env.now().await;
+    let event = env.create_event(arbiter, now).await;
+    let (house_a, house_b) = env.seed_liquidity(arbiter, &event, 1_000_000, 3_000_000).await.unwrap();
+    (event, house_a, house_b)
+}
+
+#[tokio::test]
+async fn test_seed_sets_odds() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let (event, _, _) = seeded_event(&mut env, &arbiter).await;
+
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.balance_a, bets.balance_b), (1_000_000, 3_000_000));
+
+    // Only the arbiter may seed.
+    assert!(env.seed_liquidity(&Keypair::new(), &event, 1_000_000, 1_000_000).await.is_err());
+}
+
+#[tokio::test]
+async fn test_house_loses_seed_on_losing_side() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let (event, house_a, house_b) = seeded_event(&mut env, &arbiter).await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    env.withdraw(&arbiter.pubkey(), &event, &house_b).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, arbiter_before);
+
+    // The TeamB seed is shared by the TeamA pool: (3_000_000 * 1_000_000 / 2_000_000 + 1_000_000) * 97 / 100
+    env.withdraw(&arbiter.pubkey(), &event, &house_a).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await - arbiter_before, 2_425_000);
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 2_425_000);
+}
+
+#[tokio::test]
+async fn test_house_recovers_pro_rata_on_winning_side() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let (event, _, house_b) = seeded_event(&mut env, &arbiter).await;
+    let bob = Keypair::new().pubkey();
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+    env.close_correction_window(&event).await;
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    // (1_000_000 * 3_000_000 / 4_000_000 + 3_000_000) * 97 / 100
+    env.withdraw(&arbiter.pubkey(), &event, &house_b).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await - arbiter_before, 3_637_500);
+    // (1_000_000 * 1_000_000 / 4_000_000 + 1_000_000) * 97 / 100
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+    assert_eq!(env.lamports(&bob).await, 1_212_500);
+}
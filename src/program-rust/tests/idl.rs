@@ -0,0 +1,61 @@
+// Run with `cargo test --features idl`.
+#![cfg(feature = "idl")]
+
+use helloworld::{idl, Bet, EventBets, Instruction as BetInstruction};
+use serde_json::Value;
+
+#[test]
+fn test_idl_round_trips_through_json() {
+    let json = idl::to_json_string();
+    let parsed: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, idl::generate());
+    assert_eq!(serde_json::to_string_pretty(&parsed).unwrap(), json);
+}
+
+#[test]
+fn test_idl_tags_are_the_unpack_tags() {
+    let idl = idl::generate();
+    let instructions = idl["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), 43);
+    for instruction in instructions {
+        let name = instruction["name"].as_str().unwrap();
+        let tag = instruction["tag"].as_u64().unwrap() as u8;
+        // Zeroed args of the declared sizes, an empty short sequence for the others.
+        let mut data = vec![tag];
+        for arg in instruction["args"].as_array().unwrap() {
+            let size = arg["size"].as_u64().map_or(1, |size| size as usize);
+            data.resize(data.len() + size, 0);
+        }
+        let unpacked = BetInstruction::unpack(&data).unwrap_or_else(|err| panic!("{} with tag {}: {:?}", name, tag, err));
+        let debug = format!("{:?}", unpacked);
+        assert_eq!(debug.split(|c: char| !c.is_alphanumeric()).next(), Some(name));
+        assert_eq!(unpacked.pack().len(), data.len(), "{}", name);
+    }
+}
+
+#[test]
+fn test_idl_accounts_and_instruction_accounts() {
+    let idl = idl::generate();
+    let accounts = idl["accounts"].as_array().unwrap();
+    assert_eq!((accounts[0]["name"].as_str(), accounts[0]["size"].as_u64()), (Some("EventBets"), Some(EventBets::LEN as u64)));
+    assert_eq!((accounts[1]["name"].as_str(), accounts[1]["size"].as_u64()), (Some("Bet"), Some(Bet::LEN as u64)));
+    let tournament = accounts[0]["type"]["fields"].as_array().unwrap().iter().find(|field| field["name"] == "tournament").unwrap();
+    assert_eq!(tournament["offset"].as_u64(), Some(EventBets::TOURNAMENT_OFFSET as u64));
+    assert_eq!(tournament["type"], "publicKey");
+
+    let add_bet = &idl["instructions"][1];
+    assert_eq!(add_bet["name"], "AddBet");
+    let add_bet_accounts = add_bet["accounts"].as_array().unwrap();
+    assert_eq!(add_bet_accounts[0]["name"], "betor");
+    assert_eq!((add_bet_accounts[1]["isMut"].as_bool(), add_bet_accounts[1]["optional"].as_bool()), (Some(true), Some(false)));
+    // The SPL token accounts are conditional.
+    assert!(add_bet_accounts[3]["condition"].as_str().unwrap().starts_with("Only for SPL token events"));
+    let migrate = &idl["instructions"][42];
+    assert_eq!(migrate["name"], "MigrateEvent");
+    assert_eq!((migrate["accounts"][0]["isSigner"].as_bool(), migrate["accounts"][0]["isMut"].as_bool()), (Some(true), Some(false)));
+
+    let types: Vec<_> = idl["types"].as_array().unwrap().iter().map(|t| t["name"].as_str().unwrap()).collect();
+    for name in &["ConfigParams", "EventParams", "MatchOutcome", "Odds"] {
+        assert!(types.contains(name), "{} in {:?}", name, types);
+    }
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::bet_error;
+use helloworld::{Bet, BetError, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::TransactionError,
+};
+
+fn increase_ix(env: &common::Env, betor: &Keypair, event: &Pubkey, bet: &Pubkey, amount: u64) -> Instruction {
+    Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::IncreaseBet { choice: MatchOutcome::TeamA, amount }.pack(),
+        vec![AccountMeta::new(betor.pubkey(), true), AccountMeta::new(*event, false), AccountMeta::new(*bet, false)],
+    )
+}
+
+#[test]
+fn test_increase_bet_unpack() {
+    let ix = BetInstruction::IncreaseBet { choice: MatchOutcome::TeamB, amount: 7 };
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    assert_eq!(BetInstruction::unpack(&[16, 1]).unwrap(), BetInstruction::IncreaseBet { choice: MatchOutcome::TeamA, amount: 0 });
+}
+
+#[tokio::test]
+async fn test_top_ups_add_up() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let alice = Keypair::new();
+    let bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 2_000_000).await;
+    let rent = env.rent(Bet::LEN).await;
+
+    env.try_increase_bet(&alice, &event, &bet, MatchOutcome::TeamA, 250_000, vec![]).await.unwrap();
+    env.try_increase_bet(&alice, &event, &bet, MatchOutcome::TeamA, 750_000, vec![]).await.unwrap();
+
+    assert_eq!(Bet::deserialize(&mut &env.data(&bet).await[..]).unwrap().amount, 2_000_000);
+    assert_eq!(env.lamports(&bet).await, rent);
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.balance_a, bets.balance_b), (2_000_000, 2_000_000));
+    // Still one bet on each side.
+    assert_eq!((bets.bets_count_a, bets.bets_count_b), (1, 1));
+
+    env.set_now(now + 100).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice.pubkey(), &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&alice.pubkey()).await, 4_000_000 * 97 / 100);
+}
+
+#[tokio::test]
+async fn test_top_up_stakes_the_declared_amount() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let alice = Keypair::new();
+    let bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    let rent = env.rent(Bet::LEN).await;
+    let more_than_funded = increase_ix(&env, &alice, &event, &bet, 600_001);
+    let declared = increase_ix(&env, &alice, &event, &bet, 400_000);
+    let fund = system_instruction::transfer(&env.payer().pubkey(), &bet, 600_000);
+
+    assert_eq!(
+        env.send(&[fund.clone(), more_than_funded], &[&alice]).await.unwrap_err(),
+        TransactionError::InstructionError(1, InstructionError::InsufficientFunds)
+    );
+    // The surplus over the declared amount goes back to the betor, not into the pool.
+    env.send(&[fund, declared], &[&alice]).await.unwrap();
+    assert_eq!(Bet::deserialize(&mut &env.data(&bet).await[..]).unwrap().amount, 1_400_000);
+    assert_eq!(env.lamports(&bet).await, rent);
+    assert_eq!(env.lamports(&alice.pubkey()).await, 200_000);
+    assert_eq!(EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap().balance_a, 1_400_000);
+}
+
+#[tokio::test]
+async fn test_top_up_is_rejected() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let alice = Keypair::new();
+    let bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+
+    // On the other side.
+    assert_eq!(
+        env.try_increase_bet(&alice, &event, &bet, MatchOutcome::TeamB, 1_000, vec![]).await.unwrap_err(),
+        TransactionError::InstructionError(1, InstructionError::InvalidArgument)
+    );
+    // Without the betor's signature.
+    let mut unsigned = increase_ix(&env, &alice, &event, &bet, 1_000);
+    unsigned.accounts[0].is_signer = false;
+    let fund = system_instruction::transfer(&env.payer().pubkey(), &bet, 1_000);
+    assert_eq!(
+        env.send(&[fund, unsigned], &[]).await.unwrap_err(),
+        TransactionError::InstructionError(1, InstructionError::MissingRequiredSignature)
+    );
+    // For someone else.
+    assert!(env.try_increase_bet(&Keypair::new(), &event, &bet, MatchOutcome::TeamA, 1_000, vec![]).await.is_err());
+    // With nothing to add.
+    assert!(env.try_increase_bet(&alice, &event, &bet, MatchOutcome::TeamA, 0, vec![]).await.is_err());
+
+    // After the deadline.
+    env.set_now(now + 101).await;
+    assert!(env.try_increase_bet(&alice, &event, &bet, MatchOutcome::TeamA, 1_000, vec![]).await.is_err());
+    // After the result.
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.set_now(now).await;
+    assert!(env.try_increase_bet(&alice, &event, &bet, MatchOutcome::TeamA, 1_000, vec![]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_top_up_counts_against_the_stake_cap() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { max_stake_per_wallet: 3_000_000, ..EventParams::new(now) }).await;
+    let alice = Keypair::new();
+    let accounts = env.limit_accounts(&event, &alice.pubkey());
+    let bet = env.try_place_bet_with_accounts(&alice.pubkey(), &event, MatchOutcome::TeamA, 2_000_000, accounts.clone()).await.unwrap();
+
+    assert_eq!(
+        env.try_increase_bet(&alice, &event, &bet, MatchOutcome::TeamA, 1_000_001, accounts.clone()).await.unwrap_err(),
+        bet_error(1, BetError::StakeCapExceeded)
+    );
+    env.try_increase_bet(&alice, &event, &bet, MatchOutcome::TeamA, 1_000_000, accounts).await.unwrap();
+}
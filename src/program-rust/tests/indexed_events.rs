@@ -0,0 +1,71 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{arbiter_counter_address, event_address, ArbiterCounter, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome, STATE_VERSION};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::TransactionError,
+};
+
+async fn initialize_indexed(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, params: EventParams) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::InitializeIndexed(params).pack(),
+        vec![
+            AccountMeta::new(arbiter.pubkey(), true),
+            AccountMeta::new(arbiter_counter_address(&env.program_id, &arbiter.pubkey()).0, false),
+            AccountMeta::new(*event, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+#[test]
+fn test_initialize_indexed_unpack() {
+    let ix = BetInstruction::InitializeIndexed(EventParams { bond_lamports: 5, ..EventParams::new(42) });
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+}
+
+#[tokio::test]
+async fn test_consecutive_events_get_consecutive_indexes() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let now = env.now().await;
+
+    let (first, first_bump) = event_address(&env.program_id, &arbiter.pubkey(), 0);
+    let (second, _) = event_address(&env.program_id, &arbiter.pubkey(), 1);
+    // Index 1 is not next yet.
+    assert!(initialize_indexed(&mut env, &arbiter, &second, EventParams::new(now)).await.is_err());
+    initialize_indexed(&mut env, &arbiter, &first, EventParams::new(now)).await.unwrap();
+    initialize_indexed(&mut env, &arbiter, &second, EventParams { bond_lamports: 1_000, ..EventParams::new(now) }).await.unwrap();
+    // Nor twice at the same address.
+    assert!(initialize_indexed(&mut env, &arbiter, &first, EventParams::new(now)).await.is_err());
+
+    let counter = arbiter_counter_address(&env.program_id, &arbiter.pubkey()).0;
+    assert_eq!(ArbiterCounter::deserialize(&mut &env.data(&counter).await[..]).unwrap().next_event_index, 2);
+    let bets = EventBets::deserialize(&mut &env.data(&first).await[..]).unwrap();
+    assert_eq!(bets.version, STATE_VERSION);
+    assert_eq!((bets.arbiter, bets.bump), (arbiter.pubkey(), first_bump));
+    assert_eq!(env.lamports(&second).await, env.rent(EventBets::LEN).await + 1_000);
+
+    // Another arbiter counts on its own.
+    let other = Keypair::new();
+    env.fund(&other.pubkey(), 1_000_000_000).await;
+    let (other_first, _) = event_address(&env.program_id, &other.pubkey(), 0);
+    initialize_indexed(&mut env, &other, &other_first, EventParams::new(now)).await.unwrap();
+
+    // They are regular events from there on.
+    let betor = Keypair::new().pubkey();
+    let bet = env.place_bet(&betor, &first, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &first, MatchOutcome::TeamB, 1_000_000).await;
+    env.set_winner(&arbiter, &first, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&first).await;
+    env.withdraw(&betor, &first, &bet).await.unwrap();
+    assert_eq!(env.lamports(&betor).await, 1_940_000);
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{leaderboard_address, Instruction as BetInstruction, Leaderboard, LeaderboardEntry, MatchOutcome, LEADERBOARD_SIZE};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+async fn create_leaderboard(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::CreateLeaderboard.pack(),
+        vec![
+            AccountMeta::new(arbiter.pubkey(), true),
+            AccountMeta::new_readonly(*event, false),
+            AccountMeta::new(leaderboard_address(&env.program_id, event).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+async fn leaderboard(env: &mut common::Env, event: &Pubkey) -> Leaderboard {
+    Leaderboard::try_from_slice(&env.data(&leaderboard_address(&env.program_id, event).0).await).unwrap()
+}
+
+async fn withdraw_with_leaderboard(env: &mut common::Env, betor: &Pubkey, event: &Pubkey, bet: &Pubkey, amount: u64) -> Result<(), TransactionError> {
+    let mut ix = env.withdraw_part_ix(betor, event, bet, amount);
+    ix.accounts.push(AccountMeta::new(leaderboard_address(&env.program_id, event).0, false));
+    env.send(&[ix], &[]).await
+}
+
+#[test]
+fn test_create_leaderboard_unpack() {
+    assert_eq!(BetInstruction::unpack(&[49]).unwrap(), BetInstruction::CreateLeaderboard);
+    assert_eq!(BetInstruction::CreateLeaderboard.pack(), vec![49]);
+}
+
+#[tokio::test]
+async fn test_full_board_keeps_the_largest_winnings() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    create_leaderboard(&mut env, &arbiter, &event).await.unwrap();
+    assert_eq!(leaderboard(&mut env, &event).await.entries, [LeaderboardEntry::default(); LEADERBOARD_SIZE]);
+    assert!(create_leaderboard(&mut env, &arbiter, &event).await.is_err());
+
+    // One more winner than fits, staking 1..=11 times the same amount.
+    let mut winners = vec![];
+    for i in 1..=LEADERBOARD_SIZE as u64 + 1 {
+        let betor = Keypair::new().pubkey();
+        winners.push((betor, env.place_bet(&betor, &event, MatchOutcome::TeamA, i * 100_000).await));
+    }
+    let loser = Keypair::new().pubkey();
+    let losing_bet = env.place_bet(&loser, &event, MatchOutcome::TeamB, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    // Claimed out of order, the last claim finds the board full and evicts the smallest winner.
+    for &i in &[3, 0, 10, 7, 1, 9, 4, 6, 2, 8, 5] {
+        let (betor, bet) = winners[i];
+        withdraw_with_leaderboard(&mut env, &betor, &event, &bet, 0).await.unwrap();
+    }
+    // A losing bet leaves the board alone.
+    withdraw_with_leaderboard(&mut env, &loser, &event, &losing_bet, 0).await.unwrap();
+
+    let board = leaderboard(&mut env, &event).await;
+    for (entry, (betor, _)) in board.entries.iter().zip(winners.iter().rev()) {
+        assert_eq!(entry.betor, *betor);
+        assert_eq!(entry.won, env.lamports(betor).await);
+    }
+    assert!(board.entries.windows(2).all(|pair| pair[0].won > pair[1].won));
+    assert!(board.entries.iter().all(|entry| entry.betor != winners[0].0));
+}
+
+#[tokio::test]
+async fn test_repeat_withdrawals_accumulate() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    create_leaderboard(&mut env, &arbiter, &event).await.unwrap();
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 2_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    withdraw_with_leaderboard(&mut env, &bob, &event, &bob_bet, 0).await.unwrap();
+    withdraw_with_leaderboard(&mut env, &alice, &event, &alice_bet, 900_000).await.unwrap();
+    assert_eq!(leaderboard(&mut env, &event).await.entries[..2], [
+        LeaderboardEntry { betor: bob, won: 1_940_000 },
+        LeaderboardEntry { betor: alice, won: 900_000 },
+    ]);
+
+    // Alice catches up with Bob in the same entry and, tied, stays behind him.
+    withdraw_with_leaderboard(&mut env, &alice, &event, &alice_bet, 0).await.unwrap();
+    let board = leaderboard(&mut env, &event).await;
+    assert_eq!(board.entries[..3], [
+        LeaderboardEntry { betor: bob, won: 1_940_000 },
+        LeaderboardEntry { betor: alice, won: 1_940_000 },
+        LeaderboardEntry::default(),
+    ]);
+}
+
+#[tokio::test]
+async fn test_only_the_arbiter_creates_a_leaderboard() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let stranger = Keypair::new();
+    env.fund(&stranger.pubkey(), 1_000_000_000).await;
+    assert!(create_leaderboard(&mut env, &stranger, &event).await.is_err());
+    assert_eq!(env.lamports(&leaderboard_address(&env.program_id, &event).0).await, 0);
+
+    // Without a leaderboard, passing its address is refused.
+    let alice = Keypair::new().pubkey();
+    let bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    assert!(withdraw_with_leaderboard(&mut env, &alice, &event, &bet, 0).await.is_err());
+    env.withdraw(&alice, &event, &bet).await.unwrap();
+}
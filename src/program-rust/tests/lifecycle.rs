@@ -0,0 +1,106 @@
+// An event from Initialize to the last Withdraw through BanksClient, every balance to the lamport.
+mod common;
+
+use helloworld::{Bet, EventBets, EventParams, MatchOutcome};
+use solana_sdk::{
+    instruction::InstructionError,
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const STAKE: u64 = 1_000_000;
+
+fn failed(err: InstructionError) -> TransactionError {
+    TransactionError::InstructionError(0, err)
+}
+
+#[tokio::test]
+async fn test_full_lifecycle_balances() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let deadline = env.now().await + 60;
+    let event = env.create_event(&arbiter, deadline).await;
+    let (event_rent, bet_rent) = (env.rent(EventBets::LEN).await, env.rent(Bet::LEN).await);
+    assert_eq!(env.lamports(&event).await, event_rent);
+
+    let (alice, bob) = (Keypair::new(), Keypair::new());
+    let alice_bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, STAKE).await;
+    let bob_bet = env.place_bet(&bob.pubkey(), &event, MatchOutcome::TeamB, STAKE).await;
+    // The stakes moved to the event, the bets keep their rent exemption.
+    assert_eq!(env.lamports(&event).await, event_rent + 2 * STAKE);
+    assert_eq!((env.lamports(&alice_bet).await, env.lamports(&bob_bet).await), (bet_rent, bet_rent));
+    let bets = EventBets::unpack(&env.data(&event).await).unwrap();
+    assert_eq!((bets.balance_a, bets.balance_b, bets.bets_count_a, bets.bets_count_b), (STAKE, STAKE, 1, 1));
+
+    env.set_now(deadline + 1).await;
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    // Nothing moves before the withdrawals.
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, arbiter_before);
+    assert_eq!(env.lamports(&event).await, event_rent + 2 * STAKE);
+
+    env.withdraw(&alice.pubkey(), &event, &alice_bet).await.unwrap();
+    env.withdraw(&bob.pubkey(), &event, &bob_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice.pubkey()).await, 1_940_000);
+    assert_eq!(env.lamports(&bob.pubkey()).await, 0);
+    // 3% of alice's payout stays for the arbiter.
+    assert_eq!(env.lamports(&event).await, event_rent + 60_000);
+    assert_eq!((env.lamports(&alice_bet).await, env.lamports(&bob_bet).await), (bet_rent, bet_rent));
+    env.collect_commission(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, arbiter_before + 60_000);
+    assert_eq!(env.lamports(&event).await, event_rent);
+
+    let alice_bet = Bet::unpack(&env.data(&alice_bet).await).unwrap();
+    let bob_bet = Bet::unpack(&env.data(&bob_bet).await).unwrap();
+    assert_eq!((alice_bet.outcome, alice_bet.claimed), (u8::from(MatchOutcome::Withdrawn), 1_940_000));
+    assert_eq!((bob_bet.outcome, bob_bet.claimed), (u8::from(MatchOutcome::Withdrawn), 0));
+    let bets = EventBets::unpack(&env.data(&event).await).unwrap();
+    assert_eq!(bets.total_paid_out, 1_940_000);
+}
+
+#[tokio::test]
+async fn test_bet_after_deadline_is_refused() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let deadline = env.now().await + 60;
+    let event = env.create_event(&arbiter, deadline).await;
+    env.set_now(deadline + 1).await;
+
+    let err = env.try_place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, STAKE).await.unwrap_err();
+    assert_eq!(err, failed(InstructionError::InvalidAccountData));
+    assert_eq!(env.lamports(&event).await, env.rent(EventBets::LEN).await);
+}
+
+#[tokio::test]
+async fn test_set_winner_by_non_arbiter_is_refused() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let deadline = env.now().await + 60;
+    let event = env.create_event(&arbiter, deadline).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, STAKE).await;
+    env.set_now(deadline + 1).await;
+
+    let err = env.set_winner(&Keypair::new(), &event, MatchOutcome::TeamA).await.unwrap_err();
+    assert_eq!(err, failed(InstructionError::InvalidAccountData));
+    let bets = EventBets::unpack(&env.data(&event).await).unwrap();
+    assert_eq!(bets.outcome, u8::from(MatchOutcome::Unknown));
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_double_initialize_is_refused() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let deadline = env.now().await + 60;
+    let event = env.create_event(&arbiter, deadline).await;
+
+    let other = Keypair::new();
+    let ix = env.initialize_ix(&other.pubkey(), &event, helloworld::Instruction::Initialize(EventParams::new(deadline + 60)));
+    let err = env.send(&[ix], &[&other]).await.unwrap_err();
+    assert_eq!(err, failed(InstructionError::AccountAlreadyInitialized));
+    let bets = EventBets::unpack(&env.data(&event).await).unwrap();
+    assert_eq!((bets.arbiter, bets.bets_allowed_until_ts), (arbiter.pubkey(), deadline));
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{calculate_market_payout, Bet, BetError, Instruction as BetInstruction, Market, MARKET_UNSETTLED, MAX_OUTCOMES, MEMO_BYTES, Odds};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+async fn create_market(env: &mut common::Env, arbiter: &Keypair, num_outcomes: u8) -> Result<Pubkey, TransactionError> {
+    let market = Keypair::new();
+    let lamports = env.rent(Market::LEN).await;
+    env.create_account(&market, lamports, Market::LEN).await;
+    let now = env.now().await;
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::InitializeMarket { bets_accepted_until: now, num_outcomes, commission_bps: 300 }.pack(),
+        vec![AccountMeta::new_readonly(arbiter.pubkey(), true), AccountMeta::new(market.pubkey(), false)],
+    );
+    env.send(&[ix], &[arbiter]).await?;
+    Ok(market.pubkey())
+}
+
+async fn bet_on(env: &mut common::Env, betor: &Pubkey, market: &Pubkey, outcome: u8, amount: u64) -> Result<Pubkey, TransactionError> {
+    let bet = Keypair::new();
+    let lamports = env.rent(Bet::LEN).await + amount;
+    env.create_account(&bet, lamports, Bet::LEN).await;
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::AddMarketBet { outcome }.pack(),
+        vec![AccountMeta::new_readonly(*betor, false), AccountMeta::new(*market, false), AccountMeta::new(bet.pubkey(), false)],
+    );
+    env.send(&[ix], &[]).await?;
+    Ok(bet.pubkey())
+}
+
+async fn set_market_winner(env: &mut common::Env, arbiter: &Keypair, market: &Pubkey, winner: u8) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::SetMarketWinner { winner }.pack(),
+        vec![AccountMeta::new(arbiter.pubkey(), true), AccountMeta::new(*market, false)],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+async fn withdraw_market_bet(env: &mut common::Env, betor: &Pubkey, market: &Pubkey, bet: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::WithdrawMarketBet.pack(),
+        vec![AccountMeta::new(*betor, false), AccountMeta::new(*market, false), AccountMeta::new(*bet, false)],
+    );
+    env.send(&[ix], &[]).await
+}
+
+fn bet(outcome: u8, amount: u64) -> Bet {
+    Bet {
+        is_initialized: true,
+        betor: Pubkey::new_unique(),
+        event: Pubkey::new_unique(),
+        amount,
+        outcome,
+        referrer: Pubkey::default(),
+        memo: [0; MEMO_BYTES],
+        placed_at: 0,
+        claimed: 0,
+        for_sale: false,
+        ask_lamports: 0,
+        receipt_mint: Pubkey::default(),
+        locked_odds: Odds::default(),
+        delegate: Pubkey::default(),
+    }
+}
+
+#[test]
+fn test_market_unpack() {
+    for ix in &[
+        BetInstruction::InitializeMarket { bets_accepted_until: 42, num_outcomes: 8, commission_bps: 300 },
+        BetInstruction::AddMarketBet { outcome: 7 },
+        BetInstruction::SetMarketWinner { winner: 3 },
+        BetInstruction::WithdrawMarketBet,
+    ] {
+        assert_eq!(&BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    }
+}
+
+#[test]
+fn test_market_payout() {
+    let mut market = Market {
+        is_initialized: true,
+        arbiter: Pubkey::new_unique(),
+        bets_allowed_until_ts: 0,
+        commission_bps: 0,
+        num_outcomes: 3,
+        winner: MARKET_UNSETTLED,
+        pools: [0; MAX_OUTCOMES],
+    };
+    market.pools[..3].copy_from_slice(&[100, 300, 0]);
+    assert_eq!(calculate_market_payout(&market, &bet(0, 50)), Ok(0));
+    market.winner = 0;
+    // Losing pools 300 over a winning pool of 100, plus the stake.
+    assert_eq!(calculate_market_payout(&market, &bet(0, 50)), Ok(200));
+    assert_eq!(calculate_market_payout(&market, &bet(1, 50)), Ok(0));
+    assert_eq!(calculate_market_payout(&market, &bet(3, 50)), Err(BetError::InvalidOutcome));
+    market.commission_bps = 300;
+    assert_eq!(calculate_market_payout(&market, &bet(0, 50)), Ok(194));
+    // Nobody picked the winner, stakes go back.
+    market.winner = 2;
+    assert_eq!(calculate_market_payout(&market, &bet(1, 50)), Ok(50));
+}
+
+#[tokio::test]
+async fn test_four_outcome_market() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    assert!(create_market(&mut env, &arbiter, 1).await.is_err());
+    assert!(create_market(&mut env, &arbiter, MAX_OUTCOMES as u8 + 1).await.is_err());
+    let market = create_market(&mut env, &arbiter, 4).await.unwrap();
+
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let carol = Keypair::new().pubkey();
+    let alice_bet = bet_on(&mut env, &alice, &market, 0, 1_000_000).await.unwrap();
+    let bob_bet = bet_on(&mut env, &bob, &market, 1, 2_000_000).await.unwrap();
+    bet_on(&mut env, &carol, &market, 3, 1_000_000).await.unwrap();
+    assert!(bet_on(&mut env, &carol, &market, 4, 1_000_000).await.is_err());
+
+    let state = Market::deserialize(&mut &env.data(&market).await[..]).unwrap();
+    assert_eq!(&state.pools[..5], &[1_000_000, 2_000_000, 0, 1_000_000, 0]);
+
+    assert!(withdraw_market_bet(&mut env, &alice, &market, &alice_bet).await.is_err());
+    assert!(set_market_winner(&mut env, &arbiter, &market, 4).await.is_err());
+    assert!(set_market_winner(&mut env, &Keypair::new(), &market, 0).await.is_err());
+    set_market_winner(&mut env, &arbiter, &market, 0).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, 120_000);
+    assert!(set_market_winner(&mut env, &arbiter, &market, 1).await.is_err());
+
+    withdraw_market_bet(&mut env, &alice, &market, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 3_880_000);
+    assert!(withdraw_market_bet(&mut env, &alice, &market, &alice_bet).await.is_err());
+    withdraw_market_bet(&mut env, &bob, &market, &bob_bet).await.unwrap();
+    assert_eq!(env.lamports(&bob).await, 0);
+}
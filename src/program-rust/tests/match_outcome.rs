@@ -0,0 +1,85 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use helloworld::{Bet, MatchOutcome, ParseMatchOutcomeError};
+use solana_sdk::{
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+};
+use std::convert::TryFrom;
+
+#[test]
+fn test_every_outcome_round_trips() {
+    let expected = [
+        (MatchOutcome::Unknown, "Unknown", "Unknown", 0),
+        (MatchOutcome::TeamA, "TeamA", "Team A", 1),
+        (MatchOutcome::TeamB, "TeamB", "Team B", 2),
+        (MatchOutcome::Draw, "Draw", "Draw", 3),
+        (MatchOutcome::Withdrawn, "Withdrawn", "Withdrawn", 255),
+        (MatchOutcome::Voided, "Voided", "Voided", 254),
+    ];
+    assert_eq!(expected.len(), MatchOutcome::ALL.len());
+    for &(outcome, name, display, value) in &expected {
+        assert_eq!((outcome.name(), outcome.to_string(), outcome.as_u8()), (name, display.to_string(), value));
+        assert_eq!(MatchOutcome::from_u8(value), Some(outcome));
+        for input in &[name.to_string(), name.to_lowercase(), name.to_uppercase(), display.to_string(), display.to_lowercase(), value.to_string(), format!(" {} ", name)] {
+            assert_eq!(input.parse::<MatchOutcome>(), Ok(outcome), "{:?}", input);
+        }
+        assert_eq!(outcome.to_string().parse::<MatchOutcome>(), Ok(outcome));
+    }
+}
+
+#[test]
+fn test_other_bytes_are_no_outcome() {
+    for value in 0..=u8::MAX {
+        let known = matches!(value, 0..=3 | 254 | 255);
+        assert_eq!(MatchOutcome::from_u8(value).is_some(), known, "{}", value);
+    }
+}
+
+#[test]
+fn test_invalid_inputs() {
+    for input in &["", "Team", "TeamC", "team-a", "4", "256", "-1", "1.0", "0x01", "Over"] {
+        assert_eq!(input.parse::<MatchOutcome>(), Err(ParseMatchOutcomeError(input.to_string())));
+    }
+    let message = "TeamC".parse::<MatchOutcome>().unwrap_err().to_string();
+    assert!(message.starts_with("\"TeamC\" is not a match outcome"), "{}", message);
+}
+
+#[test]
+fn test_conversions_agree_with_borsh() {
+    for value in 0..=u8::MAX {
+        let converted = MatchOutcome::try_from(value);
+        let decoded = MatchOutcome::try_from_slice(&[value]);
+        assert_eq!(converted.is_ok(), decoded.is_ok(), "{}", value);
+        if let Ok(outcome) = converted {
+            assert_eq!(decoded.unwrap(), outcome);
+            assert_eq!(u8::from(outcome), value);
+            assert_eq!(outcome.try_to_vec().unwrap(), [value]);
+        }
+    }
+    // Closed bets' outcomes convert like any other.
+    assert_eq!(MatchOutcome::try_from(255), Ok(MatchOutcome::Withdrawn));
+    assert_eq!(u8::from(MatchOutcome::try_from(255).unwrap()), 255);
+    assert!(MatchOutcome::Withdrawn.is_closed_bet() && MatchOutcome::Voided.is_closed_bet());
+    assert!(!MatchOutcome::ALL.iter().any(|outcome| outcome.is_closed_bet() && u8::from(*outcome) < 4));
+}
+
+#[tokio::test]
+async fn test_withdrawn_bet_reads_back_as_withdrawn() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+
+    let this_bet = Bet::unpack_from_slice(&env.data(&alice_bet).await).unwrap();
+    assert_eq!(MatchOutcome::try_from(this_bet.outcome), Ok(MatchOutcome::Withdrawn));
+    // And stays closed.
+    assert!(env.withdraw(&alice, &event, &alice_bet).await.is_err());
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use helloworld::{Bet, Instruction as BetInstruction, MatchOutcome, MEMO_BYTES, Odds, STATE_VERSION};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const MEMO: [u8; MEMO_BYTES] = *b"order-00000000000000000000004242";
+
+#[test]
+fn test_add_bet_memo_unpack() {
+    let ix = BetInstruction::AddBet { choice: MatchOutcome::TeamB, referrer: Pubkey::new_unique(), amount: 5, memo: MEMO };
+    let packed = ix.pack();
+    assert_eq!(BetInstruction::unpack(&packed).unwrap(), ix);
+    assert!(BetInstruction::unpack(&packed[..packed.len() - 1]).is_err());
+
+    // Older clients send the choice only.
+    assert_eq!(
+        BetInstruction::unpack(&[1, 2]).unwrap(),
+        BetInstruction::AddBet { choice: MatchOutcome::TeamB, referrer: Pubkey::default(), amount: 0, memo: [0; MEMO_BYTES] }
+    );
+}
+
+#[test]
+fn test_bet_layout() {
+    let bet = Bet {
+        version: STATE_VERSION,
+        betor: Pubkey::new_unique(),
+        event: Pubkey::new_unique(),
+        amount: 1,
+        outcome: 1,
+        referrer: Pubkey::default(),
+        memo: MEMO,
+        placed_at: 0,
+        claimed: 0,
+        for_sale: false,
+        ask_lamports: 0,
+        receipt_mint: Pubkey::default(),
+        locked_odds: Odds::default(),
+        delegate: Pubkey::default(),
+    };
+    let data = bet.try_to_vec().unwrap();
+    assert_eq!(data.len(), Bet::LEN);
+    assert_eq!(&data[Bet::LEN - 97 - MEMO_BYTES..Bet::LEN - 97], &MEMO);
+    assert_eq!(Bet::try_from_slice(&data).unwrap().memo, MEMO);
+}
+
+#[tokio::test]
+async fn test_memo_is_stored_and_ignored_by_payouts() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+
+    let alice = Keypair::new().pubkey();
+    let bet = Keypair::new();
+    let lamports = env.rent(Bet::LEN).await + 1_000_000;
+    env.create_account(&bet, lamports, Bet::LEN).await;
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::AddBet { choice: MatchOutcome::TeamA, referrer: Pubkey::default(), amount: 0, memo: MEMO }.pack(),
+        vec![AccountMeta::new(alice, false), AccountMeta::new(event, false), AccountMeta::new(bet.pubkey(), false)],
+    );
+    env.send(&[ix], &[]).await.unwrap();
+    assert_eq!(Bet::deserialize(&mut &env.data(&bet.pubkey()).await[..]).unwrap().memo, MEMO);
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&alice, &event, &bet.pubkey()).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 970_000);
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::bet_error;
+use helloworld::{client, BetError, EventBets, EventParams, Instruction, MatchOutcome};
+use solana_sdk::signature::{Keypair, Signer};
+
+const HASH: [u8; 32] = [7; 32];
+// Bytes of EventParams packed after the hash, up to correction_cooldown_secs.
+const AFTER_HASH: usize = 1 + 1 + 2 + 8 + 32 + 1 + 1 + 8;
+
+#[test]
+fn test_metadata_hash_round_trips() {
+    let params = EventParams { metadata_hash: HASH, ..EventParams::new(1_700_000_000) };
+    let packed = Instruction::Initialize(params).pack();
+    assert_eq!(Instruction::unpack(&packed).unwrap(), Instruction::Initialize(params));
+
+    // Truncated anywhere inside the hash.
+    let end = packed.len() - AFTER_HASH;
+    for cut in 1..32 {
+        assert!(Instruction::unpack(&packed[..end - cut]).is_err());
+    }
+    // Without it, the hash is zeroed.
+    let legacy = Instruction::unpack(&packed[..end - 32]).unwrap();
+    assert_eq!(legacy, Instruction::Initialize(EventParams { metadata_hash: [0; 32], ..params }));
+}
+
+#[tokio::test]
+async fn test_metadata_hash_is_stored() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { metadata_hash: HASH, ..EventParams::new(now) }).await;
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!(bets.metadata_hash, HASH);
+}
+
+#[test]
+fn test_set_winner_hash_round_trips() {
+    let ix = Instruction::SetWinner { result: MatchOutcome::TeamB, metadata_hash: HASH };
+    assert_eq!(Instruction::unpack(&ix.pack()).unwrap(), ix);
+    // Older clients send the result alone, and confirm nothing.
+    assert_eq!(Instruction::unpack(&ix.pack()[..2]).unwrap(), Instruction::SetWinner { result: MatchOutcome::TeamB, metadata_hash: [0; 32] });
+    assert!(Instruction::unpack(&ix.pack()[..33]).is_err());
+}
+
+#[tokio::test]
+async fn test_set_winner_confirms_the_hash() {
+    let mut env = common::start().await;
+    let program_id = env.program_id;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { metadata_hash: HASH, ..EventParams::new(now) }).await;
+
+    let wrong = client::set_winner_confirmed(&program_id, &arbiter.pubkey(), &event, MatchOutcome::TeamA, [8; 32]);
+    assert_eq!(env.send(&[wrong], &[&arbiter]).await.unwrap_err(), bet_error(0, BetError::EventMismatch));
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!(bets.outcome, u8::from(MatchOutcome::Unknown));
+
+    let right = client::set_winner_confirmed(&program_id, &arbiter.pubkey(), &event, MatchOutcome::TeamA, HASH);
+    env.send(&[right], &[&arbiter]).await.unwrap();
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!(bets.outcome, u8::from(MatchOutcome::TeamA));
+}
+
+#[tokio::test]
+async fn test_legacy_event_skips_the_check() {
+    let mut env = common::start().await;
+    let program_id = env.program_id;
+    let now = env.now().await;
+
+    // No hash committed at Initialize, none sent or any sent.
+    for hash in &[[0; 32], HASH] {
+        let arbiter = Keypair::new();
+        let event = env.create_event_with(&arbiter, EventParams::new(now)).await;
+        let ix = client::set_winner_confirmed(&program_id, &arbiter.pubkey(), &event, MatchOutcome::Draw, *hash);
+        env.send(&[ix], &[&arbiter]).await.unwrap();
+        let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+        assert_eq!(bets.outcome, u8::from(MatchOutcome::Draw));
+    }
+}
@@ -0,0 +1,86 @@
+// Run with `cargo test --features test-utils`.
+#![cfg(feature = "test-utils")]
+
+mod common;
+
+use common::bet_error;
+use helloworld::{test_utils::program_account, Bet, BetError, EventBets, Instruction as BetInstruction, MatchOutcome, STATE_VERSION, STATE_VERSION_V1};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+// A version 1 event and one of its bets, see state_pack.rs.
+const EVENT_FIXTURE: &[u8] = include_bytes!("fixtures/event_bets.bin");
+const BET_FIXTURE: &[u8] = include_bytes!("fixtures/bet.bin");
+
+async fn migrate(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, bets: &[Pubkey]) -> Result<(), TransactionError> {
+    let mut accounts = vec![AccountMeta::new_readonly(arbiter.pubkey(), true), AccountMeta::new(*event, false)];
+    accounts.extend(bets.iter().map(|bet| AccountMeta::new(*bet, false)));
+    let ix = Instruction::new_with_bytes(env.program_id, &BetInstruction::MigrateEvent.pack(), accounts);
+    env.send(&[ix], &[arbiter]).await
+}
+
+#[test]
+fn test_migrate_unpack() {
+    assert_eq!(BetInstruction::unpack(&[42]).unwrap(), BetInstruction::MigrateEvent);
+    assert_eq!(BetInstruction::MigrateEvent.pack(), vec![42]);
+}
+
+#[tokio::test]
+async fn test_migrate_v1_fixtures() {
+    let arbiter = Keypair::new();
+    let (event, bet) = (Pubkey::new_unique(), Pubkey::new_unique());
+    // The fixtures' arbiter and event are made up, point them at accounts of this test.
+    let mut event_data = EVENT_FIXTURE.to_vec();
+    event_data[1..33].copy_from_slice(arbiter.pubkey().as_ref());
+    let mut bet_data = BET_FIXTURE.to_vec();
+    bet_data[33..65].copy_from_slice(event.as_ref());
+    let mut env = common::start_with_accounts(vec![(event, program_account(event_data.clone(), 5_500_000)), (bet, program_account(bet_data.clone(), 0))]).await;
+
+    // Nothing reads version 1 accounts.
+    assert_eq!(env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap_err(), bet_error(0, BetError::VersionMismatch));
+    let betor = Pubkey::new_from_array([0x55; 32]);
+    assert_eq!(env.withdraw(&betor, &event, &bet).await.unwrap_err(), bet_error(0, BetError::VersionMismatch));
+
+    assert!(migrate(&mut env, &Keypair::new(), &event, &[bet]).await.is_err());
+    assert_eq!(env.data(&event).await[0], STATE_VERSION_V1);
+    migrate(&mut env, &arbiter, &event, &[bet]).await.unwrap();
+
+    // Only the version changed.
+    let migrated = env.data(&event).await;
+    assert_eq!((migrated[0], &migrated[1..]), (STATE_VERSION, &event_data[1..]));
+    let bets = EventBets::unpack_from_slice(&migrated).unwrap();
+    assert_eq!((bets.arbiter, bets.outcome, bets.balance_a, bets.balance_b), (arbiter.pubkey(), 2, 4_000_000, 1_500_000));
+    assert_eq!((bets.total_paid_out, bets.referral_reserve, bets.handicap), (970_000, 6_000, -3));
+    let migrated = env.data(&bet).await;
+    assert_eq!((migrated[0], &migrated[1..]), (STATE_VERSION, &bet_data[1..]));
+    let this_bet = Bet::unpack_from_slice(&migrated).unwrap();
+    assert_eq!((this_bet.betor, this_bet.event, this_bet.amount, this_bet.claimed), (betor, event, 1_000_000, 300_000));
+
+    // Current accounts are left alone.
+    migrate(&mut env, &arbiter, &event, &[bet]).await.unwrap();
+    assert_eq!(env.data(&bet).await, migrated);
+}
+
+#[tokio::test]
+async fn test_migrate_checks_bets() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let other = env.create_event(&arbiter, now + 100).await;
+    let bet = env.place_bet(&Keypair::new().pubkey(), &other, MatchOutcome::TeamA, 1_000_000).await;
+    assert_eq!(env.data(&bet).await[0], STATE_VERSION);
+
+    // A bet of another event.
+    assert!(migrate(&mut env, &arbiter, &event, &[bet]).await.is_err());
+    migrate(&mut env, &arbiter, &other, &[bet]).await.unwrap();
+
+    // Versions after this program's are not migrated down.
+    env.corrupt(&bet, |account| account.data[0] = STATE_VERSION + 1).await;
+    assert_eq!(migrate(&mut env, &arbiter, &other, &[bet]).await.unwrap_err(), bet_error(0, BetError::VersionMismatch));
+}
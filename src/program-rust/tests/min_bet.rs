@@ -0,0 +1,30 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{BetError, EventParams, MatchOutcome};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_minimum_bet_is_enforced_on_stake() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { min_bet_lamports: 1_000_000, ..EventParams::new(now) }).await;
+    let betor = Keypair::new().pubkey();
+
+    // The deposit covers rent + 999_999, the stake is one lamport short.
+    assert_eq!(
+        env.try_place_bet(&betor, &event, MatchOutcome::TeamA, 999_999).await.unwrap_err(),
+        bet_error(0, BetError::BelowMinimumBet)
+    );
+    env.place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await;
+}
+
+#[tokio::test]
+async fn test_zero_minimum_accepts_any_stake() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1).await;
+}
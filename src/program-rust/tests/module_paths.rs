@@ -0,0 +1,40 @@
+// The crate root re-exports the modules' items, code using either path compiles against the same types.
+use helloworld::{entrypoint, error, instruction, processor, state};
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+type Process = fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult;
+
+#[test]
+fn test_root_paths_are_module_paths() {
+    let _: Process = helloworld::process_instruction;
+    let _: Process = entrypoint::process_instruction;
+    let _: Process = processor::Processor::process;
+    let _: Process = helloworld::Processor::process;
+
+    let outcome: state::MatchOutcome = helloworld::MatchOutcome::TeamA;
+    let _: helloworld::Odds = state::Odds { numerator: 1, denominator: 1 };
+    let bets: Option<helloworld::EventBets> = None::<state::EventBets>;
+    let this_bet: Option<helloworld::Bet> = None::<state::Bet>;
+    assert!(bets.is_none() && this_bet.is_none());
+    assert_eq!(
+        (helloworld::EventBets::LEN, helloworld::Bet::LEN, helloworld::STATE_VERSION),
+        (state::EventBets::LEN, state::Bet::LEN, state::STATE_VERSION)
+    );
+
+    let instruction: instruction::Instruction = helloworld::Instruction::SetWinner { result: outcome, metadata_hash: [0; 32] };
+    assert_eq!(helloworld::Instruction::unpack(&instruction.pack()), Ok(instruction));
+    let params: helloworld::EventParams = instruction::EventParams::new(1_700_000_000);
+    let _: instruction::ConfigParams = helloworld::ConfigParams {
+        default_commission_bps: 300,
+        max_commission_bps: 1_000,
+        min_deadline_offset_secs: 60,
+        arbiter_share_bps: 2_000,
+        referral_bps: 500,
+    };
+    assert_eq!(params.bets_accepted_until, 1_700_000_000);
+
+    let err: error::BetError = helloworld::BetError::VersionMismatch;
+    assert_eq!(err, error::BetError::VersionMismatch);
+    let _: fn(&helloworld::EventBets, &helloworld::Bet) -> Result<u64, _> = processor::quote_payout;
+    let _: fn(u64, u16) -> (u64, u64) = helloworld::split_commission;
+}
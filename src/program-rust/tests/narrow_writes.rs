@@ -0,0 +1,72 @@
+mod common;
+
+use helloworld::{Bet, EventBets, MatchOutcome, STATE_VERSION};
+use solana_sdk::{
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+};
+
+// What the full unpack, update, pack round trip used to leave in the account.
+fn round_trip<T: Pack>(before: &[u8], update: impl FnOnce(&mut T)) -> Vec<u8> {
+    let mut state = T::unpack_from_slice(before).unwrap();
+    update(&mut state);
+    let mut data = before.to_vec();
+    state.pack_into_slice(&mut data[..T::LEN]);
+    data
+}
+
+#[tokio::test]
+async fn test_add_bet_writes_what_the_round_trip_did() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 500_000).await;
+
+    let before = env.data(&event).await;
+    let betor = Keypair::new().pubkey();
+    let bet = env.place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let expected = round_trip::<EventBets>(&before, |bets| {
+        bets.balance_a += 1_000_000;
+        bets.bets_count_a += 1;
+    });
+    assert_eq!(env.data(&event).await, expected);
+
+    let bet_data = env.data(&bet).await;
+    let placed_at = Bet::unpack_from_slice(&bet_data).unwrap().placed_at;
+    assert!(placed_at >= now);
+    let expected = round_trip::<Bet>(&[0; Bet::LEN], |this_bet| {
+        this_bet.version = STATE_VERSION;
+        this_bet.betor = betor;
+        this_bet.event = event;
+        this_bet.amount = 1_000_000;
+        this_bet.outcome = 1;
+        this_bet.placed_at = placed_at;
+    });
+    assert_eq!(bet_data, expected);
+}
+
+#[tokio::test]
+async fn test_withdraw_writes_what_the_round_trip_did() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let betor = Keypair::new().pubkey();
+    let bet = env.place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    let event_before = env.data(&event).await;
+    let bet_before = env.data(&bet).await;
+    env.withdraw(&betor, &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&betor).await, 1_940_000);
+    let expected = round_trip::<EventBets>(&event_before, |bets| bets.total_paid_out += 1_940_000);
+    assert_eq!(env.data(&event).await, expected);
+    let expected = round_trip::<Bet>(&bet_before, |this_bet| {
+        this_bet.claimed = 1_940_000;
+        this_bet.outcome = 255;
+    });
+    assert_eq!(env.data(&bet).await, expected);
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{EventBets, MatchOutcome};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_total_paid_out_sums_payouts() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let carol = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamA, 3_000_000).await;
+    let carol_bet = env.place_bet(&carol, &event, MatchOutcome::TeamB, 2_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+    env.withdraw(&carol, &event, &carol_bet).await.unwrap();
+
+    let paid = env.lamports(&alice).await + env.lamports(&bob).await;
+    assert_eq!(paid, 1_455_000 + 4_365_000);
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!(bets.total_paid_out, paid);
+    // Everything but the commission went out.
+    assert_eq!(bets.total_paid_out, 6_000_000 * 97 / 100);
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{
+    calculate_parlay_payout, parlay_pool_address, BetError, EventBets, Instruction as BetInstruction, MatchOutcome, Parlay, ParlayLeg, MAX_PARLAY_LEGS,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+async fn create_pool(env: &mut common::Env) -> Result<Pubkey, TransactionError> {
+    let payer = env.payer();
+    let pool = parlay_pool_address(&env.program_id).0;
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::CreateParlayPool.pack(),
+        vec![
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new(pool, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    env.send(&[ix], &[]).await?;
+    Ok(pool)
+}
+
+async fn place_parlay(env: &mut common::Env, betor: &Pubkey, legs: &[(Pubkey, MatchOutcome)], stake: u64) -> Result<Pubkey, TransactionError> {
+    let parlay = Keypair::new();
+    let lamports = env.rent(Parlay::LEN).await + stake;
+    env.create_account(&parlay, lamports, Parlay::LEN).await;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*betor, false),
+        AccountMeta::new(parlay.pubkey(), false),
+        AccountMeta::new(parlay_pool_address(&env.program_id).0, false),
+    ];
+    accounts.extend(legs.iter().map(|(event, _)| AccountMeta::new_readonly(*event, false)));
+    let choices = legs.iter().map(|&(_, choice)| choice).collect();
+    let ix = Instruction::new_with_bytes(env.program_id, &BetInstruction::PlaceParlay { choices }.pack(), accounts);
+    env.send(&[ix], &[]).await?;
+    Ok(parlay.pubkey())
+}
+
+async fn settle_parlay(env: &mut common::Env, betor: &Pubkey, parlay: &Pubkey, events: &[Pubkey]) -> Result<(), TransactionError> {
+    let mut accounts = vec![
+        AccountMeta::new(*betor, false),
+        AccountMeta::new(*parlay, false),
+        AccountMeta::new(parlay_pool_address(&env.program_id).0, false),
+    ];
+    accounts.extend(events.iter().map(|event| AccountMeta::new_readonly(*event, false)));
+    let ix = Instruction::new_with_bytes(env.program_id, &BetInstruction::SettleParlay.pack(), accounts);
+    env.send(&[ix], &[]).await
+}
+
+// An event with `pool_a` and `pool_b` staked by strangers.
+async fn event_with_pools(env: &mut common::Env, arbiter: &Keypair, pool_a: u64, pool_b: u64) -> Pubkey {
+    let now = env.now().await;
+    let event = env.create_event(arbiter, now).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, pool_a).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, pool_b).await;
+    event
+}
+
+fn settled_event(outcome: u8, balance_a: u64, balance_b: u64) -> EventBets {
+    let mut event = EventBets::try_from_slice(&[0; EventBets::LEN]).unwrap();
+    event.outcome = outcome;
+    event.balance_a = balance_a;
+    event.balance_b = balance_b;
+    event
+}
+
+#[test]
+fn test_parlay_unpack() {
+    for ix in &[
+        BetInstruction::CreateParlayPool,
+        BetInstruction::PlaceParlay { choices: vec![MatchOutcome::TeamA, MatchOutcome::TeamB, MatchOutcome::TeamA] },
+        BetInstruction::SettleParlay,
+    ] {
+        assert_eq!(&BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    }
+    assert!(BetInstruction::unpack(&[38, 2, 1]).is_err());
+}
+
+#[test]
+fn test_parlay_payout() {
+    let mut parlay = Parlay { is_initialized: true, stake: 100, num_legs: 2, ..Parlay::default() };
+    parlay.legs[0] = ParlayLeg { event: Pubkey::new_unique(), outcome: 1 };
+    parlay.legs[1] = ParlayLeg { event: Pubkey::new_unique(), outcome: 2 };
+    // 400 / 100, then 300 / 200, rounded down after each leg.
+    let events = [settled_event(1, 100, 300), settled_event(2, 100, 200)];
+    assert_eq!(calculate_parlay_payout(&parlay, &events), Ok(600));
+    let events = [settled_event(1, 100, 300), settled_event(1, 100, 200)];
+    assert_eq!(calculate_parlay_payout(&parlay, &events), Ok(0));
+    // The drawn leg counts as 1.
+    let events = [settled_event(1, 100, 300), settled_event(3, 100, 200)];
+    assert_eq!(calculate_parlay_payout(&parlay, &events), Ok(400));
+    let events = [settled_event(1, 100, 300), settled_event(0, 100, 200)];
+    assert_eq!(calculate_parlay_payout(&parlay, &events), Err(BetError::ResultNotFinal));
+    assert_eq!(calculate_parlay_payout(&parlay, &events[..1]), Err(BetError::InvalidOutcome));
+}
+
+#[tokio::test]
+async fn test_parlays_win_lose_and_skip_drawn_legs() {
+    let mut env = common::start().await;
+    let pool = create_pool(&mut env).await.unwrap();
+    assert!(create_pool(&mut env).await.is_err());
+    let arbiter = Keypair::new();
+    // TeamA pays 3.88 on the first event, TeamB 1.94 on the second, after the 3% commission.
+    let first = event_with_pools(&mut env, &arbiter, 1_000_000, 3_000_000).await;
+    let second = event_with_pools(&mut env, &arbiter, 2_000_000, 2_000_000).await;
+    let drawn = event_with_pools(&mut env, &arbiter, 1_000_000, 1_000_000).await;
+
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let carol = Keypair::new().pubkey();
+    assert!(place_parlay(&mut env, &alice, &[(first, MatchOutcome::TeamA)], 1_000_000).await.is_err());
+    assert!(place_parlay(&mut env, &alice, &[(first, MatchOutcome::TeamA), (first, MatchOutcome::TeamB)], 1_000_000).await.is_err());
+    assert!(place_parlay(&mut env, &alice, &[(first, MatchOutcome::TeamA); MAX_PARLAY_LEGS + 1], 1_000_000).await.is_err());
+    let winning = place_parlay(&mut env, &alice, &[(first, MatchOutcome::TeamA), (second, MatchOutcome::TeamB)], 1_000_000).await.unwrap();
+    let losing = place_parlay(&mut env, &bob, &[(first, MatchOutcome::TeamA), (second, MatchOutcome::TeamA)], 1_000_000).await.unwrap();
+    let with_draw = place_parlay(&mut env, &carol, &[(first, MatchOutcome::TeamA), (drawn, MatchOutcome::TeamB)], 1_000_000).await.unwrap();
+    let rent = env.rent(0).await;
+    assert_eq!(env.lamports(&pool).await, rent + 3_000_000);
+
+    env.set_winner(&arbiter, &first, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&first).await;
+    assert!(settle_parlay(&mut env, &alice, &winning, &[first, second]).await.is_err());
+    env.set_winner(&arbiter, &second, MatchOutcome::TeamB).await.unwrap();
+    env.close_correction_window(&second).await;
+    env.set_winner(&arbiter, &drawn, MatchOutcome::Draw).await.unwrap();
+    env.close_correction_window(&drawn).await;
+
+    // 7_527_200 is more than the stakes, the house funds the pool.
+    assert!(settle_parlay(&mut env, &alice, &winning, &[first, second]).await.is_err());
+    env.fund(&pool, 10_000_000).await;
+    assert!(settle_parlay(&mut env, &alice, &winning, &[second, first]).await.is_err());
+    settle_parlay(&mut env, &alice, &winning, &[first, second]).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 7_527_200);
+    assert!(settle_parlay(&mut env, &alice, &winning, &[first, second]).await.is_err());
+
+    settle_parlay(&mut env, &bob, &losing, &[first, second]).await.unwrap();
+    assert_eq!(env.lamports(&bob).await, 0);
+    settle_parlay(&mut env, &carol, &with_draw, &[first, drawn]).await.unwrap();
+    assert_eq!(env.lamports(&carol).await, 3_880_000);
+
+    let state = Parlay::deserialize(&mut &env.data(&with_draw).await[..]).unwrap();
+    assert!(state.settled);
+    assert_eq!((state.stake, state.payout), (1_000_000, 3_880_000));
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::bet_error;
+use helloworld::{quote_payout, Bet, BetError, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[test]
+fn test_withdraw_unpack() {
+    let ix = BetInstruction::Withdraw { amount: 42 };
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    // Older clients send the tag only.
+    assert_eq!(BetInstruction::unpack(&[3]).unwrap(), BetInstruction::Withdraw { amount: 0 });
+}
+
+#[tokio::test]
+async fn test_partial_claims_add_up_to_the_full_payout() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 2_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    // Bob takes it all at once.
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+    let full = env.lamports(&bob).await;
+    assert_eq!(full, 1_940_000);
+
+    env.withdraw_part(&alice, &event, &alice_bet, 900_000).await.unwrap();
+    env.withdraw_part(&alice, &event, &alice_bet, 1_000_000).await.unwrap();
+    let this_bet = Bet::deserialize(&mut &env.data(&alice_bet).await[..]).unwrap();
+    assert_eq!(this_bet.claimed, 1_900_000);
+    assert_eq!(this_bet.outcome, 1);
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!(quote_payout(&bets, &this_bet).unwrap(), 40_000);
+
+    assert_eq!(
+        env.withdraw_part(&alice, &event, &alice_bet, 40_001).await.unwrap_err(),
+        bet_error(0, BetError::ClaimExceedsPayout)
+    );
+    // No amount claims the rest.
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, full);
+    assert_eq!(Bet::deserialize(&mut &env.data(&alice_bet).await[..]).unwrap().outcome, 255);
+    assert!(env.withdraw_part(&alice, &event, &alice_bet, 1).await.is_err());
+
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!(bets.total_paid_out, 2 * full);
+}
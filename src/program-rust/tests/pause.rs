@@ -0,0 +1,78 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::bet_error;
+use helloworld::{BetError, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+async fn set_paused(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, paused: bool) -> Result<(), TransactionError> {
+    let instruction = if paused { BetInstruction::PauseEvent } else { BetInstruction::UnpauseEvent };
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &instruction.pack(),
+        vec![AccountMeta::new_readonly(arbiter.pubkey(), true), AccountMeta::new(*event, false)],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+async fn paused(env: &mut common::Env, event: &Pubkey) -> bool {
+    EventBets::deserialize(&mut &env.data(event).await[..]).unwrap().paused
+}
+
+#[test]
+fn test_pause_unpack() {
+    for ix in &[BetInstruction::PauseEvent, BetInstruction::UnpauseEvent] {
+        assert_eq!(&BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    }
+}
+
+#[tokio::test]
+async fn test_paused_event_takes_no_bets() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let betor = Keypair::new().pubkey();
+
+    // Only the arbiter.
+    assert!(set_paused(&mut env, &Keypair::new(), &event, true).await.is_err());
+    assert!(set_paused(&mut env, &arbiter, &event, false).await.is_err());
+    set_paused(&mut env, &arbiter, &event, true).await.unwrap();
+    assert!(paused(&mut env, &event).await);
+    assert!(set_paused(&mut env, &arbiter, &event, true).await.is_err());
+    assert_eq!(
+        env.try_place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await.unwrap_err(),
+        bet_error(0, BetError::EventPaused)
+    );
+
+    set_paused(&mut env, &arbiter, &event, false).await.unwrap();
+    assert!(!paused(&mut env, &event).await);
+    env.try_place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_paused_event_still_resolves_but_pays_nothing() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+
+    set_paused(&mut env, &arbiter, &event, true).await.unwrap();
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    assert_eq!(env.withdraw(&alice, &event, &bet).await.unwrap_err(), bet_error(0, BetError::EventPaused));
+
+    set_paused(&mut env, &arbiter, &event, false).await.unwrap();
+    // There is nothing left to review once the result is in.
+    assert!(set_paused(&mut env, &arbiter, &event, true).await.is_err());
+    env.withdraw(&alice, &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 1_940_000);
+}
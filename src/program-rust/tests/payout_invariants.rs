@@ -0,0 +1,107 @@
+// SetWinner then every Withdraw, in the order the bets were placed, through the same functions the
+// program uses: the pot is never overdrawn and payouts plus commission never exceed the stakes.
+use borsh::BorshDeserialize;
+use helloworld::{calculate_payout, settlement_commission, Bet, EventBets, MatchOutcome, Odds, MEMO_BYTES, STATE_VERSION};
+use proptest::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+
+// The program's MAX_COMISSION_BPS.
+const MAX_COMMISSION_BPS: u16 = 1_000;
+
+fn event(balance_a: u64, balance_b: u64, commission_bps: u16, result: MatchOutcome) -> EventBets {
+    let mut event = EventBets::try_from_slice(&[0; EventBets::LEN]).unwrap();
+    event.version = STATE_VERSION;
+    event.balance_a = balance_a;
+    event.balance_b = balance_b;
+    event.commission_bps = commission_bps;
+    event.outcome = u8::from(result);
+    event
+}
+
+fn bet(choice: MatchOutcome, amount: u64) -> Bet {
+    Bet {
+        version: STATE_VERSION,
+        betor: Pubkey::default(),
+        event: Pubkey::default(),
+        amount,
+        outcome: u8::from(choice),
+        referrer: Pubkey::default(),
+        memo: [0; MEMO_BYTES],
+        placed_at: 0,
+        claimed: 0,
+        for_sale: false,
+        ask_lamports: 0,
+        receipt_mint: Pubkey::default(),
+        locked_odds: Odds::default(),
+        delegate: Pubkey::default(),
+    }
+}
+
+// Returns the commission and each bet's payout, failing on the first one the pot can't cover.
+fn settle(bets: &[(bool, u64)], commission_bps: u16, result: MatchOutcome) -> Result<(u64, Vec<u64>), String> {
+    let choice = |on_a: bool| if on_a { MatchOutcome::TeamA } else { MatchOutcome::TeamB };
+    let balance_a = bets.iter().filter(|(on_a, _)| *on_a).map(|(_, amount)| amount).sum();
+    let balance_b = bets.iter().filter(|(on_a, _)| !*on_a).map(|(_, amount)| amount).sum();
+    let event = event(balance_a, balance_b, commission_bps, result);
+    let commission = settlement_commission(&event).unwrap();
+    let mut pot = (balance_a as u128 + balance_b as u128).checked_sub(commission as u128).ok_or("commission above the pools")?;
+    let mut payouts = vec![];
+    for (i, &(on_a, amount)) in bets.iter().enumerate() {
+        let payout = calculate_payout(&event, &bet(choice(on_a), amount), commission_bps).unwrap();
+        pot = pot.checked_sub(payout as u128).ok_or_else(|| format!("bet {} takes {} from a pot of {}", i, payout, pot))?;
+        payouts.push(payout);
+    }
+    Ok((commission, payouts))
+}
+
+fn outcome() -> impl Strategy<Value = MatchOutcome> {
+    prop_oneof![Just(MatchOutcome::TeamA), Just(MatchOutcome::TeamB), Just(MatchOutcome::Draw)]
+}
+
+proptest! {
+    #[test]
+    fn test_payouts_never_exceed_the_pot(
+        bets in prop::collection::vec((any::<bool>(), 1u64..=u64::MAX / 64), 1..32),
+        commission_bps in 0..=MAX_COMMISSION_BPS,
+        result in outcome(),
+    ) {
+        let staked: u128 = bets.iter().map(|&(_, amount)| amount as u128).sum();
+        let (commission, payouts) = settle(&bets, commission_bps, result).map_err(TestCaseError::fail)?;
+        let paid: u128 = payouts.iter().map(|&payout| payout as u128).sum();
+        prop_assert!(paid + commission as u128 <= staked, "{} + {} > {}", paid, commission, staked);
+    }
+
+    // Mostly dust against a few huge stakes, where rounding matters most.
+    #[test]
+    fn test_dust_against_whales(
+        dust in prop::collection::vec((any::<bool>(), 1u64..=1_000), 1..24),
+        whales in prop::collection::vec((any::<bool>(), u64::MAX / 8..=u64::MAX / 4), 0..3),
+        commission_bps in 0..=MAX_COMMISSION_BPS,
+        result in outcome(),
+    ) {
+        let bets = [dust, whales].concat();
+        let staked: u128 = bets.iter().map(|&(_, amount)| amount as u128).sum();
+        let (commission, payouts) = settle(&bets, commission_bps, result).map_err(TestCaseError::fail)?;
+        prop_assert!(payouts.iter().map(|&payout| payout as u128).sum::<u128>() + commission as u128 <= staked);
+    }
+}
+
+#[test]
+fn test_commission_is_taken_on_the_pools_only() {
+    let mut event = event(1_000_000, 1_000_000, 300, MatchOutcome::TeamA);
+    assert_eq!(settlement_commission(&event), Ok(60_000));
+    // The bond stays out, a bonus is charged unless it is commission free.
+    event.bond_lamports = 5_000_000;
+    event.bonus_lamports = 1_000_000;
+    assert_eq!(settlement_commission(&event), Ok(90_000));
+    event.bonus_commission_free = true;
+    assert_eq!(settlement_commission(&event), Ok(60_000));
+}
+
+#[test]
+fn test_one_lamport_against_the_rest() {
+    let bets = [(true, 1), (false, u64::MAX / 2)];
+    let (commission, payouts) = settle(&bets, MAX_COMMISSION_BPS, MatchOutcome::TeamA).unwrap();
+    assert!(payouts[0] as u128 + commission as u128 <= 1 + u64::MAX as u128 / 2);
+    assert_eq!(payouts[1], 0);
+}
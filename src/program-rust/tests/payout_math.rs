@@ -0,0 +1,147 @@
+use borsh::BorshDeserialize;
+use helloworld::{calculate_payout, settlement_commission, Bet, BetError, EventBets, MEMO_BYTES, Odds, STATE_VERSION};
+use solana_sdk::pubkey::Pubkey;
+
+fn event(outcome: u8, balance_a: u64, balance_b: u64) -> EventBets {
+    let mut event = EventBets::try_from_slice(&[0; EventBets::LEN]).unwrap();
+    event.version = STATE_VERSION;
+    event.outcome = outcome;
+    event.balance_a = balance_a;
+    event.balance_b = balance_b;
+    event
+}
+
+fn bet(outcome: u8, amount: u64) -> Bet {
+    Bet {
+        version: STATE_VERSION,
+        betor: Pubkey::new_unique(),
+        event: Pubkey::new_unique(),
+        amount,
+        outcome,
+        referrer: Pubkey::default(),
+        memo: [0; MEMO_BYTES],
+        placed_at: 0,
+        claimed: 0,
+        for_sale: false,
+        ask_lamports: 0,
+        receipt_mint: Pubkey::default(),
+        locked_odds: Odds::default(),
+        delegate: Pubkey::default(),
+    }
+}
+
+#[test]
+fn test_every_result_and_choice() {
+    // Pools of 300 on A and 600 on B, bets of 100.
+    let cases: &[(u8, u8, Result<u64, BetError>)] = &[
+        (0, 1, Ok(0)),
+        (0, 2, Ok(0)),
+        (1, 1, Ok(300)),
+        (1, 2, Ok(0)),
+        (2, 1, Ok(0)),
+        (2, 2, Ok(150)),
+        (3, 1, Ok(100)),
+        (3, 2, Ok(100)),
+        (255, 1, Err(BetError::InvalidOutcome)),
+        (7, 2, Err(BetError::InvalidOutcome)),
+    ];
+    for &(result, choice, expected) in cases {
+        assert_eq!(calculate_payout(&event(result, 300, 600), &bet(choice, 100), 0), expected, "result {} choice {}", result, choice);
+    }
+    // Only TeamA and TeamB bets exist, anything else (e.g. an already withdrawn bet) is refused.
+    for &choice in &[0, 3, 255] {
+        for &result in &[0, 1, 2, 3] {
+            assert_eq!(calculate_payout(&event(result, 300, 600), &bet(choice, 100), 0), Err(BetError::InvalidOutcome));
+        }
+    }
+}
+
+#[test]
+fn test_commission_rounds_down() {
+    assert_eq!(calculate_payout(&event(1, 300, 600), &bet(1, 100), 300), Ok(291));
+    assert_eq!(calculate_payout(&event(3, 300, 600), &bet(2, 33), 300), Ok(32));
+    assert_eq!(calculate_payout(&event(2, 7, 3), &bet(2, 1), 10_000), Ok(0));
+    // 1 * 7 / 3 + 1 = 3 before commission.
+    assert_eq!(calculate_payout(&event(2, 7, 3), &bet(2, 1), 0), Ok(3));
+    // 10 / 3 * 0.97 = 3.23, rounding the gross share to 3 first would pay 2.
+    assert_eq!(calculate_payout(&event(2, 7, 3), &bet(2, 1), 300), Ok(3));
+}
+
+// The exact payout is stake * (pool + other_pool) * keep / (pool * 10_000), checks the one paid is
+// its floor: not above it and less than a lamport under it. Only for values where that fits a u128.
+fn assert_within_a_lamport(pool: u64, other_pool: u64, stake: u64, commission_bps: u16) {
+    let paid = calculate_payout(&event(1, pool, other_pool), &bet(1, stake), commission_bps).unwrap() as u128;
+    let owed = (pool as u128 + other_pool as u128)
+        .checked_mul(stake as u128)
+        .and_then(|total| total.checked_mul(10_000 - commission_bps as u128))
+        .unwrap();
+    let denominator = pool as u128 * 10_000;
+    assert!(paid * denominator <= owed, "{} overpaid for {} on {} vs {}", paid, stake, pool, other_pool);
+    assert!(owed < (paid + 1) * denominator, "{} underpaid for {} on {} vs {}", paid, stake, pool, other_pool);
+}
+
+#[test]
+fn test_adversarial_ratios_within_a_lamport() {
+    let max = u64::MAX;
+    for &commission_bps in &[0, 1, 300, 999, 1_000, 9_999] {
+        // A lamport against everything.
+        assert_within_a_lamport(1, max - 1, 1, commission_bps);
+        // Everything against a lamport, for the smallest and the whole stake.
+        assert_within_a_lamport(max - 1, 1, 1, commission_bps);
+        assert_within_a_lamport(max - 1, 1, 1 << 50, commission_bps);
+        // Pools that never divide evenly.
+        assert_within_a_lamport(max - 2, 3, 7, commission_bps);
+        assert_within_a_lamport(3, 1 << 60, 2, commission_bps);
+        assert_within_a_lamport(999_999_937, 1_000_000_007, 1, commission_bps);
+        assert_within_a_lamport(999_999_937, 1_000_000_007, 499_999_999, commission_bps);
+    }
+}
+
+#[test]
+fn test_adversarial_pot_never_overdrawn() {
+    let max = u64::MAX;
+    // (winning stakes, losing pool): a lamport next to a whale, and many odd stakes.
+    let cases: &[(&[u64], u64)] = &[
+        (&[1, max / 2 - 1], max / 2),
+        (&[max / 2 - 1, 1], 1),
+        (&[1, 1, 1], max - 3),
+        (&[3, 5, 7, 11, 13], 1_000_000_007),
+        (&[max / 4, max / 4 - 1, 1], max / 2 - 1),
+    ];
+    for &commission_bps in &[0, 1, 300, 1_000, 9_999] {
+        for &(stakes, losing_pool) in cases {
+            let winning_pool: u64 = stakes.iter().sum();
+            let mut settled = event(1, winning_pool, losing_pool);
+            settled.commission_bps = commission_bps;
+            let mut paid = settlement_commission(&settled).unwrap() as u128;
+            for &stake in stakes {
+                paid += calculate_payout(&settled, &bet(1, stake), commission_bps).unwrap() as u128;
+            }
+            let pot = winning_pool as u128 + losing_pool as u128;
+            assert!(paid <= pot, "paid {} out of {} at {} bps", paid, pot, commission_bps);
+            // What stays behind is the rounding, under a lamport per bet plus the commission's own.
+            assert!(pot - paid <= stakes.len() as u128 + 1, "{} of dust at {} bps", pot - paid, commission_bps);
+        }
+    }
+}
+
+#[test]
+fn test_zero_pools() {
+    assert_eq!(calculate_payout(&event(1, 0, 0), &bet(1, 0), 300), Ok(0));
+    assert_eq!(calculate_payout(&event(1, 0, 500), &bet(1, 10), 300), Ok(0));
+    // Nobody on the losing side, winners get their stake back less commission.
+    assert_eq!(calculate_payout(&event(1, 500, 0), &bet(1, 500), 0), Ok(500));
+    assert_eq!(calculate_payout(&event(3, 0, 0), &bet(2, 0), 300), Ok(0));
+}
+
+#[test]
+fn test_values_near_u64_max() {
+    let max = u64::MAX;
+    // Sole winner against an equal pool would be owed 2 * u64::MAX.
+    assert_eq!(calculate_payout(&event(1, max, max), &bet(1, max), 0), Err(BetError::ArithmeticOverflow));
+    // Commission brings it back in range.
+    assert_eq!(calculate_payout(&event(1, max, max), &bet(1, max), 5_000), Ok(max));
+    assert_eq!(calculate_payout(&event(2, max - 1, 1), &bet(2, 1), 0), Ok(max));
+    assert_eq!(calculate_payout(&event(1, max, max - 1), &bet(1, max / 2), 0), Ok(max - 2));
+    assert_eq!(calculate_payout(&event(3, max, max), &bet(1, max), 300), Ok((max as u128 * 9_700 / 10_000) as u64));
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{Bet, MatchOutcome};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_bet_records_clock_time() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 1_000).await;
+
+    let bet = env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 1_000).await;
+    let placed_at = Bet::deserialize(&mut &env.data(&bet).await[..]).unwrap().placed_at;
+    assert_eq!(placed_at, env.now().await);
+
+    env.set_now(now + 500).await;
+    let later = env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000).await;
+    assert_eq!(Bet::deserialize(&mut &env.data(&later).await[..]).unwrap().placed_at, now + 500);
+
+    // Settling and paying out leaves the stored time alone.
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    assert_eq!(Bet::deserialize(&mut &env.data(&bet).await[..]).unwrap().placed_at, placed_at);
+}
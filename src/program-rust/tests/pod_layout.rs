@@ -0,0 +1,143 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use bytemuck::Zeroable;
+use helloworld::{
+    pod::{self, BetPod, EventBetsPod},
+    Bet, EventBets, Odds, STATE_VERSION,
+};
+use solana_sdk::pubkey::Pubkey;
+
+fn offset<T, F>(base: &T, field: &F) -> usize {
+    field as *const F as usize - base as *const T as usize
+}
+
+#[test]
+fn test_views_have_the_borsh_sizes() {
+    assert_eq!(std::mem::size_of::<EventBetsPod>(), EventBets::LEN);
+    assert_eq!(std::mem::size_of::<BetPod>(), Bet::LEN);
+    assert_eq!(std::mem::align_of::<EventBetsPod>(), 1);
+    assert_eq!(std::mem::align_of::<BetPod>(), 1);
+
+    let event = EventBetsPod::zeroed();
+    assert_eq!(offset(&event, &event.tournament), EventBets::TOURNAMENT_OFFSET);
+    assert_eq!(offset(&event, &event.handicap), EventBets::LEN - 83);
+    assert_eq!(offset(&event, &event.outstanding_bets), EventBets::LEN - 81);
+    assert_eq!(offset(&event, &event.commission_accrued), EventBets::LEN - 77);
+    assert_eq!(offset(&event, &event.correction_window_secs), EventBets::LEN - 69);
+    assert_eq!(offset(&event, &event.claims_count), EventBets::LEN - 61);
+    assert_eq!(offset(&event, &event.commission_recipient), EventBets::LEN - 57);
+    assert_eq!(offset(&event, &event.finalized), EventBets::LEN - 25);
+    assert_eq!(offset(&event, &event.unique_bettors), EventBets::LEN - 24);
+    assert_eq!(offset(&event, &event.max_bets_per_wallet), EventBets::LEN - 20);
+    assert_eq!(offset(&event, &event.allowlisted), EventBets::LEN - 19);
+    assert_eq!(offset(&event, &event.denylisted), EventBets::LEN - 18);
+    assert_eq!(offset(&event, &event.arbiter_may_bet), EventBets::LEN - 17);
+    assert_eq!(offset(&event, &event.correction_cooldown_secs), EventBets::LEN - 16);
+    assert_eq!(offset(&event, &event.last_result_change_at), EventBets::LEN - 8);
+    let bet = BetPod::zeroed();
+    assert_eq!(offset(&bet, &bet.memo), 1 + 32 + 32 + 8 + 1 + 32);
+    assert_eq!(offset(&bet, &bet.delegate), Bet::LEN - 32);
+}
+
+#[test]
+fn test_event_view_reads_and_writes_borsh_bytes() {
+    let mut event = EventBets::try_from_slice(&[0; EventBets::LEN]).unwrap();
+    event.version = STATE_VERSION;
+    event.arbiter = Pubkey::new_unique();
+    event.bets_allowed_until_ts = -1_700_000_000;
+    event.outcome = 2;
+    event.balance_a = 1_000_000;
+    event.balance_b = u64::MAX - 1;
+    event.commission_bps = 300;
+    event.max_imbalance_ratio = 7;
+    event.referral_reserve = 42;
+    event.gate_mint = Pubkey::new_unique();
+    event.bets_count_b = 9;
+    event.paused = true;
+    event.bump = 254;
+    event.seed[31] = b'x';
+    event.odds_b = Odds { numerator: 5, denominator: 2 };
+    event.worst_case_liability = 3_000_000;
+    event.tournament = Pubkey::new_unique();
+    event.market_kind = 1;
+    event.handicap = -3;
+    event.outstanding_bets = 11;
+    event.commission_accrued = 60_000;
+    event.correction_window_secs = 3600;
+    event.claims_count = 2;
+    event.commission_recipient = Pubkey::new_unique();
+    event.finalized = true;
+    event.unique_bettors = 1_204;
+    event.max_bets_per_wallet = 2;
+    event.allowlisted = true;
+    event.denylisted = true;
+    event.arbiter_may_bet = true;
+    event.correction_cooldown_secs = 300;
+    event.last_result_change_at = 1_700_000_000;
+    let mut data = event.try_to_vec().unwrap();
+    // Accounts may be longer than the state.
+    data.extend_from_slice(&[0xff; 3]);
+
+    let view = pod::load::<EventBetsPod>(&data).unwrap();
+    assert_eq!(view.version, STATE_VERSION);
+    assert_eq!(view.arbiter, event.arbiter);
+    assert_eq!(view.bets_allowed_until_ts.get(), -1_700_000_000);
+    assert_eq!(view.outcome, 2);
+    assert_eq!((view.balance_a.get(), view.balance_b.get()), (1_000_000, u64::MAX - 1));
+    assert_eq!((view.commission_bps.get(), view.max_imbalance_ratio.get()), (300, 7));
+    assert_eq!(view.referral_reserve.get(), 42);
+    assert_eq!(view.gate_mint, event.gate_mint);
+    assert_eq!((view.bets_count_a.get(), view.bets_count_b.get()), (0, 9));
+    assert!(view.paused.get() && !view.fixed_odds.get());
+    assert_eq!((view.bump, view.seed), (254, event.seed));
+    assert_eq!((view.odds_b.numerator.get(), view.odds_b.denominator.get()), (5, 2));
+    assert_eq!(view.worst_case_liability.get(), 3_000_000);
+    assert_eq!(view.tournament, event.tournament);
+    assert_eq!((view.market_kind, view.handicap.get()), (1, -3));
+    assert_eq!((view.outstanding_bets.get(), view.commission_accrued.get()), (11, 60_000));
+    assert_eq!((view.correction_window_secs.get(), view.claims_count.get()), (3600, 2));
+    assert_eq!((view.commission_recipient, view.finalized.get()), (event.commission_recipient, true));
+    assert_eq!((view.unique_bettors.get(), view.max_bets_per_wallet), (1_204, 2));
+    assert!(view.allowlisted.get() && view.denylisted.get() && view.arbiter_may_bet.get());
+    assert_eq!((view.correction_cooldown_secs.get(), view.last_result_change_at.get()), (300, 1_700_000_000));
+
+    let view = pod::load_mut::<EventBetsPod>(&mut data).unwrap();
+    view.paused.set(false);
+    view.balance_a.set(7);
+    let event = EventBets::deserialize(&mut &data[..]).unwrap();
+    assert!(!event.paused);
+    assert_eq!((event.balance_a, event.handicap), (7, -3));
+    assert!(pod::load::<EventBetsPod>(&data[..EventBets::LEN - 1]).is_err());
+}
+
+#[test]
+fn test_bet_view_reads_and_writes_borsh_bytes() {
+    let mut bet = Bet::try_from_slice(&[0; Bet::LEN]).unwrap();
+    bet.betor = Pubkey::new_unique();
+    bet.amount = 123_456;
+    bet.outcome = 1;
+    bet.memo = [7; 32];
+    bet.placed_at = 1_700_000_000;
+    bet.for_sale = true;
+    bet.ask_lamports = 99;
+    bet.locked_odds = Odds { numerator: 3, denominator: 1 };
+    bet.delegate = Pubkey::new_unique();
+    let mut data = bet.try_to_vec().unwrap();
+
+    let view = pod::load::<BetPod>(&data).unwrap();
+    assert_eq!(view.version, 0);
+    assert_eq!((view.betor, view.amount.get(), view.outcome), (bet.betor, 123_456, 1));
+    assert_eq!((view.memo, view.placed_at.get()), ([7; 32], 1_700_000_000));
+    assert!(view.for_sale.get());
+    assert_eq!(view.ask_lamports.get(), 99);
+    assert_eq!(view.locked_odds, pod::PodOdds::from(Odds { numerator: 3, denominator: 1 }));
+    assert_eq!(view.delegate, bet.delegate);
+
+    let event = Pubkey::new_unique();
+    let view = pod::load_mut::<BetPod>(&mut data).unwrap();
+    view.version = STATE_VERSION;
+    view.event = event;
+    view.claimed.set(5);
+    let bet = Bet::deserialize(&mut &data[..]).unwrap();
+    assert_eq!(bet.version, STATE_VERSION);
+    assert_eq!((bet.event, bet.claimed, bet.amount), (event, 5, 123_456));
+}
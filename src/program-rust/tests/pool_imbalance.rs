@@ -0,0 +1,51 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{BetError, EventParams, MatchOutcome, IMBALANCE_FLOOR_LAMPORTS};
+use solana_sdk::signature::{Keypair, Signer};
+
+const FLOOR: u64 = IMBALANCE_FLOOR_LAMPORTS;
+
+#[tokio::test]
+async fn test_imbalance_boundary() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { max_imbalance_ratio: 2, ..EventParams::new(now) }).await;
+    let betor = Keypair::new().pubkey();
+
+    // The empty pool B counts as FLOOR, so pool A may reach 2 * FLOOR.
+    env.place_bet(&betor, &event, MatchOutcome::TeamA, 2 * FLOOR).await;
+    assert_eq!(
+        env.try_place_bet(&betor, &event, MatchOutcome::TeamA, 1).await.unwrap_err(),
+        bet_error(0, BetError::PoolImbalance)
+    );
+
+    // Pool B may now reach 2 * 2 * FLOOR.
+    assert_eq!(
+        env.try_place_bet(&betor, &event, MatchOutcome::TeamB, 4 * FLOOR + 1).await.unwrap_err(),
+        bet_error(0, BetError::PoolImbalance)
+    );
+    env.place_bet(&betor, &event, MatchOutcome::TeamB, 4 * FLOOR).await;
+
+    // A = 2F, B = 4F: B is at its limit, A may grow up to 8F.
+    assert_eq!(
+        env.try_place_bet(&betor, &event, MatchOutcome::TeamB, 1).await.unwrap_err(),
+        bet_error(0, BetError::PoolImbalance)
+    );
+    env.place_bet(&betor, &event, MatchOutcome::TeamA, 1).await;
+    assert_eq!(
+        env.try_place_bet(&betor, &event, MatchOutcome::TeamA, 6 * FLOOR).await.unwrap_err(),
+        bet_error(0, BetError::PoolImbalance)
+    );
+    env.place_bet(&betor, &event, MatchOutcome::TeamA, 6 * FLOOR - 1).await;
+}
+
+#[tokio::test]
+async fn test_zero_ratio_is_unlimited() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 10 * FLOOR).await;
+}
@@ -0,0 +1,31 @@
+use helloworld::{check_program_account, id, process_instruction, Instruction as BetInstruction};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+#[test]
+fn test_declared_id() {
+    assert_eq!(id(), "3qVLgui3qavneXQ9ijH6ksoCZjDKKtDeRDonBpRZYVEp".parse::<Pubkey>().unwrap());
+    assert_eq!(check_program_account(&id()), Ok(()));
+    assert_eq!(check_program_account(&Pubkey::new_unique()), Err(ProgramError::IncorrectProgramId));
+}
+
+#[cfg(not(feature = "any-id"))]
+#[tokio::test]
+async fn test_other_deployment_is_refused() {
+    let program_id = Pubkey::new_unique();
+    let program_test = ProgramTest::new("helloworld", program_id, processor!(process_instruction));
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let ix = Instruction::new_with_bytes(program_id, &BetInstruction::QuoteOdds.pack(), vec![]);
+    let transaction = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer], recent_blockhash);
+    assert_eq!(
+        banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::IncorrectProgramId)
+    );
+}
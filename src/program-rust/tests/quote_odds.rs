@@ -0,0 +1,73 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use helloworld::{EventBets, Instruction as BetInstruction, MatchOutcome, OddsQuote};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+async fn quote(env: &mut common::Env, event: &Pubkey) -> OddsQuote {
+    EventBets::deserialize(&mut &env.data(event).await[..]).unwrap().quote_odds()
+}
+
+#[test]
+fn test_quote_odds_pack() {
+    assert_eq!(BetInstruction::QuoteOdds.pack(), vec![14]);
+    assert_eq!(BetInstruction::unpack(&[14]).unwrap(), BetInstruction::QuoteOdds);
+}
+
+#[test]
+fn test_quote_layout() {
+    let quote = OddsQuote { balance_a: 1, balance_b: 2, implied_a_bps: 3_333, implied_b_bps: 6_666, commission_bps: 300, market_kind: 1, handicap: -3 };
+    let data = quote.try_to_vec().unwrap();
+    assert_eq!(data.len(), 8 + 8 + 2 + 2 + 2 + 1 + 2);
+    assert_eq!(OddsQuote::try_from_slice(&data).unwrap(), quote);
+}
+
+#[tokio::test]
+async fn test_quote_for_pool_shapes() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    assert_eq!(
+        quote(&mut env, &event).await,
+        OddsQuote { balance_a: 0, balance_b: 0, implied_a_bps: 0, implied_b_bps: 0, commission_bps: 300, market_kind: 0, handicap: 0 }
+    );
+
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 1_000).await;
+    let one_sided = quote(&mut env, &event).await;
+    assert_eq!((one_sided.implied_a_bps, one_sided.implied_b_bps), (10_000, 0));
+
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 3_000).await;
+    let quarter = quote(&mut env, &event).await;
+    assert_eq!((quarter.balance_a, quarter.balance_b), (1_000, 3_000));
+    assert_eq!((quarter.implied_a_bps, quarter.implied_b_bps), (2_500, 7_500));
+
+    // Thirds round down on both sides.
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 500).await;
+    let thirds = quote(&mut env, &event).await;
+    assert_eq!((thirds.implied_a_bps, thirds.implied_b_bps), (3_333, 6_666));
+}
+
+#[tokio::test]
+async fn test_quote_odds_changes_nothing() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 1_000).await;
+    let data = env.data(&event).await;
+    let lamports = env.lamports(&event).await;
+
+    let ix = Instruction::new_with_bytes(env.program_id, &BetInstruction::QuoteOdds.pack(), vec![AccountMeta::new_readonly(event, false)]);
+    env.send(&[ix], &[]).await.unwrap();
+    assert_eq!(env.data(&event).await, data);
+    assert_eq!(env.lamports(&event).await, lamports);
+
+    // Only events can be quoted.
+    let ix = Instruction::new_with_bytes(env.program_id, &BetInstruction::QuoteOdds.pack(), vec![AccountMeta::new_readonly(arbiter.pubkey(), false)]);
+    assert!(env.send(&[ix], &[]).await.is_err());
+}
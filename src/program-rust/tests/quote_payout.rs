@@ -0,0 +1,80 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{quote_payout, Bet, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+async fn send_quote(env: &mut common::Env, event: &Pubkey, bet: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::QuotePayout.pack(),
+        vec![AccountMeta::new_readonly(*event, false), AccountMeta::new_readonly(*bet, false)],
+    );
+    env.send(&[ix], &[]).await
+}
+
+// Quotes the bet, withdraws it and checks the betor got exactly the quote.
+async fn quote_then_withdraw(env: &mut common::Env, betor: &Pubkey, event: &Pubkey, bet: &Pubkey) -> u64 {
+    send_quote(env, event, bet).await.unwrap();
+    let bets = EventBets::deserialize(&mut &env.data(event).await[..]).unwrap();
+    let this_bet = Bet::deserialize(&mut &env.data(bet).await[..]).unwrap();
+    let quote = quote_payout(&bets, &this_bet).unwrap();
+
+    let before = env.lamports(betor).await;
+    env.withdraw(betor, event, bet).await.unwrap();
+    assert_eq!(env.lamports(betor).await - before, quote);
+    quote
+}
+
+#[test]
+fn test_quote_payout_pack() {
+    assert_eq!(BetInstruction::QuotePayout.pack(), vec![15]);
+    assert_eq!(BetInstruction::unpack(&[15]).unwrap(), BetInstruction::QuotePayout);
+}
+
+#[tokio::test]
+async fn test_quote_matches_winner_and_loser_payouts() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    env.top_up_pot(&arbiter, &event, 333_333).await.unwrap();
+
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_234_567).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamB, 7_654_321).await;
+    // No quote before the result.
+    assert!(send_quote(&mut env, &event, &alice_bet).await.is_err());
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    assert!(quote_then_withdraw(&mut env, &alice, &event, &alice_bet).await > 1_234_567);
+    assert_eq!(quote_then_withdraw(&mut env, &bob, &event, &bob_bet).await, 0);
+    // A withdrawn bet can't be quoted, same as it can't be withdrawn again.
+    assert!(send_quote(&mut env, &event, &alice_bet).await.is_err());
+}
+
+#[tokio::test]
+async fn test_quote_matches_draw_payout() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_001).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamB, 2_000_003).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::Draw).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    assert_eq!(quote_then_withdraw(&mut env, &alice, &event, &alice_bet).await, 1_000_001 * 97 / 100);
+    assert_eq!(quote_then_withdraw(&mut env, &bob, &event, &bob_bet).await, 2_000_003 * 97 / 100);
+}
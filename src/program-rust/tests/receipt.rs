@@ -0,0 +1,111 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{receipt_mint_address, Bet, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::TransactionError,
+};
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+
+async fn tokenize_bet(env: &mut common::Env, betor: &Keypair, event: &Pubkey, bet: &Pubkey) -> Result<Pubkey, TransactionError> {
+    let mint = receipt_mint_address(&env.program_id, bet).0;
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::TokenizeBet.pack(),
+        vec![
+            AccountMeta::new(betor.pubkey(), true),
+            AccountMeta::new_readonly(*event, false),
+            AccountMeta::new(*bet, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(get_associated_token_address(&betor.pubkey(), &mint), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    env.send(&[ix], &[betor]).await.map(|_| mint)
+}
+
+fn withdraw_receipt_ix(env: &common::Env, holder: &Pubkey, event: &Pubkey, bet: &Pubkey, mint: &Pubkey) -> Instruction {
+    let mut ix = env.withdraw_ix(holder, event, bet);
+    ix.accounts[0] = AccountMeta::new(*holder, true);
+    ix.accounts.extend(vec![
+        AccountMeta::new(get_associated_token_address(holder, mint), false),
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]);
+    ix
+}
+
+#[test]
+fn test_tokenize_bet_unpack() {
+    assert_eq!(BetInstruction::unpack(&BetInstruction::TokenizeBet.pack()).unwrap(), BetInstruction::TokenizeBet);
+}
+
+#[tokio::test]
+async fn test_holder_of_the_receipt_withdraws() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    env.fund(&alice.pubkey(), 100_000_000).await;
+    env.fund(&bob.pubkey(), 100_000_000).await;
+    let bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+
+    // Only the betor can tokenize.
+    assert!(tokenize_bet(&mut env, &bob, &event, &bet).await.is_err());
+    let mint = tokenize_bet(&mut env, &alice, &event, &bet).await.unwrap();
+    let receipt = spl_token::state::Mint::unpack(&env.data(&mint).await).unwrap();
+    assert_eq!((receipt.supply, receipt.decimals, receipt.mint_authority), (1, 0, COption::None));
+    let alice_token = get_associated_token_address(&alice.pubkey(), &mint);
+    assert_eq!(env.token_balance(&alice_token).await, 1);
+    assert_eq!(Bet::deserialize(&mut &env.data(&bet).await[..]).unwrap().receipt_mint, mint);
+    // Once is enough, and the bet can no longer be handed over directly.
+    assert!(tokenize_bet(&mut env, &alice, &event, &bet).await.is_err());
+    let transfer = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::TransferBet { new_betor: bob.pubkey() }.pack(),
+        vec![AccountMeta::new_readonly(alice.pubkey(), true), AccountMeta::new_readonly(event, false), AccountMeta::new(bet, false)],
+    );
+    assert!(env.send(&[transfer], &[&alice]).await.is_err());
+
+    // Selling the bet is now a plain token transfer.
+    let bob_token = get_associated_token_address(&bob.pubkey(), &mint);
+    env.send(
+        &[
+            create_associated_token_account(&bob.pubkey(), &bob.pubkey(), &mint),
+            spl_token::instruction::transfer(&spl_token::id(), &alice_token, &bob_token, &alice.pubkey(), &[], 1).unwrap(),
+        ],
+        &[&bob, &alice],
+    )
+    .await
+    .unwrap();
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    let alice_ix = withdraw_receipt_ix(&env, &alice.pubkey(), &event, &bet, &mint);
+    assert!(env.send(&[alice_ix], &[&alice]).await.is_err());
+    // The holder has to sign for the burn.
+    let mut unsigned = withdraw_receipt_ix(&env, &bob.pubkey(), &event, &bet, &mint);
+    unsigned.accounts[0] = AccountMeta::new(bob.pubkey(), false);
+    assert!(env.send(&[unsigned], &[]).await.is_err());
+
+    let before = env.lamports(&bob.pubkey()).await;
+    let bob_ix = withdraw_receipt_ix(&env, &bob.pubkey(), &event, &bet, &mint);
+    env.send(&[bob_ix], &[&bob]).await.unwrap();
+    assert_eq!(env.lamports(&bob.pubkey()).await - before, 970_000);
+    assert_eq!(env.token_balance(&bob_token).await, 0);
+    assert_eq!(spl_token::state::Mint::unpack(&env.data(&mint).await).unwrap().supply, 0);
+    let paid = Bet::deserialize(&mut &env.data(&bet).await[..]).unwrap();
+    assert_eq!((paid.betor, paid.receipt_mint), (bob.pubkey(), Pubkey::default()));
+    assert!(env.withdraw(&bob.pubkey(), &event, &bet).await.is_err());
+}
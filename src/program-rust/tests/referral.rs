@@ -0,0 +1,144 @@
+#![cfg(feature = "test-admin")]
+
+mod common;
+
+use helloworld::{ConfigParams, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const PARAMS: ConfigParams = ConfigParams {
+    default_commission_bps: 300,
+    max_commission_bps: 1_000,
+    min_deadline_offset_secs: 0,
+    arbiter_share_bps: 10_000,
+    referral_bps: 2_000,
+};
+
+// SetWinner with the config, so that referrers are paid.
+async fn set_winner(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, result: MatchOutcome) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::SetWinner { result, metadata_hash: [0; 32] }.pack(),
+        vec![
+            AccountMeta::new(arbiter.pubkey(), true),
+            AccountMeta::new(*event, false),
+            AccountMeta::new_readonly(env.config(), false),
+            AccountMeta::new(env.treasury(), false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+#[tokio::test]
+async fn test_referrer_gets_share_of_commission() {
+    let mut env = common::start().await;
+    let admin = common::test_admin();
+    env.fund(&admin.pubkey(), 1_000_000_000).await;
+    env.initialize_config(&admin, PARAMS).await.unwrap();
+
+    let arbiter = Keypair::new();
+    let referrer = Keypair::new().pubkey();
+    env.fund(&referrer, 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let carol = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    // Self referral is rejected.
+    assert!(env.try_place_referred_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000, &alice).await.is_err());
+    let alice_bet = env.try_place_referred_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000, &referrer).await.unwrap();
+    let carol_bet = env.place_bet(&carol, &event, MatchOutcome::TeamA, 3_000_000).await;
+    let bob_bet = env.try_place_referred_bet(&bob, &event, MatchOutcome::TeamB, 4_000_000, &referrer).await.unwrap();
+
+    set_winner(&mut env, &arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, 0);
+    env.close_correction_window(&event).await;
+
+    // The referrer account is required and must be the right one.
+    assert!(env.withdraw(&alice, &event, &alice_bet).await.is_err());
+    let mut ix = env.withdraw_ix(&alice, &event, &alice_bet);
+    ix.accounts.push(AccountMeta::new(Keypair::new().pubkey(), false));
+    assert!(env.send(&[ix], &[]).await.is_err());
+
+    let referrer_before = env.lamports(&referrer).await;
+    let mut ix = env.withdraw_ix(&alice, &event, &alice_bet);
+    ix.accounts.push(AccountMeta::new(referrer, false));
+    env.send(&[ix], &[]).await.unwrap();
+    assert_eq!(env.lamports(&referrer).await - referrer_before, 12_000);
+    assert_eq!(env.lamports(&alice).await, 1_940_000);
+
+    // A losing referred bet pays nothing to anyone.
+    let mut ix = env.withdraw_ix(&bob, &event, &bob_bet);
+    ix.accounts.push(AccountMeta::new(referrer, false));
+    env.send(&[ix], &[]).await.unwrap();
+    assert_eq!(env.lamports(&referrer).await - referrer_before, 12_000);
+    assert_eq!(env.lamports(&bob).await, 0);
+
+    env.withdraw(&carol, &event, &carol_bet).await.unwrap();
+    assert_eq!(env.lamports(&carol).await, 5_820_000);
+
+    // Alice's gross payout is 2_000_000, its commission 60_000, 20% of it went to the referrer.
+    let mut ix = env.collect_commission_ix(&arbiter.pubkey(), &event);
+    ix.accounts.push(AccountMeta::new_readonly(env.config(), false));
+    ix.accounts.push(AccountMeta::new(env.treasury(), false));
+    env.send(&[ix], &[&arbiter]).await.unwrap();
+    let commission = 8_000_000 * 3 / 100;
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, commission - 12_000);
+    assert_eq!(env.lamports(&event).await, env.rent(EventBets::LEN).await);
+}
+
+#[tokio::test]
+async fn test_correction_moves_the_referral_reserve() {
+    let mut env = common::start().await;
+    let admin = common::test_admin();
+    env.fund(&admin.pubkey(), 1_000_000_000).await;
+    env.initialize_config(&admin, PARAMS).await.unwrap();
+
+    let arbiter = Keypair::new();
+    let referrer = Keypair::new().pubkey();
+    env.fund(&referrer, 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.try_place_referred_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000, &referrer).await.unwrap();
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 3_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 4_000_000).await;
+
+    // Nothing referred won on B.
+    set_winner(&mut env, &arbiter, &event, MatchOutcome::TeamB).await.unwrap();
+    assert_eq!(EventBets::unpack(&env.data(&event).await).unwrap().referral_reserve, 0);
+    env.correct_result(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    assert_eq!(EventBets::unpack(&env.data(&event).await).unwrap().referral_reserve, 12_000);
+    env.close_correction_window(&event).await;
+
+    let mut ix = env.withdraw_ix(&alice, &event, &alice_bet);
+    ix.accounts.push(AccountMeta::new(referrer, false));
+    env.send(&[ix], &[]).await.unwrap();
+    assert_eq!(env.lamports(&referrer).await, 1_000_000_000 + 12_000);
+    assert_eq!(env.lamports(&alice).await, 1_940_000);
+}
+
+#[tokio::test]
+async fn test_no_config_no_referral_share() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let referrer = Keypair::new().pubkey();
+    env.fund(&referrer, 1_000_000_000).await;
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.try_place_referred_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000, &referrer).await.unwrap();
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    let mut ix = env.withdraw_ix(&alice, &event, &alice_bet);
+    ix.accounts.push(AccountMeta::new(referrer, false));
+    env.send(&[ix], &[]).await.unwrap();
+    assert_eq!(env.lamports(&referrer).await, 1_000_000_000);
+    assert_eq!(env.lamports(&alice).await, 970_000);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::bet_error;
+use helloworld::{registry_address, BetError, EventParams, Instruction as BetInstruction, RegistryPage, REGISTRY_PAGE_CAPACITY};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+async fn create_page(env: &mut common::Env, page: u32) -> Result<(), TransactionError> {
+    let payer = env.payer();
+    let mut accounts = vec![
+        AccountMeta::new(payer.pubkey(), true),
+        AccountMeta::new(registry_address(&env.program_id, page).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if page > 0 {
+        accounts.push(AccountMeta::new_readonly(registry_address(&env.program_id, page - 1).0, false));
+    }
+    let ix = Instruction::new_with_bytes(env.program_id, &BetInstruction::CreateRegistryPage { page }.pack(), accounts);
+    env.send(&[ix], &[]).await
+}
+
+fn page_account(env: &common::Env, page: u32) -> Vec<AccountMeta> {
+    vec![AccountMeta::new(registry_address(&env.program_id, page).0, false)]
+}
+
+#[test]
+fn test_create_registry_page_unpack() {
+    let ix = BetInstruction::CreateRegistryPage { page: 3 };
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    assert!(BetInstruction::unpack(&[29, 1]).is_err());
+}
+
+#[tokio::test]
+async fn test_events_are_listed_in_order() {
+    let mut env = common::start().await;
+    create_page(&mut env, 0).await.unwrap();
+    assert!(create_page(&mut env, 0).await.is_err());
+    // Page 0 has room, so no page 1 yet.
+    assert!(create_page(&mut env, 1).await.is_err());
+
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let mut events = vec![];
+    for _ in 0..3 {
+        let accounts = page_account(&env, 0);
+        events.push(env.try_create_event_with_accounts(&arbiter, EventParams::new(now), accounts).await.unwrap());
+    }
+    // Registering is optional.
+    env.create_event(&arbiter, now).await;
+
+    let data = env.data(&registry_address(&env.program_id, 0).0).await;
+    let page = RegistryPage::deserialize(&mut &data[..]).unwrap();
+    assert_eq!((page.page, page.count), (0, 3));
+    let listed: Vec<_> = (0..3).map(|index| RegistryPage::event(&data, index).unwrap()).collect();
+    assert_eq!(listed, events);
+}
+
+#[tokio::test]
+async fn test_full_page_moves_on_to_the_next() {
+    let mut env = common::start().await;
+    create_page(&mut env, 0).await.unwrap();
+    let first = registry_address(&env.program_id, 0).0;
+    env.corrupt(&first, |account| {
+        let full = RegistryPage { is_initialized: true, page: 0, count: REGISTRY_PAGE_CAPACITY as u32 };
+        full.serialize(&mut &mut account.data[..]).unwrap();
+    })
+    .await;
+
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let accounts = page_account(&env, 0);
+    assert_eq!(
+        env.try_create_event_with_accounts(&arbiter, EventParams::new(now), accounts).await.unwrap_err(),
+        bet_error(0, BetError::RegistryPageFull)
+    );
+
+    create_page(&mut env, 1).await.unwrap();
+    let accounts = page_account(&env, 1);
+    let event = env.try_create_event_with_accounts(&arbiter, EventParams::new(now), accounts).await.unwrap();
+    let data = env.data(&registry_address(&env.program_id, 1).0).await;
+    assert_eq!(RegistryPage::deserialize(&mut &data[..]).unwrap().count, 1);
+    assert_eq!(RegistryPage::event(&data, 0), Some(event));
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use helloworld::{Bet, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::TransactionError,
+};
+
+fn reset_bet_ix(env: &common::Env, betor: &Pubkey, bet: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::ResetBet.pack(),
+        vec![AccountMeta::new_readonly(*betor, true), AccountMeta::new(*bet, false)],
+    )
+}
+
+// Funds `bet` with `amount`, resets it and places it on `event`, all in one transaction.
+async fn replace_bet(env: &mut common::Env, betor: &Keypair, bet: &Pubkey, event: &Pubkey, choice: MatchOutcome, amount: u64) -> Result<(), TransactionError> {
+    let fund = system_instruction::transfer(&env.payer().pubkey(), bet, amount);
+    let reset = reset_bet_ix(env, &betor.pubkey(), bet);
+    let add_bet = env.add_bet_ix(&betor.pubkey(), event, bet, choice);
+    env.send(&[fund, reset, add_bet], &[betor]).await
+}
+
+async fn event_state(env: &mut common::Env, event: &Pubkey) -> EventBets {
+    EventBets::unpack(&env.data(event).await).unwrap()
+}
+
+#[test]
+fn test_reset_bet_unpack() {
+    assert_eq!(BetInstruction::unpack(&[44]).unwrap(), BetInstruction::ResetBet);
+    assert_eq!(BetInstruction::ResetBet.pack(), vec![44]);
+}
+
+#[tokio::test]
+async fn test_bet_account_is_reused_after_settlement() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let first = env.create_event(&arbiter, now).await;
+    let (alice, bob) = (Keypair::new(), Keypair::new());
+    let alice_bet = env.place_bet(&alice.pubkey(), &first, MatchOutcome::TeamA, 1_000_000).await;
+    let bob_bet = env.place_bet(&bob.pubkey(), &first, MatchOutcome::TeamB, 1_000_000).await;
+    env.set_winner(&arbiter, &first, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&first).await;
+    env.withdraw(&alice.pubkey(), &first, &alice_bet).await.unwrap();
+
+    let now = env.now().await;
+    let second = env.create_event(&arbiter, now + 3600).await;
+    replace_bet(&mut env, &alice, &alice_bet, &second, MatchOutcome::TeamB, 500_000).await.unwrap();
+
+    // Every field is the new bet's, none is left over from the first event.
+    let this_bet = Bet::unpack(&env.data(&alice_bet).await).unwrap();
+    assert_eq!((this_bet.betor, this_bet.event), (alice.pubkey(), second));
+    assert_eq!((this_bet.amount, this_bet.outcome, this_bet.claimed), (500_000, u8::from(MatchOutcome::TeamB), 0));
+    assert_eq!(env.lamports(&alice_bet).await, env.rent(Bet::LEN).await);
+    let state = event_state(&mut env, &second).await;
+    assert_eq!((state.balance_a, state.balance_b, state.outstanding_bets), (0, 500_000, 1));
+    // The first event still waits for bob only.
+    assert_eq!(event_state(&mut env, &first).await.outstanding_bets, 1);
+    env.withdraw(&bob.pubkey(), &first, &bob_bet).await.unwrap();
+    assert_eq!(event_state(&mut env, &first).await.outstanding_bets, 0);
+}
+
+#[tokio::test]
+async fn test_bet_still_outstanding_is_not_reset() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let deadline = env.now().await + 60;
+    let first = env.create_event(&arbiter, deadline).await;
+    let second = env.create_event(&arbiter, deadline).await;
+    let alice = Keypair::new();
+    let alice_bet = env.place_bet(&alice.pubkey(), &first, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &first, MatchOutcome::TeamB, 1_000_000).await;
+    let failed = TransactionError::InstructionError(1, InstructionError::InvalidAccountData);
+
+    // The event is still open.
+    assert_eq!(replace_bet(&mut env, &alice, &alice_bet, &second, MatchOutcome::TeamA, 1).await.unwrap_err(), failed);
+    // Settled, but not withdrawn yet.
+    env.set_now(deadline).await;
+    env.set_winner(&arbiter, &first, MatchOutcome::TeamA).await.unwrap();
+    assert_eq!(replace_bet(&mut env, &alice, &alice_bet, &second, MatchOutcome::TeamA, 1).await.unwrap_err(), failed);
+    let this_bet = Bet::unpack(&env.data(&alice_bet).await).unwrap();
+    assert_eq!((this_bet.event, this_bet.amount), (first, 1_000_000));
+
+    // Once withdrawn, only its betor may reset it.
+    env.close_correction_window(&first).await;
+    env.withdraw(&alice.pubkey(), &first, &alice_bet).await.unwrap();
+    assert_eq!(replace_bet(&mut env, &Keypair::new(), &alice_bet, &second, MatchOutcome::TeamA, 1).await.unwrap_err(), failed);
+    assert!(env.send(&[reset_bet_ix(&env, &alice.pubkey(), &alice_bet)], &[&alice]).await.is_ok());
+    assert_eq!(env.data(&alice_bet).await, vec![0; Bet::LEN]);
+}
@@ -0,0 +1,146 @@
+use borsh::{
+    schema::{BorshSchemaContainer, Definition, Fields},
+    BorshSerialize,
+};
+use helloworld::{
+    schema::{self, WireInstruction, MATCH_OUTCOME_VALUES},
+    Bet, ConfigParams, EventBets, EventParams, Instruction as BetInstruction, MatchOutcome, Odds, MEMO_BYTES, SIGNATURE_BYTES,
+};
+use solana_sdk::pubkey::Pubkey;
+
+// Encoded size of `declaration` per the container, None when it depends on the value.
+fn fixed_size(container: &BorshSchemaContainer, declaration: &str) -> Option<usize> {
+    let primitive = match declaration {
+        "u8" | "i8" | "bool" => Some(1),
+        "u16" | "i16" => Some(2),
+        "u32" | "i32" | "f32" => Some(4),
+        "u64" | "i64" | "f64" => Some(8),
+        "u128" | "i128" => Some(16),
+        _ => None,
+    };
+    if primitive.is_some() {
+        return primitive;
+    }
+    let sum = |declarations: &mut dyn Iterator<Item = &String>| declarations.map(|d| fixed_size(container, d)).sum::<Option<usize>>();
+    match container.definitions.get(declaration).unwrap_or_else(|| panic!("no definition of {}", declaration)) {
+        Definition::Array { length, elements } => fixed_size(container, elements).map(|size| size * *length as usize),
+        Definition::Struct { fields: Fields::NamedFields(fields) } => sum(&mut fields.iter().map(|(_, d)| d)),
+        Definition::Struct { fields: Fields::UnnamedFields(fields) } => sum(&mut fields.iter()),
+        Definition::Struct { fields: Fields::Empty } => Some(0),
+        Definition::Tuple { elements } => sum(&mut elements.iter()),
+        Definition::Sequence { .. } | Definition::Enum { .. } => None,
+    }
+}
+
+fn instructions() -> Vec<BetInstruction> {
+    let params = EventParams { bond_lamports: 5, challenger: Pubkey::new_unique(), handicap: -3, ..EventParams::new(1_700_000_000) };
+    let config = ConfigParams { default_commission_bps: 300, max_commission_bps: 1000, min_deadline_offset_secs: 60, arbiter_share_bps: 2000, referral_bps: 500 };
+    vec![
+        BetInstruction::Initialize(params),
+        BetInstruction::AddBet { choice: MatchOutcome::TeamB, referrer: Pubkey::new_unique(), amount: 7, memo: [9; MEMO_BYTES] },
+        BetInstruction::SetWinner { result: MatchOutcome::Draw, metadata_hash: [0; 32] },
+        BetInstruction::Withdraw { amount: 1 },
+        BetInstruction::SetWinnerSigned { result: MatchOutcome::TeamA, event: Pubkey::new_unique(), signature: [3; SIGNATURE_BYTES] },
+        BetInstruction::CorrectResult { result: MatchOutcome::TeamB },
+        BetInstruction::SlashBond,
+        BetInstruction::ReleaseBond,
+        BetInstruction::InitializeConfig(config),
+        BetInstruction::UpdateConfig(config),
+        BetInstruction::WithdrawTreasury { amount: 2 },
+        BetInstruction::SeedLiquidity,
+        BetInstruction::TopUpPot { amount: 3 },
+        BetInstruction::AuditEvent,
+        BetInstruction::QuoteOdds,
+        BetInstruction::QuotePayout,
+        BetInstruction::IncreaseBet { choice: MatchOutcome::TeamA, amount: 4 },
+        BetInstruction::CashOut,
+        BetInstruction::TransferBet { new_betor: Pubkey::new_unique() },
+        BetInstruction::ListBet { price: 5 },
+        BetInstruction::BuyBet { price: 6 },
+        BetInstruction::TokenizeBet,
+        BetInstruction::WithdrawMany,
+        BetInstruction::SettleBets,
+        BetInstruction::VoidBet { reason: 2 },
+        BetInstruction::PauseEvent,
+        BetInstruction::UnpauseEvent,
+        BetInstruction::HaltProgram,
+        BetInstruction::ResumeProgram,
+        BetInstruction::CreateRegistryPage { page: 8 },
+        BetInstruction::InitializeIndexed(params),
+        BetInstruction::InitializeSeeded { seed: "epl-2024-md7".to_string(), params },
+        BetInstruction::InitializeMarket { bets_accepted_until: 1_700_000_000, num_outcomes: 3, commission_bps: 250 },
+        BetInstruction::AddMarketBet { outcome: 2 },
+        BetInstruction::SetMarketWinner { winner: 1 },
+        BetInstruction::WithdrawMarketBet,
+        BetInstruction::SetOdds { odds_a: Odds { numerator: 5, denominator: 2 }, odds_b: Odds { numerator: 3, denominator: 1 } },
+        BetInstruction::CreateParlayPool,
+        BetInstruction::PlaceParlay { choices: vec![MatchOutcome::TeamA, MatchOutcome::TeamB] },
+        BetInstruction::SettleParlay,
+        BetInstruction::CreateTournament { slug: "ucl".to_string(), name: "Champions League – Matchday 3".to_string() },
+        BetInstruction::AddEventToTournament,
+        BetInstruction::MigrateEvent,
+        BetInstruction::SweepDust,
+        BetInstruction::ResetBet,
+        BetInstruction::AddBetFor { choice: MatchOutcome::TeamA, beneficiary: Pubkey::new_unique(), amount: 0 },
+        BetInstruction::SetDelegate { delegate: Pubkey::new_unique() },
+        BetInstruction::CollectCommission,
+        BetInstruction::FinalizeResult,
+        BetInstruction::CreateLeaderboard,
+        BetInstruction::AddToAllowlist { wallet: Pubkey::new_unique() },
+        BetInstruction::RemoveFromAllowlist { wallet: Pubkey::new_unique() },
+        BetInstruction::AddToDenylist { wallet: Pubkey::new_unique() },
+        BetInstruction::RemoveFromDenylist { wallet: Pubkey::new_unique() },
+    ]
+}
+
+#[test]
+fn test_state_schemas_have_the_account_sizes() {
+    assert_eq!(fixed_size(&schema::event_bets(), "EventBets"), Some(EventBets::LEN));
+    assert_eq!(fixed_size(&schema::bet(), "Bet"), Some(Bet::LEN));
+    assert_eq!(fixed_size(&schema::match_outcome(), "MatchOutcome"), Some(1));
+    let declarations: Vec<_> = schema::all().into_iter().map(|container| container.declaration).collect();
+    assert_eq!(declarations, ["MatchOutcome", "EventBets", "Bet", "WireInstruction"]);
+}
+
+#[cfg(feature = "borsh-state")]
+#[test]
+fn test_state_schemas_match_borsh_lengths() {
+    use borsh::BorshDeserialize;
+    let event = EventBets::try_from_slice(&[0; EventBets::LEN]).unwrap();
+    assert_eq!(Some(event.try_to_vec().unwrap().len()), fixed_size(&schema::event_bets(), "EventBets"));
+    let bet = Bet::try_from_slice(&[0; Bet::LEN]).unwrap();
+    assert_eq!(Some(bet.try_to_vec().unwrap().len()), fixed_size(&schema::bet(), "Bet"));
+}
+
+#[test]
+fn test_match_outcome_schema_values() {
+    for &(name, value) in MATCH_OUTCOME_VALUES {
+        let outcome = [MatchOutcome::Unknown, MatchOutcome::TeamA, MatchOutcome::TeamB, MatchOutcome::Draw, MatchOutcome::Voided, MatchOutcome::Withdrawn]
+            .iter()
+            .find(|outcome| format!("{:?}", outcome) == name)
+            .unwrap();
+        assert_eq!(outcome.try_to_vec().unwrap(), [value]);
+    }
+    assert_eq!(MATCH_OUTCOME_VALUES.len(), 6);
+}
+
+#[test]
+fn test_wire_instruction_encodes_as_pack() {
+    let container = schema::instruction();
+    let variants = match &container.definitions["WireInstruction"] {
+        Definition::Enum { variants } => variants.clone(),
+        _ => panic!("WireInstruction is an enum"),
+    };
+    let instructions = instructions();
+    assert_eq!(variants.len(), instructions.len());
+    for instruction in &instructions {
+        let packed = instruction.pack();
+        assert_eq!(WireInstruction::from(instruction).try_to_vec().unwrap(), packed, "{:?}", instruction);
+        // The tag is the variant index.
+        let (name, declaration) = &variants[packed[0] as usize];
+        assert!(format!("{:?}", instruction).starts_with(name.as_str()), "{} for {:?}", name, instruction);
+        if let Some(size) = fixed_size(&container, declaration) {
+            assert_eq!(1 + size, packed.len(), "{:?}", instruction);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::bet_error;
+use helloworld::{seeded_event_address, BetError, EventBets, EventParams, Instruction as BetInstruction};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::TransactionError,
+};
+
+async fn initialize_seeded(env: &mut common::Env, arbiter: &Keypair, seed: &str, event: &Pubkey) -> Result<(), TransactionError> {
+    let now = env.now().await;
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::InitializeSeeded { seed: seed.to_string(), params: EventParams::new(now) }.pack(),
+        vec![
+            AccountMeta::new(arbiter.pubkey(), true),
+            AccountMeta::new(*event, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+#[test]
+fn test_initialize_seeded_unpack() {
+    let ix = BetInstruction::InitializeSeeded { seed: "epl-2024-ars-che".to_string(), params: EventParams::new(42) };
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    // Not UTF-8.
+    let mut data = vec![31, 2, 0xff, 0xfe];
+    data.extend_from_slice(&42i64.to_le_bytes());
+    assert!(BetInstruction::unpack(&data).is_err());
+}
+
+#[tokio::test]
+async fn test_seed_is_unique_per_arbiter() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let seed = "epl-2024-ars-che";
+    let event = seeded_event_address(&env.program_id, &arbiter.pubkey(), seed).0;
+
+    initialize_seeded(&mut env, &arbiter, seed, &event).await.unwrap();
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!(bets.arbiter, arbiter.pubkey());
+    assert_eq!(&bets.seed[..seed.len()], seed.as_bytes());
+    assert!(bets.seed[seed.len()..].iter().all(|&byte| byte == 0));
+
+    assert_eq!(initialize_seeded(&mut env, &arbiter, seed, &event).await.unwrap_err(), bet_error(0, BetError::EventSeedTaken));
+
+    let other = Keypair::new();
+    env.fund(&other.pubkey(), 1_000_000_000).await;
+    let other_event = seeded_event_address(&env.program_id, &other.pubkey(), seed).0;
+    assert_ne!(other_event, event);
+    initialize_seeded(&mut env, &other, seed, &other_event).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_bad_seeds_are_rejected() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let too_long = "x".repeat(33);
+    for seed in &["", "with space", "ünïcode", too_long.as_str()] {
+        let event = seeded_event_address(&env.program_id, &arbiter.pubkey(), "x").0;
+        assert!(initialize_seeded(&mut env, &arbiter, seed, &event).await.is_err(), "{:?}", seed);
+    }
+    // The seed must match the address.
+    let event = seeded_event_address(&env.program_id, &arbiter.pubkey(), "one").0;
+    assert!(initialize_seeded(&mut env, &arbiter, "two", &event).await.is_err());
+}
@@ -0,0 +1,71 @@
+// Run with `cargo test --features serde`.
+#![cfg(feature = "serde")]
+
+use helloworld::{Bet, EventBets, MatchOutcome};
+use serde_json::{json, Value};
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+use std::str::FromStr;
+
+// The state_pack.rs fixtures once migrated, as JSON. Changing these files changes what consumers read.
+const EVENT_JSON: &str = include_str!("fixtures/event_bets.json");
+const BET_JSON: &str = include_str!("fixtures/bet.json");
+
+fn current(fixture: &[u8]) -> Vec<u8> {
+    let mut data = fixture.to_vec();
+    data[0] = helloworld::STATE_VERSION;
+    data
+}
+
+#[test]
+fn test_event_matches_the_golden_json() {
+    let event = EventBets::unpack_from_slice(&current(include_bytes!("fixtures/event_bets.bin"))).unwrap();
+    let golden: Value = serde_json::from_str(EVENT_JSON).unwrap();
+    assert_eq!(serde_json::to_value(&event).unwrap(), golden);
+    assert_eq!(golden["arbiter"], Pubkey::new_from_array([0x11; 32]).to_string());
+    assert_eq!(golden["mint"], "11111111111111111111111111111111");
+    assert_eq!(golden["bets_allowed_until_ts"], 1_700_000_000);
+    assert_eq!(golden["handicap"], -3);
+
+    let decoded: EventBets = serde_json::from_str(EVENT_JSON).unwrap();
+    let mut data = vec![0; EventBets::LEN];
+    decoded.pack_into_slice(&mut data);
+    assert_eq!(data, current(include_bytes!("fixtures/event_bets.bin")));
+}
+
+#[test]
+fn test_bet_matches_the_golden_json() {
+    let bet = Bet::unpack_from_slice(&current(include_bytes!("fixtures/bet.bin"))).unwrap();
+    let golden: Value = serde_json::from_str(BET_JSON).unwrap();
+    assert_eq!(serde_json::to_value(&bet).unwrap(), golden);
+    assert_eq!(golden["locked_odds"], json!({ "numerator": 5, "denominator": 2 }));
+    assert_eq!(Pubkey::from_str(golden["betor"].as_str().unwrap()).unwrap(), Pubkey::new_from_array([0x55; 32]));
+
+    let decoded: Bet = serde_json::from_str(BET_JSON).unwrap();
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), golden);
+}
+
+#[test]
+fn test_bad_pubkey_is_refused() {
+    let mut golden: Value = serde_json::from_str(BET_JSON).unwrap();
+    golden["event"] = json!("not-a-pubkey");
+    assert!(serde_json::from_value::<Bet>(golden.clone()).is_err());
+    golden["event"] = json!([0; 32]);
+    assert!(serde_json::from_value::<Bet>(golden).is_err());
+}
+
+#[test]
+fn test_match_outcome_by_name() {
+    for (outcome, name, value) in &[
+        (MatchOutcome::Unknown, "Unknown", 0),
+        (MatchOutcome::TeamA, "TeamA", 1),
+        (MatchOutcome::TeamB, "TeamB", 2),
+        (MatchOutcome::Draw, "Draw", 3),
+        (MatchOutcome::Withdrawn, "Withdrawn", 255),
+        (MatchOutcome::Voided, "Voided", 254),
+    ] {
+        assert_eq!(serde_json::to_value(outcome).unwrap(), json!(name));
+        assert_eq!(serde_json::from_value::<MatchOutcome>(json!(name)).unwrap(), *outcome);
+        assert_eq!(outcome.as_u8(), *value);
+    }
+    assert!(serde_json::from_value::<MatchOutcome>(json!(1)).is_err());
+}
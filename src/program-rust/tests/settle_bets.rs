@@ -0,0 +1,95 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{Bet, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+async fn settle_bets(env: &mut common::Env, event: &Pubkey, entries: &[(Pubkey, Pubkey)]) -> Result<(), TransactionError> {
+    let mut accounts = vec![AccountMeta::new(*event, false)];
+    for (bet, betor) in entries {
+        accounts.push(AccountMeta::new(*bet, false));
+        accounts.push(AccountMeta::new(*betor, false));
+    }
+    let ix = Instruction::new_with_bytes(env.program_id, &BetInstruction::SettleBets.pack(), accounts);
+    env.send(&[ix], &[]).await
+}
+
+#[test]
+fn test_settle_bets_unpack() {
+    assert_eq!(BetInstruction::unpack(&BetInstruction::SettleBets.pack()).unwrap(), BetInstruction::SettleBets);
+}
+
+#[tokio::test]
+async fn test_crank_pays_what_withdraw_would() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let mut entries = vec![];
+    for &(choice, amount) in &[
+        (MatchOutcome::TeamA, 1_000_000),
+        (MatchOutcome::TeamA, 500_000),
+        (MatchOutcome::TeamB, 1_000_000),
+        (MatchOutcome::TeamA, 500_000),
+        (MatchOutcome::TeamB, 1_000_000),
+    ] {
+        let betor = Keypair::new().pubkey();
+        entries.push((env.place_bet(&betor, &event, choice, amount).await, betor));
+    }
+
+    // Not before the result.
+    assert!(settle_bets(&mut env, &event, &entries).await.is_err());
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    // The first winner collects on their own, the crank gets everybody else.
+    env.withdraw(&entries[0].1, &event, &entries[0].0).await.unwrap();
+    assert_eq!(env.lamports(&entries[0].1).await, 1_940_000);
+    settle_bets(&mut env, &event, &entries).await.unwrap();
+    let paid: Vec<u64> = {
+        let mut paid = vec![];
+        for (_, betor) in &entries {
+            paid.push(env.lamports(betor).await);
+        }
+        paid
+    };
+    assert_eq!(paid, vec![1_940_000, 970_000, 0, 970_000, 0]);
+    for (bet, _) in &entries {
+        assert_eq!(Bet::deserialize(&mut &env.data(bet).await[..]).unwrap().outcome, 255);
+    }
+
+    // Running it again moves nothing.
+    let pot = env.lamports(&event).await;
+    settle_bets(&mut env, &event, &entries).await.unwrap();
+    assert_eq!(env.lamports(&event).await, pot);
+}
+
+#[tokio::test]
+async fn test_mismatched_betor_skips_only_that_entry() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let mallory = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 2_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    settle_bets(&mut env, &event, &[(alice_bet, mallory), (bob_bet, bob)]).await.unwrap();
+    assert_eq!(env.lamports(&mallory).await, 0);
+    assert_eq!(env.lamports(&bob).await, 1_940_000);
+    assert_eq!(Bet::deserialize(&mut &env.data(&alice_bet).await[..]).unwrap().outcome, 1);
+
+    // Alice's bet is still hers to collect.
+    env.withdraw(&alice, &event, &alice_bet).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 1_940_000);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use borsh::BorshDeserialize;
+
+use helloworld::{result_message, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    ed25519_instruction::new_ed25519_instruction,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    sysvar,
+};
+
+fn sign_result(signer: &Keypair, event: &Pubkey, result: MatchOutcome) -> (Instruction, [u8; 64]) {
+    let dalek = ed25519_dalek::Keypair::from_bytes(&signer.to_bytes()).unwrap();
+    let ix = new_ed25519_instruction(&dalek, &result_message(event, result));
+    let mut signature = [0u8; 64];
+    signature.copy_from_slice(signer.sign_message(&result_message(event, result)).as_ref());
+    (ix, signature)
+}
+
+fn set_winner_signed_ix(
+    program_id: Pubkey,
+    arbiter: &Pubkey,
+    event: &Pubkey,
+    result: MatchOutcome,
+    signed_event: Pubkey,
+    signature: [u8; 64],
+) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &BetInstruction::SetWinnerSigned { result, event: signed_event, signature }.pack(),
+        vec![
+            AccountMeta::new(*arbiter, false),
+            AccountMeta::new(*event, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_relayed_result_settles_event() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+
+    // The relayer is the payer, the arbiter does not sign the transaction.
+    let (ed25519_ix, signature) = sign_result(&arbiter, &event, MatchOutcome::TeamB);
+    let ix = set_winner_signed_ix(env.program_id, &arbiter.pubkey(), &event, MatchOutcome::TeamB, event, signature);
+    env.send(&[ed25519_ix, ix], &[]).await.unwrap();
+
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!(bets.outcome, 2);
+
+    // Replaying the very same signed result is rejected.
+    let (ed25519_ix, signature) = sign_result(&arbiter, &event, MatchOutcome::TeamB);
+    let ix = set_winner_signed_ix(env.program_id, &arbiter.pubkey(), &event, MatchOutcome::TeamB, event, signature);
+    assert!(env.send(&[ed25519_ix, ix], &[]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_result_signed_by_stranger_is_rejected() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+
+    let forger = Keypair::new();
+    let (ed25519_ix, signature) = sign_result(&forger, &event, MatchOutcome::TeamA);
+    let ix = set_winner_signed_ix(env.program_id, &arbiter.pubkey(), &event, MatchOutcome::TeamA, event, signature);
+    assert!(env.send(&[ed25519_ix, ix], &[]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_result_signed_for_other_event_is_rejected() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let other_event = env.create_event(&arbiter, now).await;
+
+    // Claimed to be for `event`, but the signed message names `other_event`.
+    let (ed25519_ix, signature) = sign_result(&arbiter, &other_event, MatchOutcome::TeamA);
+    let ix = set_winner_signed_ix(env.program_id, &arbiter.pubkey(), &event, MatchOutcome::TeamA, event, signature);
+    assert!(env.send(&[ed25519_ix, ix], &[]).await.is_err());
+
+    // Applying the other event's result to this event is rejected as well.
+    let (ed25519_ix, signature) = sign_result(&arbiter, &other_event, MatchOutcome::TeamA);
+    let ix = set_winner_signed_ix(env.program_id, &arbiter.pubkey(), &event, MatchOutcome::TeamA, other_event, signature);
+    assert!(env.send(&[ed25519_ix, ix], &[]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_missing_ed25519_instruction_is_rejected() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+
+    let (_, signature) = sign_result(&arbiter, &event, MatchOutcome::Draw);
+    let ix = set_winner_signed_ix(env.program_id, &arbiter.pubkey(), &event, MatchOutcome::Draw, event, signature);
+    assert!(env.send(&[ix], &[]).await.is_err());
+}
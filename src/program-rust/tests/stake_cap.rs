@@ -0,0 +1,48 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::bet_error;
+use helloworld::{limit_address, BetError, EventParams, MatchOutcome, WalletStake};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn test_wallet_stake_cap() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { max_stake_per_wallet: 3_000_000, ..EventParams::new(now) }).await;
+    let alice = Keypair::new().pubkey();
+
+    let accounts = env.limit_accounts(&event, &alice);
+    env.try_place_bet_with_accounts(&alice, &event, MatchOutcome::TeamA, 1_000_000, accounts.clone()).await.unwrap();
+    env.try_place_bet_with_accounts(&alice, &event, MatchOutcome::TeamB, 1_500_000, accounts.clone()).await.unwrap();
+    assert_eq!(
+        env.try_place_bet_with_accounts(&alice, &event, MatchOutcome::TeamA, 1_000_000, accounts.clone()).await.unwrap_err(),
+        bet_error(0, BetError::StakeCapExceeded)
+    );
+    // Up to the cap exactly is fine.
+    env.try_place_bet_with_accounts(&alice, &event, MatchOutcome::TeamA, 500_000, accounts).await.unwrap();
+
+    let limit = limit_address(&env.program_id, &event, &alice).0;
+    let wallet_stake = WalletStake::deserialize(&mut &env.data(&limit).await[..]).unwrap();
+    assert_eq!(wallet_stake.total_staked, 3_000_000);
+
+    // Other wallets have their own allowance.
+    let bob = Keypair::new().pubkey();
+    let accounts = env.limit_accounts(&event, &bob);
+    env.try_place_bet_with_accounts(&bob, &event, MatchOutcome::TeamA, 3_000_000, accounts).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_capped_event_requires_limit_account() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { max_stake_per_wallet: 3_000_000, ..EventParams::new(now) }).await;
+    let alice = Keypair::new().pubkey();
+
+    assert!(env.try_place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await.is_err());
+    // Someone else's limit account doesn't count.
+    let accounts = env.limit_accounts(&event, &Keypair::new().pubkey());
+    assert!(env.try_place_bet_with_accounts(&alice, &event, MatchOutcome::TeamA, 1_000_000, accounts).await.is_err());
+}
@@ -0,0 +1,110 @@
+use helloworld::{Bet, BetError, EventBets, Odds, STATE_VERSION, STATE_VERSION_V1};
+use solana_sdk::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+// Borsh encodings of a version 1 event and bet, as the program wrote them before packing by hand.
+const EVENT_FIXTURE: &[u8] = include_bytes!("fixtures/event_bets.bin");
+const BET_FIXTURE: &[u8] = include_bytes!("fixtures/bet.bin");
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+// The fixture as MigrateEvent leaves it, the layout didn't change since version 1.
+fn current(fixture: &[u8]) -> Vec<u8> {
+    let mut data = fixture.to_vec();
+    data[0] = STATE_VERSION;
+    data
+}
+
+#[test]
+fn test_v1_fixtures_need_migration() {
+    assert_eq!((EVENT_FIXTURE[0], BET_FIXTURE[0]), (STATE_VERSION_V1, STATE_VERSION_V1));
+    let mismatch = ProgramError::from(BetError::VersionMismatch);
+    assert_eq!(EventBets::unpack_from_slice(EVENT_FIXTURE).err(), Some(mismatch.clone()));
+    assert_eq!(Bet::unpack_from_slice(BET_FIXTURE).err(), Some(mismatch.clone()));
+    let mut future = current(BET_FIXTURE);
+    future[0] = STATE_VERSION + 1;
+    assert_eq!(Bet::unpack_from_slice(&future).err(), Some(mismatch));
+    // Never written accounts still load, to be initialized.
+    assert_eq!(Bet::unpack_from_slice(&[0; Bet::LEN]).unwrap().version, 0);
+}
+
+#[test]
+fn test_event_fixture_unpacks_and_packs_back() {
+    let fixture = current(EVENT_FIXTURE);
+    let event = EventBets::unpack_from_slice(&fixture).unwrap();
+    assert_eq!(event.version, STATE_VERSION);
+    assert_eq!((event.arbiter, event.bets_allowed_until_ts, event.outcome), (key(0x11), 1_700_000_000, 2));
+    assert_eq!((event.balance_a, event.balance_b), (4_000_000, 1_500_000));
+    assert_eq!((event.dispute_window_secs, event.winner_set_at, event.finality_delay_secs), (3600, 1_700_003_600, 600));
+    assert_eq!((event.bond_lamports, event.challenger, event.bond_slashed), (50_000_000, key(0x22), false));
+    assert_eq!((event.commission_bps, event.min_bet_lamports, event.max_stake_per_wallet), (300, 10_000, 2_000_000));
+    assert_eq!((event.max_imbalance_ratio, event.bonus_lamports, event.bonus_commission_free), (5, 250_000, true));
+    assert_eq!((event.referred_a, event.referred_b, event.referral_bps, event.referral_reserve), (1_000_000, 0, 2000, 6_000));
+    assert_eq!((event.gate_mint, event.gate_min_amount), (key(0x33), 1));
+    assert_eq!((event.mint, event.token_program), (Pubkey::default(), Pubkey::default()));
+    assert_eq!(event.metadata_hash[31], 31);
+    assert_eq!((event.bets_count_a, event.bets_count_b, event.total_paid_out), (4, 2, 970_000));
+    assert_eq!((event.paused, event.bump), (false, 254));
+    assert_eq!(&event.seed[..12], &b"epl-2024-md7"[..]);
+    assert!(!event.fixed_odds && !event.odds_a.is_set());
+    assert_eq!((event.tournament, event.market_kind, event.handicap), (key(0x44), 1, -3));
+    assert_eq!((event.outstanding_bets, event.commission_accrued), (5, 7));
+    assert_eq!((event.correction_window_secs, event.claims_count, event.commission_recipient), (3600, 2, key(0x66)));
+    assert!(event.finalized);
+    assert_eq!((event.unique_bettors, event.max_bets_per_wallet), (3, 4));
+    assert!(event.allowlisted && event.denylisted && event.arbiter_may_bet);
+    assert_eq!((event.correction_cooldown_secs, event.last_result_change_at), (300, 1_700_000_000));
+
+    let mut packed = vec![0; EventBets::LEN];
+    event.pack_into_slice(&mut packed);
+    assert_eq!(packed, fixture);
+}
+
+#[test]
+fn test_bet_fixture_unpacks_and_packs_back() {
+    let fixture = current(BET_FIXTURE);
+    let bet = Bet::unpack_from_slice(&fixture).unwrap();
+    assert_eq!(bet.version, STATE_VERSION);
+    assert_eq!((bet.betor, bet.event, bet.amount, bet.outcome), (key(0x55), key(0x66), 1_000_000, 1));
+    assert_eq!(bet.referrer, key(0x77));
+    assert_eq!(&bet.memo[..9], b"order #42");
+    assert_eq!((bet.placed_at, bet.claimed), (1_699_990_000, 300_000));
+    assert_eq!((bet.for_sale, bet.ask_lamports, bet.receipt_mint), (true, 1_200_000, Pubkey::default()));
+    assert_eq!(bet.locked_odds, Odds { numerator: 5, denominator: 2 });
+    assert_eq!(bet.delegate, key(0x88));
+
+    let mut packed = vec![0; Bet::LEN];
+    bet.pack_into_slice(&mut packed);
+    assert_eq!(packed, fixture);
+    assert!(Bet::unpack_from_slice(&fixture[..Bet::LEN - 1]).is_err());
+}
+
+#[cfg(feature = "borsh-state")]
+#[test]
+fn test_borsh_reads_the_same_bytes() {
+    use borsh::{BorshDeserialize, BorshSerialize};
+    let event = EventBets::try_from_slice(EVENT_FIXTURE).unwrap();
+    assert_eq!(event.try_to_vec().unwrap(), EVENT_FIXTURE);
+    let bet = Bet::try_from_slice(BET_FIXTURE).unwrap();
+    assert_eq!(bet.try_to_vec().unwrap(), BET_FIXTURE);
+}
+
+#[test]
+fn test_pack_round_trips_and_refuses_uninitialized() {
+    let event = EventBets::unpack(&current(EVENT_FIXTURE)).unwrap();
+    let mut packed = vec![0; EventBets::LEN];
+    EventBets::pack(event, &mut packed).unwrap();
+    assert_eq!(packed, current(EVENT_FIXTURE));
+    let bet = Bet::unpack(&current(BET_FIXTURE)).unwrap();
+    let mut packed = vec![0; Bet::LEN];
+    Bet::pack(bet, &mut packed).unwrap();
+    assert_eq!(packed, current(BET_FIXTURE));
+
+    // Only unpack_unchecked reads accounts nothing was written to.
+    assert_eq!(EventBets::unpack(&[0; EventBets::LEN]).err(), Some(ProgramError::UninitializedAccount));
+    assert_eq!(Bet::unpack(&[0; Bet::LEN]).err(), Some(ProgramError::UninitializedAccount));
+    assert_eq!(Bet::unpack_unchecked(&[0; Bet::LEN]).unwrap().version, 0);
+    // Pack wants the exact length, unpack_from_slice a prefix of it.
+    assert_eq!(Bet::unpack(&[current(BET_FIXTURE), vec![0]].concat()).err(), Some(ProgramError::InvalidAccountData));
+}
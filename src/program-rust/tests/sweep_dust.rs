@@ -0,0 +1,101 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{BetError, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+// Stakes that don't divide evenly, so every payout leaves a fraction of a lamport behind.
+const STAKES_A: [u64; 3] = [1_000_001, 2_000_003, 333_337];
+const STAKE_B: u64 = 1_000_000;
+
+async fn outstanding(env: &mut common::Env, event: &Pubkey) -> u32 {
+    EventBets::unpack(&env.data(event).await).unwrap().outstanding_bets
+}
+
+// A settled event with three winning bets and a losing one, none withdrawn yet.
+async fn settled_event(env: &mut common::Env, arbiter: &Keypair) -> (Pubkey, Vec<(Pubkey, Pubkey)>) {
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let deadline = env.now().await + 60;
+    let event = env.create_event(arbiter, deadline).await;
+    let mut bets = vec![];
+    for &stake in &STAKES_A {
+        let betor = Keypair::new().pubkey();
+        bets.push((betor, env.place_bet(&betor, &event, MatchOutcome::TeamA, stake).await));
+    }
+    let betor = Keypair::new().pubkey();
+    bets.push((betor, env.place_bet(&betor, &event, MatchOutcome::TeamB, STAKE_B).await));
+    assert_eq!(outstanding(env, &event).await, 4);
+    env.set_now(deadline + 1).await;
+    env.set_winner(arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    (event, bets)
+}
+
+#[test]
+fn test_sweep_dust_unpack() {
+    assert_eq!(BetInstruction::unpack(&[43]).unwrap(), BetInstruction::SweepDust);
+    assert_eq!(BetInstruction::SweepDust.pack(), vec![43]);
+}
+
+#[tokio::test]
+async fn test_sweep_after_every_bet_is_withdrawn() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let (event, bets) = settled_event(&mut env, &arbiter).await;
+    for (betor, bet) in &bets {
+        env.withdraw(betor, &event, bet).await.unwrap();
+    }
+    assert_eq!(outstanding(&mut env, &event).await, 0);
+    env.collect_commission(&arbiter, &event).await.unwrap();
+
+    let rent = env.rent(EventBets::LEN).await;
+    let dust = env.lamports(&event).await - rent;
+    // Under a lamport per winning bet.
+    assert!(dust > 0 && dust <= STAKES_A.len() as u64, "{} lamports of dust", dust);
+    let arbiter_before = env.lamports(&arbiter.pubkey()).await;
+    assert!(env.sweep_dust(&Keypair::new(), &event).await.is_err());
+    env.sweep_dust(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&event).await, rent);
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, arbiter_before + dust);
+
+    // Nothing left to sweep, a second one moves nothing.
+    env.sweep_dust(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&event).await, rent);
+}
+
+#[tokio::test]
+async fn test_sweep_with_an_unclaimed_winner_fails() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let (event, bets) = settled_event(&mut env, &arbiter).await;
+    // The first winner doesn't withdraw.
+    for (betor, bet) in &bets[1..] {
+        env.withdraw(betor, &event, bet).await.unwrap();
+    }
+    assert_eq!(outstanding(&mut env, &event).await, 1);
+
+    let before = env.lamports(&event).await;
+    assert_eq!(env.sweep_dust(&arbiter, &event).await.unwrap_err(), bet_error(0, BetError::BetsOutstanding));
+    assert_eq!(env.lamports(&event).await, before);
+
+    let (betor, bet) = bets[0];
+    env.withdraw(&betor, &event, &bet).await.unwrap();
+    env.collect_commission(&arbiter, &event).await.unwrap();
+    env.sweep_dust(&arbiter, &event).await.unwrap();
+    assert_eq!(env.lamports(&event).await, env.rent(EventBets::LEN).await);
+}
+
+#[tokio::test]
+async fn test_sweep_before_the_result_fails() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let deadline = env.now().await + 60;
+    let event = env.create_event(&arbiter, deadline).await;
+    // No bets at all, but nothing is settled either.
+    assert_eq!(env.sweep_dust(&arbiter, &event).await.unwrap_err(), bet_error(0, BetError::ResultNotFinal));
+}
@@ -0,0 +1,54 @@
+// Run with `cargo test --features test-utils`.
+#![cfg(feature = "test-utils")]
+
+mod common;
+
+use helloworld::{
+    process_instruction,
+    test_utils::{make_bet_account, make_event_account, pause_accounts, set_winner_accounts, withdraw_accounts},
+    EventBets, Instruction as BetInstruction, MatchOutcome,
+};
+use solana_sdk::{
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn test_fixtures_settle_in_program_test() {
+    let arbiter = Keypair::new();
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let event = make_event_account(&arbiter.pubkey(), 0, (1_000_000, 1_000_000));
+    let alice_bet = make_bet_account(&event.0, &alice, 1_000_000, MatchOutcome::TeamA);
+    let bob_bet = make_bet_account(&event.0, &bob, 1_000_000, MatchOutcome::TeamB);
+
+    let set_winner = set_winner_accounts(&arbiter.pubkey(), event.clone());
+    let withdraw_alice = withdraw_accounts(&alice, event.clone(), alice_bet.clone());
+    let withdraw_bob = withdraw_accounts(&bob, event.clone(), bob_bet.clone());
+    let mut accounts = withdraw_alice.program_accounts();
+    accounts.push(bob_bet.clone());
+    let mut env = common::start_with_accounts(accounts).await;
+
+    env.send(&[set_winner.instruction(&BetInstruction::SetWinner { result: MatchOutcome::TeamA, metadata_hash: [0; 32] })], &[&arbiter]).await.unwrap();
+    env.send(&[withdraw_alice.instruction(&BetInstruction::Withdraw { amount: 0 })], &[]).await.unwrap();
+    env.send(&[withdraw_bob.instruction(&BetInstruction::Withdraw { amount: 0 })], &[]).await.unwrap();
+    assert_eq!((env.lamports(&alice).await, env.lamports(&bob).await), (1_940_000, 0));
+}
+
+#[test]
+fn test_fixtures_as_account_infos() {
+    let arbiter = Pubkey::new_unique();
+    let event = make_event_account(&arbiter, 0, (5, 7));
+    let mut accounts = pause_accounts(&arbiter, event.clone());
+    let data = BetInstruction::PauseEvent.pack();
+    accounts.with_account_infos(|infos| process_instruction(&helloworld::id(), infos, &data)).unwrap();
+    let bets = EventBets::unpack(&accounts.account(&event.0).unwrap().data).unwrap();
+    assert_eq!((bets.paused, bets.arbiter, bets.balance_a, bets.balance_b), (true, arbiter, 5, 7));
+
+    // The arbiter has to sign.
+    let mut accounts = pause_accounts(&arbiter, event);
+    accounts.0[0].is_signer = false;
+    let result = accounts.with_account_infos(|infos| process_instruction(&helloworld::id(), infos, &data));
+    assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+}
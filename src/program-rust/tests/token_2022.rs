@@ -0,0 +1,77 @@
+mod common;
+
+use helloworld::{token_2022, vault_address, vault_authority, EventParams};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, InstructionError},
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use spl_token::state::Mint;
+
+// Token-2022 extension types.
+const TRANSFER_FEE_CONFIG: u16 = 1;
+const NON_TRANSFERABLE: u16 = 9;
+
+// A Token-2022 mint with the given extensions, each as (type, value length).
+fn token_2022_mint(extensions: &[(u16, usize)]) -> Account {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    let mint = Mint { mint_authority: COption::Some(Pubkey::new_unique()), supply: 0, decimals: 6, is_initialized: true, freeze_authority: COption::None };
+    mint.pack_into_slice(&mut data[..Mint::LEN]);
+    // Account type: mint.
+    data.push(1);
+    for (extension_type, length) in extensions {
+        data.extend_from_slice(&extension_type.to_le_bytes());
+        data.extend_from_slice(&(*length as u16).to_le_bytes());
+        data.extend(std::iter::repeat(0).take(*length));
+    }
+    Account { lamports: 1_000_000_000, data, owner: token_2022::id(), executable: false, rent_epoch: 0 }
+}
+
+async fn try_initialize(env: &mut common::Env, mint: Pubkey) -> Result<(), TransactionError> {
+    let arbiter = Keypair::new();
+    let event = Keypair::new();
+    let now = env.now().await;
+    let lamports = env.rent(helloworld::EventBets::LEN).await;
+    env.create_account(&event, lamports, helloworld::EventBets::LEN).await;
+    let mut ix = env.initialize_ix(&arbiter.pubkey(), &event.pubkey(), helloworld::Instruction::Initialize(EventParams { mint, ..EventParams::new(now) }));
+    ix.accounts.push(AccountMeta::new_readonly(vault_address(&env.program_id, &event.pubkey(), &mint, &token_2022::id()), false));
+    ix.accounts.push(AccountMeta::new_readonly(mint, false));
+    env.send(&[ix], &[&arbiter]).await
+}
+
+#[tokio::test]
+async fn test_non_transferable_mint_is_rejected() {
+    let fee_mint = Pubkey::new_unique();
+    let locked_mint = Pubkey::new_unique();
+    let mut env = common::start_with_accounts(vec![
+        (fee_mint, token_2022_mint(&[(TRANSFER_FEE_CONFIG, 108)])),
+        (locked_mint, token_2022_mint(&[(TRANSFER_FEE_CONFIG, 108), (NON_TRANSFERABLE, 0)])),
+    ])
+    .await;
+
+    // A transfer fee is fine, that one only fails later for want of a vault.
+    assert_eq!(
+        try_initialize(&mut env, fee_mint).await.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::IncorrectProgramId)
+    );
+    assert_eq!(
+        try_initialize(&mut env, locked_mint).await.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn test_vault_address_matches_associated_token_account() {
+    let program_id = Pubkey::new_unique();
+    let event = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let authority = vault_authority(&program_id, &event).0;
+    assert_eq!(
+        vault_address(&program_id, &event, &mint, &spl_token::id()),
+        spl_associated_token_account::get_associated_token_address(&authority, &mint)
+    );
+}
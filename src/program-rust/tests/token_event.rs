@@ -0,0 +1,82 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{vault_address, EventBets, EventParams, MatchOutcome};
+use solana_sdk::{
+    instruction::AccountMeta,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn test_token_event_deposit_settle_payout() {
+    let mut env = common::start().await;
+    let mint = env.create_mint().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_token_event(&arbiter, EventParams { mint, ..EventParams::new(now) }).await;
+    let vault = vault_address(&env.program_id, &event, &mint, &spl_token::id());
+    let event_lamports = env.lamports(&event).await;
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let alice_tokens = env.create_token_account(&mint, &alice.pubkey(), 1_000).await;
+    let bob_tokens = env.create_token_account(&mint, &bob.pubkey(), 1_000).await;
+    let arbiter_tokens = env.create_token_account(&mint, &arbiter.pubkey(), 0).await;
+
+    let alice_bet = env.try_place_token_bet(&alice, &event, &mint, MatchOutcome::TeamA, &alice_tokens, 100).await.unwrap();
+    let bob_bet = env.try_place_token_bet(&bob, &event, &mint, MatchOutcome::TeamB, &bob_tokens, 300).await.unwrap();
+    // Someone else's tokens can't be staked.
+    assert!(env.try_place_token_bet(&bob, &event, &mint, MatchOutcome::TeamB, &alice_tokens, 1).await.is_err());
+
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.balance_a, bets.balance_b), (100, 300));
+    assert_eq!(bets.token_program, spl_token::id());
+    // Pools are what the vault received.
+    assert_eq!(env.token_balance(&vault).await, bets.balance_a + bets.balance_b);
+    // Stakes don't touch the event's lamports.
+    assert_eq!(env.lamports(&event).await, event_lamports);
+
+    // The commission must go to the arbiter's own token account.
+    let mut ix = env.set_winner_ix(&arbiter.pubkey(), &event, MatchOutcome::TeamA);
+    ix.accounts.push(AccountMeta::new(bob_tokens, false));
+    ix.accounts.extend(env.vault_accounts(&event, &mint));
+    assert!(env.send(&[ix], &[&arbiter]).await.is_err());
+
+    let mut ix = env.set_winner_ix(&arbiter.pubkey(), &event, MatchOutcome::TeamA);
+    ix.accounts.push(AccountMeta::new(arbiter_tokens, false));
+    ix.accounts.extend(env.vault_accounts(&event, &mint));
+    env.send(&[ix], &[&arbiter]).await.unwrap();
+    assert_eq!(env.token_balance(&arbiter_tokens).await, 12);
+    assert_eq!(env.lamports(&event).await, event_lamports);
+    env.close_correction_window(&event).await;
+
+    // Payouts go to the betor's token account only.
+    let mut ix = env.withdraw_ix(&alice.pubkey(), &event, &alice_bet);
+    ix.accounts.push(AccountMeta::new(bob_tokens, false));
+    ix.accounts.extend(env.vault_accounts(&event, &mint));
+    assert!(env.send(&[ix], &[]).await.is_err());
+
+    // (300 * 100 / 100 + 100) * 97 / 100
+    let mut ix = env.withdraw_ix(&alice.pubkey(), &event, &alice_bet);
+    ix.accounts.push(AccountMeta::new(alice_tokens, false));
+    ix.accounts.extend(env.vault_accounts(&event, &mint));
+    env.send(&[ix], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&alice_tokens).await, 900 + 388);
+
+    let mut ix = env.withdraw_ix(&bob.pubkey(), &event, &bob_bet);
+    ix.accounts.push(AccountMeta::new(bob_tokens, false));
+    ix.accounts.extend(env.vault_accounts(&event, &mint));
+    env.send(&[ix], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&bob_tokens).await, 700);
+    assert_eq!(env.token_balance(&vault).await, 0);
+}
+
+#[tokio::test]
+async fn test_token_event_requires_its_vault() {
+    let mut env = common::start().await;
+    let mint = env.create_mint().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    // No vault account passed.
+    assert!(env.try_create_event_with(&arbiter, EventParams { mint, ..EventParams::new(now) }).await.is_err());
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::bet_error;
+use helloworld::{BetError, EventParams, MatchOutcome};
+use solana_sdk::{
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+async fn gated_event(env: &mut common::Env, gate_mint: Pubkey) -> Pubkey {
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    env.create_event_with(&arbiter, EventParams { gate_mint, gate_min_amount: 5, ..EventParams::new(now) }).await
+}
+
+#[tokio::test]
+async fn test_holder_may_bet() {
+    let mut env = common::start().await;
+    let mint = env.create_mint().await;
+    let event = gated_event(&mut env, mint).await;
+    let betor = Keypair::new().pubkey();
+    let token_account = env.create_token_account(&mint, &betor, 5).await;
+
+    env.try_place_bet_with_accounts(&betor, &event, MatchOutcome::TeamA, 1_000_000, vec![AccountMeta::new_readonly(token_account, false)])
+        .await
+        .unwrap();
+    // The token account is required.
+    assert!(env.try_place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await.is_err());
+    // And must be the betor's own.
+    let stranger = Keypair::new().pubkey();
+    assert_eq!(
+        env.try_place_bet_with_accounts(&stranger, &event, MatchOutcome::TeamA, 1_000_000, vec![AccountMeta::new_readonly(token_account, false)])
+            .await
+            .unwrap_err(),
+        bet_error(0, BetError::TokenGateNotPassed)
+    );
+}
+
+#[tokio::test]
+async fn test_wrong_mint_is_rejected() {
+    let mut env = common::start().await;
+    let mint = env.create_mint().await;
+    let other_mint = env.create_mint().await;
+    let event = gated_event(&mut env, mint).await;
+    let betor = Keypair::new().pubkey();
+    let token_account = env.create_token_account(&other_mint, &betor, 100).await;
+
+    assert_eq!(
+        env.try_place_bet_with_accounts(&betor, &event, MatchOutcome::TeamA, 1_000_000, vec![AccountMeta::new_readonly(token_account, false)])
+            .await
+            .unwrap_err(),
+        bet_error(0, BetError::TokenGateNotPassed)
+    );
+}
+
+#[tokio::test]
+async fn test_low_balance_is_rejected() {
+    let mut env = common::start().await;
+    let mint = env.create_mint().await;
+    let event = gated_event(&mut env, mint).await;
+    let betor = Keypair::new().pubkey();
+
+    for amount in [0, 4] {
+        let token_account = env.create_token_account(&mint, &betor, amount).await;
+        assert_eq!(
+            env.try_place_bet_with_accounts(&betor, &event, MatchOutcome::TeamA, 1_000_000, vec![AccountMeta::new_readonly(token_account, false)])
+                .await
+                .unwrap_err(),
+            bet_error(0, BetError::TokenGateNotPassed)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_gate_needs_a_threshold() {
+    let mut env = common::start().await;
+    let mint = env.create_mint().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    assert!(env.try_create_event_with(&arbiter, EventParams { gate_mint: mint, ..EventParams::new(now) }).await.is_err());
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{EventBets, EventParams, MatchOutcome, MARKET_KIND_MONEYLINE, MARKET_KIND_TOTALS, MAX_TOTALS_LINE};
+use solana_sdk::signature::{Keypair, Signer};
+
+fn totals(now: i64, line: i16) -> EventParams {
+    EventParams { market_kind: MARKET_KIND_TOTALS, handicap: line, ..EventParams::new(now) }
+}
+
+#[tokio::test]
+async fn test_over_wins_and_draw_is_rejected() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    // Over/under 2.5.
+    let event = env.create_event_with(&arbiter, totals(now, 5)).await;
+    let moneyline = env.create_event(&arbiter, now).await;
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.market_kind, bets.handicap), (MARKET_KIND_TOTALS, 5));
+    let bets = EventBets::deserialize(&mut &env.data(&moneyline).await[..]).unwrap();
+    assert_eq!(bets.market_kind, MARKET_KIND_MONEYLINE);
+
+    let over = Keypair::new().pubkey();
+    let under = Keypair::new().pubkey();
+    let over_bet = env.place_bet(&over, &event, MatchOutcome::OVER, 1_000_000).await;
+    let under_bet = env.place_bet(&under, &event, MatchOutcome::UNDER, 3_000_000).await;
+
+    assert!(env.set_winner(&arbiter, &event, MatchOutcome::Draw).await.is_err());
+    env.set_winner(&arbiter, &event, MatchOutcome::OVER).await.unwrap();
+    assert!(env.correct_result(&arbiter, &event, MatchOutcome::Draw).await.is_err());
+
+    env.close_correction_window(&event).await;
+    env.withdraw(&over, &event, &over_bet).await.unwrap();
+    env.withdraw(&under, &event, &under_bet).await.unwrap();
+    assert_eq!(env.lamports(&over).await, 3_880_000);
+    assert_eq!(env.lamports(&under).await, 0);
+}
+
+#[tokio::test]
+async fn test_totals_lines_are_halves() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    for line in &[0, -5, 4, MAX_TOTALS_LINE + 1] {
+        assert!(env.try_create_event_with(&arbiter, totals(now, *line)).await.is_err(), "{}", line);
+    }
+    env.create_event_with(&arbiter, totals(now, 1)).await;
+    env.create_event_with(&arbiter, totals(now, MAX_TOTALS_LINE - 1)).await;
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{tournament_address, EventBets, Instruction as BetInstruction, Tournament};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+async fn create_tournament(env: &mut common::Env, arbiter: &Keypair, slug: &str, name: &str) -> Result<Pubkey, TransactionError> {
+    let tournament = tournament_address(&env.program_id, &arbiter.pubkey(), slug).0;
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::CreateTournament { slug: slug.to_string(), name: name.to_string() }.pack(),
+        vec![
+            AccountMeta::new(arbiter.pubkey(), true),
+            AccountMeta::new(tournament, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await?;
+    Ok(tournament)
+}
+
+async fn add_event(env: &mut common::Env, arbiter: &Keypair, tournament: &Pubkey, event: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::AddEventToTournament.pack(),
+        vec![
+            AccountMeta::new_readonly(arbiter.pubkey(), true),
+            AccountMeta::new(*tournament, false),
+            AccountMeta::new(*event, false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+#[test]
+fn test_tournament_unpack() {
+    for ix in &[
+        BetInstruction::CreateTournament { slug: "ucl-2024-md3".to_string(), name: "Champions League – Matchday 3".to_string() },
+        BetInstruction::AddEventToTournament,
+    ] {
+        assert_eq!(&BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    }
+    assert!(BetInstruction::unpack(&[40, 3, b'u', b'c', b'l']).is_err());
+}
+
+#[tokio::test]
+async fn test_events_are_grouped_by_tournament() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let name = "Champions League – Matchday 3";
+    assert!(create_tournament(&mut env, &arbiter, "ucl md3", name).await.is_err());
+    assert!(create_tournament(&mut env, &arbiter, "ucl-2024-md3", &"x".repeat(65)).await.is_err());
+    let tournament = create_tournament(&mut env, &arbiter, "ucl-2024-md3", name).await.unwrap();
+    assert!(create_tournament(&mut env, &arbiter, "ucl-2024-md3", name).await.is_err());
+
+    let now = env.now().await;
+    let first = env.create_event(&arbiter, now).await;
+    let second = env.create_event(&arbiter, now).await;
+    add_event(&mut env, &arbiter, &tournament, &first).await.unwrap();
+    add_event(&mut env, &arbiter, &tournament, &second).await.unwrap();
+    assert!(add_event(&mut env, &arbiter, &tournament, &first).await.is_err());
+
+    let state = Tournament::deserialize(&mut &env.data(&tournament).await[..]).unwrap();
+    assert_eq!((state.arbiter, state.event_count), (arbiter.pubkey(), 2));
+    assert_eq!(&state.name[..name.len()], name.as_bytes());
+    for event in &[first, second] {
+        let data = env.data(event).await;
+        assert_eq!(EventBets::deserialize(&mut &data[..]).unwrap().tournament, tournament);
+        // Where a getProgramAccounts memcmp filter looks.
+        assert_eq!(&data[EventBets::TOURNAMENT_OFFSET..EventBets::TOURNAMENT_OFFSET + 32], tournament.as_ref());
+    }
+}
+
+#[tokio::test]
+async fn test_only_the_tournament_arbiter_adds_events() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    env.fund(&arbiter.pubkey(), 1_000_000_000).await;
+    let tournament = create_tournament(&mut env, &arbiter, "epl", "Premier League").await.unwrap();
+
+    let foreign = Keypair::new();
+    let now = env.now().await;
+    let foreign_event = env.create_event(&foreign, now).await;
+    assert!(add_event(&mut env, &foreign, &tournament, &foreign_event).await.is_err());
+    // Nor another arbiter's event to its own tournament.
+    assert!(add_event(&mut env, &arbiter, &tournament, &foreign_event).await.is_err());
+    let state = Tournament::deserialize(&mut &env.data(&tournament).await[..]).unwrap();
+    assert_eq!(state.event_count, 0);
+}
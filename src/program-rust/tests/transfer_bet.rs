@@ -0,0 +1,96 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{limit_address, Bet, EventParams, Instruction as BetInstruction, MatchOutcome, WalletStake};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+async fn transfer_bet(
+    env: &mut common::Env,
+    betor: &Keypair,
+    event: &Pubkey,
+    bet: &Pubkey,
+    new_betor: &Pubkey,
+    extra_accounts: Vec<AccountMeta>,
+) -> Result<(), TransactionError> {
+    let mut ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::TransferBet { new_betor: *new_betor }.pack(),
+        vec![AccountMeta::new_readonly(betor.pubkey(), true), AccountMeta::new_readonly(*event, false), AccountMeta::new(*bet, false)],
+    );
+    ix.accounts.extend(extra_accounts);
+    env.send(&[ix], &[betor]).await
+}
+
+#[test]
+fn test_transfer_bet_unpack() {
+    let ix = BetInstruction::TransferBet { new_betor: Pubkey::new_unique() };
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    assert!(BetInstruction::unpack(&ix.pack()[..32]).is_err());
+}
+
+#[tokio::test]
+async fn test_new_owner_withdraws() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new();
+    let bob_kp = Keypair::new();
+    let bob = bob_kp.pubkey();
+    let bet = env.place_bet(&alice.pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    let before = env.data(&bet).await;
+
+    // Only the betor can hand it over.
+    let mallory = Keypair::new();
+    assert!(transfer_bet(&mut env, &mallory, &event, &bet, &mallory.pubkey(), vec![]).await.is_err());
+    transfer_bet(&mut env, &alice, &event, &bet, &bob, vec![]).await.unwrap();
+
+    let moved = Bet::deserialize(&mut &env.data(&bet).await[..]).unwrap();
+    let original = Bet::deserialize(&mut &before[..]).unwrap();
+    assert_eq!(moved.betor, bob);
+    assert_eq!((moved.event, moved.amount, moved.outcome), (original.event, original.amount, original.outcome));
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    assert!(env.withdraw(&alice.pubkey(), &event, &bet).await.is_err());
+    env.withdraw(&bob, &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&bob).await, 970_000);
+    // Nothing left to hand over.
+    assert!(transfer_bet(&mut env, &bob_kp, &event, &bet, &alice.pubkey(), vec![]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_transfer_moves_the_stake_between_allowances() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event_with(&arbiter, EventParams { max_stake_per_wallet: 3_000_000, ..EventParams::new(now) }).await;
+    let alice = Keypair::new();
+    let bob = Keypair::new().pubkey();
+    let accounts = env.limit_accounts(&event, &alice.pubkey());
+    let bet = env.try_place_bet_with_accounts(&alice.pubkey(), &event, MatchOutcome::TeamA, 2_000_000, accounts).await.unwrap();
+    let accounts = env.limit_accounts(&event, &bob);
+    env.try_place_bet_with_accounts(&bob, &event, MatchOutcome::TeamB, 1_500_000, accounts).await.unwrap();
+
+    let alice_limit = limit_address(&env.program_id, &event, &alice.pubkey()).0;
+    let bob_limit = limit_address(&env.program_id, &event, &bob).0;
+    let mut accounts = vec![AccountMeta::new(alice_limit, false)];
+    accounts.extend(env.limit_accounts(&event, &bob));
+    // Bob would be above the cap.
+    assert!(transfer_bet(&mut env, &alice, &event, &bet, &bob, accounts.clone()).await.is_err());
+
+    let carol = Keypair::new().pubkey();
+    let mut accounts = vec![AccountMeta::new(alice_limit, false)];
+    accounts.extend(env.limit_accounts(&event, &carol));
+    transfer_bet(&mut env, &alice, &event, &bet, &carol, accounts).await.unwrap();
+
+    let staked = |data: Vec<u8>| WalletStake::deserialize(&mut &data[..]).unwrap().total_staked;
+    assert_eq!(staked(env.data(&alice_limit).await), 0);
+    assert_eq!(staked(env.data(&limit_address(&env.program_id, &event, &carol).0).await), 2_000_000);
+    assert_eq!(staked(env.data(&bob_limit).await), 1_500_000);
+}
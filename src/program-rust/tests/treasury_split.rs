@@ -0,0 +1,84 @@
+#![cfg(feature = "test-admin")]
+
+mod common;
+
+use helloworld::{split_commission, ConfigParams, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    signature::{Keypair, Signer},
+};
+
+const PARAMS: ConfigParams =
+    ConfigParams { default_commission_bps: 300, max_commission_bps: 1_000, min_deadline_offset_secs: 0, arbiter_share_bps: 2_500, referral_bps: 0 };
+
+#[test]
+fn test_split_rounds_in_favor_of_treasury() {
+    assert_eq!(split_commission(101, 2_500), (25, 76));
+    assert_eq!(split_commission(3, 5_000), (1, 2));
+    assert_eq!(split_commission(7, 10_000), (7, 0));
+    assert_eq!(split_commission(7, 0), (0, 7));
+    assert_eq!(split_commission(u64::MAX, 9_999), (18_444_899_399_302_180_659, 1_844_674_407_370_956));
+}
+
+#[tokio::test]
+async fn test_commission_is_split_with_treasury() {
+    let mut env = common::start().await;
+    let admin = common::test_admin();
+    env.fund(&admin.pubkey(), 1_000_000_000).await;
+    env.initialize_config(&admin, PARAMS).await.unwrap();
+
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 1_234_567).await;
+    let winner = Keypair::new().pubkey();
+    let winner_bet = env.place_bet(&winner, &event, MatchOutcome::TeamB, 7_654_321).await;
+
+    let pot = env.lamports(&event).await;
+    let treasury = env.treasury();
+    let treasury_before = env.lamports(&treasury).await;
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::SetWinner { result: MatchOutcome::TeamB, metadata_hash: [0; 32] }.pack(),
+        vec![
+            AccountMeta::new(arbiter.pubkey(), true),
+            AccountMeta::new(event, false),
+            AccountMeta::new_readonly(env.config(), false),
+            AccountMeta::new(treasury, false),
+        ],
+    );
+    env.send(&[ix], &[&arbiter]).await.unwrap();
+    env.close_correction_window(&event).await;
+    env.withdraw(&winner, &event, &winner_bet).await.unwrap();
+
+    let mut ix = env.collect_commission_ix(&arbiter.pubkey(), &event);
+    ix.accounts.push(AccountMeta::new_readonly(env.config(), false));
+    ix.accounts.push(AccountMeta::new(treasury, false));
+    env.send(&[ix], &[&arbiter]).await.unwrap();
+
+    // The only winner is paid the whole pot, less the commission.
+    let commission = (1_234_567 + 7_654_321) * 3 / 100;
+    let arbiter_part = commission * 2_500 / 10_000;
+    assert_eq!(env.rent(EventBets::LEN).await + 1_234_567 + 7_654_321, pot);
+    assert_eq!(env.lamports(&arbiter.pubkey()).await, arbiter_part);
+    assert_eq!(env.lamports(&treasury).await - treasury_before, commission - arbiter_part);
+    assert_eq!(env.lamports(&event).await, env.rent(EventBets::LEN).await);
+}
+
+#[tokio::test]
+async fn test_config_without_treasury_is_rejected() {
+    let mut env = common::start().await;
+    let admin = common::test_admin();
+    env.fund(&admin.pubkey(), 1_000_000_000).await;
+    env.initialize_config(&admin, PARAMS).await.unwrap();
+
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let mut ix = env.set_winner_ix(&arbiter.pubkey(), &event, MatchOutcome::TeamA);
+    ix.accounts.push(AccountMeta::new_readonly(env.config(), false));
+    assert!(env.send(&[ix.clone()], &[&arbiter]).await.is_err());
+    // A look-alike treasury is rejected too.
+    ix.accounts.push(AccountMeta::new(Keypair::new().pubkey(), false));
+    assert!(env.send(&[ix], &[&arbiter]).await.is_err());
+}
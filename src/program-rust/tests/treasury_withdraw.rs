@@ -0,0 +1,67 @@
+#![cfg(feature = "test-admin")]
+
+mod common;
+
+use common::bet_error;
+use helloworld::{BetError, ConfigParams};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const PARAMS: ConfigParams =
+    ConfigParams { default_commission_bps: 300, max_commission_bps: 1_000, min_deadline_offset_secs: 0, arbiter_share_bps: 0, referral_bps: 0 };
+
+async fn setup() -> (common::Env, Keypair, u64) {
+    let mut env = common::start().await;
+    let admin = common::test_admin();
+    env.fund(&admin.pubkey(), 1_000_000_000).await;
+    env.initialize_config(&admin, PARAMS).await.unwrap();
+    // Stands in for accrued commission.
+    let treasury = env.treasury();
+    env.fund(&treasury, 5_000_000).await;
+    let rent = env.rent(0).await;
+    (env, admin, rent)
+}
+
+#[tokio::test]
+async fn test_partial_and_full_withdrawal() {
+    let (mut env, admin, rent) = setup().await;
+    let recipient = Keypair::new().pubkey();
+    let treasury = env.treasury();
+
+    env.withdraw_treasury(&admin, &recipient, 1_000_000).await.unwrap();
+    assert_eq!(env.lamports(&recipient).await, 1_000_000);
+    assert_eq!(env.lamports(&treasury).await, rent + 4_000_000);
+
+    env.withdraw_treasury(&admin, &recipient, 4_000_000).await.unwrap();
+    assert_eq!(env.lamports(&recipient).await, 5_000_000);
+    assert_eq!(env.lamports(&treasury).await, rent);
+}
+
+#[tokio::test]
+async fn test_over_withdrawal_is_rejected() {
+    let (mut env, admin, rent) = setup().await;
+    let recipient = Keypair::new().pubkey();
+
+    assert_eq!(
+        env.withdraw_treasury(&admin, &recipient, 5_000_001).await.unwrap_err(),
+        bet_error(0, BetError::TreasuryBelowRentExemption)
+    );
+    assert_eq!(
+        env.withdraw_treasury(&admin, &recipient, rent + 5_000_001).await.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InsufficientFunds)
+    );
+    assert_eq!(env.lamports(&recipient).await, 0);
+}
+
+#[tokio::test]
+async fn test_non_admin_withdrawal_is_rejected() {
+    let (mut env, _admin, _rent) = setup().await;
+    let stranger = Keypair::new();
+    assert_eq!(
+        env.withdraw_treasury(&stranger, &stranger.pubkey(), 1).await.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+}
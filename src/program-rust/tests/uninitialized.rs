@@ -0,0 +1,47 @@
+mod common;
+
+use helloworld::{Bet, EventBets, MatchOutcome};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+fn uninitialized() -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::UninitializedAccount)
+}
+
+#[tokio::test]
+async fn test_add_bet_refuses_uninitialized_event() {
+    let mut env = common::start().await;
+    let event = Keypair::new();
+    let lamports = env.rent(EventBets::LEN).await;
+    env.create_account(&event, lamports, EventBets::LEN).await;
+
+    let betor = Keypair::new().pubkey();
+    assert_eq!(env.try_place_bet(&betor, &event.pubkey(), MatchOutcome::TeamA, 1_000_000).await.unwrap_err(), uninitialized());
+}
+
+#[tokio::test]
+async fn test_withdraw_refuses_uninitialized_accounts() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let betor = Keypair::new().pubkey();
+    let this_bet = env.place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    let blank_bet = Keypair::new();
+    let lamports = env.rent(Bet::LEN).await;
+    env.create_account(&blank_bet, lamports, Bet::LEN).await;
+    assert_eq!(env.withdraw(&betor, &event, &blank_bet.pubkey()).await.unwrap_err(), uninitialized());
+
+    let blank_event = Keypair::new();
+    let lamports = env.rent(EventBets::LEN).await;
+    env.create_account(&blank_event, lamports, EventBets::LEN).await;
+    assert_eq!(env.withdraw(&betor, &blank_event.pubkey(), &this_bet).await.unwrap_err(), uninitialized());
+
+    env.withdraw(&betor, &event, &this_bet).await.unwrap();
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use helloworld::{seen_address, Bet, EventBets, MatchOutcome};
+use solana_sdk::{
+    instruction::AccountMeta,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+// AddBet with the seen marker, the betor signs and pays for it.
+async fn bet_seen(env: &mut common::Env, betor: &Keypair, event: &Pubkey, amount: u64) -> Result<Pubkey, TransactionError> {
+    let bet = Keypair::new();
+    let lamports = env.rent(Bet::LEN).await + amount;
+    env.create_account(&bet, lamports, Bet::LEN).await;
+    let mut ix = env.add_bet_ix(&betor.pubkey(), event, &bet.pubkey(), MatchOutcome::TeamA);
+    ix.accounts[0].is_signer = true;
+    ix.accounts.push(AccountMeta::new(seen_address(&env.program_id, event, &betor.pubkey()).0, false));
+    ix.accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    env.send(&[ix], &[betor]).await?;
+    Ok(bet.pubkey())
+}
+
+async fn unique_bettors(env: &mut common::Env, event: &Pubkey) -> u32 {
+    EventBets::unpack(&env.data(event).await).unwrap().unique_bettors
+}
+
+#[tokio::test]
+async fn test_wallets_count_once() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    env.fund(&alice.pubkey(), 1_000_000_000).await;
+    env.fund(&bob.pubkey(), 1_000_000_000).await;
+    assert_eq!(unique_bettors(&mut env, &event).await, 0);
+
+    bet_seen(&mut env, &alice, &event, 1_000_000).await.unwrap();
+    assert_eq!(unique_bettors(&mut env, &event).await, 1);
+    let marker = seen_address(&env.program_id, &event, &alice.pubkey()).0;
+    assert_eq!(env.lamports(&marker).await, env.rent(0).await);
+    assert_eq!(env.lamports(&alice.pubkey()).await, 1_000_000_000 - env.rent(0).await);
+
+    // The marker exists already, the bet goes through and isn't counted again.
+    bet_seen(&mut env, &alice, &event, 2_000_000).await.unwrap();
+    assert_eq!(unique_bettors(&mut env, &event).await, 1);
+    bet_seen(&mut env, &bob, &event, 1_000_000).await.unwrap();
+    assert_eq!(unique_bettors(&mut env, &event).await, 2);
+
+    // Bets without the marker aren't counted.
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 1_000_000).await;
+    assert_eq!(unique_bettors(&mut env, &event).await, 2);
+    assert_eq!(EventBets::unpack(&env.data(&event).await).unwrap().bets_count_a, 3);
+}
+
+#[tokio::test]
+async fn test_markers_are_per_event() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let first = env.create_event(&arbiter, now).await;
+    let second = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new();
+    env.fund(&alice.pubkey(), 1_000_000_000).await;
+    bet_seen(&mut env, &alice, &first, 1_000_000).await.unwrap();
+    bet_seen(&mut env, &alice, &second, 1_000_000).await.unwrap();
+    assert_eq!(unique_bettors(&mut env, &first).await, 1);
+    assert_eq!(unique_bettors(&mut env, &second).await, 1);
+}
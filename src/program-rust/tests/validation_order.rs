@@ -0,0 +1,87 @@
+mod common;
+
+use helloworld::{Bet, MatchOutcome};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+fn instruction_error(error: InstructionError) -> TransactionError {
+    TransactionError::InstructionError(0, error)
+}
+
+// Account checks run before any state is deserialized, so a bad account gets its own error
+// instead of whatever the deserialization of the wrong bytes fails with.
+#[tokio::test]
+async fn test_add_bet_checks_accounts_first() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now + 100).await;
+    let betor = Keypair::new().pubkey();
+
+    let bet = Keypair::new();
+    let lamports = env.rent(Bet::LEN).await + 1_000_000;
+    env.create_account(&bet, lamports, Bet::LEN).await;
+    let mut ix = env.add_bet_ix(&betor, &event, &bet.pubkey(), MatchOutcome::TeamA);
+    ix.accounts[1].is_writable = false;
+    assert_eq!(env.send(&[ix], &[]).await.unwrap_err(), instruction_error(InstructionError::InvalidArgument));
+
+    let short = Keypair::new();
+    let lamports = env.rent(Bet::LEN - 1).await + 1_000_000;
+    env.create_account(&short, lamports, Bet::LEN - 1).await;
+    let ix = env.add_bet_ix(&betor, &event, &short.pubkey(), MatchOutcome::TeamA);
+    assert_eq!(env.send(&[ix], &[]).await.unwrap_err(), instruction_error(InstructionError::InvalidAccountData));
+
+    let placed = env.place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let ix = env.add_bet_ix(&betor, &event, &placed, MatchOutcome::TeamA);
+    assert_eq!(env.send(&[ix], &[]).await.unwrap_err(), instruction_error(InstructionError::InvalidAccountData));
+    // The untouched bet account still goes through.
+    let ix = env.add_bet_ix(&betor, &event, &bet.pubkey(), MatchOutcome::TeamA);
+    env.send(&[ix], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_set_winner_checks_accounts_first() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+
+    // Not an account of the program at all.
+    let stranger = Keypair::new().pubkey();
+    env.fund(&stranger, 1_000_000).await;
+    let ix = env.set_winner_ix(&arbiter.pubkey(), &stranger, MatchOutcome::TeamA);
+    assert_eq!(env.send(&[ix], &[&arbiter]).await.unwrap_err(), instruction_error(InstructionError::InvalidAccountData));
+
+    // Writability comes before the result.
+    let mut ix = env.set_winner_ix(&arbiter.pubkey(), &event, MatchOutcome::Unknown);
+    ix.accounts[1].is_writable = false;
+    assert_eq!(env.send(&[ix], &[&arbiter]).await.unwrap_err(), instruction_error(InstructionError::InvalidArgument));
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_withdraw_checks_accounts_first() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let betor = Keypair::new().pubkey();
+    let bet = env.place_bet(&betor, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    let foreign = Keypair::new().pubkey();
+    env.fund(&foreign, 1_000_000).await;
+    let ix = env.withdraw_ix(&betor, &event, &foreign);
+    assert_eq!(env.send(&[ix], &[]).await.unwrap_err(), instruction_error(InstructionError::InvalidAccountData));
+    let mut ix = env.withdraw_ix(&betor, &event, &bet);
+    ix.accounts[2].is_writable = false;
+    assert_eq!(env.send(&[ix], &[]).await.unwrap_err(), instruction_error(InstructionError::InvalidArgument));
+
+    env.withdraw(&betor, &event, &bet).await.unwrap();
+    assert_eq!(env.lamports(&betor).await, 970_000);
+}
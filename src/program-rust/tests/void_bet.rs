@@ -0,0 +1,78 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{Bet, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+async fn void_bet(env: &mut common::Env, arbiter: &Keypair, event: &Pubkey, bet: &Pubkey, betor: &Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction::new_with_bytes(
+        env.program_id,
+        &BetInstruction::VoidBet { reason: 7 }.pack(),
+        vec![
+            AccountMeta::new_readonly(arbiter.pubkey(), true),
+            AccountMeta::new(*event, false),
+            AccountMeta::new(*bet, false),
+            AccountMeta::new(*betor, false),
+        ],
+    );
+    env.send(&[ix], &[arbiter]).await
+}
+
+#[test]
+fn test_void_bet_unpack() {
+    let ix = BetInstruction::VoidBet { reason: 3 };
+    assert_eq!(BetInstruction::unpack(&ix.pack()).unwrap(), ix);
+    assert!(BetInstruction::unpack(&[24]).is_err());
+}
+
+#[tokio::test]
+async fn test_voided_bet_is_refunded_and_never_wins() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamA, 1_000_000).await;
+    let bob_bet = env.place_bet(&bob, &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 2_000_000).await;
+
+    // Only the arbiter, and only for the bet's own betor.
+    assert!(void_bet(&mut env, &Keypair::new(), &event, &alice_bet, &alice).await.is_err());
+    assert!(void_bet(&mut env, &arbiter, &event, &alice_bet, &bob).await.is_err());
+    void_bet(&mut env, &arbiter, &event, &alice_bet, &alice).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 1_000_000);
+    assert_eq!(Bet::deserialize(&mut &env.data(&alice_bet).await[..]).unwrap().outcome, 254);
+    let bets = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap();
+    assert_eq!((bets.balance_a, bets.balance_b, bets.bets_count_a), (1_000_000, 2_000_000, 1));
+    // Not twice.
+    assert!(void_bet(&mut env, &arbiter, &event, &alice_bet, &alice).await.is_err());
+
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    assert!(env.withdraw(&alice, &event, &alice_bet).await.is_err());
+    assert_eq!(env.lamports(&alice).await, 1_000_000);
+    // Bob has the A pool to himself now.
+    env.withdraw(&bob, &event, &bob_bet).await.unwrap();
+    assert_eq!(env.lamports(&bob).await, 2_910_000);
+}
+
+#[tokio::test]
+async fn test_void_after_the_result_is_rejected() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let alice_bet = env.place_bet(&alice, &event, MatchOutcome::TeamB, 1_000_000).await;
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+
+    assert!(void_bet(&mut env, &arbiter, &event, &alice_bet, &alice).await.is_err());
+    assert_eq!(env.lamports(&alice).await, 0);
+}
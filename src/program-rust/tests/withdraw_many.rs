@@ -0,0 +1,95 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use helloworld::{Bet, EventBets, Instruction as BetInstruction, MatchOutcome};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+async fn withdraw_many(env: &mut common::Env, betor: &Pubkey, event: &Pubkey, bets: &[Pubkey]) -> Result<(), TransactionError> {
+    let mut accounts = vec![AccountMeta::new(*betor, false), AccountMeta::new(*event, false)];
+    accounts.extend(bets.iter().map(|bet| AccountMeta::new(*bet, false)));
+    let ix = Instruction::new_with_bytes(env.program_id, &BetInstruction::WithdrawMany.pack(), accounts);
+    env.send(&[ix], &[]).await
+}
+
+// Places the same mixed set of bets for `betor` on a fresh event, settled for TeamA.
+async fn mixed_event(env: &mut common::Env, betor: &Pubkey) -> (Pubkey, Vec<Pubkey>) {
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let mut bets = vec![];
+    for &(choice, amount) in &[(MatchOutcome::TeamA, 1_000_000), (MatchOutcome::TeamB, 500_000), (MatchOutcome::TeamA, 300_000), (MatchOutcome::TeamB, 700_000)] {
+        bets.push(env.place_bet(betor, &event, choice, amount).await);
+    }
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 2_000_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+    (event, bets)
+}
+
+#[test]
+fn test_withdraw_many_unpack() {
+    assert_eq!(BetInstruction::unpack(&BetInstruction::WithdrawMany.pack()).unwrap(), BetInstruction::WithdrawMany);
+}
+
+#[tokio::test]
+async fn test_batch_pays_the_sum_of_single_withdrawals() {
+    let mut env = common::start().await;
+    let alice = Keypair::new().pubkey();
+    let bob = Keypair::new().pubkey();
+
+    let (event, bets) = mixed_event(&mut env, &alice).await;
+    for bet in &bets {
+        env.withdraw(&alice, &event, bet).await.unwrap();
+    }
+    let single = env.lamports(&alice).await;
+    assert!(single > 0);
+
+    let (event, bets) = mixed_event(&mut env, &bob).await;
+    // One of them is collected beforehand and skipped by the batch.
+    env.withdraw(&bob, &event, &bets[2]).await.unwrap();
+    withdraw_many(&mut env, &bob, &event, &bets).await.unwrap();
+    assert_eq!(env.lamports(&bob).await, single);
+    for bet in &bets {
+        assert_eq!(Bet::deserialize(&mut &env.data(bet).await[..]).unwrap().outcome, 255);
+    }
+    let paid = EventBets::deserialize(&mut &env.data(&event).await[..]).unwrap().total_paid_out;
+    assert_eq!(paid, single);
+
+    // Everything is withdrawn now, nothing moves.
+    withdraw_many(&mut env, &bob, &event, &bets).await.unwrap();
+    assert_eq!(env.lamports(&bob).await, single);
+}
+
+#[tokio::test]
+async fn test_foreign_bet_aborts_the_batch() {
+    let mut env = common::start().await;
+    let alice = Keypair::new().pubkey();
+    let (event, mut bets) = mixed_event(&mut env, &alice).await;
+    bets.push(env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamA, 1_000_000).await);
+    assert!(withdraw_many(&mut env, &alice, &event, &bets).await.is_err());
+    assert_eq!(env.lamports(&alice).await, 0);
+}
+
+#[tokio::test]
+async fn test_many_bets_fit_in_one_transaction() {
+    let mut env = common::start().await;
+    let arbiter = Keypair::new();
+    let now = env.now().await;
+    let event = env.create_event(&arbiter, now).await;
+    let alice = Keypair::new().pubkey();
+    let mut bets = vec![];
+    for _ in 0..25 {
+        bets.push(env.place_bet(&alice, &event, MatchOutcome::TeamA, 100_000).await);
+    }
+    env.place_bet(&Keypair::new().pubkey(), &event, MatchOutcome::TeamB, 2_500_000).await;
+    env.set_winner(&arbiter, &event, MatchOutcome::TeamA).await.unwrap();
+    env.close_correction_window(&event).await;
+
+    withdraw_many(&mut env, &alice, &event, &bets).await.unwrap();
+    assert_eq!(env.lamports(&alice).await, 25 * 194_000);
+}
@@ -0,0 +1,88 @@
+// Instruction data and account decoding for the web frontend, `wasm-pack build --target web`.
+// The bytes are Instruction::pack's, the same helloworld::client puts in its instructions; the
+// frontend adds the accounts in the order documented on Instruction.
+use helloworld::{Bet, EventBets, EventParams, Instruction, MatchOutcome, MEMO_BYTES, METADATA_HASH_BYTES};
+use serde::Serialize;
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+fn outcome(input: &str) -> Result<MatchOutcome, JsValue> {
+    MatchOutcome::from_str(input).map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+fn pubkey(input: &str) -> Result<Pubkey, JsValue> {
+    Pubkey::from_str(input).map_err(|_| JsValue::from_str(&format!("{} is not a base58 pubkey", input)))
+}
+
+// Zero padded, as the program stores it.
+fn memo(input: &[u8]) -> Result<[u8; MEMO_BYTES], JsValue> {
+    if input.len() > MEMO_BYTES {
+        return Err(JsValue::from_str(&format!("memo of {} bytes, at most {}", input.len(), MEMO_BYTES)));
+    }
+    let mut memo = [0; MEMO_BYTES];
+    memo[..input.len()].copy_from_slice(input);
+    Ok(memo)
+}
+
+// Exactly METADATA_HASH_BYTES when given, all zeroes when left out, which confirms nothing.
+fn metadata_hash(input: Option<Vec<u8>>) -> Result<[u8; METADATA_HASH_BYTES], JsValue> {
+    let input = match input {
+        Some(input) => input,
+        None => return Ok([0; METADATA_HASH_BYTES]),
+    };
+    if input.len() != METADATA_HASH_BYTES {
+        return Err(JsValue::from_str(&format!("metadata hash of {} bytes, {} expected", input.len(), METADATA_HASH_BYTES)));
+    }
+    let mut hash = [0; METADATA_HASH_BYTES];
+    hash.copy_from_slice(&input);
+    Ok(hash)
+}
+
+// Pubkeys as base58 strings, MatchOutcome by name and u64 as BigInt so no amount loses precision.
+fn to_js<T: Serialize>(state: &T) -> Result<JsValue, JsValue> {
+    state
+        .serialize(&serde_wasm_bindgen::Serializer::new().serialize_large_number_types_as_bigints(true))
+        .map_err(JsValue::from)
+}
+
+fn program_error(err: solana_program::program_error::ProgramError) -> JsValue {
+    JsValue::from_str(&err.to_string())
+}
+
+// Initialize with the defaults of EventParams::new, taking bets until `bets_accepted_until`.
+#[wasm_bindgen]
+pub fn pack_initialize(bets_accepted_until: i64) -> Vec<u8> {
+    Instruction::Initialize(EventParams::new(bets_accepted_until)).pack()
+}
+
+// `choice` is a MatchOutcome name, e.g. "TeamA", `referrer` a base58 pubkey, an amount of 0 bets the
+// bet account's lamports above rent.
+#[wasm_bindgen]
+pub fn pack_add_bet(choice: &str, referrer: Option<String>, amount: u64, memo_bytes: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let referrer = referrer.as_deref().map(pubkey).transpose()?.unwrap_or_default();
+    Ok(Instruction::AddBet { choice: outcome(choice)?, referrer, amount, memo: memo(memo_bytes)? }.pack())
+}
+
+// `metadata_hash`, when given, must match the one the event was created with, see client::set_winner_confirmed.
+#[wasm_bindgen]
+pub fn pack_set_winner(result: &str, metadata_hash: Option<Vec<u8>>) -> Result<Vec<u8>, JsValue> {
+    Ok(Instruction::SetWinner { result: outcome(result)?, metadata_hash: self::metadata_hash(metadata_hash)? }.pack())
+}
+
+// An amount of 0 claims all that is left of the bet.
+#[wasm_bindgen]
+pub fn pack_withdraw(amount: u64) -> Vec<u8> {
+    Instruction::Withdraw { amount }.pack()
+}
+
+// The EventBets of an event account's data, refusing accounts that aren't initialized events.
+#[wasm_bindgen]
+pub fn decode_event(data: &[u8]) -> Result<JsValue, JsValue> {
+    to_js(&EventBets::unpack(data).map_err(program_error)?)
+}
+
+#[wasm_bindgen]
+pub fn decode_bet(data: &[u8]) -> Result<JsValue, JsValue> {
+    to_js(&Bet::unpack(data).map_err(program_error)?)
+}
@@ -0,0 +1,33 @@
+// The bindings' bytes against the native builders, `cargo test`. The decoders return JS objects,
+// tests/web.rs covers them under `wasm-pack test --node`.
+use bs_contract_wasm::{pack_add_bet, pack_initialize, pack_set_winner, pack_withdraw};
+use helloworld::{client, Instruction, MatchOutcome, MEMO_BYTES, METADATA_HASH_BYTES};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn test_packed_bytes_match_the_client_builders() {
+    let (program_id, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+    assert_eq!(pack_initialize(1_700_000_000), client::initialize(&program_id, &key, &key, 1_700_000_000).data);
+    assert_eq!(pack_add_bet("TeamB", None, 0, &[]).unwrap(), client::add_bet(&program_id, &key, &key, &key, MatchOutcome::TeamB).data);
+    assert_eq!(pack_set_winner("Draw", None).unwrap(), client::set_winner(&program_id, &key, &key, MatchOutcome::Draw).data);
+    let hash = [7; METADATA_HASH_BYTES];
+    assert_eq!(
+        pack_set_winner("TeamA", Some(hash.to_vec())).unwrap(),
+        client::set_winner_confirmed(&program_id, &key, &key, MatchOutcome::TeamA, hash).data
+    );
+    assert_eq!(pack_withdraw(0), client::withdraw(&program_id, &key, &key, &key).data);
+}
+
+#[test]
+fn test_packed_bytes_match_instruction_pack() {
+    let referrer = Pubkey::new_unique();
+    let mut memo = [0; MEMO_BYTES];
+    memo[..9].copy_from_slice(b"order #42");
+    assert_eq!(
+        pack_add_bet("team a", Some(referrer.to_string()), 7, b"order #42").unwrap(),
+        Instruction::AddBet { choice: MatchOutcome::TeamA, referrer, amount: 7, memo }.pack()
+    );
+    // Outcomes by byte too, as MatchOutcome::from_str takes them.
+    assert_eq!(pack_set_winner("2", None).unwrap(), Instruction::SetWinner { result: MatchOutcome::TeamB, metadata_hash: [0; 32] }.pack());
+    assert_eq!(pack_withdraw(u64::MAX), Instruction::Withdraw { amount: u64::MAX }.pack());
+}
@@ -0,0 +1,48 @@
+// `wasm-pack test --node`, the bindings as the frontend calls them.
+#![cfg(target_arch = "wasm32")]
+
+use bs_contract_wasm::{decode_bet, decode_event, pack_add_bet, pack_set_winner};
+use helloworld::{client, Bet, EventBets, MatchOutcome, STATE_VERSION};
+use js_sys::{BigInt, Reflect};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn field(object: &JsValue, name: &str) -> JsValue {
+    Reflect::get(object, &JsValue::from_str(name)).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_packed_bytes_match_the_client_builders() {
+    let (program_id, key) = (Pubkey::new_unique(), Pubkey::new_unique());
+    assert_eq!(pack_add_bet("TeamA", None, 0, &[]).unwrap(), client::add_bet(&program_id, &key, &key, &key, MatchOutcome::TeamA).data);
+    assert_eq!(pack_set_winner("TeamB", None).unwrap(), client::set_winner(&program_id, &key, &key, MatchOutcome::TeamB).data);
+    assert!(pack_set_winner("TeamC", None).is_err());
+    assert!(pack_set_winner("TeamB", Some(vec![0; 31])).is_err());
+    assert!(pack_add_bet("TeamA", Some("not a pubkey".to_string()), 0, &[]).is_err());
+    assert!(pack_add_bet("TeamA", None, 0, &[0; 33]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_decoded_accounts() {
+    let mut bets = EventBets::unpack_unchecked(&[0; EventBets::LEN]).unwrap();
+    bets.version = STATE_VERSION;
+    bets.arbiter = Pubkey::new_unique();
+    bets.balance_a = u64::MAX;
+    let mut data = vec![0; EventBets::LEN];
+    EventBets::pack(bets, &mut data).unwrap();
+    let arbiter = EventBets::unpack(&data).unwrap().arbiter;
+
+    let event = decode_event(&data).unwrap();
+    assert_eq!(field(&event, "arbiter").as_string(), Some(arbiter.to_string()));
+    assert_eq!(field(&event, "balance_a"), JsValue::from(BigInt::from(u64::MAX)));
+    assert!(decode_event(&[0; EventBets::LEN]).is_err());
+    assert!(decode_event(&data[1..]).is_err());
+
+    let mut this_bet = Bet::unpack_unchecked(&[0; Bet::LEN]).unwrap();
+    this_bet.version = STATE_VERSION;
+    this_bet.outcome = u8::from(MatchOutcome::TeamB);
+    let mut data = vec![0; Bet::LEN];
+    Bet::pack(this_bet, &mut data).unwrap();
+    assert_eq!(field(&decode_bet(&data).unwrap(), "outcome"), JsValue::from(u8::from(MatchOutcome::TeamB)));
+}